@@ -0,0 +1,604 @@
+use std::collections::HashMap;
+
+use utils::Span;
+
+use crate::{NumericValue, Operator, VarKind};
+
+/// Identifies a node in an [`Arena`]. IDs are handed out in the order nodes are stored, so they are stable for a given tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    /// Returns the position of the node in its arena.
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Identifies a string stored once in an [`Interner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+/// A store of unique strings, which hands out a [`Symbol`] for each one so that names and values can be compared and copied cheaply.
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    symbols: HashMap<String, Symbol>,
+    strings: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Returns the symbol for a string, storing the string if it has not been seen before.
+    pub fn intern(&mut self, string: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(string) {
+            return *symbol;
+        }
+        let symbol = Symbol(self.strings.len() as u32);
+        self.strings.push(string.to_string());
+        self.symbols.insert(string.to_string(), symbol);
+        symbol
+    }
+    /// Returns the symbol for a string, if it has been stored.
+    pub fn get(&self, string: &str) -> Option<Symbol> {
+        self.symbols.get(string).copied()
+    }
+    /// Returns the string a symbol stands for.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+    /// Returns the number of unique strings stored.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+/// A node stored in an arena.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub span: Span,
+    /// The node that contains this one, or `None` for the top-level statements.
+    pub parent: Option<NodeId>,
+}
+
+/// The shape of a node in an arena. It mirrors the syntax tree, with child nodes referred to by ID and text held as symbols.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    // Expressions.
+    Identifier(Symbol),
+    String(Symbol),
+    TemplateString {
+        parts: Vec<NodeId>,
+    },
+    Number {
+        raw: Symbol,
+        value: NumericValue,
+    },
+    Boolean(bool),
+    Character(Symbol),
+    SelfExpression,
+    Binary {
+        operator: Operator,
+        left: NodeId,
+        right: NodeId,
+    },
+    Logical {
+        operator: Operator,
+        left: NodeId,
+        right: NodeId,
+    },
+    Unary {
+        operator: Operator,
+        operand: NodeId,
+    },
+    Call {
+        callee: NodeId,
+        arguments: Vec<NodeId>,
+    },
+    Array {
+        elements: Vec<NodeId>,
+    },
+    Index {
+        accessor: NodeId,
+        property: NodeId,
+    },
+    Dot {
+        object: NodeId,
+        property: NodeId,
+    },
+    Namespace {
+        object: NodeId,
+        property: NodeId,
+    },
+    Range {
+        start: NodeId,
+        end: NodeId,
+    },
+    Ternary {
+        test: NodeId,
+        consequent: NodeId,
+        alternate: NodeId,
+    },
+    Assignment {
+        operator: Operator,
+        target: NodeId,
+        value: NodeId,
+    },
+    FnExpression {
+        generic_arguments: Vec<NodeId>,
+        parameters: Vec<NodeId>,
+        return_type: Option<NodeId>,
+        body: Option<NodeId>,
+        implicit_return: Option<NodeId>,
+    },
+    ErrorExpression,
+    // Statements.
+    If {
+        test: NodeId,
+        body: NodeId,
+        alternate: Option<NodeId>,
+    },
+    PrintLn {
+        argument: NodeId,
+    },
+    Prepend {
+        source: NodeId,
+    },
+    VariableDeclaration {
+        name: NodeId,
+        kind: VarKind,
+        type_label: Option<NodeId>,
+        initializer: Option<NodeId>,
+    },
+    Break,
+    Continue,
+    TestBlock {
+        body: NodeId,
+    },
+    Loop {
+        constraint: Option<NodeId>,
+        body: NodeId,
+    },
+    ForLoop {
+        item: NodeId,
+        iterator: NodeId,
+        body: NodeId,
+    },
+    While {
+        test: NodeId,
+        body: NodeId,
+    },
+    Public {
+        statement: NodeId,
+    },
+    ExpressionStatement {
+        expression: NodeId,
+    },
+    Block {
+        statements: Vec<NodeId>,
+    },
+    UseImport {
+        imports: Vec<NodeId>,
+        source: NodeId,
+    },
+    Import {
+        imported_name: NodeId,
+        collapsed_import: bool,
+        local_name: Option<NodeId>,
+    },
+    Return {
+        argument: Option<NodeId>,
+    },
+    Crash {
+        argument: NodeId,
+    },
+    Empty,
+    TryBlock {
+        body: NodeId,
+        recover: Option<NodeId>,
+    },
+    RecoverBlock {
+        parameters: Vec<NodeId>,
+        body: NodeId,
+    },
+    ErrorStatement,
+    // Declarations.
+    Function {
+        name: NodeId,
+        generic_arguments: Vec<NodeId>,
+        parameters: Vec<NodeId>,
+        return_type: Option<NodeId>,
+        body: NodeId,
+    },
+    GenericArgument {
+        name: NodeId,
+        implements: Vec<NodeId>,
+    },
+    Parameter {
+        name: NodeId,
+        label: Option<NodeId>,
+    },
+    TypeAlias {
+        name: NodeId,
+        generic_arguments: Vec<NodeId>,
+        value: NodeId,
+    },
+    Interface {
+        name: NodeId,
+        generic_arguments: Vec<NodeId>,
+        properties: Vec<NodeId>,
+    },
+    Class {
+        name: NodeId,
+        generic_arguments: Vec<NodeId>,
+        properties: Vec<NodeId>,
+    },
+    Method {
+        name: NodeId,
+        generic_arguments: Vec<NodeId>,
+        is_static: bool,
+        parameters: Vec<NodeId>,
+        return_type: Option<NodeId>,
+        body: NodeId,
+    },
+    Attribute {
+        key: NodeId,
+        type_label: Option<NodeId>,
+        is_static: bool,
+        is_readonly: bool,
+        value: Option<NodeId>,
+    },
+    Implement {
+        interface: NodeId,
+    },
+    Enum {
+        name: NodeId,
+        generic_arguments: Vec<NodeId>,
+        variants: Vec<NodeId>,
+    },
+    Variant {
+        name: NodeId,
+        elements: Vec<NodeId>,
+    },
+    Module {
+        name: NodeId,
+        body: NodeId,
+    },
+    Record {
+        name: NodeId,
+        mappings: Vec<NodeId>,
+    },
+    Mapping {
+        key: NodeId,
+        value: NodeId,
+    },
+    // Types.
+    ConcreteType {
+        name: NodeId,
+        arguments: Vec<NodeId>,
+    },
+    FunctionType {
+        generic_arguments: Vec<NodeId>,
+        parameters: Vec<NodeId>,
+        return_type: NodeId,
+    },
+    DotType {
+        levels: Vec<NodeId>,
+    },
+}
+
+impl NodeKind {
+    /// Returns the nodes directly contained in this one, in the order they appear in the source.
+    pub fn children(&self) -> Vec<NodeId> {
+        let mut children = vec![];
+        let mut push = |ids: &[NodeId]| children.extend_from_slice(ids);
+        match self {
+            Self::Identifier(_)
+            | Self::String(_)
+            | Self::Number { .. }
+            | Self::Boolean(_)
+            | Self::Character(_)
+            | Self::SelfExpression
+            | Self::ErrorExpression
+            | Self::Break
+            | Self::Continue
+            | Self::Empty
+            | Self::ErrorStatement => {}
+            Self::TemplateString { parts } => push(parts),
+            Self::Binary { left, right, .. } | Self::Logical { left, right, .. } => {
+                push(&[*left, *right])
+            }
+            Self::Unary { operand, .. } => push(&[*operand]),
+            Self::Call { callee, arguments } => {
+                push(&[*callee]);
+                push(arguments)
+            }
+            Self::Array { elements } => push(elements),
+            Self::Index { accessor, property } => push(&[*accessor, *property]),
+            Self::Dot { object, property } | Self::Namespace { object, property } => {
+                push(&[*object, *property])
+            }
+            Self::Range { start, end } => push(&[*start, *end]),
+            Self::Ternary {
+                test,
+                consequent,
+                alternate,
+            } => push(&[*test, *consequent, *alternate]),
+            Self::Assignment { target, value, .. } => push(&[*target, *value]),
+            Self::FnExpression {
+                generic_arguments,
+                parameters,
+                return_type,
+                body,
+                implicit_return,
+            } => {
+                push(generic_arguments);
+                push(parameters);
+                push(return_type.as_slice());
+                push(body.as_slice());
+                push(implicit_return.as_slice())
+            }
+            Self::If {
+                test,
+                body,
+                alternate,
+            } => {
+                push(&[*test, *body]);
+                push(alternate.as_slice())
+            }
+            Self::PrintLn { argument } | Self::Crash { argument } => push(&[*argument]),
+            Self::Prepend { source } => push(&[*source]),
+            Self::VariableDeclaration {
+                name,
+                type_label,
+                initializer,
+                ..
+            } => {
+                push(&[*name]);
+                push(type_label.as_slice());
+                push(initializer.as_slice())
+            }
+            Self::TestBlock { body } => push(&[*body]),
+            Self::Loop { constraint, body } => {
+                push(constraint.as_slice());
+                push(&[*body])
+            }
+            Self::ForLoop {
+                item,
+                iterator,
+                body,
+            } => push(&[*item, *iterator, *body]),
+            Self::While { test, body } => push(&[*test, *body]),
+            Self::Public { statement } => push(&[*statement]),
+            Self::ExpressionStatement { expression } => push(&[*expression]),
+            Self::Block { statements } => push(statements),
+            Self::UseImport { imports, source } => {
+                push(imports);
+                push(&[*source])
+            }
+            Self::Import {
+                imported_name,
+                local_name,
+                ..
+            } => {
+                push(&[*imported_name]);
+                push(local_name.as_slice())
+            }
+            Self::Return { argument } => push(argument.as_slice()),
+            Self::TryBlock { body, recover } => {
+                push(&[*body]);
+                push(recover.as_slice())
+            }
+            Self::RecoverBlock { parameters, body } => {
+                push(parameters);
+                push(&[*body])
+            }
+            Self::Function {
+                name,
+                generic_arguments,
+                parameters,
+                return_type,
+                body,
+            } => {
+                push(&[*name]);
+                push(generic_arguments);
+                push(parameters);
+                push(return_type.as_slice());
+                push(&[*body])
+            }
+            Self::GenericArgument { name, implements } => {
+                push(&[*name]);
+                push(implements)
+            }
+            Self::Parameter { name, label } => {
+                push(&[*name]);
+                push(label.as_slice())
+            }
+            Self::TypeAlias {
+                name,
+                generic_arguments,
+                value,
+            } => {
+                push(&[*name]);
+                push(generic_arguments);
+                push(&[*value])
+            }
+            Self::Interface {
+                name,
+                generic_arguments,
+                properties,
+            }
+            | Self::Class {
+                name,
+                generic_arguments,
+                properties,
+            } => {
+                push(&[*name]);
+                push(generic_arguments);
+                push(properties)
+            }
+            Self::Method {
+                name,
+                generic_arguments,
+                parameters,
+                return_type,
+                body,
+                ..
+            } => {
+                push(&[*name]);
+                push(generic_arguments);
+                push(parameters);
+                push(return_type.as_slice());
+                push(&[*body])
+            }
+            Self::Attribute {
+                key,
+                type_label,
+                value,
+                ..
+            } => {
+                push(&[*key]);
+                push(type_label.as_slice());
+                push(value.as_slice())
+            }
+            Self::Implement { interface } => push(&[*interface]),
+            Self::Enum {
+                name,
+                generic_arguments,
+                variants,
+            } => {
+                push(&[*name]);
+                push(generic_arguments);
+                push(variants)
+            }
+            Self::Variant { name, elements } => {
+                push(&[*name]);
+                push(elements)
+            }
+            Self::Module { name, body } => push(&[*name, *body]),
+            Self::Record { name, mappings } => {
+                push(&[*name]);
+                push(mappings)
+            }
+            Self::Mapping { key, value } => push(&[*key, *value]),
+            Self::ConcreteType { name, arguments } => {
+                push(&[*name]);
+                push(arguments)
+            }
+            Self::FunctionType {
+                generic_arguments,
+                parameters,
+                return_type,
+            } => {
+                push(generic_arguments);
+                push(parameters);
+                push(&[*return_type])
+            }
+            Self::DotType { levels } => push(levels),
+        }
+        children
+    }
+}
+
+/// An owned syntax tree, which stores every node in a single list and gives each one a [`NodeId`].<br>
+/// Names and string values are interned, so each is stored once however many nodes use it.
+/// Later stages can attach their results to nodes in side tables keyed by ID.
+#[derive(Debug, Default, Clone)]
+pub struct Arena {
+    nodes: Vec<Node>,
+    roots: Vec<NodeId>,
+    /// The doc comments of documented declarations.
+    docs: HashMap<NodeId, String>,
+    pub interner: Interner,
+}
+
+impl Arena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Stores a node and returns its ID. The node becomes the parent of every child it refers to.
+    pub fn alloc(&mut self, kind: NodeKind, span: Span) -> NodeId {
+        let id = NodeId(self.nodes.len() as u32);
+        for child in kind.children() {
+            self.nodes[child.index()].parent = Some(id);
+        }
+        self.nodes.push(Node {
+            kind,
+            span,
+            parent: None,
+        });
+        id
+    }
+    /// Attaches doc comments to a declaration.
+    pub fn set_docs(&mut self, id: NodeId, docs: impl Into<String>) {
+        self.docs.insert(id, docs.into());
+    }
+    /// Returns the doc comments of a declaration, if it has any.
+    pub fn docs(&self, id: NodeId) -> Option<&str> {
+        self.docs.get(&id).map(String::as_str)
+    }
+    /// Marks a node as a top-level statement.
+    pub fn add_root(&mut self, id: NodeId) {
+        self.roots.push(id);
+    }
+    /// Returns the top-level statements, in source order.
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+    pub fn get(&self, id: NodeId) -> &Node {
+        &self.nodes[id.index()]
+    }
+    pub fn kind(&self, id: NodeId) -> &NodeKind {
+        &self.get(id).kind
+    }
+    pub fn span(&self, id: NodeId) -> Span {
+        self.get(id).span
+    }
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.get(id).parent
+    }
+    pub fn children(&self, id: NodeId) -> Vec<NodeId> {
+        self.kind(id).children()
+    }
+    /// Returns the text of an interned name or value.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.interner.resolve(symbol)
+    }
+    /// Returns the name held by an identifier node.
+    pub fn name(&self, id: NodeId) -> Option<&str> {
+        match self.kind(id) {
+            NodeKind::Identifier(symbol) => Some(self.resolve(*symbol)),
+            _ => None,
+        }
+    }
+    /// Returns every node, with its ID, in the order they were stored.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &Node)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (NodeId(index as u32), node))
+    }
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    /// Returns `true` if both arenas hold the same tree, with the same names, values and docs, wherever its nodes were written.
+    pub fn same_tree(&self, other: &Arena) -> bool {
+        self.roots == other.roots
+            && self.docs == other.docs
+            && self.interner.strings == other.interner.strings
+            && self.nodes.len() == other.nodes.len()
+            && self
+                .nodes
+                .iter()
+                .zip(&other.nodes)
+                .all(|(node, other)| node.kind == other.kind && node.parent == other.parent)
+    }
+}
@@ -1,21 +1,20 @@
-use std::marker::PhantomData;
+use macros::{Displace, Location};
+use utils::Span;
 
-use macros::Location;
+use crate::{Displace, Displacement, Expression, Identifier, Location, Parameter, Statement};
 
-use crate::{Expression, Identifier, Location, Parameter, Statement, TextSpan};
-
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct TryBlock<'a> {
-    pub body: Block<'a>,
-    pub span: TextSpan,
-    pub recover: Option<RecoverBlock<'a>>,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct TryBlock {
+    pub body: Block,
+    pub span: Span,
+    pub recover: Option<RecoverBlock>,
 }
 
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct RecoverBlock<'a> {
-    pub params: Vec<Parameter<'a>>,
-    pub span: TextSpan,
-    pub body: Block<'a>,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct RecoverBlock {
+    pub params: Vec<Parameter>,
+    pub span: Span,
+    pub body: Block,
 }
 
 /// A block statement. e.g.
@@ -24,10 +23,10 @@ pub struct RecoverBlock<'a> {
 ///     print "This is a block statement.";
 /// }
 /// ```
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct Block<'a> {
-    pub body: Vec<Statement<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct Block {
+    pub body: Vec<Statement>,
+    pub span: Span,
 }
 
 /// A loop statement, with the form:
@@ -38,11 +37,11 @@ pub struct Block<'a> {
 /// ```
 /// The above loop runs the function `doStuff()` 10 times.
 /// To create an infinite loop the constraint can be omitted.
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Loop<'a> {
-    pub constraint: Option<Expression<'a>>,
-    pub body: Block<'a>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Loop {
+    pub constraint: Option<Expression>,
+    pub body: Block,
+    pub span: Span,
 }
 
 /// A for loop statement, with the form:
@@ -52,11 +51,12 @@ pub struct Loop<'a> {
 /// }
 /// ```
 /// The block can be replaced with a single statement.
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct ForLoop<'a> {
-    pub item: Identifier<'a>,
-    pub iterator: Expression<'a>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct ForLoop {
+    pub item: Identifier,
+    pub iterator: Expression,
+    pub body: Box<Statement>,
+    pub span: Span,
 }
 
 /// A generic if statement, as it is in other C derived languages. e.g.
@@ -68,12 +68,12 @@ pub struct ForLoop<'a> {
 /// }
 /// ```
 /// As with Javascript, the blocks can be replaced with a single statement, and the else is optional.
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct IfStatement<'a> {
-    pub test: Expression<'a>,
-    pub body: Box<Statement<'a>>,
-    pub alternate: Option<Box<Statement<'a>>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct IfStatement {
+    pub test: Expression,
+    pub body: Box<Statement>,
+    pub alternate: Option<Box<Statement>>,
+    pub span: Span,
 }
 /// A while statement, with the form:
 /// ```pebble
@@ -81,21 +81,21 @@ pub struct IfStatement<'a> {
 ///     doStuff();
 /// }
 /// ```
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct WhileStatement<'a> {
-    pub test: Expression<'a>,
-    pub body: Box<Statement<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct WhileStatement {
+    pub test: Expression,
+    pub body: Box<Statement>,
+    pub span: Span,
 }
 
 /// A statement that prints to the standard output. e.g.
 /// ```pebble
 /// println "Hello, world!";
 /// ```
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct PrintLnStatement<'a> {
-    pub argument: Expression<'a>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct PrintLnStatement {
+    pub argument: Expression,
+    pub span: Span,
 }
 
 /// A statement that halts execution of the current code context and rolls back the stack trace to the last try block.
@@ -111,35 +111,34 @@ pub struct PrintLnStatement<'a> {
 ///     core.io.printErr(error.message)
 /// }
 /// ```
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct CrashStatement<'a> {
-    pub argument: Expression<'a>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct CrashStatement {
+    pub argument: Expression,
+    pub span: Span,
 }
 
 /// A break statement that halts a loop.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Displace)]
 pub struct Break {
-    pub span: TextSpan,
+    pub span: Span,
 }
 impl Location for Break {
-    fn get_range(&self) -> TextSpan {
+    fn get_range(&self) -> Span {
         self.span
     }
 }
 
 /// A continue statement that skips over the next iteration in the loop.
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Continue<'a> {
-    pub span: TextSpan,
-    pub phantom: PhantomData<&'a i32>,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Continue {
+    pub span: Span,
 }
 
 /// Any expression statement.
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct ExpressionStatement<'a> {
-    pub expression: Expression<'a>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct ExpressionStatement {
+    pub expression: Expression,
+    pub span: Span,
 }
 
 /// A return statement.
@@ -148,8 +147,8 @@ pub struct ExpressionStatement<'a> {
 ///     return x;
 /// }
 /// ```
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct ReturnStatement<'a> {
-    pub argument: Option<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct ReturnStatement {
+    pub argument: Option<Expression>,
+    pub span: Span,
 }
@@ -1,6 +1,9 @@
-use macros::Location;
+use macros::{Displace, Location};
+use utils::Span;
 
-use crate::{Block, Expression, Identifier, Location, Statement, TextSpan, TextString, Type};
+use crate::{
+    Block, Displace, Displacement, Expression, Identifier, Location, Statement, TextString, Type,
+};
 
 /// A function declaration. e.g.
 /// ```pebble
@@ -8,40 +11,42 @@ use crate::{Block, Expression, Identifier, Location, Statement, TextSpan, TextSt
 ///     println "Hello, world!";
 /// }
 /// ```
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Function<'a> {
-    pub name: Identifier<'a>,
-    pub generic_arguments: Option<Vec<GenericArgument<'a>>>,
-    pub parameters: Vec<Parameter<'a>>,
-    pub return_type: Option<Type<'a>>,
-    pub body: Block<'a>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Function {
+    pub name: Identifier,
+    pub generic_arguments: Option<Vec<GenericArgument>>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<Type>,
+    pub body: Block,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct GenericArgument<'a> {
-    pub name: Identifier<'a>,
-    pub implements: Option<Vec<Identifier<'a>>>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct GenericArgument {
+    pub name: Identifier,
+    pub implements: Option<Vec<Identifier>>,
+    pub span: Span,
 }
 
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct Parameter<'a> {
-    pub name: Identifier<'a>,
-    pub label: Option<Type<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct Parameter {
+    pub name: Identifier,
+    pub label: Option<Type>,
+    pub span: Span,
 }
 
 /// Declaration of a type name that is an alias of another.
 /// ```pebble
 /// @type Meters = Number;
 /// ```
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct TypeAlias<'a> {
-    pub name: Identifier<'a>,
-    pub generic_arguments: Option<Vec<GenericArgument<'a>>>,
-    pub value: Type<'a>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct TypeAlias {
+    pub name: Identifier,
+    pub generic_arguments: Option<Vec<GenericArgument>>,
+    pub value: Type,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
 /// An abstract structure that allows the enforcing of properties on classes and objects. e.g.
@@ -52,12 +57,13 @@ pub struct TypeAlias<'a> {
 ///     isLesser: (rhs: Self) -> Boolean,
 /// }
 /// ```
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct Interface<'a> {
-    pub name: Identifier<'a>,
-    pub generic_arguments: Option<Vec<GenericArgument<'a>>>,
-    pub properties: Vec<Property<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct Interface {
+    pub name: Identifier,
+    pub generic_arguments: Option<Vec<GenericArgument>>,
+    pub properties: Vec<Property>,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
 /// A template-like structure for creating instances and objects in Pebble. e.g.
@@ -73,51 +79,66 @@ pub struct Interface<'a> {
 ///     id: String,
 /// }
 /// ```
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct Class<'a> {
-    pub name: Identifier<'a>,
-    pub generic_arguments: Option<Vec<GenericArgument<'a>>>,
-    pub properties: Vec<Property<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct Class {
+    pub name: Identifier,
+    pub generic_arguments: Option<Vec<GenericArgument>>,
+    pub properties: Vec<Property>,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
 /// The property of a class or interface.
-#[derive(Clone, Debug, PartialEq)]
-pub enum Property<'a> {
-    Method(Method<'a>),
-    Attribute(Attribute<'a>),
-    Implement(Implement<'a>),
+#[derive(Clone, Debug, PartialEq, Displace)]
+pub enum Property {
+    Method(Method),
+    Attribute(Attribute),
+    Implement(Implement),
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Method<'a> {
-    pub name: Identifier<'a>,
-    pub generic_arguments: Option<Vec<GenericArgument<'a>>>,
+#[derive(Clone, Debug, PartialEq, Displace)]
+pub struct Method {
+    pub name: Identifier,
+    pub generic_arguments: Option<Vec<GenericArgument>>,
     pub is_static: bool,
-    pub parameters: Vec<Parameter<'a>>,
-    pub return_type: Option<Type<'a>>,
-    pub body: Block<'a>,
-    pub span: TextSpan,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<Type>,
+    pub body: Block,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Attribute<'a> {
-    pub key: Identifier<'a>,
-    pub type_label: Option<Type<'a>>,
+#[derive(Clone, Debug, PartialEq, Displace)]
+pub struct Attribute {
+    pub key: Identifier,
+    pub type_label: Option<Type>,
     pub is_static: bool,
     pub is_readonly: bool,
-    pub value: Option<Expression<'a>>,
-    pub span: TextSpan,
+    pub value: Option<Expression>,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Implement<'a> {
-    pub interface: Identifier<'a>,
-    pub span: TextSpan,
+#[derive(Clone, Debug, PartialEq, Displace)]
+pub struct Implement {
+    pub interface: Identifier,
+    pub span: Span,
 }
 
-impl<'a> Location for Property<'a> {
-    fn get_range(&self) -> TextSpan {
+impl Property {
+    /// Returns the doc comments written before the property, if it is a method or an attribute.
+    pub fn docs(&self) -> Option<&str> {
+        match self {
+            Self::Method(Method { docs, .. }) | Self::Attribute(Attribute { docs, .. }) => {
+                docs.as_deref()
+            }
+            Self::Implement(_) => None,
+        }
+    }
+}
+
+impl Location for Property {
+    fn get_range(&self) -> Span {
         match self {
             Self::Method(Method { span, .. })
             | Self::Attribute(Attribute { span, .. })
@@ -135,30 +156,41 @@ impl<'a> Location for Property<'a> {
 ///         Right
 ///     }
 /// ```
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Enum<'a> {
-    pub name: Identifier<'a>,
-    pub generic_arguments: Option<Vec<GenericArgument<'a>>>,
-    pub variants: Vec<Variant<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Enum {
+    pub name: Identifier,
+    pub generic_arguments: Option<Vec<GenericArgument>>,
+    pub variants: Vec<Variant>,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
 /// Any of the values an enum could take.
-#[derive(Debug, Clone, PartialEq)]
-pub enum Variant<'a> {
+#[derive(Debug, Clone, PartialEq, Displace)]
+pub enum Variant {
     Tuple {
-        name: Identifier<'a>,
-        elements: Vec<Type<'a>>,
-        span: TextSpan,
+        name: Identifier,
+        elements: Vec<Type>,
+        docs: Option<String>,
+        span: Span,
     },
     Concrete {
-        name: Identifier<'a>,
-        span: TextSpan,
+        name: Identifier,
+        docs: Option<String>,
+        span: Span,
     },
 }
 
-impl Location for Variant<'_> {
-    fn get_range(&self) -> TextSpan {
+impl Variant {
+    pub fn docs(&self) -> Option<&str> {
+        match self {
+            Self::Tuple { docs, .. } | Self::Concrete { docs, .. } => docs.as_deref(),
+        }
+    }
+}
+
+impl Location for Variant {
+    fn get_range(&self) -> Span {
         match self {
             Self::Tuple { span, .. } | Self::Concrete { span, .. } => *span,
         }
@@ -173,27 +205,29 @@ impl Location for Variant<'_> {
 ///     }
 /// }
 /// ```
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct Module<'a> {
-    pub name: Identifier<'a>,
-    pub body: Block<'a>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct Module {
+    pub name: Identifier,
+    pub body: Block,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
 /// A variable declaration.
 /// ```pebble
 /// @let name: String = "johnny";
 /// ```
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct VariableDeclaration<'a> {
-    pub name: Identifier<'a>,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct VariableDeclaration {
+    pub name: Identifier,
     pub kind: VarKind,
-    pub initializer: Option<Expression<'a>>,
-    pub type_label: Option<Type<'a>>,
-    pub span: TextSpan,
+    pub initializer: Option<Expression>,
+    pub type_label: Option<Type>,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Displace)]
 pub enum VarKind {
     Let,
     Const,
@@ -223,10 +257,10 @@ impl VarKind {
 ///     }
 /// }
 /// ```
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct TestBlock<'a> {
-    pub body: Block<'a>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct TestBlock {
+    pub body: Block,
+    pub span: Span,
 }
 
 /// A modifier that indicates that a function, variable, class, record or type is accessible from outside the file in which it was defined.
@@ -235,20 +269,20 @@ pub struct TestBlock<'a> {
 ///     // Doing stuff...
 /// }
 /// ```
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct PublicModifier<'a> {
-    pub statement: Box<Statement<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct PublicModifier {
+    pub statement: Box<Statement>,
+    pub span: Span,
 }
 
 /// A statement that concatenates the content of another file to the top of a file. e.g.
 /// ```pebble
 /// @prepend "./otherfile.peb";
 /// ```
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct PrependStatement<'a> {
-    pub source: Expression<'a>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct PrependStatement {
+    pub source: Expression,
+    pub span: Span,
 }
 
 /// A statement that retrieves a function, module, class, type or variable from another file, module or pile in the workspace.
@@ -256,20 +290,20 @@ pub struct PrependStatement<'a> {
 /// ```pebble
 /// @use { colors, timer } from "utils";
 /// ```
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct UseImport<'a> {
-    pub imports: Vec<Import<'a>>,
-    pub source: TextString<'a>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct UseImport {
+    pub imports: Vec<Import>,
+    pub source: TextString,
+    pub span: Span,
 }
 
 /// An import into a module or file.
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Import<'a> {
-    pub imported_name: Identifier<'a>,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Import {
+    pub imported_name: Identifier,
     pub collapsed_import: bool,
-    pub local_name: Option<Identifier<'a>>,
-    pub span: TextSpan,
+    pub local_name: Option<Identifier>,
+    pub span: Span,
 }
 
 /// An immutable store of values that can be accessed by any part of the program in O(1) time.
@@ -279,16 +313,17 @@ pub struct Import<'a> {
 ///         2 -> "Jonathan"
 ///     }
 /// ```
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Record<'a> {
-    pub name: Identifier<'a>,
-    pub mappings: Vec<Mapping<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Record {
+    pub name: Identifier,
+    pub mappings: Vec<Mapping>,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Mapping<'a> {
-    pub key: Expression<'a>,
-    pub value: Expression<'a>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Mapping {
+    pub key: Expression,
+    pub value: Expression,
+    pub span: Span,
 }
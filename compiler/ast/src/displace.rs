@@ -0,0 +1,71 @@
+use utils::Span;
+
+use crate::{NumericValue, Operator};
+
+/// A move of the text after a point in a file, as when text before it is inserted or removed.<br>
+/// Every byte offset from `from` on moves by `delta` bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Displacement {
+    pub from: usize,
+    pub delta: isize,
+}
+
+impl Displacement {
+    pub fn new(from: usize, delta: isize) -> Self {
+        Displacement { from, delta }
+    }
+    /// Returns `true` if the displacement does not move anything.
+    pub fn is_identity(&self) -> bool {
+        self.delta == 0
+    }
+    /// Returns where an offset is after the move. Offsets before `from` stay where they are.
+    pub fn apply(&self, offset: usize) -> usize {
+        match offset >= self.from {
+            true => offset.checked_add_signed(self.delta).unwrap(),
+            false => offset,
+        }
+    }
+}
+
+/// A node with positions in the source text, which can be moved along with the text.
+pub trait Displace {
+    fn displace(&mut self, by: &Displacement);
+}
+
+impl Displace for Span {
+    fn displace(&mut self, by: &Displacement) {
+        self.start = by.apply(self.start);
+        self.end = by.apply(self.end);
+    }
+}
+
+impl<T: Displace> Displace for Vec<T> {
+    fn displace(&mut self, by: &Displacement) {
+        self.iter_mut().for_each(|item| item.displace(by))
+    }
+}
+
+impl<T: Displace> Displace for Option<T> {
+    fn displace(&mut self, by: &Displacement) {
+        if let Some(item) = self {
+            item.displace(by)
+        }
+    }
+}
+
+impl<T: Displace + ?Sized> Displace for Box<T> {
+    fn displace(&mut self, by: &Displacement) {
+        self.as_mut().displace(by)
+    }
+}
+
+/// Implements `Displace` for types that hold no positions.
+macro_rules! fixed {
+    ($($fixed:ty),*) => {
+        $(impl Displace for $fixed {
+            fn displace(&mut self, _: &Displacement) {}
+        })*
+    };
+}
+
+fixed!(String, Operator, bool, NumericValue);
@@ -1,172 +1,204 @@
-use macros::Location;
+use macros::{Displace, Location};
+use utils::Span;
 
-use crate::{Block, GenericArgument, Location, Operator, Parameter, TextSpan, Type};
+use crate::{
+    Block, Displace, Displacement, GenericArgument, Location, NumericValue, Operator, Parameter,
+    Type,
+};
 
 /// The base node for an expression.
-#[derive(Debug, Clone, PartialEq)]
-pub enum Expression<'a> {
-    IdentifierExpression(Identifier<'a>),
-    StringExpression(TextString<'a>),
-    NumericExpression(Number<'a>),
-    BooleanExpression(Boolean<'a>),
-    CharacterExpression(Character<'a>),
+#[derive(Debug, Clone, PartialEq, Displace)]
+pub enum Expression {
+    IdentifierExpression(Identifier),
+    StringExpression(TextString),
+    TemplateString(TemplateString),
+    NumericExpression(Number),
+    BooleanExpression(Boolean),
+    CharacterExpression(Character),
     SelfExpression(SelfExpression),
-    BinaryExpression(BinaryExpression<'a>),
-    LogicalExpression(LogicalExpression<'a>),
-    UnaryExpression(UnaryExpression<'a>),
-    CallExpression(CallExpression<'a>),
-    ArrayExpression(ArrayExpression<'a>),
-    IndexExpression(IndexExpression<'a>),
-    DotExpression(DotExpression<'a>),
-    NamespaceExpression(NamespaceExpression<'a>),
-    RangeExpression(RangeExpression<'a>),
-    TernaryExpression(TernaryExpression<'a>),
-    AssignmentExpression(AssignmentExpression<'a>),
-    FnExpression(FnExpression<'a>),
+    BinaryExpression(BinaryExpression),
+    LogicalExpression(LogicalExpression),
+    UnaryExpression(UnaryExpression),
+    CallExpression(CallExpression),
+    ArrayExpression(ArrayExpression),
+    IndexExpression(IndexExpression),
+    DotExpression(DotExpression),
+    NamespaceExpression(NamespaceExpression),
+    RangeExpression(RangeExpression),
+    TernaryExpression(TernaryExpression),
+    AssignmentExpression(AssignmentExpression),
+    FnExpression(FnExpression),
+    /// An expression that could not be parsed, covering the tokens skipped over to recover from the error.
+    Error(Span),
 }
 
 /// An expression consisting of a single identifier.
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Identifier<'a> {
-    pub value: &'a str,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Identifier {
+    pub value: String,
+    pub span: Span,
 }
 
 /// A string literal in Pebble. e.g. `"John Doe", "One does not simply walk into Mordor"`
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct TextString<'a> {
-    pub value: &'a str,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct TextString {
+    pub value: String,
+    pub span: Span,
 }
 
-/// A number literal in Pebble. e.g. `1, 3.5, 4e9, 0x03, Ob22, 007`
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Number<'a> {
-    pub value: &'a str,
-    pub span: TextSpan,
+/// A string literal with embedded expressions. e.g. `"Hello, {name}!"`
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct TemplateString {
+    pub parts: Vec<TemplatePart>,
+    pub span: Span,
+}
+
+/// A piece of an interpolated string, either raw text or an embedded expression.
+#[derive(Debug, Clone, PartialEq, Displace)]
+pub enum TemplatePart {
+    Text(TextString),
+    Expression(Expression),
+}
+
+impl Location for TemplatePart {
+    fn get_range(&self) -> Span {
+        match self {
+            Self::Text(TextString { span, .. }) => *span,
+            Self::Expression(expression) => expression.get_range(),
+        }
+    }
+}
+
+/// A number literal in Pebble. e.g. `1, 3.5, 4e-9, 0x03, 0b11, 0o07, 1_000`
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Number {
+    /// The number as written in the source.
+    pub raw: String,
+    pub value: NumericValue,
+    pub span: Span,
 }
 
 // A boolean literal, i.e. `true` or `false`
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Boolean<'a> {
-    pub value: &'a str,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Boolean {
+    pub value: String,
+    pub span: Span,
 }
 
 /// An operation that occurs on two operands e.g. `a + b`
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct BinaryExpression<'a> {
-    pub operator: &'a Operator,
-    pub operands: Vec<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct BinaryExpression {
+    pub operator: Operator,
+    pub operands: Vec<Expression>,
+    pub span: Span,
 }
 
 /// An operation expressiong a logical operation, e.g. `a || b`
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct LogicalExpression<'a> {
-    pub operator: &'a Operator,
-    pub operands: Vec<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct LogicalExpression {
+    pub operator: Operator,
+    pub operands: Vec<Expression>,
+    pub span: Span,
 }
 
 /// An operation that occurs on only one operand. e.g. `!a, ~b`
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct UnaryExpression<'a> {
-    pub operator: &'a Operator,
-    pub operand: Box<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct UnaryExpression {
+    pub operator: Operator,
+    pub operand: Box<Expression>,
+    pub span: Span,
 }
 
 /// A reference value to the current class instance.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Displace)]
 pub struct SelfExpression {
-    pub span: TextSpan,
+    pub span: Span,
 }
 
 impl Location for SelfExpression {
-    fn get_range(&self) -> TextSpan {
+    fn get_range(&self) -> Span {
         self.span
     }
 }
 
 /// A function call expression. e.g. `a(b)`.
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct CallExpression<'a> {
-    pub callee: Box<Expression<'a>>,
-    pub arguments: Vec<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct CallExpression {
+    pub callee: Box<Expression>,
+    pub arguments: Vec<Expression>,
+    pub span: Span,
 }
 
 /// A member or dot access of a class. e.g. `a.b`
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct DotExpression<'a> {
-    pub object: Box<Expression<'a>>,
-    pub property: Box<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct DotExpression {
+    pub object: Box<Expression>,
+    pub property: Box<Expression>,
+    pub span: Span,
 }
 
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct NamespaceExpression<'a> {
-    pub object: Box<Expression<'a>>,
-    pub property: Box<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct NamespaceExpression {
+    pub object: Box<Expression>,
+    pub property: Box<Expression>,
+    pub span: Span,
 }
 
 /// An expression that expresses a numeric or alphabetic range. e.g. `a..b`
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct RangeExpression<'a> {
-    pub boundaries: Vec<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct RangeExpression {
+    pub boundaries: Vec<Expression>,
+    pub span: Span,
 }
 
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct TernaryExpression<'a> {
-    pub test: Box<Expression<'a>>,
-    pub consequent: Box<Expression<'a>>,
-    pub alternate: Box<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct TernaryExpression {
+    pub test: Box<Expression>,
+    pub consequent: Box<Expression>,
+    pub alternate: Box<Expression>,
+    pub span: Span,
 }
 
 /// An array of expression. e.g. `[a, b, c]`
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct ArrayExpression<'a> {
-    pub elements: Vec<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct ArrayExpression {
+    pub elements: Vec<Expression>,
+    pub span: Span,
 }
 
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct AssignmentExpression<'a> {
-    pub operands: Vec<Expression<'a>>,
-    pub operator: &'a Operator,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct AssignmentExpression {
+    pub operands: Vec<Expression>,
+    pub operator: Operator,
+    pub span: Span,
 }
 
 /// An expression that access an index of an array. e.g `a[b]`.
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct IndexExpression<'a> {
-    pub accessor_and_property: Vec<Expression<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct IndexExpression {
+    pub accessor_and_property: Vec<Expression>,
+    pub span: Span,
 }
 
 /// A functional expression.
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct FnExpression<'a> {
-    pub labels: Option<Vec<GenericArgument<'a>>>,
-    pub parameters: Vec<Parameter<'a>>,
-    pub return_type: Option<Type<'a>>,
-    pub body: Option<Block<'a>>,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct FnExpression {
+    pub labels: Option<Vec<GenericArgument>>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<Type>,
+    pub body: Option<Block>,
     /// A functional expression may consist only of its return expression.
-    pub implicit_return: Option<Box<Expression<'a>>>,
-    pub span: TextSpan,
+    pub implicit_return: Option<Box<Expression>>,
+    pub span: Span,
 }
 
-#[derive(Location, Debug, Clone, PartialEq)]
-pub struct Character<'a> {
-    pub value: &'a str,
-    pub span: TextSpan,
+#[derive(Location, Debug, Clone, PartialEq, Displace)]
+pub struct Character {
+    pub value: String,
+    pub span: Span,
 }
 
-impl<'a> Expression<'a> {
+impl Expression {
     /// Returns `true` if the expression is [`IdentifierExpr`].
     ///
     /// [`IdentifierExpr`]: Expression::IdentifierExpr
@@ -181,6 +213,13 @@ impl<'a> Expression<'a> {
         matches!(self, Self::StringExpression(_))
     }
 
+    /// Returns `true` if the expression is [`TemplateString`].
+    ///
+    /// [`TemplateString`]: Expression::TemplateString
+    pub fn is_template_string(&self) -> bool {
+        matches!(self, Self::TemplateString(_))
+    }
+
     /// Returns `true` if the expression is [`NumericExpr`].
     ///
     /// [`NumericExpr`]: Expression::NumericExpr
@@ -204,15 +243,13 @@ impl<'a> Expression<'a> {
 
     /// Returns true if the expression is a literal.
     pub fn is_literal(&self) -> bool {
-        if let Self::StringExpression(_)
-        | Self::BooleanExpression(_)
-        | Self::NumericExpression(_)
-        | Self::CharacterExpression(_) = self
-        {
-            true
-        } else {
-            false
-        }
+        matches!(
+            self,
+            Self::StringExpression(_)
+                | Self::BooleanExpression(_)
+                | Self::NumericExpression(_)
+                | Self::CharacterExpression(_)
+        )
     }
     /// Check if an expression is a valid left hand side assignment target.
     pub fn is_valid_assignment_target(&self) -> bool {
@@ -233,30 +270,52 @@ impl<'a> Expression<'a> {
     }
 }
 
-impl<'a> Expression<'a> {
+impl Expression {
     /// Creates an identifier expression node.
-    pub fn create_ident_expr(value: &'a str, span: TextSpan) -> Self {
-        Expression::IdentifierExpression(Identifier { value, span })
+    pub fn create_ident_expr(value: &str, span: Span) -> Self {
+        Expression::IdentifierExpression(Identifier {
+            value: value.to_string(),
+            span,
+        })
     }
     /// Creates a string expression node.
-    pub fn create_str_expr(value: &'a str, span: TextSpan) -> Self {
-        Expression::StringExpression(TextString { value, span })
+    pub fn create_str_expr(value: &str, span: Span) -> Self {
+        Expression::StringExpression(TextString {
+            value: value.to_string(),
+            span,
+        })
+    }
+    /// Creates an interpolated string expression node.
+    pub fn create_template_str_expr(parts: Vec<TemplatePart>, span: Span) -> Self {
+        Expression::TemplateString(TemplateString { parts, span })
     }
-    /// Creates a numeric expression node.
-    pub fn create_num_expr(value: &'a str, span: TextSpan) -> Self {
-        Expression::NumericExpression(Number { value, span })
+    /// Creates a numeric expression node from the text of the number.
+    /// Malformed numbers are reported by the scanner, so they stand in as zero here.
+    pub fn create_num_expr(raw: &str, span: Span) -> Self {
+        let value = NumericValue::parse(raw).unwrap_or_default();
+        Expression::NumericExpression(Number {
+            raw: raw.to_string(),
+            value,
+            span,
+        })
     }
     /// Creates a boolean expression node.
-    pub fn create_bool_expr(value: &'a str, span: TextSpan) -> Self {
-        Expression::BooleanExpression(Boolean { value, span })
+    pub fn create_bool_expr(value: &str, span: Span) -> Self {
+        Expression::BooleanExpression(Boolean {
+            value: value.to_string(),
+            span,
+        })
     }
     /// Creates a character expression node.
-    pub fn create_char_expr(value: &'a str, span: TextSpan) -> Self {
-        Expression::CharacterExpression(Character { value, span })
+    pub fn create_char_expr(value: &str, span: Span) -> Self {
+        Expression::CharacterExpression(Character {
+            value: value.to_string(),
+            span,
+        })
     }
     /// Creates a dot expression.
     pub fn create_dot_expr(object: Self, property: Self) -> Self {
-        let span = [object.get_range()[0], property.get_range()[1]];
+        let span = object.get_range().to(property.get_range());
         Expression::DotExpression(DotExpression {
             object: Box::new(object),
             property: Box::new(property),
@@ -265,7 +324,7 @@ impl<'a> Expression<'a> {
     }
     /// Creates a namespace expression.
     pub fn create_namespace_expr(object: Self, property: Self) -> Self {
-        let span = [object.get_range()[0], property.get_range()[1]];
+        let span = object.get_range().to(property.get_range());
         Expression::NamespaceExpression(NamespaceExpression {
             object: Box::new(object),
             property: Box::new(property),
@@ -273,8 +332,8 @@ impl<'a> Expression<'a> {
         })
     }
     /// Creates a binary expression.
-    pub fn create_bin_expr(left: Self, operator: &'a Operator, right: Self) -> Self {
-        let span = [left.get_range()[0], right.get_range()[1]];
+    pub fn create_bin_expr(left: Self, operator: Operator, right: Self) -> Self {
+        let span = left.get_range().to(right.get_range());
         Expression::BinaryExpression(BinaryExpression {
             operator,
             operands: vec![left, right],
@@ -282,25 +341,34 @@ impl<'a> Expression<'a> {
         })
     }
     /// Creates a call expression.
-    pub fn create_call_expr(callee: Self, arguments: Vec<Self>, end: [u64; 2]) -> Self {
-        let start = callee.get_range()[0];
+    pub fn create_call_expr(callee: Self, arguments: Vec<Self>, end: usize) -> Self {
+        let span = Span {
+            end,
+            ..callee.get_range()
+        };
         Expression::CallExpression(CallExpression {
             callee: Box::new(callee),
             arguments,
-            span: [start, end],
+            span,
         })
     }
     /// Creates a index expression.
-    pub fn create_index_expr(accessor: Self, property: Self, end: [u64; 2]) -> Self {
-        let span = [accessor.get_range()[0], end];
+    pub fn create_index_expr(accessor: Self, property: Self, end: usize) -> Self {
+        let span = Span {
+            end,
+            ..accessor.get_range()
+        };
         Expression::IndexExpression(IndexExpression {
             accessor_and_property: vec![accessor, property],
             span,
         })
     }
     /// Creates a unary expression.
-    pub fn create_unary_expr(start: [u64; 2], operator: &'a Operator, operand: Self) -> Self {
-        let span = [start, operand.get_range()[1]];
+    pub fn create_unary_expr(start: usize, operator: Operator, operand: Self) -> Self {
+        let span = Span {
+            start,
+            ..operand.get_range()
+        };
         Expression::UnaryExpression(UnaryExpression {
             operator,
             operand: Box::new(operand),
@@ -309,15 +377,15 @@ impl<'a> Expression<'a> {
     }
     /// Creates a range expression.
     pub fn create_range_expr(top: Self, bottom: Self) -> Self {
-        let span = [top.get_range()[0], bottom.get_range()[1]];
+        let span = top.get_range().to(bottom.get_range());
         Expression::RangeExpression(RangeExpression {
             boundaries: vec![top, bottom],
             span,
         })
     }
     /// Creates a logical expression.
-    pub fn create_logical_expr(left: Self, operator: &'a Operator, right: Self) -> Self {
-        let span = [left.get_range()[0], right.get_range()[1]];
+    pub fn create_logical_expr(left: Self, operator: Operator, right: Self) -> Self {
+        let span = left.get_range().to(right.get_range());
         Expression::LogicalExpression(LogicalExpression {
             operator,
             operands: vec![left, right],
@@ -326,7 +394,7 @@ impl<'a> Expression<'a> {
     }
     /// Creates a ternary expression.
     pub fn create_ternary_expr(test: Self, consequent: Self, alternate: Self) -> Self {
-        let span = [test.get_range()[0], alternate.get_range()[1]];
+        let span = test.get_range().to(alternate.get_range());
         Expression::TernaryExpression(TernaryExpression {
             test: Box::new(test),
             consequent: Box::new(consequent),
@@ -335,8 +403,8 @@ impl<'a> Expression<'a> {
         })
     }
     /// Creates an assignment expression.
-    pub fn create_assign_expr(left: Self, operator: &'a Operator, right: Self) -> Self {
-        let span = [left.get_range()[0], right.get_range()[1]];
+    pub fn create_assign_expr(left: Self, operator: Operator, right: Self) -> Self {
+        let span = left.get_range().to(right.get_range());
         Expression::AssignmentExpression(AssignmentExpression {
             operator,
             operands: vec![left, right],
@@ -345,11 +413,12 @@ impl<'a> Expression<'a> {
     }
 }
 
-impl Location for Expression<'_> {
-    fn get_range(&self) -> TextSpan {
+impl Location for Expression {
+    fn get_range(&self) -> Span {
         match self {
             Self::IdentifierExpression(Identifier { span, .. })
             | Self::StringExpression(TextString { span, .. })
+            | Self::TemplateString(TemplateString { span, .. })
             | Self::NumericExpression(Number { span, .. })
             | Self::BooleanExpression(Boolean { span, .. })
             | Self::CharacterExpression(Character { span, .. })
@@ -365,7 +434,8 @@ impl Location for Expression<'_> {
             | Self::RangeExpression(RangeExpression { span, .. })
             | Self::TernaryExpression(TernaryExpression { span, .. })
             | Self::AssignmentExpression(AssignmentExpression { span, .. })
-            | Self::FnExpression(FnExpression { span, .. }) => *span,
+            | Self::FnExpression(FnExpression { span, .. })
+            | Self::Error(span) => *span,
         }
     }
 }
@@ -6,7 +6,10 @@ pub struct TokenIdentifier {
 #[derive(Debug, PartialEq, Clone)]
 pub struct Literal {
     pub kind: LiteralKind,
+    /// The value of the literal. For strings and characters, this is the decoded text with escape sequences replaced.
     pub value: String,
+    /// The text of the literal exactly as it appears in the source, excluding its delimiters.
+    pub raw: String,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -15,4 +18,31 @@ pub enum LiteralKind {
     NumericLiteral,
     BooleanLiteral,
     CharacterLiteral,
+    /// The text of an interpolated string before its first embedded expression, e.g. `"Hello, {`
+    TemplateHead,
+    /// The text of an interpolated string between two embedded expressions, e.g. `} and {`
+    TemplateMiddle,
+    /// The text of an interpolated string after its last embedded expression, e.g. `}!"`
+    TemplateTail,
+    /// A string whose body is kept exactly as written, e.g. `r"C:\Users"` or `r#"He said "hi""#`
+    RawStringLiteral,
+    /// A string spanning several lines between triple quotes, with its shared indentation stripped.
+    MultiLineStringLiteral,
+}
+
+impl LiteralKind {
+    /// Returns `true` if the literal is a complete string, whatever form it was written in.
+    pub fn is_string(&self) -> bool {
+        matches!(
+            self,
+            Self::StringLiteral | Self::RawStringLiteral | Self::MultiLineStringLiteral
+        )
+    }
+    /// Returns `true` if the literal is a segment of an interpolated string.
+    pub fn is_template_segment(&self) -> bool {
+        matches!(
+            self,
+            Self::TemplateHead | Self::TemplateMiddle | Self::TemplateTail
+        )
+    }
 }
@@ -1,10 +1,9 @@
-pub const KEYWORDS: &'static [&'static str; 21] = &[
+pub const KEYWORDS: &[&str; 20] = &[
     "if",
     "else",
     "for",
     "fn",
     "in",
-    "of",
     "loop",
     "from",
     "as",
@@ -21,7 +20,7 @@ pub const KEYWORDS: &'static [&'static str; 21] = &[
     "static",
     "readonly",
 ];
-pub const INJUNCTIONS: &'static [&'static str; 15] = &[
+pub const INJUNCTIONS: &[&str; 15] = &[
     "implement",
     "interface",
     "class",
@@ -81,3 +80,23 @@ pub enum Injunction {
     /// An unrecognized injunction.
     Unknown(String),
 }
+
+impl Keyword {
+    /// Returns `true` if the keyword can begin a statement or a declaration, which makes it a point where parsing can resume after an error.
+    pub fn begins_statement(&self) -> bool {
+        matches!(
+            self,
+            Keyword::If
+                | Keyword::For
+                | Keyword::While
+                | Keyword::Loop
+                | Keyword::Break
+                | Keyword::Continue
+                | Keyword::Return
+                | Keyword::Crash
+                | Keyword::Try
+                | Keyword::Println
+                | Keyword::Injunction(_)
+        )
+    }
+}
@@ -1,11 +1,15 @@
 #![allow(dead_code)]
 
+mod arena;
 mod comment;
 mod control;
 mod declarations;
+mod displace;
 mod expression;
 mod identifier;
 mod keyword;
+mod lower;
+mod number;
 mod operator;
 mod punctuation;
 mod statement;
@@ -14,12 +18,15 @@ mod traits;
 mod types;
 mod visitor;
 
+pub use arena::*;
 pub use comment::*;
 pub use control::*;
 pub use declarations::*;
+pub use displace::*;
 pub use expression::*;
 pub use identifier::*;
 pub use keyword::*;
+pub use number::*;
 pub use operator::*;
 pub use punctuation::*;
 pub use statement::*;
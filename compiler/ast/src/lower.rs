@@ -0,0 +1,474 @@
+use crate::{
+    Arena, ArrayExpression, AssignmentExpression, Attribute, BinaryExpression, Block, Boolean,
+    CallExpression, Character, Class, ConcreteType, CrashStatement, DotExpression, DotType, Enum,
+    Expression, ExpressionStatement, FnExpression, ForLoop, Function, FunctionType,
+    GenericArgument, Identifier, IfStatement, Implement, Import, IndexExpression, Interface,
+    Location, LogicalExpression, Loop, Mapping, Method, Module, NamespaceExpression, NodeId,
+    NodeKind, Number, Parameter, PrependStatement, PrintLnStatement, Property, PublicModifier,
+    RangeExpression, Record, RecoverBlock, ReturnStatement, Statement, TemplatePart,
+    TemplateString, TernaryExpression, TestBlock, TextString, TryBlock, Type, TypeAlias,
+    UnaryExpression, UseImport, VariableDeclaration, Variant, WhileStatement,
+};
+
+/// Lowering from the syntax tree.
+impl Arena {
+    /// Copies a parsed list of statements into a new arena, with the statements as its roots.
+    pub fn from_statements(statements: &[Statement]) -> Self {
+        let mut arena = Arena::new();
+        for statement in statements {
+            let id = arena.statement(statement);
+            arena.add_root(id);
+        }
+        arena
+    }
+    fn identifier(&mut self, identifier: &Identifier) -> NodeId {
+        let symbol = self.interner.intern(&identifier.value);
+        self.alloc(NodeKind::Identifier(symbol), identifier.span)
+    }
+    fn string(&mut self, string: &TextString) -> NodeId {
+        let symbol = self.interner.intern(&string.value);
+        self.alloc(NodeKind::String(symbol), string.span)
+    }
+    fn expressions(&mut self, expressions: &[Expression]) -> Vec<NodeId> {
+        expressions
+            .iter()
+            .map(|expression| self.expression(expression))
+            .collect()
+    }
+    fn optional_expression(&mut self, expression: &Option<Expression>) -> Option<NodeId> {
+        expression
+            .as_ref()
+            .map(|expression| self.expression(expression))
+    }
+    pub fn expression(&mut self, expression: &Expression) -> NodeId {
+        let kind = match expression {
+            Expression::IdentifierExpression(identifier) => return self.identifier(identifier),
+            Expression::StringExpression(string) => return self.string(string),
+            Expression::TemplateString(TemplateString { parts, .. }) => NodeKind::TemplateString {
+                parts: parts
+                    .iter()
+                    .map(|part| match part {
+                        TemplatePart::Text(text) => self.string(text),
+                        TemplatePart::Expression(expression) => self.expression(expression),
+                    })
+                    .collect(),
+            },
+            Expression::NumericExpression(Number { raw, value, .. }) => NodeKind::Number {
+                raw: self.interner.intern(raw),
+                value: *value,
+            },
+            Expression::BooleanExpression(Boolean { value, .. }) => {
+                NodeKind::Boolean(*value == "true")
+            }
+            Expression::CharacterExpression(Character { value, .. }) => {
+                NodeKind::Character(self.interner.intern(value))
+            }
+            Expression::SelfExpression(_) => NodeKind::SelfExpression,
+            Expression::BinaryExpression(BinaryExpression {
+                operator, operands, ..
+            }) => NodeKind::Binary {
+                operator: (*operator).clone(),
+                left: self.expression(&operands[0]),
+                right: self.expression(&operands[1]),
+            },
+            Expression::LogicalExpression(LogicalExpression {
+                operator, operands, ..
+            }) => NodeKind::Logical {
+                operator: (*operator).clone(),
+                left: self.expression(&operands[0]),
+                right: self.expression(&operands[1]),
+            },
+            Expression::UnaryExpression(UnaryExpression {
+                operator, operand, ..
+            }) => NodeKind::Unary {
+                operator: (*operator).clone(),
+                operand: self.expression(operand),
+            },
+            Expression::CallExpression(CallExpression {
+                callee, arguments, ..
+            }) => NodeKind::Call {
+                callee: self.expression(callee),
+                arguments: self.expressions(arguments),
+            },
+            Expression::ArrayExpression(ArrayExpression { elements, .. }) => NodeKind::Array {
+                elements: self.expressions(elements),
+            },
+            Expression::IndexExpression(IndexExpression {
+                accessor_and_property,
+                ..
+            }) => NodeKind::Index {
+                accessor: self.expression(&accessor_and_property[0]),
+                property: self.expression(&accessor_and_property[1]),
+            },
+            Expression::DotExpression(DotExpression {
+                object, property, ..
+            }) => NodeKind::Dot {
+                object: self.expression(object),
+                property: self.expression(property),
+            },
+            Expression::NamespaceExpression(NamespaceExpression {
+                object, property, ..
+            }) => NodeKind::Namespace {
+                object: self.expression(object),
+                property: self.expression(property),
+            },
+            Expression::RangeExpression(RangeExpression { boundaries, .. }) => NodeKind::Range {
+                start: self.expression(&boundaries[0]),
+                end: self.expression(&boundaries[1]),
+            },
+            Expression::TernaryExpression(TernaryExpression {
+                test,
+                consequent,
+                alternate,
+                ..
+            }) => NodeKind::Ternary {
+                test: self.expression(test),
+                consequent: self.expression(consequent),
+                alternate: self.expression(alternate),
+            },
+            Expression::AssignmentExpression(AssignmentExpression {
+                operands, operator, ..
+            }) => NodeKind::Assignment {
+                operator: (*operator).clone(),
+                target: self.expression(&operands[0]),
+                value: self.expression(&operands[1]),
+            },
+            Expression::FnExpression(FnExpression {
+                labels,
+                parameters,
+                return_type,
+                body,
+                implicit_return,
+                ..
+            }) => NodeKind::FnExpression {
+                generic_arguments: self.generic_arguments(labels),
+                parameters: self.parameters(parameters),
+                return_type: self.optional_type(return_type),
+                body: body.as_ref().map(|body| self.block(body)),
+                implicit_return: implicit_return
+                    .as_ref()
+                    .map(|expression| self.expression(expression)),
+            },
+            Expression::Error(_) => NodeKind::ErrorExpression,
+        };
+        self.alloc(kind, expression.get_range())
+    }
+    fn block(&mut self, block: &Block) -> NodeId {
+        let statements = block
+            .body
+            .iter()
+            .map(|statement| self.statement(statement))
+            .collect();
+        self.alloc(NodeKind::Block { statements }, block.span)
+    }
+    pub fn statement(&mut self, statement: &Statement) -> NodeId {
+        let kind = match statement {
+            Statement::IfStatement(IfStatement {
+                test,
+                body,
+                alternate,
+                ..
+            }) => NodeKind::If {
+                test: self.expression(test),
+                body: self.statement(body),
+                alternate: alternate
+                    .as_ref()
+                    .map(|statement| self.statement(statement)),
+            },
+            Statement::PrintLnStatement(PrintLnStatement { argument, .. }) => NodeKind::PrintLn {
+                argument: self.expression(argument),
+            },
+            Statement::PrependStatement(PrependStatement { source, .. }) => NodeKind::Prepend {
+                source: self.expression(source),
+            },
+            Statement::VariableDeclaration(VariableDeclaration {
+                name,
+                kind,
+                initializer,
+                type_label,
+                ..
+            }) => NodeKind::VariableDeclaration {
+                name: self.identifier(name),
+                kind: kind.clone(),
+                type_label: self.optional_type(type_label),
+                initializer: self.optional_expression(initializer),
+            },
+            Statement::Break(_) => NodeKind::Break,
+            Statement::Continue(_) => NodeKind::Continue,
+            Statement::TestBlock(TestBlock { body, .. }) => NodeKind::TestBlock {
+                body: self.block(body),
+            },
+            Statement::LoopStmnt(Loop {
+                constraint, body, ..
+            }) => NodeKind::Loop {
+                constraint: self.optional_expression(constraint),
+                body: self.block(body),
+            },
+            Statement::ForLoop(ForLoop {
+                item,
+                iterator,
+                body,
+                ..
+            }) => NodeKind::ForLoop {
+                item: self.identifier(item),
+                iterator: self.expression(iterator),
+                body: self.statement(body),
+            },
+            Statement::WhileLoop(WhileStatement { test, body, .. }) => NodeKind::While {
+                test: self.expression(test),
+                body: self.statement(body),
+            },
+            Statement::PublicModifier(PublicModifier { statement, .. }) => NodeKind::Public {
+                statement: self.statement(statement),
+            },
+            Statement::ExpressionStatement(ExpressionStatement { expression, .. }) => {
+                NodeKind::ExpressionStatement {
+                    expression: self.expression(expression),
+                }
+            }
+            Statement::BlockStatement(block) => return self.block(block),
+            Statement::UseImport(UseImport {
+                imports, source, ..
+            }) => NodeKind::UseImport {
+                imports: imports.iter().map(|import| self.import(import)).collect(),
+                source: self.string(source),
+            },
+            Statement::ReturnStatement(ReturnStatement { argument, .. }) => NodeKind::Return {
+                argument: self.optional_expression(argument),
+            },
+            Statement::CrashStmnt(CrashStatement { argument, .. }) => NodeKind::Crash {
+                argument: self.expression(argument),
+            },
+            Statement::EmptyStatement(_) => NodeKind::Empty,
+            Statement::TryBlock(TryBlock { body, recover, .. }) => NodeKind::TryBlock {
+                body: self.block(body),
+                recover: recover.as_ref().map(|recover| self.recover_block(recover)),
+            },
+            Statement::Function(Function {
+                name,
+                generic_arguments,
+                parameters,
+                return_type,
+                body,
+                ..
+            }) => NodeKind::Function {
+                name: self.identifier(name),
+                generic_arguments: self.generic_arguments(generic_arguments),
+                parameters: self.parameters(parameters),
+                return_type: self.optional_type(return_type),
+                body: self.block(body),
+            },
+            Statement::TypeAlias(TypeAlias {
+                name,
+                generic_arguments,
+                value,
+                ..
+            }) => NodeKind::TypeAlias {
+                name: self.identifier(name),
+                generic_arguments: self.generic_arguments(generic_arguments),
+                value: self.type_(value),
+            },
+            Statement::Interface(Interface {
+                name,
+                generic_arguments,
+                properties,
+                ..
+            }) => NodeKind::Interface {
+                name: self.identifier(name),
+                generic_arguments: self.generic_arguments(generic_arguments),
+                properties: self.properties(properties),
+            },
+            Statement::Enum(Enum {
+                name,
+                generic_arguments,
+                variants,
+                ..
+            }) => NodeKind::Enum {
+                name: self.identifier(name),
+                generic_arguments: self.generic_arguments(generic_arguments),
+                variants: variants
+                    .iter()
+                    .map(|variant| self.variant(variant))
+                    .collect(),
+            },
+            Statement::Class(Class {
+                name,
+                generic_arguments,
+                properties,
+                ..
+            }) => NodeKind::Class {
+                name: self.identifier(name),
+                generic_arguments: self.generic_arguments(generic_arguments),
+                properties: self.properties(properties),
+            },
+            Statement::Module(Module { name, body, .. }) => NodeKind::Module {
+                name: self.identifier(name),
+                body: self.block(body),
+            },
+            Statement::Record(Record { name, mappings, .. }) => NodeKind::Record {
+                name: self.identifier(name),
+                mappings: mappings
+                    .iter()
+                    .map(|mapping| self.mapping(mapping))
+                    .collect(),
+            },
+            Statement::Error(_) => NodeKind::ErrorStatement,
+        };
+        let id = self.alloc(kind, statement.get_range());
+        // The docs of a public declaration belong to the declaration, not to the modifier.
+        if !matches!(statement, Statement::PublicModifier(_)) {
+            self.lower_docs(id, statement.docs());
+        }
+        id
+    }
+    fn lower_docs(&mut self, id: NodeId, docs: Option<&str>) {
+        if let Some(docs) = docs {
+            self.set_docs(id, docs);
+        }
+    }
+    fn import(&mut self, import: &Import) -> NodeId {
+        let kind = NodeKind::Import {
+            imported_name: self.identifier(&import.imported_name),
+            collapsed_import: import.collapsed_import,
+            local_name: import.local_name.as_ref().map(|name| self.identifier(name)),
+        };
+        self.alloc(kind, import.span)
+    }
+    fn recover_block(&mut self, recover: &RecoverBlock) -> NodeId {
+        let kind = NodeKind::RecoverBlock {
+            parameters: self.parameters(&recover.params),
+            body: self.block(&recover.body),
+        };
+        self.alloc(kind, recover.span)
+    }
+    fn generic_arguments(&mut self, arguments: &Option<Vec<GenericArgument>>) -> Vec<NodeId> {
+        let Some(arguments) = arguments else {
+            return vec![];
+        };
+        arguments
+            .iter()
+            .map(|argument| {
+                let kind = NodeKind::GenericArgument {
+                    name: self.identifier(&argument.name),
+                    implements: argument
+                        .implements
+                        .iter()
+                        .flatten()
+                        .map(|interface| self.identifier(interface))
+                        .collect(),
+                };
+                self.alloc(kind, argument.span)
+            })
+            .collect()
+    }
+    fn parameters(&mut self, parameters: &[Parameter]) -> Vec<NodeId> {
+        parameters
+            .iter()
+            .map(|parameter| {
+                let kind = NodeKind::Parameter {
+                    name: self.identifier(&parameter.name),
+                    label: self.optional_type(&parameter.label),
+                };
+                self.alloc(kind, parameter.span)
+            })
+            .collect()
+    }
+    fn properties(&mut self, properties: &[Property]) -> Vec<NodeId> {
+        properties
+            .iter()
+            .map(|property| self.property(property))
+            .collect()
+    }
+    fn property(&mut self, property: &Property) -> NodeId {
+        let kind = match property {
+            Property::Method(Method {
+                name,
+                generic_arguments,
+                is_static,
+                parameters,
+                return_type,
+                body,
+                ..
+            }) => NodeKind::Method {
+                name: self.identifier(name),
+                generic_arguments: self.generic_arguments(generic_arguments),
+                is_static: *is_static,
+                parameters: self.parameters(parameters),
+                return_type: self.optional_type(return_type),
+                body: self.block(body),
+            },
+            Property::Attribute(Attribute {
+                key,
+                type_label,
+                is_static,
+                is_readonly,
+                value,
+                ..
+            }) => NodeKind::Attribute {
+                key: self.identifier(key),
+                type_label: self.optional_type(type_label),
+                is_static: *is_static,
+                is_readonly: *is_readonly,
+                value: self.optional_expression(value),
+            },
+            Property::Implement(Implement { interface, .. }) => NodeKind::Implement {
+                interface: self.identifier(interface),
+            },
+        };
+        let id = self.alloc(kind, property.get_range());
+        self.lower_docs(id, property.docs());
+        id
+    }
+    fn variant(&mut self, variant: &Variant) -> NodeId {
+        let kind = match variant {
+            Variant::Tuple { name, elements, .. } => NodeKind::Variant {
+                name: self.identifier(name),
+                elements: elements.iter().map(|element| self.type_(element)).collect(),
+            },
+            Variant::Concrete { name, .. } => NodeKind::Variant {
+                name: self.identifier(name),
+                elements: vec![],
+            },
+        };
+        let id = self.alloc(kind, variant.get_range());
+        self.lower_docs(id, variant.docs());
+        id
+    }
+    fn mapping(&mut self, mapping: &Mapping) -> NodeId {
+        let kind = NodeKind::Mapping {
+            key: self.expression(&mapping.key),
+            value: self.expression(&mapping.value),
+        };
+        self.alloc(kind, mapping.span)
+    }
+    fn optional_type(&mut self, type_: &Option<Type>) -> Option<NodeId> {
+        type_.as_ref().map(|type_| self.type_(type_))
+    }
+    fn type_(&mut self, type_: &Type) -> NodeId {
+        let kind = match type_ {
+            Type::Concrete(ConcreteType {
+                name, arguments, ..
+            }) => NodeKind::ConcreteType {
+                name: self.identifier(name),
+                arguments: arguments
+                    .iter()
+                    .map(|argument| self.type_(argument))
+                    .collect(),
+            },
+            Type::Function(FunctionType {
+                parameters,
+                return_type,
+                generic_arguments,
+                ..
+            }) => NodeKind::FunctionType {
+                generic_arguments: self.generic_arguments(generic_arguments),
+                parameters: self.parameters(parameters),
+                return_type: self.type_(return_type),
+            },
+            Type::Dot(DotType { levels, .. }) => NodeKind::DotType {
+                levels: levels.iter().map(|level| self.type_(level)).collect(),
+            },
+        };
+        self.alloc(kind, type_.get_range())
+    }
+}
@@ -0,0 +1,55 @@
+use errors::LexicalError;
+use std::num::IntErrorKind;
+
+/// The value of a numeric literal, following the `UnsignedInt` and `Float` classes that make up a `Number`.<br>
+/// Literals have no sign, since the minus sign in `-1` is a unary operator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumericValue {
+    /// A whole number with no sign, e.g. `42`, `0xFF` or `1_000_000`.
+    UnsignedInt(u64),
+    /// A number with a fractional part or an exponent, e.g. `2.5` or `1e-5`.
+    Float(f64),
+}
+
+impl Default for NumericValue {
+    fn default() -> Self {
+        Self::UnsignedInt(0)
+    }
+}
+
+impl NumericValue {
+    /// Parses the text of a numeric literal, as scanned, into its value.<br>
+    /// Digit separators are ignored. A number with a decimal point or an exponent is a float, and every other number is an unsigned integer.
+    pub fn parse(raw: &str) -> Result<Self, LexicalError> {
+        let text = raw.replace('_', "");
+        let (radix, digits) = match text.get(..2) {
+            Some("0x") => (16, &text[2..]),
+            Some("0b") => (2, &text[2..]),
+            Some("0o") => (8, &text[2..]),
+            _ => (10, text.as_str()),
+        };
+        if radix == 10 && digits.contains(['.', 'e', 'E']) {
+            return match digits.parse::<f64>() {
+                Ok(value) if value.is_infinite() => Err(LexicalError::NumberOutOfRange),
+                Ok(value) => Ok(Self::Float(value)),
+                Err(_) => Err(LexicalError::MissingExponent),
+            };
+        }
+        match u64::from_str_radix(digits, radix) {
+            Ok(value) => Ok(Self::UnsignedInt(value)),
+            Err(error) if *error.kind() == IntErrorKind::PosOverflow => {
+                Err(LexicalError::NumberOutOfRange)
+            }
+            Err(_) if radix != 10 => Err(LexicalError::MissingDigits(text[..2].to_string())),
+            Err(_) => Err(LexicalError::UnknownToken(raw.to_string())),
+        }
+    }
+    /// Returns `true` if the value is a whole number.
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Self::UnsignedInt(_))
+    }
+    /// Returns `true` if the value is a float.
+    pub fn is_float(&self) -> bool {
+        matches!(self, Self::Float(_))
+    }
+}
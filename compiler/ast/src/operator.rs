@@ -1,182 +1,309 @@
 use std::fmt::Display;
 
+use Associativity::{Left, Right};
+use OperatorKind::*;
+
 /// All valid Operators in Pebble.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operator {
-    Add,                 // a + b
-    Multiply,            // a * b
-    Subtract,            // a - b
-    Divide,              // a / b
-    Remainder,           // a % b
-    PowerOf,             // a ** b
-    LogicalAnd,          // a && b
-    LogicalOr,           // a || b
-    LogicalNot,          // !a
-    BitwiseOr,           // a | b
-    BitwiseAnd,          // a & b
-    BitWiseNot,          // ~a
-    BitwiseLeftShift,    // a << b
-    BitwiseRightShift,   // a >> b
-    RangeBetween,        // a..b
-    Assign,              // a = b
-    AddAssign,           // a += b
-    SubtractAssign,      // a -= b
-    DivideAssign,        // a /= b
-    MultiplyAssign,      // a *= b
-    LogicalAndAssign,    // a &&= b
-    LogicalOrAssign,     // a ||= b
-    Equals,              // a == b
-    NotEquals,           // a != b
-    GreaterThan,         // a > b
-    LessThan,            // a < b
-    GreaterThanOrEquals, // a >= b
-    LessThanOrEquals,    // a <= b
-    Confirm,             // a ? b
-    Colon,               // a:b
-    RestOf,              // ...a
-    Namespace,           // a::b
-    Dot,                 // a.b
-    Arrow,               // a => b
-    Returns,             // a -> b
-    Increment,           // a++
-    Decrement,           // a--
-    Call,                // a(b)
-    Index,               // a[b]
-    Temp,                // A pseudo operator.
+    Add,                     // a + b
+    Multiply,                // a * b
+    Subtract,                // a - b
+    Divide,                  // a / b
+    Remainder,               // a % b
+    PowerOf,                 // a ** b
+    LogicalAnd,              // a && b
+    LogicalOr,               // a || b
+    LogicalNot,              // !a
+    BitwiseOr,               // a | b
+    BitwiseAnd,              // a & b
+    BitWiseNot,              // ~a
+    BitwiseLeftShift,        // a << b
+    BitwiseRightShift,       // a >> b
+    RangeBetween,            // a..b
+    Assign,                  // a = b
+    AddAssign,               // a += b
+    SubtractAssign,          // a -= b
+    DivideAssign,            // a /= b
+    MultiplyAssign,          // a *= b
+    RemainderAssign,         // a %= b
+    PowerOfAssign,           // a **= b
+    LogicalAndAssign,        // a &&= b
+    LogicalOrAssign,         // a ||= b
+    BitwiseAndAssign,        // a &= b
+    BitwiseOrAssign,         // a |= b
+    BitwiseLeftShiftAssign,  // a <<= b
+    BitwiseRightShiftAssign, // a >>= b
+    Equals,                  // a == b
+    NotEquals,               // a != b
+    GreaterThan,             // a > b
+    LessThan,                // a < b
+    GreaterThanOrEquals,     // a >= b
+    LessThanOrEquals,        // a <= b
+    Confirm,                 // a ? b
+    Colon,                   // a:b
+    RestOf,                  // ...a
+    Namespace,               // a::b
+    Dot,                     // a.b
+    Arrow,                   // a => b
+    Returns,                 // a -> b
+    Increment,               // ++a
+    Decrement,               // --a
+    Call,                    // a(b)
+    Index,                   // a[b]
+}
+
+/// The role an operator plays in an expression, which decides the node it produces.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OperatorKind {
+    /// An operator that combines two operands into a new value, e.g. `a + b` or `a == b`.
+    Binary,
+    /// A short-circuiting operator, e.g. `a && b`.
+    Logical,
+    /// An operator that stores a value in its left operand, e.g. `a = b` or `a += b`.
+    Assignment,
+    /// An operator that accesses a member of its left operand, e.g. `a.b` or `a::b`.
+    Member,
+    /// The range operator, i.e. `a..b`.
+    Range,
+    /// The ternary operator, i.e. `a ? b : c`.
+    Conditional,
+    /// An operator that can only be placed before its operand, e.g. `!a`.
+    Prefix,
+    /// An operation formed by brackets after its operand, i.e. `a(b)` and `a[b]`.
+    Postfix,
+    /// A symbol that separates the parts of a larger construct, rather than operating on values, e.g. `:` or `->`.
+    Punctuator,
 }
 
-// The operator representations. This array is sorted in descending order by the length of the operators.
-pub const OPERATORS: &'static [&'static str; 36] = &[
-    "...", "&&=", "||=", "*=", "/=", "+=", "-=", "==", "!=", ">=", "<=", "=>", "->", "++", "--",
-    "..", "::", "||", "&&", "<<", "^", "*", "/", "+", "-", "%", "&", "|", "!", "~", ".", "?", ":",
-    ">", "<", "=",
+/// The side that groups first when operators of the same precedence follow each other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a = b = c` is `a = (b = c)`.
+    Right,
+}
+
+/// A row in the operator table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorInfo {
+    pub symbol: &'static str,
+    pub operator: Operator,
+    pub kind: OperatorKind,
+    /// How tightly the operator binds to its operands. Operators with higher values are grouped first.
+    pub precedence: u8,
+    pub associativity: Associativity,
+    /// Whether the operator can also be placed before a single operand, e.g. `-a`.
+    pub prefix: bool,
+}
+
+const fn row(
+    symbol: &'static str,
+    operator: Operator,
+    kind: OperatorKind,
+    precedence: u8,
+    associativity: Associativity,
+    prefix: bool,
+) -> OperatorInfo {
+    OperatorInfo {
+        symbol,
+        operator,
+        kind,
+        precedence,
+        associativity,
+        prefix,
+    }
+}
+
+/// The operator table, which is the single description of every operator used in scanning, parsing and printing.<br>
+/// It is sorted in descending order by the length of the symbols, so that the scanner always picks the longest operator that matches.
+/// The first row for an operator holds its canonical symbol. `^` remains an alternative spelling of `**`.
+pub const OPERATORS: &[OperatorInfo] = &[
+    row("...", Operator::RestOf, Punctuator, 0, Left, false),
+    row(
+        "&&=",
+        Operator::LogicalAndAssign,
+        Assignment,
+        1,
+        Right,
+        false,
+    ),
+    row(
+        "||=",
+        Operator::LogicalOrAssign,
+        Assignment,
+        1,
+        Right,
+        false,
+    ),
+    row("**=", Operator::PowerOfAssign, Assignment, 1, Right, false),
+    row(
+        "<<=",
+        Operator::BitwiseLeftShiftAssign,
+        Assignment,
+        1,
+        Right,
+        false,
+    ),
+    row(
+        ">>=",
+        Operator::BitwiseRightShiftAssign,
+        Assignment,
+        1,
+        Right,
+        false,
+    ),
+    row("*=", Operator::MultiplyAssign, Assignment, 1, Right, false),
+    row("/=", Operator::DivideAssign, Assignment, 1, Right, false),
+    row("+=", Operator::AddAssign, Assignment, 1, Right, false),
+    row("-=", Operator::SubtractAssign, Assignment, 1, Right, false),
+    row("%=", Operator::RemainderAssign, Assignment, 1, Right, false),
+    row(
+        "&=",
+        Operator::BitwiseAndAssign,
+        Assignment,
+        1,
+        Right,
+        false,
+    ),
+    row("|=", Operator::BitwiseOrAssign, Assignment, 1, Right, false),
+    row("==", Operator::Equals, Binary, 7, Left, false),
+    row("!=", Operator::NotEquals, Binary, 7, Left, false),
+    row(">=", Operator::GreaterThanOrEquals, Binary, 8, Left, false),
+    row("<=", Operator::LessThanOrEquals, Binary, 8, Left, false),
+    row("=>", Operator::Arrow, Punctuator, 0, Left, false),
+    row("->", Operator::Returns, Punctuator, 0, Left, false),
+    row(
+        "++",
+        Operator::Increment,
+        Prefix,
+        PREFIX_PRECEDENCE,
+        Right,
+        true,
+    ),
+    row(
+        "--",
+        Operator::Decrement,
+        Prefix,
+        PREFIX_PRECEDENCE,
+        Right,
+        true,
+    ),
+    row("..", Operator::RangeBetween, Range, 9, Left, false),
+    row("::", Operator::Namespace, Member, 17, Left, false),
+    row("||", Operator::LogicalOr, Logical, 3, Left, false),
+    row("&&", Operator::LogicalAnd, Logical, 4, Left, false),
+    row("<<", Operator::BitwiseLeftShift, Binary, 10, Left, false),
+    row(">>", Operator::BitwiseRightShift, Binary, 10, Left, false),
+    row("**", Operator::PowerOf, Binary, 14, Right, false),
+    row("^", Operator::PowerOf, Binary, 14, Right, false),
+    row("*", Operator::Multiply, Binary, 12, Left, false),
+    row("/", Operator::Divide, Binary, 12, Left, false),
+    row("+", Operator::Add, Binary, 11, Left, true),
+    row("-", Operator::Subtract, Binary, 11, Left, true),
+    row("%", Operator::Remainder, Binary, 12, Left, false),
+    row("&", Operator::BitwiseAnd, Binary, 6, Left, false),
+    row("|", Operator::BitwiseOr, Binary, 5, Left, false),
+    row(
+        "!",
+        Operator::LogicalNot,
+        Prefix,
+        PREFIX_PRECEDENCE,
+        Right,
+        true,
+    ),
+    row(
+        "~",
+        Operator::BitWiseNot,
+        Prefix,
+        PREFIX_PRECEDENCE,
+        Right,
+        true,
+    ),
+    row(".", Operator::Dot, Member, 18, Left, false),
+    row("?", Operator::Confirm, Conditional, 2, Right, false),
+    row(":", Operator::Colon, Punctuator, 0, Right, false),
+    row(">", Operator::GreaterThan, Binary, 8, Left, false),
+    row("<", Operator::LessThan, Binary, 8, Left, false),
+    row("=", Operator::Assign, Assignment, 1, Right, false),
+    row("()", Operator::Call, Postfix, 15, Left, false),
+    row("[]", Operator::Index, Postfix, 16, Left, false),
 ];
 
-pub fn precedence_of(operator: &Operator) -> i32 {
-    match operator {
-        Operator::Dot => 20,
-        Operator::Namespace => 19,
-        Operator::Index => 18,
-        Operator::Call => 17,
-        Operator::RangeBetween => 16,
-        Operator::LogicalNot | Operator::BitWiseNot => 15,
-        Operator::PowerOf => 14,
-        Operator::Multiply | Operator::Remainder | Operator::Divide => 13,
-        Operator::Add | Operator::Subtract => 12,
-        Operator::BitwiseLeftShift | Operator::BitwiseRightShift => 11,
-        Operator::LessThan
-        | Operator::GreaterThan
-        | Operator::LessThanOrEquals
-        | Operator::GreaterThanOrEquals => 10,
-        Operator::Equals | Operator::NotEquals => 11,
-        Operator::BitwiseAnd => 10,
-        Operator::BitwiseOr => 9,
-        Operator::LogicalAnd => 8,
-        Operator::LogicalOr => 7,
-        Operator::Confirm => 6,
-        Operator::Colon => 5,
-        Operator::Assign
-        | Operator::AddAssign
-        | Operator::DivideAssign
-        | Operator::MultiplyAssign
-        | Operator::SubtractAssign
-        | Operator::LogicalOrAssign
-        | Operator::LogicalAndAssign => 4,
-        Operator::Temp => 0,
-        _ => todo!(),
+impl OperatorInfo {
+    /// Returns `true` if the operator is scanned as a token of its own, rather than formed by brackets.
+    pub fn is_token(&self) -> bool {
+        self.kind != Postfix
     }
 }
 
+/// The precedence of every operator placed before its operand, e.g. `-a` or `!a`.
+/// Prefixes bind more tightly than any binary operator except `**`, so `-a * b` is `(-a) * b` but `-a ** b` is `-(a ** b)`.
+pub const PREFIX_PRECEDENCE: u8 = 13;
+
+pub fn precedence_of(operator: &Operator) -> u8 {
+    operator.info().precedence
+}
+
 impl Operator {
     pub fn from(op: &str) -> Self {
-        match op {
-            "+" => Operator::Add,
-            "*" => Operator::Multiply,
-            "-" => Operator::Subtract,
-            "/" => Operator::Divide,
-            "%" => Operator::Remainder,
-            "^" => Operator::PowerOf,
-            "||" => Operator::LogicalOr,
-            "&&" => Operator::LogicalAnd,
-            "!" => Operator::LogicalNot,
-            "|" => Operator::BitwiseOr,
-            "&" => Operator::BitwiseAnd,
-            "~" => Operator::BitWiseNot,
-            "<<" => Operator::BitwiseLeftShift,
-            ".." => Operator::RangeBetween,
-            "=" => Operator::Assign,
-            "+=" => Operator::AddAssign,
-            "-=" => Operator::SubtractAssign,
-            "/=" => Operator::DivideAssign,
-            "*=" => Operator::MultiplyAssign,
-            "||=" => Operator::LogicalAndAssign,
-            "==" => Operator::Equals,
-            "!=" => Operator::NotEquals,
-            ">" => Operator::GreaterThan,
-            "<" => Operator::LessThan,
-            ">=" => Operator::GreaterThanOrEquals,
-            "<=" => Operator::LessThanOrEquals,
-            "?" => Operator::Confirm,
-            ":" => Operator::Colon,
-            "..." => Operator::RestOf,
-            "::" => Operator::Namespace,
-            "." => Operator::Dot,
-            "=>" => Operator::Arrow,
-            "->" => Operator::Returns,
-            "++" => Operator::Increment,
-            "--" => Operator::Decrement,
-            _ => unreachable!(),
+        match OPERATORS
+            .iter()
+            .find(|info| info.is_token() && info.symbol == op)
+        {
+            Some(info) => info.operator.clone(),
+            None => unreachable!(),
+        }
+    }
+    /// Returns the row of the operator table that describes this operator.
+    pub fn info(&self) -> &'static OperatorInfo {
+        match OPERATORS.iter().find(|info| &info.operator == self) {
+            Some(info) => info,
+            None => unreachable!(),
+        }
+    }
+    pub fn kind(&self) -> OperatorKind {
+        self.info().kind
+    }
+    pub fn associativity(&self) -> Associativity {
+        self.info().associativity
+    }
+    /// Returns how tightly the operator binds to the operands on its left and its right, when it follows an operand.<br>
+    /// An operator takes the expression before it as its left operand only if its left power is at least the power that expression is being parsed with.
+    /// A right-associative operator binds a little more loosely on its right, so that the next operator of the same precedence claims the operand instead.
+    /// Returns `None` for operators that never follow an operand, such as `!` or `->`.
+    pub fn infix_binding_power(&self) -> Option<(u8, u8)> {
+        let info = self.info();
+        if matches!(info.kind, Prefix | Punctuator) {
+            return None;
+        }
+        let power = info.precedence * 2;
+        match info.associativity {
+            Left => Some((power, power + 1)),
+            Right => Some((power + 1, power)),
+        }
+    }
+    /// Returns how tightly a prefix operator binds to its operand.
+    pub fn prefix_binding_power() -> u8 {
+        PREFIX_PRECEDENCE * 2 + 1
+    }
+    /// Returns `true` if the operator can be placed before a single operand.
+    pub fn is_prefix(&self) -> bool {
+        self.info().prefix
+    }
+    /// Returns the operation performed by a compound assignment before storing its result, e.g. `+` for `+=`.
+    pub fn compound_operator(&self) -> Option<Operator> {
+        let info = self.info();
+        match (info.kind, info.symbol.strip_suffix('=')) {
+            (Assignment, Some(symbol)) if !symbol.is_empty() => Some(Operator::from(symbol)),
+            _ => None,
         }
     }
 }
 
 impl Display for Operator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Operator::Add => "+",
-                Operator::Multiply => "*",
-                Operator::Subtract => "-",
-                Operator::Divide => "/",
-                Operator::Remainder => "*",
-                Operator::PowerOf => "^",
-                Operator::LogicalAnd => "&&",
-                Operator::LogicalOr => "||",
-                Operator::LogicalNot => "!",
-                Operator::BitwiseOr => "|",
-                Operator::BitwiseAnd => "&",
-                Operator::BitWiseNot => "~",
-                Operator::BitwiseLeftShift => "<<",
-                Operator::BitwiseRightShift => ">>",
-                Operator::RangeBetween => "..",
-                Operator::Assign => "=",
-                Operator::AddAssign => "+=",
-                Operator::SubtractAssign => "-=",
-                Operator::DivideAssign => "/=",
-                Operator::MultiplyAssign => "*=",
-                Operator::LogicalAndAssign => "&&=",
-                Operator::LogicalOrAssign => "||=",
-                Operator::Equals => "==",
-                Operator::NotEquals => "!=",
-                Operator::GreaterThan => ">",
-                Operator::LessThan => "<",
-                Operator::GreaterThanOrEquals => ">=",
-                Operator::LessThanOrEquals => "<=",
-                Operator::Confirm => "?",
-                Operator::Colon => ":",
-                Operator::RestOf => "...",
-                Operator::Namespace => "::",
-                Operator::Dot => ".",
-                Operator::Arrow => "=>",
-                Operator::Returns => "->",
-                Operator::Increment => "++",
-                Operator::Decrement => "--",
-                Operator::Call => "()",
-                Operator::Index => "[]",
-                Operator::Temp => "0",
-            }
-        )
+        write!(f, "{}", self.info().symbol)
     }
 }
@@ -15,3 +15,13 @@ pub enum BracketKind {
     LeftSquare,       // [
     RightSquare,      // ]
 }
+
+impl BracketKind {
+    /// Returns `true` for `{`, `(` and `[`.
+    pub fn is_opening(&self) -> bool {
+        matches!(
+            self,
+            BracketKind::LeftCurly | BracketKind::LeftParenthesis | BracketKind::LeftSquare
+        )
+    }
+}
@@ -1,63 +1,84 @@
+use macros::Displace;
+use utils::Span;
+
 use crate::{
-    Block, Break, Class, Continue, CrashStatement, Enum, Expression, ExpressionStatement, ForLoop,
-    Function, IfStatement, Interface, Location, Loop, Module, PrependStatement, PrintLnStatement,
-    PublicModifier, Record, ReturnStatement, TestBlock, TextSpan, TryBlock, TypeAlias, UseImport,
-    VariableDeclaration, WhileStatement,
+    Block, Break, Class, Continue, CrashStatement, Displace, Displacement, Enum, Expression,
+    ExpressionStatement, ForLoop, Function, IfStatement, Interface, Location, Loop, Module,
+    PrependStatement, PrintLnStatement, PublicModifier, Record, ReturnStatement, TestBlock,
+    TryBlock, TypeAlias, UseImport, VariableDeclaration, WhileStatement,
 };
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum Statement<'a> {
-    IfStatement(IfStatement<'a>),
-    PrintLnStatement(PrintLnStatement<'a>),
-    PrependStatement(PrependStatement<'a>),
-    VariableDeclaration(VariableDeclaration<'a>),
+#[derive(Debug, Clone, PartialEq, Displace)]
+pub enum Statement {
+    IfStatement(IfStatement),
+    PrintLnStatement(PrintLnStatement),
+    PrependStatement(PrependStatement),
+    VariableDeclaration(VariableDeclaration),
     Break(Break),
-    Continue(Continue<'a>),
-    TestBlock(TestBlock<'a>),
-    LoopStmnt(Loop<'a>),
-    ForLoop(ForLoop<'a>),
-    WhileLoop(WhileStatement<'a>),
-    PublicModifier(PublicModifier<'a>),
-    ExpressionStatement(ExpressionStatement<'a>),
-    BlockStatement(Block<'a>),
-    UseImport(UseImport<'a>),
-    ReturnStatement(ReturnStatement<'a>),
-    CrashStmnt(CrashStatement<'a>),
-    EmptyStatement(TextSpan),
-    TryBlock(TryBlock<'a>),
-    Function(Function<'a>),
-    TypeAlias(TypeAlias<'a>),
-    Interface(Interface<'a>),
-    Enum(Enum<'a>),
-    Class(Class<'a>),
-    Module(Module<'a>),
-    Record(Record<'a>),
+    Continue(Continue),
+    TestBlock(TestBlock),
+    LoopStmnt(Loop),
+    ForLoop(ForLoop),
+    WhileLoop(WhileStatement),
+    PublicModifier(PublicModifier),
+    ExpressionStatement(ExpressionStatement),
+    BlockStatement(Block),
+    UseImport(UseImport),
+    ReturnStatement(ReturnStatement),
+    CrashStmnt(CrashStatement),
+    EmptyStatement(Span),
+    TryBlock(TryBlock),
+    Function(Function),
+    TypeAlias(TypeAlias),
+    Interface(Interface),
+    Enum(Enum),
+    Class(Class),
+    Module(Module),
+    Record(Record),
+    /// A statement that could not be parsed, covering the tokens skipped over to recover from the error.
+    Error(Span),
 }
 
-impl<'a> Statement<'a> {
-    pub fn create_expr_stmnt(expression: Expression<'a>) -> Self {
+impl Statement {
+    pub fn create_expr_stmnt(expression: Expression) -> Self {
         let span = expression.get_range();
         Statement::ExpressionStatement(ExpressionStatement { expression, span })
     }
     pub fn is_declaration(&self) -> bool {
-        match self {
+        matches!(
+            self,
             Statement::PrependStatement(_)
-            | Statement::VariableDeclaration(_)
-            | Statement::TestBlock(_)
-            | Statement::PublicModifier(_)
-            | Statement::UseImport(_)
-            | Statement::Function(_)
-            | Statement::TypeAlias(_)
-            | Statement::Interface(_)
-            | Statement::Class(_)
-            | Statement::Module(_) => true,
-            _ => false,
+                | Statement::VariableDeclaration(_)
+                | Statement::TestBlock(_)
+                | Statement::PublicModifier(_)
+                | Statement::UseImport(_)
+                | Statement::Function(_)
+                | Statement::TypeAlias(_)
+                | Statement::Interface(_)
+                | Statement::Class(_)
+                | Statement::Module(_)
+        )
+    }
+    /// Returns the doc comments written before a declaration, joined into one text.
+    /// A public declaration returns the docs of the declaration it makes public.
+    pub fn docs(&self) -> Option<&str> {
+        match self {
+            Self::VariableDeclaration(VariableDeclaration { docs, .. })
+            | Self::Function(Function { docs, .. })
+            | Self::TypeAlias(TypeAlias { docs, .. })
+            | Self::Module(Module { docs, .. })
+            | Self::Interface(Interface { docs, .. })
+            | Self::Class(Class { docs, .. })
+            | Self::Record(Record { docs, .. })
+            | Self::Enum(Enum { docs, .. }) => docs.as_deref(),
+            Self::PublicModifier(PublicModifier { statement, .. }) => statement.docs(),
+            _ => None,
         }
     }
 }
 
-impl<'a> Location for Statement<'a> {
-    fn get_range(&self) -> TextSpan {
+impl Location for Statement {
+    fn get_range(&self) -> Span {
         match self {
             Self::IfStatement(IfStatement { span, .. })
             | Self::WhileLoop(WhileStatement { span, .. })
@@ -80,6 +101,7 @@ impl<'a> Location for Statement<'a> {
             | Self::TypeAlias(TypeAlias { span, .. })
             | Self::Module(Module { span, .. })
             | Self::EmptyStatement(span)
+            | Self::Error(span)
             | Self::Interface(Interface { span, .. })
             | Self::Class(Class { span, .. })
             | Self::Record(Record { span, .. })
@@ -1,7 +1,8 @@
+use utils::Span;
+
 use crate::{
     identifier::{Literal, LiteralKind},
-    BracketKind, Comment, CommentKind, Injunction, Keyword, Operator, Punctuation, TextSpan,
-    TokenIdentifier,
+    BracketKind, Comment, CommentKind, Injunction, Keyword, Operator, Punctuation, TokenIdentifier,
 };
 
 #[derive(Debug, PartialEq, Clone)]
@@ -19,12 +20,12 @@ pub enum TokenKind {
 /// A piece of code collected when scanning the input source file.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token {
-    pub span: TextSpan,
+    pub span: Span,
     pub kind: TokenKind,
 }
 
 impl Token {
-    pub fn create_line_comment(content: String, span: TextSpan) -> Self {
+    pub fn create_line_comment(content: String, span: Span) -> Self {
         Token {
             kind: TokenKind::Comment(Comment {
                 kind: CommentKind::Line,
@@ -33,7 +34,7 @@ impl Token {
             span,
         }
     }
-    pub fn create_block_comment(content: String, span: TextSpan) -> Self {
+    pub fn create_block_comment(content: String, span: Span) -> Self {
         Token {
             kind: TokenKind::Comment(Comment {
                 kind: CommentKind::Block,
@@ -42,7 +43,7 @@ impl Token {
             span,
         }
     }
-    pub fn create_doc_comment(content: String, span: TextSpan) -> Self {
+    pub fn create_doc_comment(content: String, span: Span) -> Self {
         Token {
             span,
             kind: TokenKind::Comment(Comment {
@@ -138,22 +139,37 @@ impl Token {
             }
         )
     }
-    pub fn create_literal(literal_type: &str, value: String, span: TextSpan) -> Self {
+    pub fn create_literal(literal_type: &str, value: String, span: Span) -> Self {
+        Token::create_decoded_literal(literal_type, value.clone(), value, span)
+    }
+    /// Creates a literal whose value differs from its source text, e.g. a string with escape sequences.
+    pub fn create_decoded_literal(
+        literal_type: &str,
+        raw: String,
+        value: String,
+        span: Span,
+    ) -> Self {
         Token {
             span,
             kind: TokenKind::Literal(Literal {
                 value,
+                raw,
                 kind: match literal_type {
                     "string" => LiteralKind::StringLiteral,
                     "boolean" => LiteralKind::BooleanLiteral,
                     "number" => LiteralKind::NumericLiteral,
                     "character" => LiteralKind::CharacterLiteral,
+                    "template head" => LiteralKind::TemplateHead,
+                    "template middle" => LiteralKind::TemplateMiddle,
+                    "template tail" => LiteralKind::TemplateTail,
+                    "raw string" => LiteralKind::RawStringLiteral,
+                    "multi-line string" => LiteralKind::MultiLineStringLiteral,
                     _ => unreachable!(),
                 },
             }),
         }
     }
-    pub fn create_injunction(value: &str, span: TextSpan) -> Self {
+    pub fn create_injunction(value: &str, span: Span) -> Self {
         Token {
             span,
             kind: TokenKind::Keyword(Keyword::Injunction(match value {
@@ -175,7 +191,7 @@ impl Token {
             })),
         }
     }
-    pub fn create_bracket(value: &char, span: TextSpan) -> Self {
+    pub fn create_bracket(value: &char, span: Span) -> Self {
         Token {
             span,
             kind: TokenKind::Punctuation(Punctuation::Bracket(match value {
@@ -189,25 +205,25 @@ impl Token {
             })),
         }
     }
-    pub fn create_semi_colon(span: TextSpan) -> Self {
+    pub fn create_semi_colon(span: Span) -> Self {
         Token {
             span,
             kind: TokenKind::Punctuation(Punctuation::SemiColon),
         }
     }
-    pub fn create_comma(span: TextSpan) -> Self {
+    pub fn create_comma(span: Span) -> Self {
         Token {
             span,
             kind: TokenKind::Punctuation(Punctuation::Comma),
         }
     }
-    pub fn create_identifier(value: String, span: TextSpan) -> Self {
+    pub fn create_identifier(value: String, span: Span) -> Self {
         Token {
             span,
             kind: TokenKind::Identifier(TokenIdentifier { value }),
         }
     }
-    pub fn create_keyword(value: String, span: TextSpan) -> Self {
+    pub fn create_keyword(value: String, span: Span) -> Self {
         Token {
             span,
             kind: TokenKind::Keyword(match value.as_str() {
@@ -235,19 +251,19 @@ impl Token {
             }),
         }
     }
-    pub fn create_operator(value: &str, span: TextSpan) -> Self {
+    pub fn create_operator(value: &str, span: Span) -> Self {
         Token {
             span,
             kind: TokenKind::Operator(Operator::from(value)),
         }
     }
-    pub fn create_unknown(value: String, span: TextSpan) -> Self {
+    pub fn create_unknown(value: String, span: Span) -> Self {
         Token {
             span,
             kind: TokenKind::Invalid(value),
         }
     }
-    pub fn eof(span: TextSpan) -> Self {
+    pub fn eof(span: Span) -> Self {
         Token {
             span,
             kind: TokenKind::EOF,
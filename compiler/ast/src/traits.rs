@@ -1,4 +1,5 @@
-pub type TextSpan = [[u64; 2]; 2];
+use utils::Span;
+
 pub trait Location {
-    fn get_range(&self) -> TextSpan;
+    fn get_range(&self) -> Span;
 }
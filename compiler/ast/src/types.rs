@@ -1,15 +1,16 @@
-use macros::Location;
+use macros::{Displace, Location};
+use utils::Span;
 
-use crate::{GenericArgument, Identifier, Location, Parameter, TextSpan};
+use crate::{Displace, Displacement, GenericArgument, Identifier, Location, Parameter};
 
-#[derive(Clone, Debug, PartialEq)]
-pub enum Type<'a> {
-    Concrete(ConcreteType<'a>),
-    Function(FunctionType<'a>),
-    Dot(DotType<'a>),
+#[derive(Clone, Debug, PartialEq, Displace)]
+pub enum Type {
+    Concrete(ConcreteType),
+    Function(FunctionType),
+    Dot(DotType),
 }
 
-impl Type<'_> {
+impl Type {
     /// Returns `true` if the type is [`Dot`].
     ///
     /// [`Dot`]: Type::Dot
@@ -32,9 +33,9 @@ impl Type<'_> {
     }
 }
 
-impl<'a> Type<'a> {
+impl Type {
     pub fn create_dot_type(object: Self, property: Self) -> Self {
-        let span = [object.get_range()[0], property.get_range()[1]];
+        let span = object.get_range().to(property.get_range());
         Type::Dot(DotType {
             levels: vec![object, property],
             span,
@@ -42,8 +43,8 @@ impl<'a> Type<'a> {
     }
 }
 
-impl Location for Type<'_> {
-    fn get_range(&self) -> TextSpan {
+impl Location for Type {
+    fn get_range(&self) -> Span {
         match self {
             Type::Concrete(ConcreteType { span, .. })
             | Type::Function(FunctionType { span, .. })
@@ -53,25 +54,25 @@ impl Location for Type<'_> {
 }
 
 /// Types that imply classes or enums. e.g. `a: String` or `b: Stack<UnsignedInt>`
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct ConcreteType<'a> {
-    pub name: Identifier<'a>,
-    pub arguments: Vec<Type<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct ConcreteType {
+    pub name: Identifier,
+    pub arguments: Vec<Type>,
+    pub span: Span,
 }
 
 /// Types that imply functions. e.g. `a: () -> Nil` or `b: <T>(a: T) -> T`
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct FunctionType<'a> {
-    pub parameters: Vec<Parameter<'a>>,
-    pub return_type: Box<Type<'a>>,
-    pub generic_arguments: Option<Vec<GenericArgument<'a>>>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct FunctionType {
+    pub parameters: Vec<Parameter>,
+    pub return_type: Box<Type>,
+    pub generic_arguments: Option<Vec<GenericArgument>>,
+    pub span: Span,
 }
 
 /// Types that are children of external modules or files. e.g. `a: core.prelude.String`
-#[derive(Location, Clone, Debug, PartialEq)]
-pub struct DotType<'a> {
-    pub levels: Vec<Type<'a>>,
-    pub span: TextSpan,
+#[derive(Location, Clone, Debug, PartialEq, Displace)]
+pub struct DotType {
+    pub levels: Vec<Type>,
+    pub span: Span,
 }
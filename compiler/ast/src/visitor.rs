@@ -5,18 +5,20 @@ use crate::{
     GenericArgument, Identifier, IfStatement, Implement, IndexExpression, Interface,
     LogicalExpression, Loop, Mapping, Method, Module, NamespaceExpression, Number, Parameter,
     PrependStatement, PrintLnStatement, Property, PublicModifier, RangeExpression, Record,
-    ReturnStatement, SelfExpression, Statement, TernaryExpression, TestBlock, TextString, TryBlock,
-    Type, TypeAlias, UnaryExpression, UseImport, VariableDeclaration, Variant, WhileStatement,
+    ReturnStatement, SelfExpression, Statement, TemplatePart, TemplateString, TernaryExpression,
+    TestBlock, TextString, TryBlock, Type, TypeAlias, UnaryExpression, UseImport,
+    VariableDeclaration, Variant, WhileStatement,
 };
 
 /// Recursively visits every node in the Abstract syntax tree using the visitor pattern.
-pub trait ASTVisitor<'a> {
+pub trait ASTVisitor {
     type Output: Default;
 
-    fn visit_expression(&'a self, exp: &Expression<'a>) -> Self::Output {
+    fn visit_expression(&self, exp: &Expression) -> Self::Output {
         match exp {
             Expression::IdentifierExpression(identifier) => self.visit_identifier(identifier),
             Expression::StringExpression(string) => self.visit_string(string),
+            Expression::TemplateString(template) => self.visit_template_string(template),
             Expression::NumericExpression(number) => self.visit_number(number),
             Expression::BooleanExpression(boolean) => self.visit_boolean(boolean),
             Expression::CharacterExpression(character) => self.visit_character(character),
@@ -55,39 +57,44 @@ pub trait ASTVisitor<'a> {
                 self.visit_assignment_expression(assignment_expression)
             }
             Expression::FnExpression(fn_expression) => self.visit_fn_expression(fn_expression),
+            Expression::Error(_) => Default::default(),
         }
     }
-    fn visit_identifier(&'a self, ident: &Identifier<'a>) -> Self::Output;
-    fn visit_string(&'a self, string: &TextString<'a>) -> Self::Output;
-    fn visit_number(&'a self, number: &Number<'a>) -> Self::Output;
-    fn visit_character(&'a self, charac: &Character<'a>) -> Self::Output;
-    fn visit_boolean(&'a self, boolean: &Boolean<'a>) -> Self::Output;
-    fn visit_self_expression(&'a self, self_: &SelfExpression) -> Self::Output;
-    fn visit_binary_expression(&'a self, bin_exp: &BinaryExpression<'a>) -> Self::Output {
+    fn visit_identifier(&self, ident: &Identifier) -> Self::Output;
+    fn visit_string(&self, string: &TextString) -> Self::Output;
+    fn visit_template_string(&self, template: &TemplateString) -> Self::Output {
+        for part in &template.parts {
+            if let TemplatePart::Expression(expression) = part {
+                self.visit_expression(expression);
+            }
+        }
+        Default::default()
+    }
+    fn visit_number(&self, number: &Number) -> Self::Output;
+    fn visit_character(&self, charac: &Character) -> Self::Output;
+    fn visit_boolean(&self, boolean: &Boolean) -> Self::Output;
+    fn visit_self_expression(&self, self_: &SelfExpression) -> Self::Output;
+    fn visit_binary_expression(&self, bin_exp: &BinaryExpression) -> Self::Output {
         self.visit_expression(&bin_exp.operands[0]);
         self.visit_expression(&bin_exp.operands[1]);
         Default::default()
     }
-    fn visit_logical_expression(&'a self, log_exp: &LogicalExpression<'a>) -> Self::Output {
+    fn visit_logical_expression(&self, log_exp: &LogicalExpression) -> Self::Output {
         self.visit_expression(&log_exp.operands[0]);
         self.visit_expression(&log_exp.operands[1]);
         Default::default()
     }
-    fn visit_dot_expression(&'a self, dot_exp: &DotExpression<'a>) -> Self::Output;
-    fn visit_unary_expression(&'a self, unary_exp: &UnaryExpression<'a>) -> Self::Output;
-    fn visit_namespace_expression(
-        &'a self,
-        namespace_exp: &NamespaceExpression<'a>,
-    ) -> Self::Output;
-    fn visit_assignment_expression(&'a self, assign_exp: &AssignmentExpression<'a>)
-        -> Self::Output;
-    fn visit_index_expression(&'a self, index_exp: &IndexExpression<'a>) -> Self::Output;
-    fn visit_call_expression(&'a self, call_exp: &CallExpression<'a>) -> Self::Output;
-    fn visit_array_expression(&'a self, array_exp: &ArrayExpression<'a>) -> Self::Output;
-    fn visit_ternary_expression(&'a self, tern_exp: &TernaryExpression<'a>) -> Self::Output;
-    fn visit_range_expression(&'a self, rang_exp: &RangeExpression<'a>) -> Self::Output;
-    fn visit_fn_expression(&'a self, fn_exp: &FnExpression<'a>) -> Self::Output;
-    fn visit_statement(&'a self, statement: &Statement<'a>) {
+    fn visit_dot_expression(&self, dot_exp: &DotExpression) -> Self::Output;
+    fn visit_unary_expression(&self, unary_exp: &UnaryExpression) -> Self::Output;
+    fn visit_namespace_expression(&self, namespace_exp: &NamespaceExpression) -> Self::Output;
+    fn visit_assignment_expression(&self, assign_exp: &AssignmentExpression) -> Self::Output;
+    fn visit_index_expression(&self, index_exp: &IndexExpression) -> Self::Output;
+    fn visit_call_expression(&self, call_exp: &CallExpression) -> Self::Output;
+    fn visit_array_expression(&self, array_exp: &ArrayExpression) -> Self::Output;
+    fn visit_ternary_expression(&self, tern_exp: &TernaryExpression) -> Self::Output;
+    fn visit_range_expression(&self, rang_exp: &RangeExpression) -> Self::Output;
+    fn visit_fn_expression(&self, fn_exp: &FnExpression) -> Self::Output;
+    fn visit_statement(&self, statement: &Statement) {
         match statement {
             Statement::IfStatement(if_stmnt) => self.visit_if_statement(if_stmnt),
             Statement::PrintLnStatement(println_stmnt) => {
@@ -110,6 +117,7 @@ pub trait ASTVisitor<'a> {
             Statement::ReturnStatement(return_stmnt) => self.visit_return_statement(return_stmnt),
             Statement::CrashStmnt(crash) => self.visit_crash(crash),
             Statement::EmptyStatement(_) => self.visit_empty_statement(statement),
+            Statement::Error(_) => self.visit_error_statement(statement),
             Statement::TryBlock(try_block) => self.visit_try_block(try_block),
             Statement::Function(function) => self.visit_function(function),
             Statement::TypeAlias(type_alias) => self.visit_type_alias(type_alias),
@@ -120,45 +128,46 @@ pub trait ASTVisitor<'a> {
             Statement::Record(record) => self.visit_record_declaration(record),
         }
     }
-    fn visit_if_statement(&'a self, if_stmnt: &IfStatement<'a>);
-    fn visit_println_statement(&'a self, println_stmnt: &PrintLnStatement<'a>) {
+    fn visit_if_statement(&self, if_stmnt: &IfStatement);
+    fn visit_println_statement(&self, println_stmnt: &PrintLnStatement) {
         self.visit_expression(&println_stmnt.argument);
     }
-    fn visit_prepend_statement(&'a self, prepend_stmnt: &PrependStatement<'a>);
-    fn visit_variable_declaration(&'a self, var_decl: &VariableDeclaration<'a>);
-    fn visit_break(&'a self, break_: &Break);
-    fn visit_continue(&'a self, continue_: &Continue<'a>);
-    fn visit_test_block(&'a self, test_block: &TestBlock<'a>);
-    fn visit_loop_statement(&'a self, loop_stmnt: &Loop<'a>);
-    fn visit_for_loop(&'a self, for_loop: &ForLoop<'a>);
-    fn visit_while_loop(&'a self, while_stmnt: &WhileStatement<'a>);
-    fn visit_public_statement(&'a self, public_mod: &PublicModifier<'a>);
-    fn visit_expression_statement(&'a self, exp_stmnt: &ExpressionStatement<'a>) {
+    fn visit_prepend_statement(&self, prepend_stmnt: &PrependStatement);
+    fn visit_variable_declaration(&self, var_decl: &VariableDeclaration);
+    fn visit_break(&self, break_: &Break);
+    fn visit_continue(&self, continue_: &Continue);
+    fn visit_test_block(&self, test_block: &TestBlock);
+    fn visit_loop_statement(&self, loop_stmnt: &Loop);
+    fn visit_for_loop(&self, for_loop: &ForLoop);
+    fn visit_while_loop(&self, while_stmnt: &WhileStatement);
+    fn visit_public_statement(&self, public_mod: &PublicModifier);
+    fn visit_expression_statement(&self, exp_stmnt: &ExpressionStatement) {
         self.visit_expression(&exp_stmnt.expression);
     }
-    fn visit_block(&'a self, block: &Block<'a>);
-    fn visit_use_import(&'a self, use_stmnt: &UseImport<'a>);
-    fn visit_return_statement(&'a self, return_stmnt: &ReturnStatement<'a>);
-    fn visit_crash(&'a self, crash: &CrashStatement<'a>);
-    fn visit_empty_statement(&'a self, _empty: &Statement<'a>) {}
-    fn visit_try_block(&'a self, try_block: &TryBlock<'a>);
-    fn visit_function(&'a self, function: &Function<'a>);
-    fn visit_module(&'a self, module: &Module<'a>);
-    fn visit_enum_declaration(&'a self, enum_: &Enum<'a>);
-    fn visit_record_declaration(&'a self, record: &Record<'a>);
-    fn visit_class_declaration(&'a self, class: &Class<'a>);
-    fn visit_property(&'a self, property: &Property<'a>) -> Self::Output;
-    fn visit_method(&'a self, method: &Method<'a>) -> Self::Output;
-    fn visit_constructor(&'a self, method: &Method<'a>) -> Self::Output;
-    fn visit_attribute(&'a self, attrib: &Attribute<'a>) -> Self::Output;
-    fn visit_implement(&'a self, implement: &Implement<'a>) -> Self::Output;
-    fn visit_record_mapping(&'a self, map: &Mapping<'a>);
-    fn visit_variant(&'a self, variant: &Variant<'a>);
-    fn visit_parameter(&'a self, param: &Parameter<'a>) -> Self::Output;
-    fn visit_type_alias(&'a self, type_alias: &TypeAlias<'a>);
-    fn visit_type_label(&'a self, label: &Type<'a>) -> Self::Output;
-    fn visit_concrete_type(&'a self, concrete_type: &ConcreteType<'a>) -> Self::Output;
-    fn visit_functional_type(&'a self, functional_type: &FunctionType<'a>) -> Self::Output;
-    fn visit_interface(&'a self, interface: &Interface<'a>);
-    fn visit_generic_argument(&'a self, argument: &GenericArgument);
+    fn visit_block(&self, block: &Block);
+    fn visit_use_import(&self, use_stmnt: &UseImport);
+    fn visit_return_statement(&self, return_stmnt: &ReturnStatement);
+    fn visit_crash(&self, crash: &CrashStatement);
+    fn visit_empty_statement(&self, _empty: &Statement) {}
+    fn visit_error_statement(&self, _error: &Statement) {}
+    fn visit_try_block(&self, try_block: &TryBlock);
+    fn visit_function(&self, function: &Function);
+    fn visit_module(&self, module: &Module);
+    fn visit_enum_declaration(&self, enum_: &Enum);
+    fn visit_record_declaration(&self, record: &Record);
+    fn visit_class_declaration(&self, class: &Class);
+    fn visit_property(&self, property: &Property) -> Self::Output;
+    fn visit_method(&self, method: &Method) -> Self::Output;
+    fn visit_constructor(&self, method: &Method) -> Self::Output;
+    fn visit_attribute(&self, attrib: &Attribute) -> Self::Output;
+    fn visit_implement(&self, implement: &Implement) -> Self::Output;
+    fn visit_record_mapping(&self, map: &Mapping);
+    fn visit_variant(&self, variant: &Variant);
+    fn visit_parameter(&self, param: &Parameter) -> Self::Output;
+    fn visit_type_alias(&self, type_alias: &TypeAlias);
+    fn visit_type_label(&self, label: &Type) -> Self::Output;
+    fn visit_concrete_type(&self, concrete_type: &ConcreteType) -> Self::Output;
+    fn visit_functional_type(&self, functional_type: &FunctionType) -> Self::Output;
+    fn visit_interface(&self, interface: &Interface);
+    fn visit_generic_argument(&self, argument: &GenericArgument);
 }
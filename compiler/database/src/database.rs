@@ -0,0 +1,293 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use ast::Arena;
+use errors::ResolverError;
+use parser::{parse_in, Diagnostic, Program};
+use resolver::{relative_to, resolve, BindingKind, Resolution, Workspace};
+use utils::{FileId, SourceFile, SourceMap, TextEdit};
+
+use crate::{
+    query::{Memo, Table},
+    Query, Revision,
+};
+
+/// A name declared at the top of a file, which other files can import if it is public.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Export {
+    pub name: String,
+    pub kind: BindingKind,
+    pub is_public: bool,
+}
+
+/// The source files of a compilation, and the answers to the queries asked about them.<br>
+/// A query is computed the first time it is asked, and remembers the queries it read. After a file changes, only the answers that read something that changed are computed again,
+/// and an answer that comes out the same as before does not cause the answers computed from it to be computed again.
+#[derive(Default)]
+pub struct Database {
+    sources: SourceMap,
+    revision: Revision,
+    /// The revision in which the text of each file last changed.
+    changed: HashMap<FileId, Revision>,
+    /// The revision in which the last file was added.
+    added: Revision,
+    /// The edits made to each file since it was last parsed, which are applied to its old program rather than parsing all of it again.<br>
+    /// It is `None` for a file whose whole text was replaced.
+    edits: RefCell<HashMap<FileId, Option<Vec<TextEdit>>>>,
+    parses: Table<Program>,
+    resolutions: Table<Resolution>,
+    exports: Table<Vec<Export>>,
+    checks: Table<Vec<Diagnostic>>,
+    arenas: Table<Arena>,
+    /// The queries being computed, innermost last, each with the queries it has read so far.
+    active: RefCell<Vec<(Query, Vec<Query>)>>,
+    /// Every query that has been computed, in the order they finished.
+    pub(crate) computed: RefCell<Vec<Query>>,
+}
+
+impl Database {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets the text of the file with a name, adding the file if there is none, and returns its ID.<br>
+    /// Setting a file to the text it already has changes nothing, so no query is computed again.
+    pub fn set_source(&mut self, name: &str, text: impl Into<String>) -> FileId {
+        let text = text.into();
+        let existing = self
+            .sources
+            .find(name)
+            .map(|file| (file.id, file.text == text));
+        if let Some((file, true)) = existing {
+            return file;
+        }
+        self.revision += 1;
+        let file = match existing {
+            Some((file, _)) => {
+                self.sources.update(file, text);
+                file
+            }
+            None => {
+                self.added = self.revision;
+                self.sources.add(name, text)
+            }
+        };
+        self.changed.insert(file, self.revision);
+        self.edits.get_mut().insert(file, None);
+        file
+    }
+    /// Replaces part of the text of a file. The next time the file is parsed, only the statements around the edits made since are parsed again.
+    pub fn edit(&mut self, edit: &TextEdit) {
+        if edit.span.is_empty() && edit.text.is_empty() {
+            return;
+        }
+        let file = edit.span.file;
+        let text = self.sources.get(file).apply(std::slice::from_ref(edit));
+        self.sources.update(file, text);
+        self.revision += 1;
+        self.changed.insert(file, self.revision);
+        if let Some(Some(edits)) = self.edits.get_mut().get_mut(&file) {
+            edits.push(edit.clone());
+        }
+    }
+    /// Returns the source files.<br>
+    /// Reading them this way is not remembered by the query being computed, so it is meant for reporting answers, e.g. to turn spans into lines and columns.
+    pub fn sources(&self) -> &SourceMap {
+        &self.sources
+    }
+    pub fn source(&self, file: FileId) -> &SourceFile {
+        self.record(Query::Source(file));
+        self.sources.get(file)
+    }
+    /// Returns the file with a name, if it is in the database.
+    pub fn file(&self, name: &str) -> Option<FileId> {
+        self.record(Query::Files);
+        self.sources.find(name).map(|file| file.id)
+    }
+    /// Scans and parses a file.
+    pub fn parse(&self, file: FileId) -> Rc<Program> {
+        self.fetch(Query::Parse(file), file, &self.parses)
+    }
+    /// Resolves the names used in a file to their declarations.
+    pub fn resolve(&self, file: FileId) -> Rc<Resolution> {
+        self.fetch(Query::Resolve(file), file, &self.resolutions)
+    }
+    /// Returns the names declared at the top of a file. Files that import from the file only read these, so they are not checked again when the rest of it changes.
+    pub fn exports(&self, file: FileId) -> Rc<Vec<Export>> {
+        self.fetch(Query::Exports(file), file, &self.exports)
+    }
+    /// Returns the problems in a file, in the order they appear in the text: the errors and warnings found while parsing it and resolving the names in it,
+    /// and the imports of names that the file imported from does not export.<br>
+    /// There is no type checker yet, so type errors are not among them.
+    pub fn check(&self, file: FileId) -> Rc<Vec<Diagnostic>> {
+        self.fetch(Query::Check(file), file, &self.checks)
+    }
+    /// Copies the syntax tree of a file into an arena.
+    pub fn lower(&self, file: FileId) -> Rc<Arena> {
+        self.fetch(Query::Lower(file), file, &self.arenas)
+    }
+    /// Links the resolutions of some of the files into a workspace, in which names can be followed from one file to another.<br>
+    /// The files are given new IDs in the workspace, in the order they are listed.
+    pub fn workspace(&self, files: &[FileId]) -> Workspace {
+        let mut sources = SourceMap::new();
+        let mut resolutions = HashMap::new();
+        for file in files {
+            let source = self.sources.get(*file);
+            let id = sources.add(source.name.as_str(), source.text.as_str());
+            resolutions.insert(id, self.resolve(*file));
+        }
+        Workspace::from_resolutions(sources, resolutions)
+    }
+
+    /// Remembers that the query being computed read another one.
+    fn record(&self, query: Query) {
+        if let Some((_, inputs)) = self.active.borrow_mut().last_mut() {
+            inputs.push(query);
+        }
+    }
+    fn fetch<V>(&self, query: Query, file: FileId, table: &Table<V>) -> Rc<V> {
+        self.record(query);
+        self.verify(query);
+        table.borrow()[&file].value.clone()
+    }
+    /// Brings the answer to a query up to date, and returns the revision in which it last changed.
+    fn verify(&self, query: Query) -> Revision {
+        match query {
+            Query::Source(file) => self.changed[&file],
+            Query::Files => self.added,
+            Query::Parse(file) => self.update(
+                query,
+                file,
+                &self.parses,
+                |db, old| db.compute_parse(file, old.take()),
+                |_, _| false,
+            ),
+            Query::Resolve(file) => self.update(
+                query,
+                file,
+                &self.resolutions,
+                |db, _| resolve(db.parse(file).statements()),
+                PartialEq::eq,
+            ),
+            Query::Exports(file) => self.update(
+                query,
+                file,
+                &self.exports,
+                |db, _| db.compute_exports(file),
+                PartialEq::eq,
+            ),
+            Query::Check(file) => self.update(
+                query,
+                file,
+                &self.checks,
+                |db, _| db.compute_check(file),
+                PartialEq::eq,
+            ),
+            Query::Lower(file) => self.update(
+                query,
+                file,
+                &self.arenas,
+                |db, _| db.parse(file).to_arena(),
+                |_, _| false,
+            ),
+        }
+    }
+    /// Computes the answer to a query again, unless none of the queries it read have changed since it was last brought up to date.<br>
+    /// `compute` is given the old answer, which it can take to reuse. If it does not, and the new answer is the `same` as the old one, the answer keeps the revision it last changed in.
+    fn update<V>(
+        &self,
+        query: Query,
+        file: FileId,
+        table: &Table<V>,
+        compute: impl FnOnce(&Self, &mut Option<Rc<V>>) -> V,
+        same: fn(&V, &V) -> bool,
+    ) -> Revision {
+        let memo = table
+            .borrow()
+            .get(&file)
+            .map(|memo| (memo.changed_at, memo.verified_at, memo.inputs.clone()));
+        if let Some((changed_at, verified_at, inputs)) = memo {
+            if verified_at == self.revision
+                || inputs
+                    .iter()
+                    .all(|input| self.verify(*input) <= verified_at)
+            {
+                table.borrow_mut().get_mut(&file).unwrap().verified_at = self.revision;
+                return changed_at;
+            }
+        }
+        let cycle = self
+            .active
+            .borrow()
+            .iter()
+            .any(|(active, _)| *active == query);
+        assert!(!cycle, "The query {query:?} depends on itself.");
+        let old = table.borrow_mut().remove(&file);
+        let last_changed = old.as_ref().map(|memo| memo.changed_at);
+        let mut old = old.map(|memo| memo.value);
+        self.active.borrow_mut().push((query, vec![]));
+        let value = compute(self, &mut old);
+        let (_, inputs) = self.active.borrow_mut().pop().unwrap();
+        self.computed.borrow_mut().push(query);
+        let changed_at = match (old, last_changed) {
+            (Some(old), Some(changed_at)) if same(&old, &value) => changed_at,
+            _ => self.revision,
+        };
+        let memo = Memo {
+            value: Rc::new(value),
+            changed_at,
+            verified_at: self.revision,
+            inputs,
+        };
+        table.borrow_mut().insert(file, memo);
+        changed_at
+    }
+
+    /// Parses a file. If it was only edited since it was last parsed, and nothing else holds on to the old program, the edits are applied to the old program instead.
+    fn compute_parse(&self, file: FileId, old: Option<Rc<Program>>) -> Program {
+        self.record(Query::Source(file));
+        let edits = self.edits.borrow_mut().insert(file, Some(vec![])).flatten();
+        match (old.and_then(|old| Rc::try_unwrap(old).ok()), edits) {
+            (Some(mut program), Some(edits)) => {
+                for edit in &edits {
+                    program.edit(edit);
+                }
+                program
+            }
+            _ => parse_in(&self.sources, file),
+        }
+    }
+    fn compute_exports(&self, file: FileId) -> Vec<Export> {
+        self.resolve(file)
+            .iter()
+            .filter(|(_, binding)| binding.parent.is_none() && !binding.is_builtin())
+            .map(|(_, binding)| Export {
+                name: binding.name.clone(),
+                kind: binding.kind,
+                is_public: binding.is_public,
+            })
+            .collect()
+    }
+    fn compute_check(&self, file: FileId) -> Vec<Diagnostic> {
+        let program = self.parse(file);
+        let resolution = self.resolve(file);
+        let mut diagnostics = program.diagnostics.clone();
+        diagnostics.extend(resolution.diagnostics.iter().cloned());
+        // Names never change, so reading the name of the file is not remembered.
+        let name = &self.sources.get(file).name;
+        for import in &resolution.imports {
+            // The database is not always given every file, e.g. the ones an editor has open, so a file that is missing is not reported.
+            let Some(source) = self.file(&relative_to(name, &import.source)) else {
+                continue;
+            };
+            let exports = self.exports(source);
+            let name = import.imported_name.clone();
+            let error = match exports.iter().find(|export| export.name == name) {
+                Some(export) if export.is_public => continue,
+                Some(_) => ResolverError::PrivateExport(name),
+                None => ResolverError::NoSuchExport(name),
+            };
+            diagnostics.push(Diagnostic::Resolver(error, import.imported_span));
+        }
+        diagnostics.sort_by_key(|diagnostic| diagnostic.span().start);
+        diagnostics
+    }
+}
@@ -0,0 +1,6 @@
+mod database;
+mod query;
+mod test;
+
+pub use database::{Database, Export};
+pub use query::{Query, Revision};
@@ -0,0 +1,34 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use utils::FileId;
+
+/// A point in the history of a database. It moves forward every time the text of a file changes or a file is added.
+pub type Revision = u64;
+
+/// A question asked of a database about a file. Each one is answered once, and answered again only when something it read has changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Query {
+    /// The text of a file, which is an input set from outside the database.
+    Source(FileId),
+    /// The names of the files in the database, which is an input that changes when a file is added.
+    Files,
+    Parse(FileId),
+    Resolve(FileId),
+    Exports(FileId),
+    Check(FileId),
+    Lower(FileId),
+}
+
+/// The last answer to a query, along with the queries it was computed from.
+pub(crate) struct Memo<V> {
+    pub value: Rc<V>,
+    /// The revision in which the answer last changed. An answer that is computed again and comes out the same keeps the revision it had.
+    pub changed_at: Revision,
+    /// The last revision in which the answer was known to be up to date.
+    pub verified_at: Revision,
+    /// The queries read while computing the answer, in the order they were read.
+    pub inputs: Vec<Query>,
+}
+
+/// The answers to one kind of query, by the file they are about.
+pub(crate) type Table<V> = RefCell<HashMap<FileId, Memo<V>>>;
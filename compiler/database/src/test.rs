@@ -0,0 +1,107 @@
+#![cfg(test)]
+
+use parser::parse_source;
+use utils::{Span, TextEdit};
+
+use crate::{Database, Query};
+
+const SHAPES: &str = "@public @function area(side: Number) -> Number {
+    return side * side;
+}";
+
+/// Returns the queries computed since the last call.
+fn computed(database: &Database) -> Vec<Query> {
+    database.computed.take()
+}
+
+/// Returns an edit that replaces the first occurrence of `old` in a file.
+fn replace(database: &Database, name: &str, old: &str, new: &str) -> TextEdit {
+    let file = database.sources().find(name).unwrap();
+    let start = file.text.find(old).unwrap();
+    TextEdit::new(Span::new(file.id, start, start + old.len()), new)
+}
+
+#[test]
+fn it_computes_only_the_queries_whose_inputs_changed() {
+    let mut database = Database::new();
+    let main = database.set_source(
+        "main.peb",
+        "@use { area } from \"./shapes.peb\";\nprintln area(2);",
+    );
+    let shapes = database.set_source("shapes.peb", SHAPES);
+    assert_eq!(database.check(main).as_slice(), &[]);
+    assert_eq!(
+        computed(&database),
+        vec![
+            Query::Parse(main),
+            Query::Resolve(main),
+            Query::Parse(shapes),
+            Query::Resolve(shapes),
+            Query::Exports(shapes),
+            Query::Check(main),
+        ]
+    );
+    database.check(main);
+    database.set_source("shapes.peb", SHAPES);
+    database.check(main);
+    assert_eq!(computed(&database), vec![]);
+
+    // The body of a function is not part of the exports, so the file that imports it is not checked again.
+    let edit = replace(&database, "shapes.peb", "side * side", "side * side + side");
+    database.edit(&edit);
+    database.check(main);
+    assert_eq!(
+        computed(&database),
+        vec![
+            Query::Parse(shapes),
+            Query::Resolve(shapes),
+            Query::Exports(shapes)
+        ]
+    );
+
+    database.edit(&replace(&database, "shapes.peb", "@public ", ""));
+    let problems = database.check(main);
+    assert_eq!(computed(&database).last(), Some(&Query::Check(main)));
+    assert_eq!(problems.len(), 1);
+    assert_eq!(
+        problems[0].to_string(),
+        "'area' is not public, so it cannot be imported."
+    );
+}
+
+#[test]
+fn it_checks_imports_against_the_files_it_has() {
+    let mut database = Database::new();
+    let main = database.set_source(
+        "src/main.peb",
+        "@use { Circle } from \"./shapes.peb\";\n@use { Square } from \"../missing.peb\";\nprintln Circle;\nprintln Square;",
+    );
+    let problems = database.check(main);
+    assert_eq!(problems.as_slice(), &[]);
+    database.set_source("src/shapes.peb", SHAPES);
+    let problems = database.check(main);
+    assert_eq!(problems.len(), 1);
+    assert_eq!(
+        problems[0].to_string(),
+        "'Circle' is not declared in that file."
+    );
+    assert_eq!(database.lower(main).roots().len(), 4);
+}
+
+#[test]
+fn it_parses_edited_files_like_new_ones() {
+    let mut database = Database::new();
+    let text = (0..20)
+        .map(|index| format!("@function f{index}(a: Number) {{\n    return a + {index};\n}}\n"))
+        .collect::<String>();
+    let file = database.set_source("main.peb", text);
+    database.parse(file);
+    database.edit(&replace(&database, "main.peb", "a + 7", "a +"));
+    database.edit(&replace(&database, "main.peb", "f12", "g"));
+    database.edit(&replace(&database, "main.peb", "}\n@function f3", "@let b"));
+    let program = database.parse(file);
+    let reparsed = parse_source(&database.sources().get(file).text);
+    assert_eq!(program.statements(), reparsed.statements());
+    assert_eq!(program.diagnostics, reparsed.diagnostics);
+    assert!(!program.is_valid());
+}
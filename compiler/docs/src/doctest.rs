@@ -0,0 +1,194 @@
+use ast::{Comment, CommentKind, Injunction, Keyword, Token, TokenKind};
+use parser::{parse_source, Diagnostic, Program, Scanner};
+use utils::{Encoding, FileId, LineColumn, SourceFile, SourceMap, Span};
+
+/// A fenced example in a doc comment, e.g.
+/// ```pebble
+/// ## ```pebble
+/// ## @let content = core.fs.readToString("foo.bar");
+/// ## ```
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocTest {
+    /// The file the example is written in.
+    pub file: FileId,
+    /// The name of the declaration the doc comment documents, if it documents one.
+    pub item: Option<String>,
+    /// The zero-based line of the opening fence.
+    pub line: usize,
+    /// The code of the example, with the `##` of each line removed.
+    pub code: String,
+    /// Set if the fence is marked `ignore`, e.g. ```` ```pebble,ignore ````.
+    pub ignore: bool,
+    /// The byte offset in the file where each line of `code` begins.
+    line_offsets: Vec<usize>,
+}
+
+/// An error found in an example, located in the doc comment it was written in.
+#[derive(Debug, PartialEq)]
+pub struct Failure {
+    pub diagnostic: Diagnostic,
+    pub span: Span,
+}
+
+/// A check run on an example after it is parsed, such as type checking or execution.
+pub type Check = Box<dyn Fn(&Program) -> Vec<Diagnostic>>;
+
+/// Resolves the names in an example, so that one that uses a name it does not declare or import fails.<br>
+/// Names are looked up as if the example were a file of its own, so examples reach the declarations of a pile through `core` or an import.
+pub fn resolve_names(program: &Program) -> Vec<Diagnostic> {
+    resolver::resolve(program.statements()).diagnostics
+}
+
+impl DocTest {
+    /// Returns a name for the example, e.g. `fs.peb:23 (readToString)`.
+    pub fn name(&self, sources: &SourceMap) -> String {
+        let location = format!("{}:{}", sources.get(self.file).name, self.line + 1);
+        match &self.item {
+            Some(item) => format!("{location} ({item})"),
+            None => location,
+        }
+    }
+    /// Parses the example as a file of its own, then runs each check on it, stopping at the first check that fails.<br>
+    /// Only the errors are failures, and they are located in the doc comment rather than in the example.
+    pub fn run(&self, checks: &[Check]) -> Vec<Failure> {
+        let mut program = parse_source(&self.code);
+        let mut failures = self.locate(std::mem::take(&mut program.diagnostics));
+        for check in checks {
+            if !failures.is_empty() {
+                break;
+            }
+            failures = self.locate(check(&program));
+        }
+        failures
+    }
+    fn locate(&self, diagnostics: Vec<Diagnostic>) -> Vec<Failure> {
+        let mut map = SourceMap::new();
+        let snippet = map.add("", self.code.as_str());
+        let snippet = map.get(snippet);
+        diagnostics
+            .into_iter()
+            .filter(Diagnostic::is_error)
+            .map(|diagnostic| {
+                let span = diagnostic.span();
+                let span = Span::new(
+                    self.file,
+                    self.original_offset(snippet, span.start),
+                    self.original_offset(snippet, span.end),
+                );
+                Failure { diagnostic, span }
+            })
+            .collect()
+    }
+    /// Maps a byte offset in the example to the same text in the doc comment.
+    fn original_offset(&self, snippet: &SourceFile, offset: usize) -> usize {
+        let LineColumn { line, column } = snippet.line_column(offset, Encoding::Utf8);
+        match self.line_offsets.get(line) {
+            Some(start) => start + column,
+            None => self.line_offsets.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// Finds every ```` ```pebble ```` example in the doc comments of a file.
+pub fn extract(file: &SourceFile) -> Vec<DocTest> {
+    let mut scanner = Scanner::new(&file.text);
+    scanner.run();
+    let mut tests = vec![];
+    let mut group: Vec<(usize, &str)> = vec![];
+    let mut last_line = None;
+    for comment in &scanner.comments {
+        let TokenKind::Comment(Comment {
+            kind: CommentKind::Doc,
+            content,
+        }) = &comment.kind
+        else {
+            continue;
+        };
+        let line = file.line_column(comment.span.start, Encoding::Utf8).line;
+        if last_line.is_some_and(|last| last + 1 != line) {
+            let item = documented_item(file, &scanner.tokens, last_line.unwrap());
+            examples(file, &group, item, &mut tests);
+            group.clear();
+        }
+        last_line = Some(line);
+        // The content follows the ##, and a single space after it is not part of the docs.
+        let content = content.trim_end_matches('\r');
+        let (start, content) = match content.strip_prefix(' ') {
+            Some(content) => (comment.span.start + 3, content),
+            None => (comment.span.start + 2, content),
+        };
+        group.push((start, content));
+    }
+    if let Some(last) = last_line {
+        let item = documented_item(file, &scanner.tokens, last);
+        examples(file, &group, item, &mut tests);
+    }
+    tests
+}
+
+/// Collects the examples in a group of consecutive doc comments.
+fn examples(
+    file: &SourceFile,
+    group: &[(usize, &str)],
+    item: Option<String>,
+    tests: &mut Vec<DocTest>,
+) {
+    let mut lines = group.iter();
+    while let Some((start, content)) = lines.next() {
+        let Some(info) = content.trim_start().strip_prefix("```") else {
+            continue;
+        };
+        let mut tags = info.split(',').map(str::trim);
+        if tags.next() != Some("pebble") {
+            // Skip over the block, so that its closing fence is not taken for an opening one.
+            lines
+                .by_ref()
+                .find(|(_, line)| line.trim_start().starts_with("```"));
+            continue;
+        }
+        let ignore = tags.any(|tag| tag == "ignore");
+        let mut code = vec![];
+        let mut line_offsets = vec![];
+        for (start, line) in lines.by_ref() {
+            if line.trim_start().starts_with("```") {
+                break;
+            }
+            code.push(*line);
+            line_offsets.push(*start);
+        }
+        tests.push(DocTest {
+            file: file.id,
+            item: item.clone(),
+            line: file.line_column(*start, Encoding::Utf8).line,
+            code: code.join("\n"),
+            ignore,
+            line_offsets,
+        });
+    }
+}
+
+/// Returns the name of the declaration that begins on the line after `line`, looking past any `@public` before it.
+fn documented_item(file: &SourceFile, tokens: &[Token], line: usize) -> Option<String> {
+    let line_of = |token: &Token| file.line_column(token.span.start, Encoding::Utf8).line;
+    let mut tokens = tokens
+        .iter()
+        .skip_while(|token| line_of(token) <= line)
+        .skip_while(|token| token.is_keyword(&Keyword::Injunction(Injunction::Public)));
+    let declaration = tokens.next()?;
+    if line_of(declaration) != line + 1 {
+        return None;
+    }
+    let name = match &declaration.kind {
+        TokenKind::Keyword(Keyword::Injunction(_)) => tokens.next()?,
+        // A class member is named by its first token, after any modifiers.
+        TokenKind::Keyword(Keyword::Static | Keyword::ReadOnly) => tokens.find(|token| {
+            !token.is_keyword(&Keyword::Static) && !token.is_keyword(&Keyword::ReadOnly)
+        })?,
+        _ => declaration,
+    };
+    match &name.kind {
+        TokenKind::Identifier(identifier) => Some(identifier.value.clone()),
+        _ => None,
+    }
+}
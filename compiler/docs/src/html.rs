@@ -0,0 +1,214 @@
+use crate::{
+    item::{Item, Segment},
+    pile::Pile,
+    search::search_index,
+};
+
+const STYLE: &str =
+    "body{font-family:sans-serif;max-width:60rem;margin:auto;padding:1rem;line-height:1.5}\
+nav ul{list-style:none;padding-left:1rem}\
+pre{background:#f4f4f4;padding:.75rem;overflow-x:auto}\
+section{border-top:1px solid #ddd;margin-top:1.5rem}\
+section section{margin-left:1rem}\
+#results a{display:block}";
+
+const SEARCH: &str = "const input=document.getElementById('search');\
+const results=document.getElementById('results');\
+input.addEventListener('input',()=>{\
+const query=input.value.toLowerCase();results.innerHTML='';\
+if(!query)return;\
+for(const entry of INDEX.filter(e=>e.path.toLowerCase().includes(query)).slice(0,20)){\
+const link=document.createElement('a');link.href=entry.href;\
+link.textContent=entry.path+(entry.summary?' \u{2014} '+entry.summary:'');results.appendChild(link);}});";
+
+/// Renders the documentation of a pile as a single HTML page, with a search box over its items.
+pub fn html(pile: &Pile) -> String {
+    let mut page = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    page.push_str(&format!("<title>{}</title>\n", escape(&pile.name)));
+    page.push_str(&format!("<style>{STYLE}</style>\n</head>\n<body>\n"));
+    page.push_str(&format!("<h1>{}</h1>\n", escape(&pile.name)));
+    if !pile.description.is_empty() {
+        page.push_str(&format!("<p>{}</p>\n", escape(&pile.description)));
+    }
+    if !pile.version.is_empty() {
+        page.push_str(&format!("<p>Version {}</p>\n", escape(&pile.version)));
+    }
+    page.push_str("<input id=\"search\" type=\"search\" placeholder=\"Search\">\n<div id=\"results\"></div>\n");
+    page.push_str("<nav>\n<h2>Contents</h2>\n<ul>\n");
+    for item in &pile.items {
+        contents(item, &mut page);
+    }
+    page.push_str("</ul>\n</nav>\n");
+    for item in &pile.items {
+        section(pile, item, 2, &mut page);
+    }
+    // The index is embedded so that the page can be searched when opened straight from disk.
+    let index = search_index(pile).replace("</", "<\\/");
+    page.push_str(&format!(
+        "<script>\nconst INDEX={index};\n{SEARCH}\n</script>\n</body>\n</html>\n"
+    ));
+    page
+}
+
+fn contents(item: &Item, page: &mut String) {
+    page.push_str(&format!(
+        "<li><a href=\"#{}\"><code>{}</code></a>",
+        escape(&item.path),
+        escape(&item.path)
+    ));
+    if !item.members.is_empty() {
+        page.push_str("\n<ul>\n");
+        for member in &item.members {
+            contents(member, page);
+        }
+        page.push_str("</ul>\n");
+    }
+    page.push_str("</li>\n");
+}
+
+fn section(pile: &Pile, item: &Item, level: usize, page: &mut String) {
+    let level = level.min(6);
+    page.push_str(&format!("<section id=\"{}\">\n", escape(&item.path)));
+    page.push_str(&format!(
+        "<h{level}>{} <code>{}</code></h{level}>\n",
+        item.kind.title(),
+        escape(&item.path)
+    ));
+    page.push_str("<pre class=\"signature\"><code>");
+    for segment in &item.signature {
+        match segment {
+            Segment::Type(name) if pile.link(name).is_some() => page.push_str(&format!(
+                "<a href=\"#{}\">{}</a>",
+                escape(pile.link(name).unwrap()),
+                escape(name)
+            )),
+            Segment::Text(text) | Segment::Type(text) => page.push_str(&escape(text)),
+        }
+    }
+    page.push_str("</code></pre>\n");
+    if let Some(docs) = &item.docs {
+        page.push_str(&markdown_to_html(pile, docs));
+    }
+    for member in &item.members {
+        section(pile, member, level + 1, page);
+    }
+    page.push_str("</section>\n");
+}
+
+/// Converts the Markdown in docs to HTML.<br>
+/// Only what doc comments commonly use is understood: paragraphs, `-` lists, fenced code and inline code, including links to documented types written as [`Name`].
+fn markdown_to_html(pile: &Pile, docs: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<&str> = vec![];
+    let mut list: Vec<&str> = vec![];
+    let mut code: Option<(String, Vec<&str>)> = None;
+    for line in docs.lines() {
+        if let Some((language, lines)) = &mut code {
+            if line.trim_start().starts_with("```") {
+                html.push_str(&format!(
+                    "<pre><code class=\"language-{}\">{}</code></pre>\n",
+                    escape(language),
+                    escape(&lines.join("\n"))
+                ));
+                code = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+        let trimmed = line.trim();
+        if let Some(language) = trimmed.strip_prefix("```") {
+            flush(pile, &mut paragraph, &mut list, &mut html);
+            code = Some((language.trim().to_string(), vec![]));
+        } else if let Some(entry) = trimmed.strip_prefix("- ") {
+            if !paragraph.is_empty() {
+                flush(pile, &mut paragraph, &mut vec![], &mut html);
+            }
+            list.push(entry);
+        } else if trimmed.is_empty() {
+            flush(pile, &mut paragraph, &mut list, &mut html);
+        } else {
+            if !list.is_empty() {
+                flush(pile, &mut vec![], &mut list, &mut html);
+            }
+            paragraph.push(trimmed);
+        }
+    }
+    // An unclosed fence runs to the end of the docs.
+    if let Some((language, lines)) = code {
+        html.push_str(&format!(
+            "<pre><code class=\"language-{}\">{}</code></pre>\n",
+            escape(&language),
+            escape(&lines.join("\n"))
+        ));
+    }
+    flush(pile, &mut paragraph, &mut list, &mut html);
+    html
+}
+
+fn flush<'d>(
+    pile: &Pile,
+    paragraph: &mut Vec<&'d str>,
+    list: &mut Vec<&'d str>,
+    html: &mut String,
+) {
+    if !paragraph.is_empty() {
+        html.push_str(&format!("<p>{}</p>\n", inline(pile, &paragraph.join(" "))));
+        paragraph.clear();
+    }
+    if !list.is_empty() {
+        html.push_str("<ul>\n");
+        for entry in list.iter() {
+            html.push_str(&format!("<li>{}</li>\n", inline(pile, entry)));
+        }
+        html.push_str("</ul>\n");
+        list.clear();
+    }
+}
+
+/// Renders inline code, and turns [`Name`] into a link if a type with that name is documented.
+fn inline(pile: &Pile, text: &str) -> String {
+    let mut html = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('`') {
+        let Some(length) = rest[start + 1..].find('`') else {
+            break;
+        };
+        let code = &rest[start + 1..start + 1 + length];
+        let mut before = &rest[..start];
+        let mut after = &rest[start + length + 2..];
+        let mut link = None;
+        if let (Some(opened), Some(closed)) = (before.strip_suffix('['), after.strip_prefix(']')) {
+            if let Some(path) = pile.link(code).filter(|_| !closed.starts_with('(')) {
+                (before, after, link) = (opened, closed, Some(path));
+            }
+        }
+        html.push_str(&escape(before));
+        match link {
+            Some(path) => html.push_str(&format!(
+                "<a href=\"#{}\"><code>{}</code></a>",
+                escape(path),
+                escape(code)
+            )),
+            None => html.push_str(&format!("<code>{}</code>", escape(code))),
+        }
+        rest = after;
+    }
+    html.push_str(&escape(rest));
+    html
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(char),
+        }
+    }
+    escaped
+}
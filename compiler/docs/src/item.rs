@@ -0,0 +1,364 @@
+use ast::{
+    Attribute, Class, ConcreteType, DotType, Enum, Function, FunctionType, GenericArgument,
+    Interface, Method, Module, Parameter, Property, PublicModifier, Statement, Type, Variant,
+};
+use serde::Serialize;
+
+/// The kinds of declarations that are documented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Module,
+    Function,
+    Class,
+    Interface,
+    Enum,
+    Method,
+    Attribute,
+    Variant,
+}
+
+impl ItemKind {
+    /// Returns `true` if the item declares a type, which signatures can link to.
+    pub fn is_type(&self) -> bool {
+        matches!(self, Self::Class | Self::Interface | Self::Enum)
+    }
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Module => "module",
+            Self::Function => "function",
+            Self::Class => "class",
+            Self::Interface => "interface",
+            Self::Enum => "enum",
+            Self::Method => "method",
+            Self::Attribute => "attribute",
+            Self::Variant => "variant",
+        }
+    }
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Module => "Module",
+            Self::Function => "Function",
+            Self::Class => "Class",
+            Self::Interface => "Interface",
+            Self::Enum => "Enum",
+            Self::Method => "Method",
+            Self::Attribute => "Attribute",
+            Self::Variant => "Variant",
+        }
+    }
+}
+
+/// A piece of a signature.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Text(String),
+    /// The name of a type, which is linked to the type's documentation if the pile documents it.
+    Type(String),
+}
+
+/// A documented declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub name: String,
+    /// The names of the declarations the item is nested in, followed by its own, e.g. `fs.readFile`.
+    pub path: String,
+    pub signature: Vec<Segment>,
+    pub docs: Option<String>,
+    pub members: Vec<Item>,
+    /// The name of the file the item is declared in.
+    pub file: String,
+}
+
+impl Item {
+    /// Returns the signature as plain text.
+    pub fn signature_text(&self) -> String {
+        self.signature
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) | Segment::Type(text) => text.as_str(),
+            })
+            .collect()
+    }
+    /// Returns the first line of the item's docs.
+    pub fn summary(&self) -> &str {
+        self.docs
+            .as_deref()
+            .and_then(|docs| docs.lines().next())
+            .map(str::trim_end)
+            .unwrap_or_default()
+    }
+}
+
+/// Collects the public declarations in a list of statements, and the members of those declarations.
+pub fn collect(statements: &[Statement], file: &str) -> Vec<Item> {
+    let collector = Collector { file };
+    collector.public_items(statements, "")
+}
+
+struct Collector<'f> {
+    file: &'f str,
+}
+
+impl Collector<'_> {
+    fn public_items(&self, statements: &[Statement], scope: &str) -> Vec<Item> {
+        statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::PublicModifier(PublicModifier { statement, .. }) => {
+                    self.declaration(statement, scope)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+    fn declaration(&self, statement: &Statement, scope: &str) -> Option<Item> {
+        let mut signature = Signature::default();
+        let (kind, name, members) = match statement {
+            Statement::Function(Function {
+                name,
+                generic_arguments,
+                parameters,
+                return_type,
+                ..
+            }) => {
+                signature.text("@function ");
+                signature.text(&name.value);
+                signature.callable(generic_arguments, parameters, return_type.as_ref());
+                (ItemKind::Function, &name.value, vec![])
+            }
+            Statement::Class(Class {
+                name,
+                generic_arguments,
+                properties,
+                ..
+            })
+            | Statement::Interface(Interface {
+                name,
+                generic_arguments,
+                properties,
+                ..
+            }) => {
+                let kind = match statement {
+                    Statement::Class(_) => ItemKind::Class,
+                    _ => ItemKind::Interface,
+                };
+                signature.text(&format!("@{} ", kind.label()));
+                signature.text(&name.value);
+                signature.generic_arguments(generic_arguments);
+                let path = join(scope, &name.value);
+                let members = properties
+                    .iter()
+                    .filter_map(|property| self.property(property, &path))
+                    .collect();
+                (kind, &name.value, members)
+            }
+            Statement::Enum(Enum {
+                name,
+                generic_arguments,
+                variants,
+                ..
+            }) => {
+                signature.text("@enum ");
+                signature.text(&name.value);
+                signature.generic_arguments(generic_arguments);
+                let path = join(scope, &name.value);
+                let members = variants
+                    .iter()
+                    .map(|variant| self.variant(variant, &path))
+                    .collect();
+                (ItemKind::Enum, &name.value, members)
+            }
+            Statement::Module(Module { name, body, .. }) => {
+                signature.text("@module ");
+                signature.text(&name.value);
+                let members = self.public_items(&body.body, &join(scope, &name.value));
+                (ItemKind::Module, &name.value, members)
+            }
+            _ => return None,
+        };
+        Some(Item {
+            kind,
+            name: name.to_string(),
+            path: join(scope, name),
+            signature: signature.segments,
+            docs: statement.docs().map(str::to_string),
+            members,
+            file: self.file.to_string(),
+        })
+    }
+    fn property(&self, property: &Property, scope: &str) -> Option<Item> {
+        let mut signature = Signature::default();
+        let (kind, name) = match property {
+            Property::Method(Method {
+                name,
+                generic_arguments,
+                is_static,
+                parameters,
+                return_type,
+                ..
+            }) => {
+                if *is_static {
+                    signature.text("static ");
+                }
+                signature.text(&name.value);
+                signature.callable(generic_arguments, parameters, return_type.as_ref());
+                (ItemKind::Method, &name.value)
+            }
+            Property::Attribute(Attribute {
+                key,
+                type_label,
+                is_static,
+                is_readonly,
+                ..
+            }) => {
+                if *is_static {
+                    signature.text("static ");
+                }
+                if *is_readonly {
+                    signature.text("readonly ");
+                }
+                signature.text(&key.value);
+                if let Some(type_label) = type_label {
+                    signature.text(": ");
+                    signature.type_(type_label);
+                }
+                (ItemKind::Attribute, &key.value)
+            }
+            Property::Implement(_) => return None,
+        };
+        Some(Item {
+            kind,
+            name: name.to_string(),
+            path: join(scope, name),
+            signature: signature.segments,
+            docs: property.docs().map(str::to_string),
+            members: vec![],
+            file: self.file.to_string(),
+        })
+    }
+    fn variant(&self, variant: &Variant, scope: &str) -> Item {
+        let mut signature = Signature::default();
+        let name = match variant {
+            Variant::Tuple { name, elements, .. } => {
+                signature.text(&name.value);
+                signature.text("(");
+                signature.list(elements, Signature::type_);
+                signature.text(")");
+                &name.value
+            }
+            Variant::Concrete { name, .. } => {
+                signature.text(&name.value);
+                &name.value
+            }
+        };
+        Item {
+            kind: ItemKind::Variant,
+            name: name.to_string(),
+            path: join(scope, name),
+            signature: signature.segments,
+            docs: variant.docs().map(str::to_string),
+            members: vec![],
+            file: self.file.to_string(),
+        }
+    }
+}
+
+fn join(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+/// Builds a signature out of segments, merging neighbouring text.
+#[derive(Default)]
+struct Signature {
+    segments: Vec<Segment>,
+}
+
+impl Signature {
+    fn text(&mut self, text: &str) {
+        match self.segments.last_mut() {
+            Some(Segment::Text(last)) => last.push_str(text),
+            _ => self.segments.push(Segment::Text(text.to_string())),
+        }
+    }
+    fn list<T>(&mut self, items: &[T], mut render: impl FnMut(&mut Self, &T)) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                self.text(", ");
+            }
+            render(self, item);
+        }
+    }
+    fn generic_arguments(&mut self, arguments: &Option<Vec<GenericArgument>>) {
+        let Some(arguments) = arguments else {
+            return;
+        };
+        self.text("<");
+        self.list(arguments, |signature, argument| {
+            signature.text(&argument.name.value);
+            let implements = argument.implements.iter().flatten();
+            for (index, interface) in implements.enumerate() {
+                signature.text(if index == 0 { " implements " } else { " + " });
+                signature
+                    .segments
+                    .push(Segment::Type(interface.value.to_string()));
+            }
+        });
+        self.text(">");
+    }
+    fn parameter(&mut self, parameter: &Parameter) {
+        self.text(&parameter.name.value);
+        if let Some(label) = &parameter.label {
+            self.text(": ");
+            self.type_(label);
+        }
+    }
+    fn callable(
+        &mut self,
+        generic_arguments: &Option<Vec<GenericArgument>>,
+        parameters: &[Parameter],
+        return_type: Option<&Type>,
+    ) {
+        self.generic_arguments(generic_arguments);
+        self.text("(");
+        self.list(parameters, Self::parameter);
+        self.text(")");
+        if let Some(return_type) = return_type {
+            self.text(" -> ");
+            self.type_(return_type);
+        }
+    }
+    fn type_(&mut self, type_: &Type) {
+        match type_ {
+            Type::Concrete(ConcreteType {
+                name, arguments, ..
+            }) => {
+                self.segments.push(Segment::Type(name.value.to_string()));
+                if !arguments.is_empty() {
+                    self.text("<");
+                    self.list(arguments, Self::type_);
+                    self.text(">");
+                }
+            }
+            Type::Function(FunctionType {
+                parameters,
+                return_type,
+                generic_arguments,
+                ..
+            }) => self.callable(generic_arguments, parameters, Some(return_type)),
+            Type::Dot(DotType { levels, .. }) => {
+                for (index, level) in levels.iter().enumerate() {
+                    if index > 0 {
+                        self.text(".");
+                    }
+                    self.type_(level);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+mod doctest;
+mod html;
+mod item;
+mod markdown;
+mod pile;
+mod search;
+mod test;
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+pub use doctest::{extract, resolve_names, Check, DocTest, Failure};
+pub use html::html;
+pub use item::{collect, Item, ItemKind, Segment};
+pub use markdown::markdown;
+pub use pile::Pile;
+pub use search::search_index;
+
+/// The formats documentation can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Html,
+    Markdown,
+}
+
+/// Writes the documentation of a pile into `folder` in each of the given formats, along with its search index.
+/// Returns the paths of the files written.
+pub fn write(
+    pile: &Pile,
+    folder: impl AsRef<Path>,
+    formats: &[Format],
+) -> io::Result<Vec<PathBuf>> {
+    let folder = folder.as_ref();
+    std::fs::create_dir_all(folder)?;
+    let mut written = vec![];
+    for format in formats {
+        let (name, content) = match format {
+            Format::Html => ("index.html", html(pile)),
+            Format::Markdown => ("index.md", markdown(pile)),
+        };
+        let path = folder.join(name);
+        std::fs::write(&path, content)?;
+        written.push(path);
+    }
+    let path = folder.join("search-index.json");
+    std::fs::write(&path, search_index(pile))?;
+    written.push(path);
+    Ok(written)
+}
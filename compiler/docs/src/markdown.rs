@@ -0,0 +1,95 @@
+use crate::{
+    item::{Item, Segment},
+    pile::Pile,
+};
+
+/// Renders the documentation of a pile as a single Markdown page.<br>
+/// Every item is preceded by an anchor named after its path, which the contents, the links in signatures and the links in docs point to.
+pub fn markdown(pile: &Pile) -> String {
+    let mut page = format!("# {}\n\n", pile.name);
+    if !pile.description.is_empty() {
+        page.push_str(&format!("{}\n\n", pile.description));
+    }
+    if !pile.version.is_empty() {
+        page.push_str(&format!("Version {}\n\n", pile.version));
+    }
+    page.push_str("## Contents\n\n");
+    for item in &pile.items {
+        contents(item, 0, &mut page);
+    }
+    for item in &pile.items {
+        page.push_str("\n---\n\n");
+        section(pile, item, 2, &mut page);
+    }
+    page
+}
+
+fn contents(item: &Item, depth: usize, page: &mut String) {
+    page.push_str(&format!(
+        "{}- [`{}`](#{})",
+        "  ".repeat(depth),
+        item.path,
+        item.path
+    ));
+    if !item.summary().is_empty() {
+        page.push_str(&format!(" — {}", item.summary()));
+    }
+    page.push('\n');
+    for member in &item.members {
+        contents(member, depth + 1, page);
+    }
+}
+
+fn section(pile: &Pile, item: &Item, level: usize, page: &mut String) {
+    page.push_str(&format!("<a id=\"{}\"></a>\n\n", item.path));
+    page.push_str(&format!(
+        "{} {} `{}`\n\n",
+        "#".repeat(level.min(6)),
+        item.kind.title(),
+        item.path
+    ));
+    page.push_str(&format!("```pebble\n{}\n```\n\n", item.signature_text()));
+    if let Some(docs) = &item.docs {
+        page.push_str(&link_docs(pile, docs));
+        page.push_str("\n\n");
+    }
+    let mut uses = vec![];
+    for segment in &item.signature {
+        if let Segment::Type(name) = segment {
+            if let Some(path) = pile.link(name) {
+                let link = format!("[`{name}`](#{path})");
+                if !uses.contains(&link) {
+                    uses.push(link);
+                }
+            }
+        }
+    }
+    if !uses.is_empty() {
+        page.push_str(&format!("Uses: {}\n\n", uses.join(", ")));
+    }
+    for member in &item.members {
+        section(pile, member, level + 1, page);
+    }
+}
+
+/// Turns references to documented types in docs, written as [`Name`], into links.
+fn link_docs(pile: &Pile, docs: &str) -> String {
+    let mut linked = String::new();
+    let mut rest = docs;
+    while let Some(start) = rest.find("[`") {
+        linked.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find("`]") else {
+            break;
+        };
+        let name = &rest[2..end];
+        let after = &rest[end + 2..];
+        linked.push_str(&rest[..end + 2]);
+        if let (Some(path), false) = (pile.link(name), after.starts_with('(')) {
+            linked.push_str(&format!("(#{path})"));
+        }
+        rest = after;
+    }
+    linked.push_str(rest);
+    linked
+}
@@ -0,0 +1,111 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use parser::{parse_in, Diagnostic};
+use serde::Deserialize;
+use utils::{FileId, SourceMap};
+
+use crate::item::{collect, Item};
+
+/// The fields of `pile.json` that the documentation uses.
+#[derive(Debug, Default, Deserialize)]
+struct Manifest {
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// The public declarations of a pile, gathered from every source file in it.
+pub struct Pile {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub items: Vec<Item>,
+    pub sources: SourceMap,
+    /// The problems found while parsing the source files.
+    pub diagnostics: Vec<(FileId, Diagnostic)>,
+    /// The path of every documented type, by name.
+    types: HashMap<String, String>,
+}
+
+impl Pile {
+    /// Reads the manifest of the pile in `root`, and documents every `.peb` file in its `src` folder.
+    pub fn load(root: impl AsRef<Path>) -> io::Result<Self> {
+        let root = root.as_ref();
+        let manifest = std::fs::read_to_string(root.join("pile.json"))?;
+        let manifest: Manifest = serde_json::from_str(&manifest)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let mut paths = vec![];
+        source_files(&root.join("src"), &mut paths)?;
+        paths.sort();
+        let mut sources = SourceMap::new();
+        for path in &paths {
+            sources.load(path)?;
+        }
+        let mut pile = Pile::from_sources(manifest.name, sources);
+        pile.version = manifest.version;
+        pile.description = manifest.description;
+        Ok(pile)
+    }
+    /// Documents the files in a source map as a pile with the given name.
+    pub fn from_sources(name: impl Into<String>, sources: SourceMap) -> Self {
+        let mut items = vec![];
+        let mut diagnostics = vec![];
+        for file in sources.files() {
+            let program = parse_in(&sources, file.id);
+            items.extend(collect(program.statements(), &file.name));
+            diagnostics.extend(
+                program
+                    .diagnostics
+                    .into_iter()
+                    .map(|diagnostic| (file.id, diagnostic)),
+            );
+        }
+        let mut types = HashMap::new();
+        for item in items.iter().flat_map(flatten) {
+            if item.kind.is_type() {
+                types
+                    .entry(item.name.clone())
+                    .or_insert_with(|| item.path.clone());
+            }
+        }
+        Pile {
+            name: name.into(),
+            version: String::new(),
+            description: String::new(),
+            items,
+            sources,
+            diagnostics,
+            types,
+        }
+    }
+    /// Returns the path of the documented type with the given name, if there is one.
+    pub fn link(&self, name: &str) -> Option<&str> {
+        self.types.get(name).map(String::as_str)
+    }
+    /// Returns every item and member in the pile, each before its members.
+    pub fn all_items(&self) -> impl Iterator<Item = &Item> {
+        self.items.iter().flat_map(flatten)
+    }
+}
+
+fn flatten(item: &Item) -> Box<dyn Iterator<Item = &Item> + '_> {
+    Box::new(std::iter::once(item).chain(item.members.iter().flat_map(flatten)))
+}
+
+fn source_files(folder: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            source_files(&path, paths)?;
+        } else if path.extension().is_some_and(|extension| extension == "peb") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use serde::Serialize;
+
+use crate::{item::ItemKind, pile::Pile};
+
+/// An item in the search index.
+#[derive(Debug, Serialize)]
+struct Entry<'p> {
+    name: &'p str,
+    path: &'p str,
+    kind: ItemKind,
+    signature: String,
+    summary: &'p str,
+    /// The link to the item in the HTML page.
+    href: String,
+}
+
+/// Builds a JSON array with an entry for every item and member in the pile.
+pub fn search_index(pile: &Pile) -> String {
+    let entries = pile
+        .all_items()
+        .map(|item| Entry {
+            name: &item.name,
+            path: &item.path,
+            kind: item.kind,
+            signature: item.signature_text(),
+            summary: item.summary(),
+            href: format!("#{}", item.path),
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string(&entries).expect("search entries are always serializable")
+}
@@ -0,0 +1,225 @@
+#![cfg(test)]
+
+use ast::Location;
+use errors::{ResolverError, SyntaxError};
+use parser::Diagnostic;
+use utils::{Encoding, FileId, LineColumn, SourceMap, Span};
+
+use crate::{extract, html, markdown, resolve_names, search_index, Check, ItemKind, Pile, Segment};
+
+const SOURCE: &str = "## Working with files.
+@public @module fs {
+    ## A file system error.
+    @public @class FileSystemError {
+        ## What went wrong.
+        message: String
+    }
+    ## Reads a file.
+    ## Fails with a [`FileSystemError`] if the file is missing.
+    ## ```pebble
+    ## @let content = fs.readFile(\"a.txt\");
+    ## ```
+    @public @function readFile<T implements Display>(p: Path, options: Array<T>) -> Result<String, FileSystemError> {
+        crash \"Not implemented\";
+    }
+    @function hidden() {}
+}
+@function private() {}
+";
+
+fn pile() -> Pile {
+    let mut sources = SourceMap::new();
+    sources.add("fs.peb", SOURCE);
+    Pile::from_sources("core", sources)
+}
+
+#[test]
+fn it_collects_public_items_and_their_members() {
+    let pile = pile();
+    assert!(pile.diagnostics.is_empty());
+    let paths = pile
+        .all_items()
+        .map(|item| (item.kind, item.path.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![
+            (ItemKind::Module, "fs"),
+            (ItemKind::Class, "fs.FileSystemError"),
+            (ItemKind::Attribute, "fs.FileSystemError.message"),
+            (ItemKind::Function, "fs.readFile"),
+        ]
+    );
+    let read_file = &pile.items[0].members[1];
+    assert_eq!(
+        read_file.signature_text(),
+        "@function readFile<T implements Display>(p: Path, options: Array<T>) -> Result<String, FileSystemError>"
+    );
+    assert!(read_file
+        .signature
+        .contains(&Segment::Type(String::from("FileSystemError"))));
+    assert_eq!(read_file.summary(), "Reads a file.");
+    assert_eq!(read_file.file, "fs.peb");
+    assert_eq!(pile.link("FileSystemError"), Some("fs.FileSystemError"));
+    assert_eq!(pile.link("String"), None);
+}
+
+#[test]
+fn it_renders_html_with_cross_links() {
+    let page = html(&pile());
+    assert!(page.contains("<section id=\"fs.readFile\">"));
+    // Both the signature and the docs link to the error class.
+    assert!(
+        page.contains("Result&lt;String, <a href=\"#fs.FileSystemError\">FileSystemError</a>&gt;")
+    );
+    assert!(page.contains(
+        "<a href=\"#fs.FileSystemError\"><code>FileSystemError</code></a> if the file is missing."
+    ));
+    assert!(page.contains(
+        "<pre><code class=\"language-pebble\">@let content = fs.readFile(&quot;a.txt&quot;);</code></pre>"
+    ));
+    assert!(!page.contains("hidden"));
+    assert!(page.contains("const INDEX=[{"));
+}
+
+#[test]
+fn it_renders_markdown_with_cross_links() {
+    let page = markdown(&pile());
+    assert!(page.starts_with("# core\n"));
+    assert!(page.contains(
+        "- [`fs`](#fs) — Working with files.\n  - [`fs.FileSystemError`](#fs.FileSystemError)"
+    ));
+    assert!(page.contains("<a id=\"fs.readFile\"></a>\n\n### Function `fs.readFile`"));
+    assert!(page
+        .contains("Fails with a [`FileSystemError`](#fs.FileSystemError) if the file is missing."));
+    assert!(page.contains("Uses: [`FileSystemError`](#fs.FileSystemError)\n"));
+}
+
+#[test]
+fn it_builds_a_search_index() {
+    let index: serde_json::Value = serde_json::from_str(&search_index(&pile())).unwrap();
+    let entries = index.as_array().unwrap();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(entries[3]["path"], "fs.readFile");
+    assert_eq!(entries[3]["kind"], "function");
+    assert_eq!(entries[3]["href"], "#fs.readFile");
+    assert_eq!(entries[2]["signature"], "message: String");
+}
+
+const EXAMPLES: &str = "## Reads a file.
+## ```pebble
+## @let content = fs.readFile(\"a.txt\");
+## ```
+## ```text
+## Not Pebble.
+## ```
+@public @function readFile() {}
+    ## Greets.
+    ## ```pebble
+    ## @let greeting = \"Hello\"
+    ## println greeting;
+    ## ```
+    ## ```pebble,ignore
+    ## @let =
+    ## ```
+    @function greet() {}
+";
+
+#[test]
+fn it_extracts_examples_from_doc_comments() {
+    let mut sources = SourceMap::new();
+    let file = sources.add("examples.peb", EXAMPLES);
+    let tests = extract(sources.get(file));
+    assert_eq!(tests.len(), 3);
+    assert_eq!(tests[0].name(&sources), "examples.peb:2 (readFile)");
+    assert_eq!(tests[0].code, "@let content = fs.readFile(\"a.txt\");");
+    assert!(tests[0].run(&[]).is_empty());
+    assert_eq!(tests[1].item.as_deref(), Some("greet"));
+    assert_eq!(
+        tests[1].code,
+        "@let greeting = \"Hello\"\nprintln greeting;"
+    );
+    assert!(!tests[1].ignore);
+    assert!(tests[2].ignore);
+}
+
+#[test]
+fn it_locates_failures_in_the_doc_comment() {
+    let mut sources = SourceMap::new();
+    let file = sources.add("examples.peb", EXAMPLES);
+    let tests = extract(sources.get(file));
+    let failures = tests[1].run(&[]);
+    assert_eq!(failures.len(), 1);
+    let failure = &failures[0];
+    assert_eq!(
+        failure.diagnostic,
+        Diagnostic::Syntax(
+            SyntaxError::ExpectedSemiColon,
+            Span::new(FileId::default(), 24, 31)
+        )
+    );
+    assert_eq!(sources.slice(failure.span), "println");
+    assert_eq!(
+        sources.start(failure.span, Encoding::Chars),
+        LineColumn {
+            line: 11,
+            column: 7
+        }
+    );
+}
+
+#[test]
+fn it_runs_checks_after_an_example_parses() {
+    let mut sources = SourceMap::new();
+    let file = sources.add("examples.peb", EXAMPLES);
+    let tests = extract(sources.get(file));
+    let check: Check = Box::new(|program| {
+        vec![Diagnostic::Syntax(
+            SyntaxError::ExpectedExpression,
+            program.statements()[0].get_range(),
+        )]
+    });
+    let failures = tests[0].run(&[check]);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(
+        sources.slice(failures[0].span),
+        "@let content = fs.readFile(\"a.txt\")"
+    );
+}
+
+#[test]
+fn it_fails_examples_that_use_undeclared_names() {
+    let mut sources = SourceMap::new();
+    let file = sources.add(
+        "examples.peb",
+        "## Greets.
+## ```pebble
+## @let name = \"Pebble\";
+## println greet(name);
+## ```
+## ```pebble
+## println core;
+## ```
+@function greet(name: String) {}
+",
+    );
+    let tests = extract(sources.get(file));
+    let failures = tests[0].run(&[Box::new(resolve_names)]);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(
+        failures[0].diagnostic,
+        Diagnostic::Resolver(
+            ResolverError::UndefinedVariable("greet".to_string()),
+            Span::new(FileId::default(), 30, 35)
+        )
+    );
+    assert_eq!(sources.slice(failures[0].span), "greet");
+    assert_eq!(
+        sources.start(failures[0].span, Encoding::Chars),
+        LineColumn {
+            line: 3,
+            column: 11
+        }
+    );
+    assert!(tests[1].run(&[Box::new(resolve_names)]).is_empty());
+}
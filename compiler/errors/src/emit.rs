@@ -6,6 +6,8 @@ use std::{
     path::Path,
 };
 
+use utils::Span;
+
 use crate::Error;
 
 pub enum Mode {
@@ -14,6 +16,6 @@ pub enum Mode {
 }
 
 /// Emits an error to the console.
-pub fn emit_error<T: Error + Display>(error: T, span: Option<[[u64; 2]; 2]>, source: (Mode, &str)) {
+pub fn emit_error<T: Error + Display>(error: T, span: Option<Span>, source: (Mode, &str)) {
     if let Mode::File = source.0 {}
 }
@@ -1,24 +1,53 @@
 mod _trait;
 mod emit;
 mod resolve;
-mod types;
 mod syntax;
+mod types;
 
 use std::fmt::Display;
 
 pub use _trait::*;
 pub use emit::*;
 pub use resolve::*;
-pub use types::*;
 pub use syntax::*;
+pub use types::*;
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum LexicalError {
     /// A character not included in the language specification has been encountered.
     UnknownToken(String),
     /// A String does not have a closing quote mark.
     UnterminatedStringLiteral,
+    /// A character does not have a closing quote mark.
+    UnterminatedCharacterLiteral,
+    /// A block comment does not have a closing `*/`.
+    UnterminatedBlockComment,
     /// A character token has more than one character in its body.
     InvalidCharacterCount,
+    /// A backslash is followed by a character that does not form a known escape sequence.
+    UnknownEscapeSequence(char),
+    /// A `\x` escape is not followed by two hexadecimal digits, or represents a value outside the ASCII range.
+    InvalidHexEscape,
+    /// A `\u` escape is not followed by four hexadecimal digits or a braced code point.
+    InvalidUnicodeEscape,
+    /// A `\u` escape represents a surrogate or a value above `10FFFF`.
+    InvalidUnicodeScalar(String),
+    /// The opening quotes of a multi-line string are followed by text on the same line.
+    MultiLineStringStart,
+    /// A hexadecimal, binary or octal prefix is not followed by any digits, e.g. `0x`.
+    MissingDigits(String),
+    /// A digit is not valid in the radix of its number, e.g. the `2` in `0b102`.
+    InvalidDigit(char, u32),
+    /// A decimal point is not followed by any digits, e.g. `1.`.
+    MissingFraction,
+    /// An exponent is not followed by any digits, e.g. `1e`.
+    MissingExponent,
+    /// A hexadecimal, binary or octal number has a fractional part or an exponent.
+    NonDecimalFloat,
+    /// A `_` digit separator is not placed between two digits.
+    MisplacedDigitSeparator,
+    /// A number is too large to be stored in its numeric type.
+    NumberOutOfRange,
 }
 
 impl Display for LexicalError {
@@ -28,10 +57,35 @@ impl Display for LexicalError {
             "{}.",
             match self {
                 LexicalError::UnknownToken(x) => format!("Unexpected token {x}"),
-                LexicalError::UnterminatedStringLiteral => format!("String has no closing quotes"),
-                LexicalError::InvalidCharacterCount => format!(
+                LexicalError::UnterminatedStringLiteral =>
+                    "String has no closing quotes".to_string(),
+                LexicalError::UnterminatedCharacterLiteral =>
+                    "Character has no closing quote".to_string(),
+                LexicalError::UnterminatedBlockComment =>
+                    "Block comment has no closing */".to_string(),
+                LexicalError::InvalidCharacterCount =>
                     "Invalid character. Characters can only consist of a single..well, character"
+                        .to_string(),
+                LexicalError::UnknownEscapeSequence(x) => format!("Unknown escape sequence \\{x}"),
+                LexicalError::InvalidHexEscape => "Invalid escape. \\x must be followed by two hexadecimal digits, not greater than 7F".to_string(),
+                LexicalError::InvalidUnicodeEscape => "Invalid escape. \\u must be followed by four hexadecimal digits, or a code point within braces, e.g. \\u{1F600}".to_string(),
+                LexicalError::InvalidUnicodeScalar(x) => format!("Invalid escape. '{x}' is not a valid Unicode scalar value"),
+                LexicalError::MultiLineStringStart => "Multi-line strings begin on the line after their opening quotes".to_string(),
+                LexicalError::MissingDigits(x) => format!("Expected digits after {x}"),
+                LexicalError::InvalidDigit(digit, radix) => format!(
+                    "'{digit}' is not a valid digit in a {} number",
+                    match radix {
+                        2 => "binary",
+                        8 => "octal",
+                        16 => "hexadecimal",
+                        _ => "decimal",
+                    }
                 ),
+                LexicalError::MissingFraction => "Expected digits after the decimal point".to_string(),
+                LexicalError::MissingExponent => "Expected digits in the exponent".to_string(),
+                LexicalError::NonDecimalFloat => "Only decimal numbers can have a fractional part or an exponent".to_string(),
+                LexicalError::MisplacedDigitSeparator => "Digit separators can only be placed between digits".to_string(),
+                LexicalError::NumberOutOfRange => "Number is too large to be represented".to_string(),
             }
         )
     }
@@ -1,19 +1,74 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum ResolverError {
-    UndefinedVariable,
-    UseBeforeAssign,
-    UndefinedType,
-    UsingTypeAsVariable,
-    UsingVariableAsType,
-    CannotFindFile,
-    NoSuchExport,
-    PrivateExport,
+    /// A name is used as a value, but nothing with that name is visible where it is used.
+    UndefinedVariable(String),
+    /// A variable is used in its own initializer, before it has a value.
+    UseBeforeAssign(String),
+    /// A name is used as a type, but no type with that name is visible where it is used.
+    UndefinedType(String),
+    /// A name is used after `::`, but the enum or module before it has no member with that name.
+    UndefinedMember(String, String),
+    /// A name that only stands for a type, such as an interface, is used as a value.
+    UsingTypeAsVariable(String),
+    /// A name that only stands for a value, such as a variable, is used as a type.
+    UsingVariableAsType(String),
+    CannotFindFile(String),
+    NoSuchExport(String),
+    PrivateExport(String),
     CircularImport,
     RecursiveImport,
     PrependError,
 }
 
+impl Display for ResolverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.",
+            match self {
+                ResolverError::UndefinedVariable(x) => format!("Cannot find a value named '{x}'"),
+                ResolverError::UseBeforeAssign(x) =>
+                    format!("'{x}' is used in its own initializer, before it is assigned"),
+                ResolverError::UndefinedType(x) => format!("Cannot find a type named '{x}'"),
+                ResolverError::UndefinedMember(owner, x) =>
+                    format!("'{owner}' has no member named '{x}'"),
+                ResolverError::UsingTypeAsVariable(x) =>
+                    format!("'{x}' is a type, and cannot be used as a value"),
+                ResolverError::UsingVariableAsType(x) =>
+                    format!("'{x}' is a value, and cannot be used as a type"),
+                ResolverError::CannotFindFile(x) => format!("Cannot find the file '{x}'"),
+                ResolverError::NoSuchExport(x) => format!("'{x}' is not declared in that file"),
+                ResolverError::PrivateExport(x) =>
+                    format!("'{x}' is not public, so it cannot be imported"),
+                ResolverError::CircularImport => "Files cannot import each other".to_string(),
+                ResolverError::RecursiveImport => "A file cannot import itself".to_string(),
+                ResolverError::PrependError => "The file cannot be prepended".to_string(),
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum ResolverWarnings {
-    UnusedVariable,
+    /// A variable, parameter or import is declared, but never used.
+    UnusedVariable(String),
     DeadCode,
     InvalidCase,
 }
+
+impl Display for ResolverWarnings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.",
+            match self {
+                ResolverWarnings::UnusedVariable(x) => format!("'{x}' is never used"),
+                ResolverWarnings::DeadCode => "This code will never run".to_string(),
+                ResolverWarnings::InvalidCase =>
+                    "The name is not written in the expected case".to_string(),
+            }
+        )
+    }
+}
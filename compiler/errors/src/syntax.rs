@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SyntaxError {
     NamedFunctionExpr,
     UninitializedTypeAlias,
@@ -108,3 +108,23 @@ impl Display for SyntaxError {
         )
     }
 }
+
+/// A problem in the source text that does not stop it from being parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxWarning {
+    /// A doc comment is not followed by a declaration it could document.
+    OrphanedDocComment,
+}
+
+impl Display for SyntaxWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.",
+            match self {
+                SyntaxWarning::OrphanedDocComment =>
+                    "Doc comment does not document anything. Doc comments must be placed directly before a declaration",
+            }
+        )
+    }
+}
@@ -43,10 +43,10 @@ where
             "{}.",
             match self {
                 TypeError::UnequalGenericArgs(x, y, z) => format!("Unequal generic arguments. Expected {y} arguments for '{x}' and got {z}"),
-                TypeError::AssignmentToConst => format!("Assignment to constant variable or parameter"),
+                TypeError::AssignmentToConst => "Assignment to constant variable or parameter".to_string(),
                 TypeError::UnexpectedGenerics(x) => format!("Unexpected arguments for type '{x}'. '{x}' has no generic arguments"),
                 TypeError::Unaddable(x, y) => format!("Cannot add types '{x}' and '{y}'"),
-                TypeError::OperationOnNil => format!("Cannot perform operation on possibly nil values"),
+                TypeError::OperationOnNil => "Cannot perform operation on possibly nil values".to_string(),
                 TypeError::UnsupportedOperation(op, x, y) => format!("The operation '{op}' is not defined for types '{x}' and '{y}'"),
                 TypeError::UnsupportedUnaryOperation(op, x) => format!("The operation {op} is not defined for '{x}'"),
                 TypeError::InvalidTernaryTest(x) => format!("Expected Boolean type for ternary operation test, got '{x}'"),
@@ -56,11 +56,11 @@ where
                 TypeError::Uninitialized(x) => format!("'{x}' is being used before it is initialized"),
                 TypeError::InvalidIndex(x) => format!("The type '{x}' is not an indexable type"),
                 TypeError::InvalidIndexer(x) => format!("The type '{x}' cannot be used as an index"),
-                TypeError::InvalidRangeBoundaries => format!("Invalid range. The boundaries of a range must be both be either characters or numbers"),
+                TypeError::InvalidRangeBoundaries => "Invalid range. The boundaries of a range must be both be either characters or numbers".to_string(),
                 TypeError::Undeclared(x) => format!("'{x}' is not defined"),
                 TypeError::AlreadyDeclared(x) => format!("'{x}' has  already been declared in this scope"),
                 TypeError::UnknownAssignment(x) => format!("Cannot infer the type of '{x}' from its usage."),
-                TypeError::IllegalTestBlock => format!("Invalid @tests block. Test blocks can only be used in the global scope of a module or file"),
+                TypeError::IllegalTestBlock => "Invalid @tests block. Test blocks can only be used in the global scope of a module or file".to_string(),
                 TypeError::Unassignable(x, y) => format!("Type '{y}' cannot be assigned to type '{x}'"),
                 TypeError::Uncallable(x) => format!("'{x}' is not a callable type"),
                 TypeError::UnsatisfiedGenericConstraint(x, y) => format!("{x} does not satisfy the generic constraint because it does not implement '{y}'"),
@@ -69,7 +69,7 @@ where
                 TypeError::HeterogenousArray(x, y) => format!(
                     "Elements of type '{x}' and '{y}' cannot be put in the same array. Arrays can only contain elements of the same type",
                 ),
-                TypeError::AssigningToNil => format!("Cannot assign nil value to variable or constant"),
+                TypeError::AssigningToNil => "Cannot assign nil value to variable or constant".to_string(),
             }
         )
     }
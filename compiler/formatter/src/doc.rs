@@ -0,0 +1,171 @@
+/// A layout of text, in which groups of lines are broken only when they do not fit in the line width.<br>
+/// It follows Wadler's "prettier printer": every group is first tried flat, with each of its lines printed as a space or as nothing, and is broken when the rest of the line would overflow.
+#[derive(Debug, Clone)]
+pub enum Doc {
+    Text(String),
+    /// A space, or a line break when the group it belongs to is broken.
+    Line,
+    /// Nothing, or a line break when the group it belongs to is broken.
+    SoftLine,
+    /// A line break that is always taken, and that breaks every group around it.
+    HardLine,
+    /// Text that is only printed when the group it belongs to is broken, e.g. a trailing comma.
+    IfBreak(&'static str),
+    /// Indents the lines within it by one level.
+    Nest(Box<Doc>),
+    Group {
+        doc: Box<Doc>,
+        breaks: bool,
+    },
+    Concat(Vec<Doc>),
+}
+
+pub fn text(text: impl Into<String>) -> Doc {
+    Doc::Text(text.into())
+}
+
+pub fn concat(docs: Vec<Doc>) -> Doc {
+    Doc::Concat(docs)
+}
+
+pub fn nest(doc: Doc) -> Doc {
+    Doc::Nest(Box::new(doc))
+}
+
+/// Groups a layout, so that its lines are broken together. A group that holds a hard line is always broken.
+pub fn group(doc: Doc) -> Doc {
+    let breaks = doc.has_hard_line();
+    Doc::Group {
+        doc: Box::new(doc),
+        breaks,
+    }
+}
+
+/// Lays out a layout on a single line, as if every group in it fitted. Hard lines are still taken.
+pub fn flat(doc: Doc) -> Doc {
+    match doc {
+        Doc::Line => text(" "),
+        Doc::SoftLine | Doc::IfBreak(_) => concat(vec![]),
+        Doc::Nest(doc) => nest(flat(*doc)),
+        Doc::Group { doc, .. } => flat(*doc),
+        Doc::Concat(docs) => concat(docs.into_iter().map(flat).collect()),
+        doc @ (Doc::Text(_) | Doc::HardLine) => doc,
+    }
+}
+
+/// Joins layouts with a separator.
+pub fn join(docs: Vec<Doc>, separator: impl Fn() -> Doc) -> Doc {
+    let mut joined = Vec::with_capacity(docs.len() * 2);
+    for (index, doc) in docs.into_iter().enumerate() {
+        if index > 0 {
+            joined.push(separator());
+        }
+        joined.push(doc);
+    }
+    concat(joined)
+}
+
+impl Doc {
+    fn has_hard_line(&self) -> bool {
+        match self {
+            Self::HardLine => true,
+            Self::Group { breaks, .. } => *breaks,
+            Self::Nest(doc) => doc.has_hard_line(),
+            Self::Concat(docs) => docs.iter().any(Self::has_hard_line),
+            Self::Text(text) => text.contains('\n'),
+            Self::Line | Self::SoftLine | Self::IfBreak(_) => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// Lays out a document in lines of at most `width` characters where possible, indenting each level by `indent` spaces.
+pub fn render(doc: &Doc, width: usize, indent: usize) -> String {
+    let mut writer = Writer::default();
+    let mut stack = vec![(0, Mode::Break, doc)];
+    while let Some((level, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Text(text) => writer.text(text),
+            Doc::Line if mode == Mode::Flat => writer.text(" "),
+            Doc::SoftLine | Doc::IfBreak(_) if mode == Mode::Flat => {}
+            Doc::IfBreak(text) => writer.text(text),
+            Doc::Line | Doc::SoftLine | Doc::HardLine => writer.line(level),
+            Doc::Nest(doc) => stack.push((level + indent, mode, doc)),
+            Doc::Group { doc, breaks } => {
+                let remaining = width as isize - writer.column as isize;
+                let flat = !breaks && (mode == Mode::Flat || fits(remaining, doc, &stack));
+                stack.push((level, if flat { Mode::Flat } else { Mode::Break }, doc));
+            }
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (level, mode, doc))),
+        }
+    }
+    writer.output
+}
+
+#[derive(Default)]
+struct Writer {
+    output: String,
+    column: usize,
+    /// Indentation is written with the first text on a line, so that empty lines have no trailing spaces.
+    pending_indent: Option<usize>,
+}
+
+impl Writer {
+    fn text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if let Some(spaces) = self.pending_indent.take() {
+            self.output.push_str(&" ".repeat(spaces));
+            self.column = spaces;
+        }
+        self.output.push_str(text);
+        self.column = match text.rfind('\n') {
+            Some(newline) => text[newline + 1..].chars().count(),
+            None => self.column + text.chars().count(),
+        };
+    }
+    fn line(&mut self, level: usize) {
+        self.output.push('\n');
+        self.column = 0;
+        self.pending_indent = Some(level);
+    }
+}
+
+/// Checks if a group fits, flat, in the rest of the line, along with whatever follows it up to the next line break.
+fn fits(mut remaining: isize, doc: &Doc, rest: &[(usize, Mode, &Doc)]) -> bool {
+    let mut rest = rest.iter().rev().copied();
+    let mut stack = vec![(Mode::Flat, doc)];
+    loop {
+        if remaining < 0 {
+            return false;
+        }
+        let (mode, doc) = match stack.pop() {
+            Some(next) => next,
+            None => match rest.next() {
+                Some((_, mode, doc)) => (mode, doc),
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Text(text) => match text.find('\n') {
+                Some(newline) => return remaining >= text[..newline].chars().count() as isize,
+                None => remaining -= text.chars().count() as isize,
+            },
+            Doc::Line if mode == Mode::Flat => remaining -= 1,
+            Doc::SoftLine | Doc::IfBreak(_) if mode == Mode::Flat => {}
+            Doc::IfBreak(text) => remaining -= text.len() as isize,
+            Doc::Line | Doc::SoftLine | Doc::HardLine => return true,
+            Doc::Nest(doc) => stack.push((mode, doc)),
+            Doc::Group { doc, breaks } => {
+                stack.push((if *breaks { Mode::Break } else { mode }, doc));
+            }
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (mode, doc))),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+mod doc;
+mod printer;
+mod test;
+
+use std::fmt::Display;
+
+use parser::{parse_source, Diagnostic};
+
+use crate::{doc::render, printer::Printer};
+
+/// How formatted code is laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The number of characters a line should fit in. Lines are only longer when they cannot be broken.
+    pub width: usize,
+    /// The number of spaces in each level of indentation.
+    pub indent: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            width: 100,
+            indent: 4,
+        }
+    }
+}
+
+/// The reasons a file could not be formatted.
+#[derive(Debug, PartialEq)]
+pub enum FormatError {
+    /// The file has errors, so its meaning is unclear and it is left as it is.
+    Invalid(Vec<Diagnostic>),
+    /// The formatted text would not parse into the same syntax tree, or would lose a comment.
+    Unstable,
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(diagnostics) => {
+                let errors = diagnostics
+                    .iter()
+                    .filter(|diagnostic| diagnostic.is_error());
+                write!(
+                    f,
+                    "The file has {} error(s), so it was not formatted.",
+                    errors.count()
+                )
+            }
+            Self::Unstable => write!(
+                f,
+                "Formatting would change the meaning of the file, so it was not formatted."
+            ),
+        }
+    }
+}
+
+/// Formats the source text of a file.<br>
+/// Every comment is kept, and the formatted text is parsed again to make sure it means exactly what the source did, i.e. that it has the same syntax tree, apart from where each node is written.
+pub fn format(source: &str, options: &Options) -> Result<String, FormatError> {
+    let program = parse_source(source);
+    if !program.is_valid() {
+        return Err(FormatError::Invalid(program.diagnostics));
+    }
+    let doc = Printer::new(source).program(program.statements());
+    let mut formatted = render(&doc, options.width, options.indent)
+        .trim_start_matches('\n')
+        .to_string();
+    if !formatted.is_empty() {
+        formatted.push('\n');
+    }
+    let reparsed = parse_source(&formatted);
+    let same = reparsed.is_valid()
+        && reparsed.comments.len() == program.comments.len()
+        && reparsed.to_arena().same_tree(&program.to_arena());
+    if !same {
+        return Err(FormatError::Unstable);
+    }
+    Ok(formatted)
+}
@@ -0,0 +1,898 @@
+use ast::{
+    ArrayExpression, AssignmentExpression, Attribute, BinaryExpression, Block, Boolean,
+    BracketKind, CallExpression, Character, Class, ConcreteType, CrashStatement, DotExpression,
+    DotType, Enum, Expression, ExpressionStatement, FnExpression, ForLoop, Function, FunctionType,
+    GenericArgument, Identifier, IfStatement, Implement, Import, IndexExpression, Interface,
+    Keyword, LiteralKind, Location, LogicalExpression, Loop, Mapping, Method, Module,
+    NamespaceExpression, Number, Operator, Parameter, PrependStatement, PrintLnStatement, Property,
+    PublicModifier, RangeExpression, Record, RecoverBlock, ReturnStatement, Statement,
+    TemplatePart, TemplateString, TernaryExpression, TestBlock, TextString, TokenKind, TryBlock,
+    Type, TypeAlias, UnaryExpression, UseImport, VarKind, VariableDeclaration, Variant,
+    WhileStatement,
+};
+use parser::Scanner;
+use utils::Span;
+
+use crate::doc::{concat, flat, group, join, nest, text, Doc};
+
+/// The binding power at an edge of an expression that nothing can take part of, such as a name or the `)` of a call.
+const CLOSED: u8 = u8::MAX;
+
+/// A printed expression, with how tightly the operators at its left and right edges bind.<br>
+/// The syntax tree does not record parentheses, so they are put back wherever an operator next to the expression would bind more tightly than the operator at that edge, and would take part of the expression away when the output is parsed again.
+struct Operand {
+    doc: Doc,
+    left: u8,
+    right: u8,
+}
+
+impl Operand {
+    fn closed(doc: Doc) -> Self {
+        Self {
+            doc,
+            left: CLOSED,
+            right: CLOSED,
+        }
+    }
+    fn parenthesized(self) -> Self {
+        Self::closed(concat(vec![text("("), self.doc, text(")")]))
+    }
+}
+
+/// Lays out a parsed file, taking the text of literals and comments from its tokens.
+pub struct Printer<'s> {
+    source: &'s str,
+    scanner: Scanner,
+    /// The index of the first comment that has not been printed.
+    next_comment: usize,
+}
+
+impl<'s> Printer<'s> {
+    pub fn new(source: &'s str) -> Self {
+        let mut scanner = Scanner::new(source);
+        scanner.run();
+        Self {
+            source,
+            scanner,
+            next_comment: 0,
+        }
+    }
+    /// Lays out the top-level statements of a file, followed by any comments after them.
+    pub fn program(&mut self, statements: &[Statement]) -> Doc {
+        concat(self.statements(statements, None))
+    }
+}
+
+/// Comments and spacing
+impl Printer<'_> {
+    /// Returns the index of the token that begins at a position.
+    fn token_at(&self, position: usize) -> Option<usize> {
+        let tokens = &self.scanner.tokens;
+        let index = tokens.partition_point(|token| token.span.start < position);
+        (index < tokens.len() && tokens[index].span.start == position).then_some(index)
+    }
+    /// Returns the byte offset of a node that begins at a position, including any modifiers written before it, such as `static`.
+    fn offset_of(&self, position: usize) -> Option<usize> {
+        let mut index = self.token_at(position)?;
+        let tokens = &self.scanner.tokens;
+        while index > 0
+            && (tokens[index - 1].is_keyword(&Keyword::Static)
+                || tokens[index - 1].is_keyword(&Keyword::ReadOnly))
+        {
+            index -= 1;
+        }
+        Some(tokens[index].span.start)
+    }
+    /// Returns the whitespace written directly before a byte offset.
+    fn whitespace_before(&self, offset: usize) -> &str {
+        let before = &self.source[..offset];
+        &before[before.trim_end().len()..]
+    }
+    /// Begins a new line, keeping a single blank line where the source had one or more before `offset`.
+    fn separator(&self, offset: Option<usize>) -> Doc {
+        let blank =
+            offset.is_some_and(|offset| self.whitespace_before(offset).matches('\n').count() > 1);
+        match blank {
+            true => concat(vec![Doc::HardLine, Doc::HardLine]),
+            false => Doc::HardLine,
+        }
+    }
+    /// Prints every comment that begins before `end`, or every comment left if there is no end.<br>
+    /// A comment written on the same line as the code before it stays at the end of that line, and every other comment is given a line of its own.
+    fn comments_before(&mut self, end: Option<usize>, parts: &mut Vec<Doc>) {
+        while let Some(comment) = self.scanner.comments.get(self.next_comment) {
+            if end.is_some_and(|end| comment.span.start >= end) {
+                break;
+            }
+            let range = comment.span.range();
+            let content = self.source[range.clone()].trim_end_matches('\r');
+            let whitespace = self.whitespace_before(range.start);
+            let trailing = whitespace.len() < range.start && !whitespace.contains('\n');
+            if trailing {
+                parts.push(text(format!(" {content}")));
+            } else {
+                parts.push(self.separator(Some(range.start)));
+                parts.push(text(content));
+            }
+            self.next_comment += 1;
+        }
+    }
+    /// Prints statements one on each line, along with the comments before them and any comments after them that begin before `end`.
+    fn statements(&mut self, statements: &[Statement], end: Option<usize>) -> Vec<Doc> {
+        let mut parts = vec![];
+        let mut previous = None;
+        for statement in statements {
+            let start = statement.get_range().start;
+            self.comments_before(Some(start), &mut parts);
+            parts.push(self.separator(self.offset_of(start)));
+            let doc = match (previous, statement) {
+                // The statement before takes the first semi-colon as its own ending.
+                (Some(previous), Statement::EmptyStatement(_)) if takes_semi_colon(previous) => {
+                    text(";;")
+                }
+                _ => self.statement(statement),
+            };
+            parts.push(doc);
+            previous = Some(statement);
+        }
+        self.comments_before(end, &mut parts);
+        parts
+    }
+    /// Prints the comma-separated members of a class, interface, enum or record in braces, one on each line.
+    fn members<T: Location>(
+        &mut self,
+        members: &[T],
+        end: usize,
+        mut member: impl FnMut(&mut Self, &T) -> Doc,
+    ) -> Doc {
+        let mut parts = vec![];
+        for (index, item) in members.iter().enumerate() {
+            let start = item.get_range().start;
+            self.comments_before(Some(start), &mut parts);
+            parts.push(self.separator(self.offset_of(start)));
+            parts.push(member(self, item));
+            if index + 1 < members.len() {
+                parts.push(text(","));
+            }
+        }
+        self.comments_before(Some(end), &mut parts);
+        braces(parts)
+    }
+    /// Returns the text of a literal token exactly as it is written, including its quotes and escape sequences.
+    fn literal(&self, span: Span) -> &str {
+        match self.token_at(span.start) {
+            Some(index) => &self.source[self.scanner.tokens[index].span.range()],
+            None => unreachable!("every literal begins at a token"),
+        }
+    }
+    /// Prints the block comments that begin before a position inside an expression, so that they stay between the same operands.<br>
+    /// A line comment would hide the rest of the expression, so it and the comments after it are left to be printed at the end of the statement.
+    fn inline_comments(&mut self, end: usize, leading: bool) -> Option<Doc> {
+        let mut parts = vec![];
+        while let Some(comment) = self.scanner.comments.get(self.next_comment) {
+            let content = &self.source[comment.span.range()];
+            if comment.span.start >= end || !content.starts_with("/*") {
+                break;
+            }
+            parts.push(match leading {
+                true => text(format!("{content} ")),
+                false => text(format!(" {content}")),
+            });
+            self.next_comment += 1;
+        }
+        (!parts.is_empty()).then(|| concat(parts))
+    }
+    /// Prints the block comments between an expression and the token after it, such as an operator or a comma.
+    /// Parentheses that close around the expression are skipped, as the comments may not be printed inside them.
+    fn trailing_comments(&mut self, expression: &Expression) -> Option<Doc> {
+        let tokens = &self.scanner.tokens;
+        let index = tokens.partition_point(|token| token.span.start < expression.get_range().end);
+        let next = tokens[index..]
+            .iter()
+            .find(|token| !token.is_bracket(&BracketKind::RightParenthesis))?;
+        self.inline_comments(next.span.start, false)
+    }
+    /// Returns the index of the text segment of an interpolated string that ends the expression embedded at a position.
+    fn segment_after(&self, position: usize) -> usize {
+        let tokens = &self.scanner.tokens;
+        let mut depth = 0;
+        let start = tokens.partition_point(|token| token.span.start < position);
+        for (index, token) in tokens.iter().enumerate().skip(start) {
+            if let TokenKind::Literal(literal) = &token.kind {
+                match literal.kind {
+                    LiteralKind::TemplateHead => depth += 1,
+                    LiteralKind::TemplateMiddle | LiteralKind::TemplateTail if depth == 0 => {
+                        return index
+                    }
+                    LiteralKind::TemplateTail => depth -= 1,
+                    _ => {}
+                }
+            }
+        }
+        unreachable!("every embedded expression is closed in a valid file")
+    }
+    /// Prints an interpolated string, keeping the text between its embedded expressions exactly as it is written, and laying out the expressions on one line.
+    fn template(&mut self, template: &TemplateString) -> Doc {
+        let Some(mut segment) = self.token_at(template.span.start) else {
+            unreachable!("every interpolated string begins at a token")
+        };
+        let mut parts = vec![];
+        let expressions = template.parts.iter().filter_map(|part| match part {
+            TemplatePart::Expression(expression) => Some(expression),
+            TemplatePart::Text(_) => None,
+        });
+        for expression in expressions {
+            parts.push(text(
+                &self.source[self.scanner.tokens[segment].span.range()],
+            ));
+            parts.push(flat(self.expression(expression)));
+            segment = self.segment_after(expression.get_range().end);
+        }
+        parts.push(text(
+            &self.source[self.scanner.tokens[segment].span.range()],
+        ));
+        concat(parts)
+    }
+}
+
+/// Statements
+impl Printer<'_> {
+    fn statement(&mut self, statement: &Statement) -> Doc {
+        match statement {
+            Statement::Function(Function {
+                name,
+                generic_arguments,
+                parameters,
+                return_type,
+                body,
+                ..
+            }) => concat(vec![
+                text("@function "),
+                text(&name.value),
+                self.signature(generic_arguments, parameters, return_type.as_ref()),
+                text(" "),
+                self.block(body),
+            ]),
+            Statement::Class(Class {
+                name,
+                generic_arguments,
+                properties,
+                span,
+                ..
+            })
+            | Statement::Interface(Interface {
+                name,
+                generic_arguments,
+                properties,
+                span,
+                ..
+            }) => {
+                let injunction = match statement {
+                    Statement::Class(_) => "@class ",
+                    _ => "@interface ",
+                };
+                concat(vec![
+                    text(injunction),
+                    text(&name.value),
+                    self.generic_arguments(generic_arguments),
+                    text(" "),
+                    self.members(properties, span.end, Self::property),
+                ])
+            }
+            Statement::Enum(Enum {
+                name,
+                generic_arguments,
+                variants,
+                span,
+                ..
+            }) => concat(vec![
+                text("@enum "),
+                text(&name.value),
+                self.generic_arguments(generic_arguments),
+                text(" "),
+                self.members(variants, span.end, Self::variant),
+            ]),
+            Statement::Record(Record {
+                name,
+                mappings,
+                span,
+                ..
+            }) => concat(vec![
+                text("@record "),
+                text(&name.value),
+                text(" "),
+                self.members(mappings, span.end, Self::mapping),
+            ]),
+            Statement::Module(Module { name, body, .. }) => concat(vec![
+                text("@module "),
+                text(&name.value),
+                text(" "),
+                self.block(body),
+            ]),
+            Statement::TypeAlias(TypeAlias {
+                name,
+                generic_arguments,
+                value,
+                ..
+            }) => concat(vec![
+                text("@type "),
+                text(&name.value),
+                self.generic_arguments(generic_arguments),
+                text(" = "),
+                self.type_(value),
+                text(";"),
+            ]),
+            Statement::VariableDeclaration(VariableDeclaration {
+                name,
+                kind,
+                initializer,
+                type_label,
+                ..
+            }) => {
+                let mut parts = vec![
+                    text(match kind {
+                        VarKind::Let => "@let ",
+                        VarKind::Const => "@const ",
+                    }),
+                    text(&name.value),
+                ];
+                if let Some(type_label) = type_label {
+                    parts.push(text(": "));
+                    parts.push(self.type_(type_label));
+                }
+                if let Some(initializer) = initializer {
+                    parts.push(text(" = "));
+                    parts.push(self.expression(initializer));
+                }
+                parts.push(text(";"));
+                concat(parts)
+            }
+            Statement::UseImport(UseImport {
+                imports, source, ..
+            }) => {
+                let imports = imports.iter().map(import).collect::<Vec<_>>();
+                concat(vec![
+                    text("@use "),
+                    group(concat(vec![
+                        text("{"),
+                        nest(concat(vec![
+                            Doc::Line,
+                            join(imports, || concat(vec![text(","), Doc::Line])),
+                        ])),
+                        Doc::IfBreak(","),
+                        Doc::Line,
+                        text("}"),
+                    ])),
+                    text(" from "),
+                    text(self.literal(source.span)),
+                    text(";"),
+                ])
+            }
+            Statement::PrependStatement(PrependStatement { source, .. }) => {
+                concat(vec![text("@prepend "), self.expression(source), text(";")])
+            }
+            Statement::TestBlock(TestBlock { body, .. }) => {
+                concat(vec![text("@tests "), self.block(body)])
+            }
+            Statement::PublicModifier(PublicModifier { statement, .. }) => {
+                concat(vec![text("@public "), self.statement(statement)])
+            }
+            Statement::IfStatement(IfStatement {
+                test,
+                body,
+                alternate,
+                ..
+            }) => {
+                let mut parts = vec![
+                    text("if "),
+                    self.condition(test),
+                    text(" "),
+                    self.statement(body),
+                ];
+                if let Some(alternate) = alternate {
+                    // An if without an else inside the body would take the else for its own, unless a semi-colon ends it first.
+                    if ends_with_open_if(body) {
+                        parts.push(text(";"));
+                    }
+                    parts.push(text(" else "));
+                    parts.push(self.statement(alternate));
+                }
+                concat(parts)
+            }
+            Statement::WhileLoop(WhileStatement { test, body, .. }) => concat(vec![
+                text("while "),
+                self.condition(test),
+                text(" "),
+                self.statement(body),
+            ]),
+            Statement::ForLoop(ForLoop {
+                item,
+                iterator,
+                body,
+                ..
+            }) => concat(vec![
+                text("for ("),
+                text(&item.value),
+                text(" in "),
+                self.expression(iterator),
+                text(") "),
+                self.statement(body),
+            ]),
+            Statement::LoopStmnt(Loop {
+                constraint, body, ..
+            }) => {
+                let mut parts = vec![text("loop ")];
+                if let Some(constraint) = constraint {
+                    parts.push(self.condition(constraint));
+                    parts.push(text(" "));
+                }
+                parts.push(self.block(body));
+                concat(parts)
+            }
+            Statement::TryBlock(TryBlock { body, recover, .. }) => {
+                let mut parts = vec![text("try "), self.block(body)];
+                if let Some(RecoverBlock { params, body, .. }) = recover {
+                    parts.push(text(" recover"));
+                    parts.push(self.parameters(params));
+                    parts.push(text(" "));
+                    parts.push(self.block(body));
+                }
+                concat(parts)
+            }
+            Statement::PrintLnStatement(PrintLnStatement { argument, .. }) => {
+                concat(vec![text("println "), self.expression(argument), text(";")])
+            }
+            Statement::CrashStmnt(CrashStatement { argument, .. }) => {
+                concat(vec![text("crash "), self.expression(argument), text(";")])
+            }
+            Statement::ReturnStatement(ReturnStatement { argument, .. }) => match argument {
+                Some(argument) => {
+                    concat(vec![text("return "), self.expression(argument), text(";")])
+                }
+                None => text("return;"),
+            },
+            Statement::Break(_) => text("break;"),
+            Statement::Continue(_) => text("continue;"),
+            Statement::ExpressionStatement(ExpressionStatement { expression, .. }) => {
+                concat(vec![self.expression(expression), text(";")])
+            }
+            Statement::BlockStatement(block) => self.block(block),
+            Statement::EmptyStatement(_) => text(";"),
+            Statement::Error(_) => unreachable!("files with errors are not formatted"),
+        }
+    }
+    fn block(&mut self, block: &Block) -> Doc {
+        let parts = self.statements(&block.body, Some(block.span.end));
+        braces(parts)
+    }
+    /// Prints the condition of an if statement, a while loop or a loop in parentheses.
+    fn condition(&mut self, test: &Expression) -> Doc {
+        group(concat(vec![
+            text("("),
+            nest(concat(vec![Doc::SoftLine, self.expression(test)])),
+            Doc::SoftLine,
+            text(")"),
+        ]))
+    }
+    fn property(&mut self, property: &Property) -> Doc {
+        match property {
+            Property::Method(Method {
+                name,
+                generic_arguments,
+                is_static,
+                parameters,
+                return_type,
+                body,
+                ..
+            }) => concat(vec![
+                text(if *is_static { "static " } else { "" }),
+                text(&name.value),
+                self.signature(generic_arguments, parameters, return_type.as_ref()),
+                text(" "),
+                self.block(body),
+            ]),
+            Property::Attribute(Attribute {
+                key,
+                type_label,
+                is_static,
+                is_readonly,
+                value,
+                ..
+            }) => {
+                let mut parts = vec![
+                    text(if *is_static { "static " } else { "" }),
+                    text(if *is_readonly { "readonly " } else { "" }),
+                    text(&key.value),
+                ];
+                if let Some(type_label) = type_label {
+                    parts.push(text(": "));
+                    parts.push(self.type_(type_label));
+                }
+                if let Some(value) = value {
+                    parts.push(text(" = "));
+                    parts.push(self.expression(value));
+                }
+                concat(parts)
+            }
+            Property::Implement(Implement { interface, .. }) => {
+                text(format!("@implement {}", interface.value))
+            }
+        }
+    }
+    fn variant(&mut self, variant: &Variant) -> Doc {
+        match variant {
+            Variant::Tuple { name, elements, .. } => {
+                let elements = elements.iter().map(|element| self.type_(element)).collect();
+                concat(vec![text(&name.value), list("(", elements, ")")])
+            }
+            Variant::Concrete { name, .. } => text(&name.value),
+        }
+    }
+    fn mapping(&mut self, mapping: &Mapping) -> Doc {
+        concat(vec![
+            self.expression(&mapping.key),
+            text(" -> "),
+            self.expression(&mapping.value),
+        ])
+    }
+}
+
+/// Signatures and types
+impl Printer<'_> {
+    fn signature(
+        &mut self,
+        generic_arguments: &Option<Vec<GenericArgument>>,
+        parameters: &[Parameter],
+        return_type: Option<&Type>,
+    ) -> Doc {
+        let mut parts = vec![
+            self.generic_arguments(generic_arguments),
+            self.parameters(parameters),
+        ];
+        if let Some(return_type) = return_type {
+            parts.push(text(" -> "));
+            parts.push(self.type_(return_type));
+        }
+        concat(parts)
+    }
+    fn generic_arguments(&mut self, arguments: &Option<Vec<GenericArgument>>) -> Doc {
+        let Some(arguments) = arguments else {
+            return concat(vec![]);
+        };
+        let arguments = arguments
+            .iter()
+            .map(|argument| {
+                let implements = argument.implements.iter().flatten();
+                let implements = implements
+                    .map(|interface| interface.value.as_str())
+                    .collect::<Vec<_>>();
+                match implements.is_empty() {
+                    true => text(&argument.name.value),
+                    false => text(format!(
+                        "{} implements {}",
+                        argument.name.value,
+                        implements.join(" + ")
+                    )),
+                }
+            })
+            .collect();
+        list("<", arguments, ">")
+    }
+    fn parameters(&mut self, parameters: &[Parameter]) -> Doc {
+        let parameters = parameters
+            .iter()
+            .map(|parameter| match &parameter.label {
+                Some(label) => concat(vec![
+                    text(&parameter.name.value),
+                    text(": "),
+                    self.type_(label),
+                ]),
+                None => text(&parameter.name.value),
+            })
+            .collect();
+        list("(", parameters, ")")
+    }
+    fn type_(&mut self, type_: &Type) -> Doc {
+        match type_ {
+            Type::Concrete(ConcreteType {
+                name, arguments, ..
+            }) => {
+                if arguments.is_empty() {
+                    return text(&name.value);
+                }
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.type_(argument))
+                    .collect();
+                concat(vec![text(&name.value), list("<", arguments, ">")])
+            }
+            Type::Function(FunctionType {
+                parameters,
+                return_type,
+                generic_arguments,
+                ..
+            }) => self.signature(generic_arguments, parameters, Some(return_type)),
+            Type::Dot(DotType { levels, .. }) => {
+                let levels = levels.iter().map(|level| self.type_(level)).collect();
+                join(levels, || text("."))
+            }
+        }
+    }
+}
+
+/// Expressions
+impl Printer<'_> {
+    fn expression(&mut self, expression: &Expression) -> Doc {
+        self.operand(expression).doc
+    }
+    /// Prints an expression along with the block comments written before it.
+    fn operand(&mut self, expression: &Expression) -> Operand {
+        let comments = self.inline_comments(expression.get_range().start, true);
+        let mut printed = self.uncommented(expression);
+        if let Some(comments) = comments {
+            printed.doc = concat(vec![comments, printed.doc]);
+        }
+        printed
+    }
+    fn uncommented(&mut self, expression: &Expression) -> Operand {
+        match expression {
+            Expression::IdentifierExpression(Identifier { value, .. })
+            | Expression::BooleanExpression(Boolean { value, .. }) => Operand::closed(text(value)),
+            Expression::NumericExpression(Number { raw, .. }) => Operand::closed(text(raw)),
+            Expression::StringExpression(TextString { span, .. })
+            | Expression::CharacterExpression(Character { span, .. }) => {
+                Operand::closed(text(self.literal(*span)))
+            }
+            Expression::TemplateString(template) => Operand::closed(self.template(template)),
+            Expression::SelfExpression(_) => Operand::closed(text("self")),
+            Expression::BinaryExpression(BinaryExpression {
+                operator, operands, ..
+            })
+            | Expression::LogicalExpression(LogicalExpression {
+                operator, operands, ..
+            })
+            | Expression::AssignmentExpression(AssignmentExpression {
+                operator, operands, ..
+            }) => self.infix(operator, &operands[0], &operands[1]),
+            Expression::RangeExpression(RangeExpression { boundaries, .. }) => {
+                self.infix(&Operator::RangeBetween, &boundaries[0], &boundaries[1])
+            }
+            Expression::DotExpression(DotExpression {
+                object, property, ..
+            }) => self.infix(&Operator::Dot, object, property),
+            Expression::NamespaceExpression(NamespaceExpression {
+                object, property, ..
+            }) => self.infix(&Operator::Namespace, object, property),
+            Expression::UnaryExpression(UnaryExpression {
+                operator, operand, ..
+            }) => {
+                let power = Operator::prefix_binding_power();
+                let mut printed = self.operand(operand);
+                // A prefix before another would be scanned as one operator, e.g. `--a` for `-(-a)`.
+                if printed.left < power || matches!(**operand, Expression::UnaryExpression(_)) {
+                    printed = printed.parenthesized();
+                }
+                Operand {
+                    doc: concat(vec![text(operator.to_string()), printed.doc]),
+                    left: CLOSED,
+                    right: power.min(printed.right),
+                }
+            }
+            Expression::CallExpression(CallExpression {
+                callee, arguments, ..
+            }) => {
+                let arguments = self.items(arguments);
+                self.postfix(&Operator::Call, callee, list("(", arguments, ")"))
+            }
+            Expression::IndexExpression(IndexExpression {
+                accessor_and_property,
+                ..
+            }) => {
+                let property = self.expression(&accessor_and_property[1]);
+                let index = concat(vec![text("["), property, text("]")]);
+                self.postfix(&Operator::Index, &accessor_and_property[0], index)
+            }
+            Expression::ArrayExpression(ArrayExpression { elements, .. }) => {
+                let elements = self.items(elements);
+                Operand::closed(list("[", elements, "]"))
+            }
+            Expression::TernaryExpression(TernaryExpression {
+                test,
+                consequent,
+                alternate,
+                ..
+            }) => {
+                let (left_power, right_power) = power_of(&Operator::Confirm);
+                let mut test = self.operand(test);
+                if test.right <= left_power {
+                    test = test.parenthesized();
+                }
+                // The consequent is closed by the colon, so it never needs parentheses.
+                let consequent = self.expression(consequent);
+                let mut alternate = self.operand(alternate);
+                if alternate.left < right_power {
+                    alternate = alternate.parenthesized();
+                }
+                Operand {
+                    doc: group(concat(vec![
+                        test.doc,
+                        nest(concat(vec![
+                            Doc::Line,
+                            text("? "),
+                            consequent,
+                            Doc::Line,
+                            text(": "),
+                            alternate.doc,
+                        ])),
+                    ])),
+                    left: left_power.min(test.left),
+                    right: right_power.min(alternate.right),
+                }
+            }
+            Expression::FnExpression(FnExpression {
+                labels,
+                parameters,
+                return_type,
+                body,
+                implicit_return,
+                ..
+            }) => {
+                let mut parts = vec![
+                    text("fn"),
+                    self.signature(labels, parameters, return_type.as_ref()),
+                    text(" "),
+                ];
+                match (body, implicit_return) {
+                    (Some(body), _) => {
+                        parts.push(self.block(body));
+                        Operand::closed(concat(parts))
+                    }
+                    (None, Some(implicit_return)) => {
+                        parts.push(self.expression(implicit_return));
+                        // The returned expression takes every operator after it.
+                        Operand {
+                            doc: concat(parts),
+                            left: CLOSED,
+                            right: 0,
+                        }
+                    }
+                    (None, None) => unreachable!("a functional expression has a body"),
+                }
+            }
+            Expression::Error(_) => unreachable!("files with errors are not formatted"),
+        }
+    }
+    /// Prints an operator between two operands, wrapping either operand in parentheses if the operator would otherwise take part of it.
+    /// Prints the items of a list, each with the block comments written after it.
+    fn items(&mut self, expressions: &[Expression]) -> Vec<Doc> {
+        expressions
+            .iter()
+            .map(|expression| {
+                let doc = self.expression(expression);
+                match self.trailing_comments(expression) {
+                    Some(comments) => concat(vec![doc, comments]),
+                    None => doc,
+                }
+            })
+            .collect()
+    }
+    fn infix(&mut self, operator: &Operator, left: &Expression, right: &Expression) -> Operand {
+        let (left_power, right_power) = power_of(operator);
+        let mut left_operand = self.operand(left);
+        // A number followed by a dot would be scanned as a decimal point.
+        let is_number = matches!(left, Expression::NumericExpression(_));
+        if left_operand.right <= left_power || (is_number && operator == &Operator::Dot) {
+            left_operand = left_operand.parenthesized();
+        }
+        if let Some(comments) = self.trailing_comments(left) {
+            left_operand.doc = concat(vec![left_operand.doc, comments]);
+        }
+        let mut right_operand = self.operand(right);
+        if right_operand.left < right_power {
+            right_operand = right_operand.parenthesized();
+        }
+        let doc = match operator {
+            Operator::Dot | Operator::Namespace | Operator::RangeBetween => concat(vec![
+                left_operand.doc,
+                text(operator.to_string()),
+                right_operand.doc,
+            ]),
+            _ => group(concat(vec![
+                left_operand.doc,
+                text(format!(" {operator}")),
+                nest(concat(vec![Doc::Line, right_operand.doc])),
+            ])),
+        };
+        Operand {
+            doc,
+            left: left_power.min(left_operand.left),
+            right: right_power.min(right_operand.right),
+        }
+    }
+    /// Prints a call or an index after the expression it applies to.
+    fn postfix(&mut self, operator: &Operator, operand: &Expression, brackets: Doc) -> Operand {
+        let (left_power, _) = power_of(operator);
+        let mut printed = self.operand(operand);
+        if printed.right <= left_power {
+            printed = printed.parenthesized();
+        }
+        Operand {
+            doc: concat(vec![printed.doc, brackets]),
+            left: left_power.min(printed.left),
+            right: CLOSED,
+        }
+    }
+}
+
+fn power_of(operator: &Operator) -> (u8, u8) {
+    match operator.infix_binding_power() {
+        Some(power) => power,
+        None => unreachable!("{operator} is not an infix operator"),
+    }
+}
+
+fn import(import: &Import) -> Doc {
+    let Import {
+        imported_name,
+        local_name,
+        ..
+    } = import;
+    match local_name {
+        Some(local_name) => text(format!("{} as {}", imported_name.value, local_name.value)),
+        None => text(&imported_name.value),
+    }
+}
+
+/// Prints a comma-separated list between brackets, on one line if it fits, or else with each item on a line of its own and a trailing comma.
+fn list(open: &str, items: Vec<Doc>, close: &str) -> Doc {
+    if items.is_empty() {
+        return text(format!("{open}{close}"));
+    }
+    group(concat(vec![
+        text(open),
+        nest(concat(vec![
+            Doc::SoftLine,
+            join(items, || concat(vec![text(","), Doc::Line])),
+        ])),
+        Doc::IfBreak(","),
+        Doc::SoftLine,
+        text(close),
+    ]))
+}
+
+/// Prints lines in braces, or empty braces if there are none.
+fn braces(lines: Vec<Doc>) -> Doc {
+    if lines.is_empty() {
+        return text("{}");
+    }
+    concat(vec![
+        text("{"),
+        nest(concat(lines)),
+        Doc::HardLine,
+        text("}"),
+    ])
+}
+
+/// Returns `true` if a statement ends with an if that has no else, which would take an else written after the statement for its own.
+fn ends_with_open_if(statement: &Statement) -> bool {
+    match statement {
+        Statement::IfStatement(IfStatement { alternate, .. }) => match alternate {
+            Some(alternate) => ends_with_open_if(alternate),
+            None => true,
+        },
+        Statement::WhileLoop(WhileStatement { body, .. })
+        | Statement::ForLoop(ForLoop { body, .. }) => ends_with_open_if(body),
+        _ => false,
+    }
+}
+
+/// Returns `true` if a statement ends with an if, a loop or a try block, which each take a semi-colon written straight after them as part of themselves.
+fn takes_semi_colon(statement: &Statement) -> bool {
+    match statement {
+        Statement::IfStatement(_) | Statement::LoopStmnt(_) | Statement::TryBlock(_) => true,
+        Statement::WhileLoop(WhileStatement { body, .. })
+        | Statement::ForLoop(ForLoop { body, .. }) => takes_semi_colon(body),
+        Statement::PublicModifier(PublicModifier { statement, .. }) => takes_semi_colon(statement),
+        _ => false,
+    }
+}
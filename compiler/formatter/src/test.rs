@@ -0,0 +1,167 @@
+#![cfg(test)]
+
+use parser::Diagnostic;
+
+use crate::{format, FormatError, Options};
+
+fn formatted(source: &str) -> String {
+    format(source, &Options::default()).unwrap()
+}
+
+#[test]
+fn it_formats_declarations() {
+    let source = "@function  add (a:Number,b :Number)->Number
+{
+    @let   sum=a+b;
+    return sum;
+}
+if (a==b) { println \"same\"; } else { println \"different\"; }";
+    assert_eq!(
+        formatted(source),
+        "@function add(a: Number, b: Number) -> Number {
+    @let sum = a + b;
+    return sum;
+}
+if (a == b) {
+    println \"same\";
+} else {
+    println \"different\";
+}
+"
+    );
+}
+
+#[test]
+fn it_keeps_comments() {
+    let source = "// Start.
+## Greets.
+@function greet() { // Nothing here.
+}
+@function wave() {
+  /* Nothing here either. */ }
+
+
+@let x = 2; // Two.
+// End.
+";
+    assert_eq!(
+        formatted(source),
+        "// Start.
+## Greets.
+@function greet() { // Nothing here.
+}
+@function wave() {
+    /* Nothing here either. */
+}
+
+@let x = 2; // Two.
+// End.
+"
+    );
+}
+
+#[test]
+fn it_breaks_long_lists() {
+    let source = "@function connect(address: String, port: Number, timeout: Number, retries: Number) -> Connection {}
+connect(\"localhost\", 8080, 3000, 5);";
+    let options = Options {
+        width: 60,
+        ..Options::default()
+    };
+    assert_eq!(
+        format(source, &options).unwrap(),
+        "@function connect(
+    address: String,
+    port: Number,
+    timeout: Number,
+    retries: Number,
+) -> Connection {}
+connect(\"localhost\", 8080, 3000, 5);
+"
+    );
+}
+
+#[test]
+fn it_keeps_needed_parentheses() {
+    let source = "@let a = ((1 + 2)) * 3;
+@let b = a - (b - c);
+@let c = (a - b) - c;
+@let d = -(-x);
+@let e = (-a) ** 2;
+@let f = (fn(x) x)(1);";
+    assert_eq!(
+        formatted(source),
+        "@let a = (1 + 2) * 3;
+@let b = a - (b - c);
+@let c = a - b - c;
+@let d = -(-x);
+@let e = (-a) ** 2;
+@let f = (fn(x) x)(1);
+"
+    );
+}
+
+#[test]
+fn it_formats_expressions_in_interpolated_strings() {
+    let source = "println \"sum: {a+b*2}, {  greet( name,\"x{y}\" )  } \\{ok}\";";
+    assert_eq!(
+        formatted(source),
+        "println \"sum: {a + b * 2}, {greet(name, \"x{y}\")} \\{ok}\";\n"
+    );
+}
+
+#[test]
+fn it_keeps_block_comments_inside_expressions() {
+    let source = "@let x = a /* c */ + b;
+@let y = a + /* d */ b * c; // Done.
+call(/* first */ 1, 2 /* second */);";
+    assert_eq!(
+        formatted(source),
+        "@let x = a /* c */ + b;
+@let y = a + /* d */ b * c; // Done.
+call(/* first */ 1, 2 /* second */);
+"
+    );
+}
+
+#[test]
+fn it_formats_stably() {
+    for source in [
+        include_str!("../../../template/src/fs.peb"),
+        include_str!("../../../template/src/prelude.peb"),
+    ] {
+        let once = formatted(source);
+        assert_eq!(formatted(&once), once);
+    }
+}
+
+#[test]
+fn it_leaves_invalid_files_alone() {
+    let result = format("@let = 2;", &Options::default());
+    assert!(matches!(result, Err(FormatError::Invalid(diagnostics)) if !diagnostics.is_empty()));
+    for source in [
+        "@let a = 1;\n/* unclosed",
+        "@let a = \"unclosed;\n",
+        "@let b = 0x;\n",
+    ] {
+        let result = format(source, &Options::default());
+        assert!(
+            matches!(&result, Err(FormatError::Invalid(diagnostics)) if diagnostics.iter().any(|diagnostic| matches!(diagnostic, Diagnostic::Lexical(..)))),
+            "{source:?} gave {result:?}"
+        );
+    }
+}
+
+#[test]
+fn it_formats_idempotently() {
+    for source in [
+        "@let a=1;/* trailing */",
+        "@function f(){return 1;}\n\n\n// last\n\n",
+        "@let a = 1;\n/* unclosed",
+    ] {
+        // Formatting the output again changes nothing, and a file that cannot be formatted stays that way.
+        let once = format(source, &Options::default());
+        let again = format(once.as_deref().unwrap_or(source), &Options::default());
+        assert_eq!(again, once, "{source:?}");
+    }
+}
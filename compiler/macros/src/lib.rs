@@ -1,17 +1,82 @@
 use proc_macro::TokenStream;
 // use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Index};
 
 #[proc_macro_derive(Location)]
 pub fn derive_location(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
     TokenStream::from(quote! {
-        impl Location for #name<'_> {
-            fn get_range(&self) -> [[u64; 2]; 2] {
+        impl #impl_generics Location for #name #type_generics #where_clause {
+            fn get_range(&self) -> utils::Span {
                 self.span
             }
         }
     })
 }
+
+/// Implements `Displace` by displacing every field in turn, so that every span nested in the node is moved.
+#[proc_macro_derive(Displace)]
+pub fn derive_displace(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let members = data
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| match &field.ident {
+                    Some(ident) => quote!(#ident),
+                    None => {
+                        let index = Index::from(index);
+                        quote!(#index)
+                    }
+                });
+            quote! { #(Displace::displace(&mut self.#members, by);)* }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                match &variant.fields {
+                    Fields::Named(fields) => {
+                        let names = fields.named.iter().map(|field| &field.ident);
+                        let displaced = names.clone();
+                        quote! {
+                            Self::#ident { #(#names),* } => {
+                                #(Displace::displace(#displaced, by);)*
+                            }
+                        }
+                    }
+                    Fields::Unnamed(fields) => {
+                        let names = (0..fields.unnamed.len())
+                            .map(|index| format_ident!("field{}", index))
+                            .collect::<Vec<_>>();
+                        quote! {
+                            Self::#ident(#(#names),*) => {
+                                #(Displace::displace(#names, by);)*
+                            }
+                        }
+                    }
+                    Fields::Unit => quote!(Self::#ident => {}),
+                }
+            });
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => panic!("Displace cannot be derived for unions."),
+    };
+    TokenStream::from(quote! {
+        impl #impl_generics Displace for #name #type_generics #where_clause {
+            fn displace(&mut self, by: &Displacement) {
+                #body
+            }
+        }
+    })
+}
@@ -1,20 +1,29 @@
-use std::{cell::RefCell, marker::PhantomData};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashSet,
+};
 
-use crate::scanner::Scanner;
+use crate::{
+    cst::{SyntaxEvent, SyntaxKind},
+    incremental::{Event, List, Unit},
+    scanner::Scanner,
+    trace::{TraceEvent, TraceHook},
+};
 use ast::{
-    precedence_of, ArrayExpression, Attribute, Block, BracketKind, Break, Class, ConcreteType,
-    Continue, CrashStatement, Enum, Expression, FnExpression, ForLoop, Function, FunctionType,
-    GenericArgument, Identifier, IfStatement, Implement, Import, Injunction, Interface, Keyword,
-    Literal, LiteralKind, Location, Loop, Mapping, Method, Module, Operator, Parameter,
-    PrependStatement, PrintLnStatement, Property, PublicModifier, Punctuation, Record,
-    RecoverBlock, ReturnStatement, SelfExpression, Statement, TestBlock, TextSpan, TextString,
-    Token, TokenIdentifier, TokenKind, TryBlock, Type, TypeAlias, UseImport, VarKind,
-    VariableDeclaration, Variant, WhileStatement,
+    ArrayExpression, Attribute, Block, BracketKind, Break, Class, Comment, CommentKind,
+    ConcreteType, Continue, CrashStatement, Enum, Expression, FnExpression, ForLoop, Function,
+    FunctionType, GenericArgument, Identifier, IfStatement, Implement, Import, Injunction,
+    Interface, Keyword, Literal, LiteralKind, Location, Loop, Mapping, Method, Module, Operator,
+    OperatorKind, Parameter, PrependStatement, PrintLnStatement, Property, PublicModifier,
+    Punctuation, Record, RecoverBlock, ReturnStatement, SelfExpression, Statement, TemplatePart,
+    TestBlock, TextString, Token, TokenIdentifier, TokenKind, TryBlock, Type, TypeAlias, UseImport,
+    VarKind, VariableDeclaration, Variant, WhileStatement,
 };
-use errors::SyntaxError;
-use utils::Stack;
+use errors::{SyntaxError, SyntaxWarning};
+use utils::Span;
 
-pub type ParserError = (SyntaxError, TextSpan);
+pub type ParserError = (SyntaxError, Span);
+pub type ParserWarning = (SyntaxWarning, Span);
 type NodeOrError<T> = Result<T, ParserError>;
 
 /// The provider is a pseudo iterator that supplies tokens to the parser.
@@ -37,90 +46,267 @@ impl Provider {
 }
 
 /// The recursive descent parser that goes over the array of tokens scanned from the source text, and iteratively builds out the AST syntax tree.
-pub struct Parser<'a> {
+pub struct Parser {
     provider: RefCell<Provider>,
-    operators: RefCell<Stack<&'a Operator>>,
+    /// Set when the first half of a >> token has closed a list of type arguments.
+    split_angle: Cell<bool>,
     pub diagnostics: RefCell<Vec<ParserError>>,
-    pub statements: RefCell<Vec<Statement<'a>>>,
+    pub warnings: RefCell<Vec<ParserWarning>>,
+    /// The indices of the doc comments that have been attached to a declaration.
+    documented: RefCell<HashSet<usize>>,
+    pub statements: RefCell<Vec<Statement>>,
+    /// Receives the steps taken by the parser, if tracing has been turned on.
+    hook: RefCell<Option<TraceHook>>,
+    /// The index of the furthest token looked at while parsing the current statement.
+    furthest: Cell<usize>,
+    /// The statements parsed so far in each block being parsed, from the top level of the file inward.
+    lists: RefCell<Vec<List>>,
+    /// The statements being parsed, from the outermost inward, with what has been done while parsing them.
+    units: RefCell<Vec<Unit>>,
+    /// The steps taken to build the concrete syntax tree, if one is being built.
+    syntax: RefCell<Option<Vec<SyntaxEvent>>>,
 }
 
 /// Utilities
-impl<'a> Parser<'a> {
+impl Parser {
     /// Return a reference to the current token.
     fn token(&self) -> &Token {
         unsafe { self.provider.try_borrow_unguarded().unwrap().current() }
     }
     /// Shift to the next token in the stream.
     fn advance(&self) {
-        self.provider.borrow_mut().next()
+        self.emit(TraceEvent::Consume(self.token()));
+        self.step(SyntaxEvent::Token);
+        self.provider.borrow_mut().next();
+        self.furthest.set(self.furthest.get().max(self.index()));
     }
     /// Rewind to the previous token in the stream.
     fn backtrack(&self) {
         self.provider.borrow_mut().previous()
     }
     /// Check if the stream has ended.
-    fn end(&'a self) -> bool {
+    fn end(&self) -> bool {
         self.provider.borrow().current().is_eof()
     }
-    /// Push an operator unto the operator stack.
-    fn push_operator(&'a self, operator: &'a Operator) {
-        self.operators.borrow_mut().push(operator)
-    }
-    /// Check if the current operator in the stream is lower in precedence to the operator previously parsed.
-    fn is_lower_precedence(&'a self, operator: &Operator) -> bool {
-        match self.operators.borrow().top() {
-            None => false,
-            Some(op) => {
-                if precedence_of(operator) <= precedence_of(op) {
-                    true
-                } else {
-                    false
-                }
-            }
+    /// Checks if the current token closes a list of type arguments.
+    fn sees_closing_angle(&self) -> bool {
+        self.token().is_operator(&Operator::GreaterThan)
+            || self.token().is_operator(&Operator::BitwiseRightShift)
+    }
+    /// Moves past the > that closes a list of type arguments, and returns the byte offset where it ends.
+    /// A >> token closes two nested lists at once, e.g. in `Array<Array<Number>>`, so it is consumed in two halves.
+    fn close_angle(&self) -> usize {
+        let span = self.token().span;
+        if self.token().is_operator(&Operator::BitwiseRightShift) && !self.split_angle.get() {
+            self.split_angle.set(true);
+            return span.end - 1;
+        }
+        self.split_angle.set(false);
+        self.advance(); // Move past >
+        span.end
+    }
+    /// Report a step to the trace hook, if there is one.
+    fn emit(&self, event: TraceEvent) {
+        if let Some(hook) = self.hook.borrow_mut().as_mut() {
+            hook(event)
+        }
+    }
+    /// Runs a production, reporting when it is entered and exited to the trace hook.
+    fn traced<T>(&self, production: &'static str, parse: impl FnOnce() -> T) -> T {
+        self.emit(TraceEvent::Enter(production));
+        let result = parse();
+        self.emit(TraceEvent::Exit(production));
+        result
+    }
+    /// Records a step in building the concrete syntax tree, if one is being built.
+    fn step(&self, event: SyntaxEvent) {
+        if let Some(events) = self.syntax.borrow_mut().as_mut() {
+            events.push(event);
+        }
+    }
+    /// Returns the point in the concrete syntax tree reached so far, where a node can later be started around what is parsed after it.
+    fn checkpoint(&self) -> usize {
+        self.syntax.borrow().as_ref().map_or(0, Vec::len)
+    }
+    /// Runs a production inside a node of the concrete syntax tree. The node is finished even if the production fails, so that the tokens it consumed stay in it.
+    fn node<T>(&self, kind: SyntaxKind, parse: impl FnOnce() -> T) -> T {
+        self.node_at(self.checkpoint(), kind, parse)
+    }
+    /// Runs a production inside a node that starts at `checkpoint`, so that the node also holds what was parsed since, such as the left operand of a binary expression.
+    fn node_at<T>(&self, checkpoint: usize, kind: SyntaxKind, parse: impl FnOnce() -> T) -> T {
+        if let Some(events) = self.syntax.borrow_mut().as_mut() {
+            events.insert(checkpoint, SyntaxEvent::Start(kind));
         }
+        let result = parse();
+        self.step(SyntaxEvent::Finish);
+        result
     }
     /// Store an error that has been encountered.
     fn store_error(&self, error: ParserError) {
+        self.record(Event::Error(error.clone()));
         self.diagnostics.borrow_mut().push(error)
     }
+    /// Records something done while parsing the current statement, so that it can be replayed if the statement is reused after an edit.
+    fn record(&self, event: Event) {
+        if let Some(unit) = self.units.borrow_mut().last_mut() {
+            unit.events.push(event);
+        }
+    }
+    /// Returns the position of the current token in the stream.
+    fn index(&self) -> usize {
+        self.provider.borrow().index
+    }
+    /// Returns the span in the file being parsed between two byte offsets.
+    fn span(&self, start: usize, end: usize) -> Span {
+        Span::new(self.token().span.file, start, end)
+    }
+    /// Returns the byte offset where the token before the current one ends.
+    fn previous_end(&self) -> usize {
+        let provider = self.provider.borrow();
+        provider.scanner.tokens[provider.index.saturating_sub(1)]
+            .span
+            .end
+    }
+    /// Collects the doc comments written directly before the declaration that begins at the current token, or at an `@public` in front of it.<br>
+    /// Doc comments are collected for as long as each one is on the line just before the next, so a blank line or another comment ends them.
+    fn docs(&self) -> Option<String> {
+        let provider = self.provider.borrow();
+        let tokens = &provider.scanner.tokens;
+        let comments = &provider.scanner.comments;
+        let mut index = provider.index;
+        while index > 0 && tokens[index - 1].is_keyword(&Keyword::Injunction(Injunction::Public)) {
+            index -= 1;
+        }
+        let after = match index {
+            0 => 0,
+            _ => tokens[index - 1].span.end,
+        };
+        let last =
+            comments.partition_point(|comment| comment.span.start < tokens[index].span.start);
+        let mut first = last;
+        let mut line = provider.scanner.line(tokens[index].span.start);
+        while first > 0 {
+            let comment = &comments[first - 1];
+            let comment_line = provider.scanner.line(comment.span.start);
+            if comment.span.start < after || comment_line + 1 != line || !is_doc(comment) {
+                break;
+            }
+            line = comment_line;
+            first -= 1;
+        }
+        if first == last {
+            return None;
+        }
+        self.documented.borrow_mut().extend(first..last);
+        for comment in &comments[first..last] {
+            self.record(Event::Documented(comment.span.start));
+        }
+        let lines = comments[first..last]
+            .iter()
+            .map(|comment| match &comment.kind {
+                TokenKind::Comment(Comment { content, .. }) => {
+                    let content = content.trim_end_matches('\r');
+                    content.strip_prefix(' ').unwrap_or(content)
+                }
+                _ => unreachable!(),
+            });
+        Some(lines.collect::<Vec<_>>().join("\n"))
+    }
+    /// Warns about every doc comment that was not attached to a declaration.
+    fn report_orphaned_docs(&self) {
+        let provider = self.provider.borrow();
+        let documented = self.documented.borrow();
+        for (index, comment) in provider.scanner.comments.iter().enumerate() {
+            if is_doc(comment) && !documented.contains(&index) {
+                self.warnings
+                    .borrow_mut()
+                    .push((SyntaxWarning::OrphanedDocComment, comment.span));
+            }
+        }
+    }
+    /// Checks if the current token is a keyword that begins a statement or a declaration.
+    fn sees_statement_keyword(&self) -> bool {
+        matches!(&self.token().kind, TokenKind::Keyword(keyword) if keyword.begins_statement())
+    }
 }
 
 /// API
-impl<'a> Parser<'a> {
-    pub fn from(provider: Provider) -> Parser<'a> {
+impl Parser {
+    pub fn from(provider: Provider) -> Parser {
         Parser {
             provider: RefCell::new(provider),
-            operators: RefCell::new(Stack::new()),
+            split_angle: Cell::new(false),
             diagnostics: RefCell::new(vec![]),
+            warnings: RefCell::new(vec![]),
+            documented: RefCell::new(HashSet::new()),
             statements: RefCell::new(vec![]),
+            hook: RefCell::new(None),
+            furthest: Cell::new(0),
+            lists: RefCell::new(vec![List::new(None)]),
+            units: RefCell::new(vec![]),
+            syntax: RefCell::new(None),
         }
     }
-    pub fn parse(&'a self) {
+    /// Creates a parser that starts at the current token of `provider`, in the middle of a file.<br>
+    /// `split_angle` is set if the first half of a `>>` token before it has already closed a list of type arguments.
+    pub(crate) fn resume(provider: Provider, split_angle: bool) -> Parser {
+        let parser = Parser::from(provider);
+        parser.split_angle.set(split_angle);
+        parser
+    }
+    /// Turns on tracing, so that every production entered and exited, and every token consumed, is reported to `hook`.
+    pub fn trace(&self, hook: impl FnMut(TraceEvent<'_>) + 'static) {
+        *self.hook.borrow_mut() = Some(Box::new(hook));
+    }
+    /// Turns on building the concrete syntax tree, so that the parser records where each node starts and finishes among the tokens it consumes.
+    pub(crate) fn build_syntax(&self) {
+        *self.syntax.borrow_mut() = Some(vec![]);
+    }
+    /// Takes the steps recorded for building the concrete syntax tree.
+    pub(crate) fn take_syntax(&self) -> Vec<SyntaxEvent> {
+        self.syntax.take().unwrap_or_default()
+    }
+    pub fn parse(&self) {
         // Continously parse statements and store them in a statements array until the end of the file is reached.
         while !self.end() {
-            let parsed = self.statement();
-            match parsed {
-                Ok(stmnt) => {
-                    self.statements.borrow_mut().push(stmnt);
-                }
-                Err(e) => {
-                    // Errors are checked at the statement boundary.
-                    // If an error is found while parsing, the parser stores it, skips over till it finds the next statement, then continues parsing from there.
-                    self.store_error(e);
-                    while !(self.end()
-                        || self.token().is_semi_colon()
-                        || self.token().is_comment()
-                        || self.token().is_comma())
-                    {
-                        self.advance();
-                    }
-                }
-            };
+            let statement = self.statement_or_error();
+            self.statements.borrow_mut().push(statement);
+        }
+        self.report_orphaned_docs();
+    }
+    /// Parses statements from the current token until the end of the file, or the `}` that closes the enclosing block if `in_block` is set.<br>
+    /// Before each statement, `resume` is given the index of its first token and whether the first half of a `>>` token has been consumed, and parsing stops early if it returns `true`.
+    /// Returns the index of the token where parsing stopped.
+    pub(crate) fn parse_list(
+        &self,
+        in_block: bool,
+        mut resume: impl FnMut(usize, bool) -> bool,
+    ) -> usize {
+        while !(self.end() || in_block && self.token().is_bracket(&BracketKind::RightCurly)) {
+            if resume(self.index(), self.split_angle.get()) {
+                break;
+            }
+            let statement = self.statement_or_error();
+            self.statements.borrow_mut().push(statement);
         }
+        self.index()
+    }
+    /// Takes the statements parsed at the top level, with what was done while parsing each of them.
+    pub(crate) fn take_units(&self) -> Vec<Unit> {
+        std::mem::take(&mut self.lists.borrow_mut()[0].units)
+    }
+    /// Gives back the tokens the parser was given, and the comments between them.
+    pub(crate) fn into_tokens(self) -> (Vec<Token>, Vec<Token>) {
+        let scanner = self.provider.into_inner().scanner;
+        (scanner.tokens, scanner.comments)
+    }
+    /// Returns the index of the furthest token the parser has looked at.
+    pub(crate) fn furthest(&self) -> usize {
+        self.furthest.get()
     }
     /// Parse a single statement.
-    fn statement(&'a self) -> NodeOrError<Statement<'a>> {
-        match &self.token().kind {
+    fn statement(&self) -> NodeOrError<Statement> {
+        self.traced("statement", || match &self.token().kind {
             TokenKind::Keyword(Keyword::Injunction(injunction)) => self.injunction(injunction),
             TokenKind::Keyword(Keyword::Fn | Keyword::Self_) => self.expression_statement(),
             TokenKind::Keyword(keyword) => self.control_statement(keyword),
@@ -129,51 +315,107 @@ impl<'a> Parser<'a> {
                 self.block_statement()
             }
             _ => self.expression_statement(),
-        }
+        })
     }
-    fn empty_statement(&'a self) -> NodeOrError<Statement<'a>> {
+    fn empty_statement(&self) -> NodeOrError<Statement> {
         let emp_stat = Statement::EmptyStatement(self.token().span);
-        self.advance();
+        self.node(SyntaxKind::EmptyStatement, || self.advance());
         Ok(emp_stat)
     }
-    fn expression_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let parsed = self.expression();
-        match parsed {
-            Err(e) => Err(e),
-            Ok(exp) => {
-                if self.token().is_semi_colon() {
-                    self.advance();
-                    Ok(Statement::create_expr_stmnt(exp))
-                } else {
-                    // Every expression statement must end with a semi-colon.
-                    Err((SyntaxError::ExpectedSemiColon, self.token().span))
+    fn expression_statement(&self) -> NodeOrError<Statement> {
+        self.node(SyntaxKind::ExpressionStatement, || {
+            let parsed = self.expression();
+            match parsed {
+                Err(e) => Err(e),
+                Ok(exp) => {
+                    if self.token().is_semi_colon() {
+                        self.advance();
+                        Ok(Statement::create_expr_stmnt(exp))
+                    } else {
+                        // Every expression statement must end with a semi-colon.
+                        Err((SyntaxError::ExpectedSemiColon, self.token().span))
+                    }
                 }
             }
+        })
+    }
+    /// Parses an expression, with every operator it contains.
+    fn expression(&self) -> NodeOrError<Expression> {
+        self.traced("expression", || self.expression_with_power(0))
+    }
+    /// Parses an expression, stopping at the first operator that binds to its left operand less tightly than `minimum`.<br>
+    /// An operand is first parsed on its own, then each operator that follows it takes the expression so far as its left operand, for as long as the operator binds tightly enough.
+    /// The right operand of each operator is parsed with the operator's own right binding power, which decides its precedence and associativity.
+    fn expression_with_power(&self, minimum: u8) -> NodeOrError<Expression> {
+        let checkpoint = self.checkpoint();
+        let mut left = self.operand()?;
+        loop {
+            let operator = match &self.token().kind {
+                TokenKind::Operator(operator) => operator,
+                TokenKind::Punctuation(Punctuation::Bracket(BracketKind::LeftParenthesis)) => {
+                    &Operator::Call
+                }
+                TokenKind::Punctuation(Punctuation::Bracket(BracketKind::LeftSquare)) => {
+                    &Operator::Index
+                }
+                _ => break,
+            };
+            let right_power = match operator.infix_binding_power() {
+                Some((left_power, right_power)) if left_power >= minimum => right_power,
+                _ => break,
+            };
+            let kind = SyntaxKind::operator(operator);
+            left = self.node_at(checkpoint, kind, || match operator.kind() {
+                OperatorKind::Postfix if operator == &Operator::Call => self.call_expression(left),
+                OperatorKind::Postfix => self.index_expression(left),
+                OperatorKind::Member if operator == &Operator::Dot => {
+                    self.dot_expression(left, right_power)
+                }
+                OperatorKind::Member => self.namespace_expression(left, right_power),
+                OperatorKind::Range => self.range_expression(left, right_power),
+                OperatorKind::Logical => {
+                    self.logical_expression(left, operator.clone(), right_power)
+                }
+                OperatorKind::Conditional => self.ternary_expression(left, right_power),
+                OperatorKind::Assignment => {
+                    self.assign_expression(left, operator.clone(), right_power)
+                }
+                _ => self.binary_expression(left, operator.clone(), right_power),
+            })?;
         }
+        Ok(left)
     }
-    fn expression(&'a self) -> NodeOrError<Expression<'a>> {
-        match &self.token().kind {
+    /// Parses a single operand, i.e. a literal, a name, a prefixed operand, or a bracketed expression.
+    fn operand(&self) -> NodeOrError<Expression> {
+        self.traced("operand", || match &self.token().kind {
             TokenKind::Literal(literal) => self.literal(literal),
-            TokenKind::Identifier(id) => self.identifier(id),
-            TokenKind::Operator(operator) => self.unary_expression(operator),
-            TokenKind::Keyword(Keyword::Fn) => self.functional_expression(),
-            TokenKind::Keyword(Keyword::Self_) => self.self_expression(),
+            TokenKind::Identifier(id) => {
+                self.node(SyntaxKind::NameExpression, || self.identifier(id))
+            }
+            TokenKind::Operator(operator) => self.node(SyntaxKind::UnaryExpression, || {
+                self.unary_expression(operator.clone())
+            }),
+            TokenKind::Keyword(Keyword::Fn) => {
+                self.node(SyntaxKind::FnExpression, || self.functional_expression())
+            }
+            TokenKind::Keyword(Keyword::Self_) => {
+                self.node(SyntaxKind::SelfExpression, || self.self_expression())
+            }
             TokenKind::Punctuation(Punctuation::Bracket(BracketKind::LeftSquare)) => {
-                self.array_expression()
+                self.node(SyntaxKind::ArrayExpression, || self.array_expression())
             }
             TokenKind::Punctuation(Punctuation::Bracket(BracketKind::LeftParenthesis)) => {
-                self.grouped_expression()
-            }
-            _ => {
-                println!("{:?}", self.token());
-                Err((SyntaxError::ExpectedExpression, self.token().span))
+                self.node(SyntaxKind::GroupExpression, || self.grouped_expression())
             }
-        }
+            _ => Err((SyntaxError::ExpectedExpression, self.token().span)),
+        })
     }
     /// Parses a literal token into its respective expression node.
-    fn literal(&'a self, literal: &'a Literal) -> NodeOrError<Expression<'a>> {
+    fn literal(&self, literal: &Literal) -> NodeOrError<Expression> {
         let node = match literal.kind {
-            LiteralKind::StringLiteral => {
+            LiteralKind::StringLiteral
+            | LiteralKind::RawStringLiteral
+            | LiteralKind::MultiLineStringLiteral => {
                 Expression::create_str_expr(&literal.value, self.token().span)
             }
             LiteralKind::NumericLiteral => {
@@ -185,288 +427,315 @@ impl<'a> Parser<'a> {
             LiteralKind::CharacterLiteral => {
                 Expression::create_char_expr(&literal.value, self.token().span)
             }
+            LiteralKind::TemplateHead => {
+                return self.node(SyntaxKind::TemplateString, || self.template_string())
+            }
+            LiteralKind::TemplateMiddle | LiteralKind::TemplateTail => {
+                return Err((SyntaxError::ExpectedExpression, self.token().span))
+            }
         };
-        self.advance();
-        Ok(self.reparse(node)?)
+        self.node(SyntaxKind::LiteralExpression, || self.advance());
+        Ok(node)
     }
     /// Parses an identifier token into an identifier expression.
-    fn identifier(&'a self, identifier: &'a TokenIdentifier) -> NodeOrError<Expression<'a>> {
+    fn identifier(&self, identifier: &TokenIdentifier) -> NodeOrError<Expression> {
         let node = Expression::create_ident_expr(&identifier.value, self.token().span);
         self.advance();
-        Ok(self.reparse(node)?)
+        Ok(node)
     }
 }
 
-/// Expressions
-impl<'a> Parser<'a> {
-    fn reparse(&'a self, node: Expression<'a>) -> NodeOrError<Expression<'a>> {
-        match &self.token().kind {
-            TokenKind::Operator(operator) => match operator {
-                Operator::Dot => self.dot_expression(node, operator),
-                Operator::Namespace => self.namespace_expression(node, operator),
-                Operator::RangeBetween => self.range_expression(node, operator),
-                Operator::LogicalAnd | Operator::LogicalOr => {
-                    self.logical_expression(node, operator)
+/// Error recovery
+impl Parser {
+    /// Parses a statement, recovering from any error in it.<br>
+    /// The error is stored and the rest of the statement is skipped, so that the statement is replaced by an error node and parsing resumes at the next one.
+    fn statement_or_error(&self) -> Statement {
+        let index = self.index();
+        let start = self.token().span.start;
+        let furthest = self.furthest.replace(index);
+        let unit = Unit::new(index, self.first_read(index), self.split_angle.get());
+        self.units.borrow_mut().push(unit);
+        let statement = match self.statement() {
+            Ok(statement) => statement,
+            Err(error) => {
+                self.store_error(error);
+                self.node(SyntaxKind::Error, || self.synchronize(index));
+                Statement::Error(self.span(start, self.previous_end()))
+            }
+        };
+        let mut unit = self.units.borrow_mut().pop().unwrap();
+        unit.reads[1] = self.furthest.get();
+        self.furthest.set(furthest.max(unit.reads[1]));
+        self.lists.borrow_mut().last_mut().unwrap().units.push(unit);
+        statement
+    }
+    /// Returns the index of the first token that a statement beginning at `index` could read: the one before it, or before the `@public` tokens in front of it, where the search for its doc comments ends.
+    fn first_read(&self, index: usize) -> usize {
+        let provider = self.provider.borrow();
+        let tokens = &provider.scanner.tokens;
+        let mut first = index.saturating_sub(1);
+        while first > 0 && tokens[first].is_keyword(&Keyword::Injunction(Injunction::Public)) {
+            first -= 1;
+        }
+        first
+    }
+    /// Skips the rest of a malformed statement that began at `index`.<br>
+    /// Parsing resumes after the `;` that ends the statement, or before a `}` that closes the enclosing block, or before a keyword that begins the next statement.
+    /// Blocks opened along the way are skipped whole, and at least one token is always skipped, so that a token which cannot begin a statement is not parsed twice.
+    fn synchronize(&self, index: usize) {
+        let mut depth = 0;
+        while !self.end() {
+            let token = self.token();
+            let resumes =
+                token.is_bracket(&BracketKind::RightCurly) || self.sees_statement_keyword();
+            if depth == 0 && self.index() > index && resumes {
+                break;
+            }
+            self.advance();
+            match &token.kind {
+                TokenKind::Punctuation(Punctuation::Bracket(BracketKind::LeftCurly)) => depth += 1,
+                TokenKind::Punctuation(Punctuation::Bracket(BracketKind::RightCurly))
+                    if depth > 1 =>
+                {
+                    depth -= 1
                 }
-                Operator::Confirm => self.ternary_expression(node, operator),
-                Operator::Add
-                | Operator::Multiply
-                | Operator::Divide
-                | Operator::Subtract
-                | Operator::Remainder
-                | Operator::LessThan
-                | Operator::GreaterThanOrEquals
-                | Operator::LessThanOrEquals
-                | Operator::BitwiseLeftShift
-                | Operator::BitwiseOr
-                | Operator::BitwiseAnd
-                | Operator::Equals
-                | Operator::NotEquals
-                | Operator::PowerOf => self.binary_expression(node, operator),
-                // Differentiate between > and >> operators.
-                Operator::GreaterThan => {
-                    self.advance();
-                    if let Token {
-                        kind: TokenKind::Operator(Operator::GreaterThan),
-                        ..
-                    } = self.token()
-                    {
-                        self.binary_expression(node, &Operator::BitwiseRightShift)
-                    } else {
-                        self.backtrack();
-                        self.binary_expression(node, &Operator::GreaterThan)
+                // The block that was skipped has closed, or the } was stray.
+                TokenKind::Punctuation(Punctuation::Bracket(BracketKind::RightCurly)) => break,
+                TokenKind::Punctuation(Punctuation::SemiColon) if depth == 0 => break,
+                _ => {}
+            }
+        }
+    }
+    /// Parses an expression in a list or a condition, recovering from any error in it.<br>
+    /// The error is stored and the rest of the expression is skipped, so that it is replaced by an error node and the enclosing node is still built.
+    fn expression_or_error(&self) -> Expression {
+        let index = self.index();
+        let start = self.token().span.start;
+        match self.expression() {
+            Ok(expression) => expression,
+            Err(error) => {
+                self.store_error(error);
+                self.node(SyntaxKind::Error, || self.skip_list_item());
+                let end = if self.index() > index {
+                    self.previous_end()
+                } else {
+                    start
+                };
+                Expression::Error(self.span(start, end))
+            }
+        }
+    }
+    /// Skips the rest of a malformed item in a comma-separated list.<br>
+    /// Parsing resumes before the comma that ends the item, or before a closing bracket, `;` or statement keyword that ends the list. Brackets opened along the way are skipped whole.
+    fn skip_list_item(&self) {
+        let mut depth = 0;
+        while !self.end() {
+            match &self.token().kind {
+                TokenKind::Punctuation(Punctuation::Bracket(kind)) if kind.is_opening() => {
+                    depth += 1
+                }
+                TokenKind::Punctuation(Punctuation::Bracket(_)) if depth > 0 => depth -= 1,
+                _ if depth > 0 => {}
+                TokenKind::Punctuation(_) => break,
+                TokenKind::Keyword(keyword) if keyword.begins_statement() => break,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+    /// Parses the comma-separated members in the body of a class, interface, record or enum, up to its closing brace.<br>
+    /// A malformed member, or a missing comma, is reported and skipped, so that the members after it are still parsed.
+    fn members<T>(&self, member: fn(&Self) -> NodeOrError<T>) -> Vec<T> {
+        let mut members = vec![];
+        while !(self.end() || self.token().is_bracket(&BracketKind::RightCurly)) {
+            let index = self.index();
+            match member(self) {
+                Ok(parsed) => {
+                    members.push(parsed);
+                    if self.token().is_comma() {
+                        self.advance();
+                        continue;
+                    }
+                    if self.end() || self.token().is_bracket(&BracketKind::RightCurly) {
+                        continue;
                     }
+                    self.store_error((SyntaxError::ExpectedCommaOrRCurly, self.token().span));
                 }
-                Operator::Assign
-                | Operator::AddAssign
-                | Operator::DivideAssign
-                | Operator::MultiplyAssign
-                | Operator::SubtractAssign
-                | Operator::LogicalOrAssign
-                | Operator::LogicalAndAssign => self.assign_expression(node, operator),
-                _ => Ok(node),
-            },
-            TokenKind::Punctuation(punctuation) => match punctuation {
-                Punctuation::Bracket(BracketKind::LeftParenthesis) => self.call_expression(node),
-                Punctuation::Bracket(BracketKind::LeftSquare) => self.index_expression(node),
-                _ => Ok(node),
-            },
-            _ => Ok(node),
+                Err(error) => self.store_error(error),
+            }
+            self.node(SyntaxKind::Error, || {
+                self.skip_list_item();
+                // Move past the comma, or a stray bracket or semi-colon the member could not use.
+                let stray = !(self.end()
+                    || self.token().is_bracket(&BracketKind::RightCurly)
+                    || self.sees_statement_keyword());
+                if stray || self.index() == index {
+                    self.advance();
+                }
+            });
         }
+        members
     }
-    fn grouped_expression(&'a self) -> NodeOrError<Expression<'a>> {
-        self.advance();
-        self.operators.borrow_mut().push(&Operator::Temp);
+}
+
+/// Expressions
+impl Parser {
+    /// Parses an interpolated string, alternating between its text segments and its embedded expressions.
+    fn template_string(&self) -> NodeOrError<Expression> {
+        let start = self.token().span.start;
+        let mut parts = vec![];
+        loop {
+            let (kind, value, raw, span) = match self.token() {
+                Token {
+                    kind: TokenKind::Literal(Literal { kind, value, raw }),
+                    span,
+                } if kind.is_template_segment() => (kind, value, raw, *span),
+                // Every embedded expression must be closed before the string continues.
+                token => return Err((SyntaxError::ExpectedRCurly, token.span)),
+            };
+            if !value.is_empty() {
+                // The text begins after the opening quote or the } of the previous expression.
+                let text_start = span.start + 1;
+                parts.push(TemplatePart::Text(TextString {
+                    value: value.clone(),
+                    span: self.span(text_start, text_start + raw.len()),
+                }));
+            }
+            self.advance(); // Move past segment.
+            if let LiteralKind::TemplateTail = kind {
+                let template =
+                    Expression::create_template_str_expr(parts, self.span(start, span.end));
+                return Ok(template);
+            }
+            let expression = self.expression()?;
+            parts.push(TemplatePart::Expression(expression));
+        }
+    }
+    /// Parses an expression in parentheses, which is grouped before any operator around it.
+    fn grouped_expression(&self) -> NodeOrError<Expression> {
+        self.advance(); // Move past (
         let expression = self.expression()?;
-        self.operators.borrow_mut().pop();
-        self.advance();
-        Ok(self.reparse(expression)?)
+        if !self.token().is_bracket(&BracketKind::RightParenthesis) {
+            return Err((SyntaxError::ExpectedRParen, self.token().span));
+        }
+        self.advance(); // Move past )
+        Ok(expression)
     }
     /// Parses a dot or member expression.
-    fn dot_expression(
-        &'a self,
-        object: Expression<'a>,
-        operator: &'a Operator,
-    ) -> NodeOrError<Expression<'a>> {
-        if self.is_lower_precedence(operator) {
-            Ok(object)
-        } else {
-            self.advance(); // Move past operator.
-            self.operators.borrow_mut().push(operator); // Push the dot operator onto the stack.
-            let property = self.expression()?; // Parse the property of the object.
-            self.operators.borrow_mut().pop(); // Remove the dot operator from the stack.
-            let dot_exp = Expression::create_dot_expr(object, property);
-            Ok(self.reparse(dot_exp)?)
-        }
+    fn dot_expression(&self, object: Expression, power: u8) -> NodeOrError<Expression> {
+        self.advance(); // Move past operator.
+        let property = self.expression_with_power(power)?; // Parse the property of the object.
+        Ok(Expression::create_dot_expr(object, property))
     }
     /// Parses a namespace expression.
-    fn namespace_expression(
-        &'a self,
-        namespace: Expression<'a>,
-        operator: &'a Operator,
-    ) -> NodeOrError<Expression<'a>> {
-        if self.is_lower_precedence(operator) {
-            Ok(namespace)
-        } else {
-            self.advance(); // Move past operator.
-            self.operators.borrow_mut().push(operator); // Push the namespace operator onto the stack.
-            let property = self.expression()?; // Parse the property of the object.
-            self.operators.borrow_mut().pop(); // Remove the namespace operator from the stack.
-            let dot_exp = Expression::create_namespace_expr(namespace, property);
-            Ok(self.reparse(dot_exp)?)
-        }
+    fn namespace_expression(&self, namespace: Expression, power: u8) -> NodeOrError<Expression> {
+        self.advance(); // Move past operator.
+        let property = self.expression_with_power(power)?;
+        Ok(Expression::create_namespace_expr(namespace, property))
     }
     /// Parses a binary expression.
     fn binary_expression(
-        &'a self,
-        left: Expression<'a>,
-        operator: &'a Operator,
-    ) -> NodeOrError<Expression<'a>> {
-        if self.is_lower_precedence(&operator) {
-            Ok(left)
-        } else {
-            self.advance(); // Move past operator.
-            self.operators.borrow_mut().push(operator); // Push the binary operator onto the stack.
-            let right = self.expression()?; // Parse the expression at the right hand side of the binary expression.
-            self.operators.borrow_mut().pop(); // Remove the binary operator from the stack.
-            let bin_exp = Expression::create_bin_expr(left, operator, right);
-            Ok(self.reparse(bin_exp)?)
-        }
+        &self,
+        left: Expression,
+        operator: Operator,
+        power: u8,
+    ) -> NodeOrError<Expression> {
+        self.advance(); // Move past operator.
+        let right = self.expression_with_power(power)?; // Parse the expression at the right hand side of the binary expression.
+        Ok(Expression::create_bin_expr(left, operator, right))
     }
     /// Parses a call expression.
-    fn call_expression(&'a self, callee: Expression<'a>) -> NodeOrError<Expression<'a>> {
-        let call_op = Operator::Call;
-        if self.is_lower_precedence(&call_op) {
-            Ok(callee)
-        } else {
-            self.advance(); // Move past operator.
-            self.operators.borrow_mut().push(&Operator::Temp);
-            let mut arguments = vec![];
-            let right_bracket = BracketKind::RightParenthesis;
-            while !(self.end() || self.token().is_bracket(&right_bracket)) {
-                let argument = self.expression()?;
-                arguments.push(argument);
-                if self.token().is_comma() {
-                    self.advance(); // Move past the comma.
-                } else if !self.token().is_bracket(&right_bracket) {
-                    return Err((SyntaxError::ExpectedFunctionArgument, self.token().span));
-                }
-            }
-            self.operators.borrow_mut().pop();
-            if self.end() {
-                Err((SyntaxError::ExpectedRParen, self.token().span))
-            } else {
-                let end = self.token().span[1];
-                self.advance(); // Move past )
-                let callexp = Expression::create_call_expr(callee, arguments, end);
-
-                Ok(self.reparse(callexp)?)
+    fn call_expression(&self, callee: Expression) -> NodeOrError<Expression> {
+        self.advance(); // Move past (
+        let mut arguments = vec![];
+        let right_bracket = BracketKind::RightParenthesis;
+        while !(self.end() || self.token().is_bracket(&right_bracket)) {
+            let argument = self.expression_or_error();
+            arguments.push(argument);
+            if self.token().is_comma() {
+                self.advance(); // Move past the comma.
+            } else if !self.token().is_bracket(&right_bracket) {
+                return Err((SyntaxError::ExpectedFunctionArgument, self.token().span));
             }
         }
+        if self.end() {
+            return Err((SyntaxError::ExpectedRParen, self.token().span));
+        }
+        let end = self.token().span.end;
+        self.advance(); // Move past )
+        Ok(Expression::create_call_expr(callee, arguments, end))
     }
     /// Parses an index expression.
-    fn index_expression(&'a self, accessor: Expression<'a>) -> NodeOrError<Expression<'a>> {
-        let index_op = Operator::Index;
-        if self.is_lower_precedence(&index_op) {
-            Ok(accessor)
-        } else {
-            self.advance(); // Move past [
-            self.operators.borrow_mut().push(&Operator::Temp);
-            let property = self.expression()?; // Parse property.
-            self.operators.borrow_mut().pop();
-            if !self.token().is_bracket(&BracketKind::RightSquare) {
-                return Err((SyntaxError::ExpectedRSquareBrac, self.token().span));
-            }
-            let end = self.token().span[1];
-            self.advance(); // Move past ]
-            let index_exp = Expression::create_index_expr(accessor, property, end);
-            Ok(self.reparse(index_exp)?)
+    fn index_expression(&self, accessor: Expression) -> NodeOrError<Expression> {
+        self.advance(); // Move past [
+        let property = self.expression()?; // Parse property.
+        if !self.token().is_bracket(&BracketKind::RightSquare) {
+            return Err((SyntaxError::ExpectedRSquareBrac, self.token().span));
         }
+        let end = self.token().span.end;
+        self.advance(); // Move past ]
+        Ok(Expression::create_index_expr(accessor, property, end))
     }
     /// Parses a unary expression.
-    fn unary_expression(&'a self, operator: &'a Operator) -> NodeOrError<Expression<'a>> {
-        match operator {
-            Operator::Add
-            | Operator::Subtract
-            | Operator::Decrement
-            | Operator::Increment
-            | Operator::LogicalNot
-            | Operator::BitWiseNot => {
-                self.operators.borrow_mut().push(operator);
-                let start = self.token().span[0];
-                self.advance(); // Move past operator.
-                let operand = self.expression()?;
-                self.operators.borrow_mut().pop();
-                let un_exp = Expression::create_unary_expr(start, operator, operand);
-                Ok(self.reparse(un_exp)?)
-            }
-            _ => Err((SyntaxError::UnexpectedOperator, self.token().span)),
+    fn unary_expression(&self, operator: Operator) -> NodeOrError<Expression> {
+        if !operator.is_prefix() {
+            return Err((SyntaxError::UnexpectedOperator, self.token().span));
         }
+        let start = self.token().span.start;
+        self.advance(); // Move past operator.
+        let operand = self.expression_with_power(Operator::prefix_binding_power())?;
+        Ok(Expression::create_unary_expr(start, operator, operand))
     }
     /// Parses a range expression.
-    fn range_expression(
-        &'a self,
-        top: Expression<'a>,
-        operator: &'a Operator,
-    ) -> NodeOrError<Expression<'a>> {
-        if self.is_lower_precedence(operator) {
-            Ok(top)
-        } else {
-            self.advance(); // Move past operator.
-            self.operators.borrow_mut().push(operator);
-            let bottom = self.expression()?;
-            self.operators.borrow_mut().pop();
-            let range_exp = Expression::create_range_expr(top, bottom);
-            Ok(self.reparse(range_exp)?)
-        }
+    fn range_expression(&self, top: Expression, power: u8) -> NodeOrError<Expression> {
+        self.advance(); // Move past operator.
+        let bottom = self.expression_with_power(power)?;
+        Ok(Expression::create_range_expr(top, bottom))
     }
     /// Parses a logical expression.
     fn logical_expression(
-        &'a self,
-        left: Expression<'a>,
-        operator: &'a Operator,
-    ) -> NodeOrError<Expression<'a>> {
-        if self.is_lower_precedence(operator) {
-            Ok(left)
-        } else {
-            self.advance(); // Move past operator.
-            self.operators.borrow_mut().push(operator);
-            let right = self.expression()?;
-            self.operators.borrow_mut().pop();
-            let log_exp = Expression::create_logical_expr(left, operator, right);
-            Ok(self.reparse(log_exp)?)
-        }
+        &self,
+        left: Expression,
+        operator: Operator,
+        power: u8,
+    ) -> NodeOrError<Expression> {
+        self.advance(); // Move past operator.
+        let right = self.expression_with_power(power)?;
+        Ok(Expression::create_logical_expr(left, operator, right))
     }
     /// Parses a ternary expression.
-    fn ternary_expression(
-        &'a self,
-        test: Expression<'a>,
-        operator: &'a Operator,
-    ) -> NodeOrError<Expression<'a>> {
-        if self.is_lower_precedence(operator) {
-            Ok(test)
-        } else {
-            self.advance(); // Move past operator.
-            self.operators.borrow_mut().push(operator);
-            let consequent = self.expression()?;
-            self.operators.borrow_mut().pop();
-            if !self.token().is_colon() {
-                return Err((SyntaxError::ExpectedColon, self.token().span));
-            }
-            self.advance();
-            self.operators.borrow_mut().push(&Operator::Colon);
-            let alternate = self.expression()?;
-            self.operators.borrow_mut().pop();
-            let tern_exp = Expression::create_ternary_expr(test, consequent, alternate);
-            Ok(self.reparse(tern_exp)?)
+    /// The consequent is enclosed by ? and :, so it can hold any expression, while the alternate takes the binding power of the ternary operator.
+    fn ternary_expression(&self, test: Expression, power: u8) -> NodeOrError<Expression> {
+        self.advance(); // Move past ?
+        let consequent = self.expression()?;
+        if !self.token().is_colon() {
+            return Err((SyntaxError::ExpectedColon, self.token().span));
         }
+        self.advance(); // Move past :
+        let alternate = self.expression_with_power(power)?;
+        Ok(Expression::create_ternary_expr(test, consequent, alternate))
     }
     /// Parses an assignment expression.
     fn assign_expression(
-        &'a self,
-        left: Expression<'a>,
-        operator: &'a Operator,
-    ) -> NodeOrError<Expression<'a>> {
-        if self.is_lower_precedence(operator) {
-            Ok(left)
-        } else {
-            if !left.is_valid_assignment_target() {
-                return Err((SyntaxError::InvalidLHS, self.token().span));
-            }
-            self.advance(); // Move past operator.
-            let right = self.expression()?;
-            let assign_exp = Expression::create_assign_expr(left, operator, right);
-            Ok(self.reparse(assign_exp)?)
-        }
+        &self,
+        left: Expression,
+        operator: Operator,
+        power: u8,
+    ) -> NodeOrError<Expression> {
+        if !left.is_valid_assignment_target() {
+            return Err((SyntaxError::InvalidLHS, self.token().span));
+        }
+        self.advance(); // Move past operator.
+        let right = self.expression_with_power(power)?;
+        Ok(Expression::create_assign_expr(left, operator, right))
     }
     /// Parses an array expression.
-    fn array_expression(&'a self) -> NodeOrError<Expression<'a>> {
-        let start = self.token().span[0];
+    fn array_expression(&self) -> NodeOrError<Expression> {
+        let start = self.token().span.start;
         self.advance(); // Move past [
-        self.operators.borrow_mut().push(&Operator::Temp);
         let mut elements = vec![];
         while !(self.end() || self.token().is_bracket(&BracketKind::RightSquare)) {
-            let element = self.expression()?;
+            let element = self.expression_or_error();
             if self.token().is_comma() {
                 self.advance();
             } else if !self.token().is_bracket(&BracketKind::RightSquare) {
@@ -477,26 +746,25 @@ impl<'a> Parser<'a> {
         if self.end() {
             return Err((SyntaxError::ExpectedRSquareBrac, self.token().span));
         }
-        let end = self.token().span[1];
-        self.operators.borrow_mut().pop();
+        let end = self.token().span.end;
         self.advance(); // Move past ]
         let array_exp = Expression::ArrayExpression(ArrayExpression {
             elements,
-            span: [start, end],
+            span: self.span(start, end),
         });
-        Ok(self.reparse(array_exp)?)
+        Ok(array_exp)
     }
     /// Parses a self expression.
-    fn self_expression(&'a self) -> NodeOrError<Expression<'a>> {
+    fn self_expression(&self) -> NodeOrError<Expression> {
         let self_exp = Expression::SelfExpression(SelfExpression {
             span: self.token().span,
         });
         self.advance();
-        Ok(self.reparse(self_exp)?)
+        Ok(self_exp)
     }
     /// Parses an anonymous function.
-    fn functional_expression(&'a self) -> NodeOrError<Expression<'a>> {
-        let start = self.token().span[0];
+    fn functional_expression(&self) -> NodeOrError<Expression> {
+        let start = self.token().span.start;
         self.advance(); // Move past fn.
         let labels = self.maybe_generic_arguments()?;
         if self.token().is_identifier() {
@@ -510,11 +778,11 @@ impl<'a> Parser<'a> {
         if self.token().is_bracket(&BracketKind::LeftCurly) {
             body = Some(self.block()?);
             implicit_return = None;
-            end = body.as_ref().unwrap().get_range()[1];
+            end = body.as_ref().unwrap().get_range().end;
         } else {
             body = None;
             implicit_return = Some(Box::new(self.expression()?));
-            end = implicit_return.as_ref().as_ref().unwrap().get_range()[1];
+            end = implicit_return.as_ref().as_ref().unwrap().get_range().end;
         }
         let fn_exp = Expression::FnExpression(FnExpression {
             labels,
@@ -522,16 +790,16 @@ impl<'a> Parser<'a> {
             return_type,
             body,
             implicit_return,
-            span: [start, end],
+            span: self.span(start, end),
         });
-        Ok(self.reparse(fn_exp)?)
+        Ok(fn_exp)
     }
 }
 
 /// Injunctions
-impl<'a> Parser<'a> {
-    fn injunction(&'a self, injunction: &Injunction) -> NodeOrError<Statement<'a>> {
-        match injunction {
+impl Parser {
+    fn injunction(&self, injunction: &Injunction) -> NodeOrError<Statement> {
+        self.node(SyntaxKind::injunction(injunction), || match injunction {
             Injunction::Function => self.function_declaration(),
             Injunction::Type => self.type_alias(),
             Injunction::Class => self.class_declaration(),
@@ -547,16 +815,19 @@ impl<'a> Parser<'a> {
             Injunction::Module => self.module(),
             Injunction::Public => self.public_statement(),
             Injunction::Unknown(_) => self.unknown_injunction(),
-        }
+        })
     }
-    fn get_identifer(&'a self) -> NodeOrError<Identifier<'a>> {
+    fn get_identifer(&self) -> NodeOrError<Identifier> {
         let name;
         if let Token {
             span,
             kind: TokenKind::Identifier(TokenIdentifier { value }),
         } = self.token()
         {
-            name = Identifier { value, span: *span }
+            name = Identifier {
+                value: value.clone(),
+                span: *span,
+            }
         } else {
             return Err((SyntaxError::ExpectedIdentifier, self.token().span));
         }
@@ -564,8 +835,9 @@ impl<'a> Parser<'a> {
         Ok(name)
     }
     /// Parses a function decalaration.
-    fn function_declaration(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn function_declaration(&self) -> NodeOrError<Statement> {
+        let docs = self.docs();
+        let start = self.token().span.start;
         self.advance(); // Move past @function
         let name;
         if let Token {
@@ -573,7 +845,10 @@ impl<'a> Parser<'a> {
             span,
         } = &self.token()
         {
-            name = Identifier { value, span: *span };
+            name = Identifier {
+                value: value.clone(),
+                span: *span,
+            };
             self.advance(); // Move past function name.
         } else {
             return Err((SyntaxError::ExpectedFunctionName, self.token().span));
@@ -582,52 +857,47 @@ impl<'a> Parser<'a> {
         let parameters = self.parameters()?;
         let return_type = self.maybe_return_type()?;
         let body = self.block()?;
-        let end = self.token().span[1];
+        let end = body.span.end;
         let decl = Statement::Function(Function {
             name,
             generic_arguments: labels,
             parameters,
             return_type,
             body,
-            span: [start, end],
+            docs,
+            span: self.span(start, end),
         });
         Ok(decl)
     }
     /// Parses a class declaration.
-    fn class_declaration(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn class_declaration(&self) -> NodeOrError<Statement> {
+        let docs = self.docs();
+        let start = self.token().span.start;
         self.advance(); // Move past @class
         let (name, generic_arguments) = self.typed_identifier()?;
         if !self.token().is_bracket(&BracketKind::LeftCurly) {
             return Err((SyntaxError::ExpectedLCurly, self.token().span));
         }
         self.advance();
-        let mut properties = vec![];
-        while !(self.end() || self.token().is_bracket(&BracketKind::RightCurly)) {
-            let property = self.property()?;
-            properties.push(property);
-            if self.token().is_comma() {
-                self.advance();
-            } else if !self.token().is_bracket(&BracketKind::RightCurly) {
-                return Err((SyntaxError::ExpectedCommaOrRCurly, self.token().span));
-            }
-        }
+        let properties = self.members(Self::property);
         if self.end() {
             return Err((SyntaxError::ExpectedRCurly, self.token().span));
         }
-        let end = self.token().span[1];
+        let end = self.token().span.end;
         self.advance();
         let class = Statement::Class(Class {
             name,
             generic_arguments,
             properties,
-            span: [start, end],
+            docs,
+            span: self.span(start, end),
         });
         Ok(class)
     }
     /// Parses a type alias.
-    fn type_alias(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn type_alias(&self) -> NodeOrError<Statement> {
+        let docs = self.docs();
+        let start = self.token().span.start;
         self.advance(); // Move past @type
         let (name, labels) = self.typed_identifier()?;
         if !self.token().is_operator(&Operator::Assign) {
@@ -638,63 +908,62 @@ impl<'a> Parser<'a> {
         if !self.token().is_semi_colon() {
             return Err((SyntaxError::ExpectedSemiColon, self.token().span));
         }
-        let end = self.token().span[1];
+        let end = self.token().span.end;
+        self.advance(); // Move past ;
         let type_alias = Statement::TypeAlias(TypeAlias {
             name,
             generic_arguments: labels,
             value,
-            span: [start, end],
+            docs,
+            span: self.span(start, end),
         });
         Ok(type_alias)
     }
-    fn record_declaration(&'a self) -> NodeOrError<Statement<'a>> {
+    fn record_declaration(&self) -> NodeOrError<Statement> {
+        let docs = self.docs();
+        let start = self.token().span.start;
         self.advance(); // Move past @record
         let name = self.get_identifer()?;
         if !self.token().is_bracket(&BracketKind::LeftCurly) {
             return Err((SyntaxError::ExpectedLCurly, self.token().span));
         }
         self.advance(); // Move past {
-        let mut mappings = vec![];
-        while !(self.end() || self.token().is_bracket(&BracketKind::RightCurly)) {
-            let mapping = self.record_mapping()?;
-            mappings.push(mapping);
-            if self.token().is_comma() {
-                self.advance();
-            } else if !self.token().is_bracket(&BracketKind::RightCurly) {
-                return Err((SyntaxError::ExpectedCommaOrRCurly, self.token().span));
-            }
-        }
+        let mappings = self.members(Self::record_mapping);
         if self.end() {
             return Err((SyntaxError::ExpectedRCurly, self.token().span));
         }
-        let span = [name.get_range()[0], self.token().span[1]];
+        let span = self.span(start, self.token().span.end);
         self.advance();
         let rec = Statement::Record(Record {
             name,
             mappings,
+            docs,
             span,
         });
         Ok(rec)
     }
-    fn record_mapping(&'a self) -> NodeOrError<Mapping<'a>> {
-        let key = self.expression()?;
-        if !self.token().is_operator(&Operator::Returns) {
-            return Err((SyntaxError::ExpectedArrow, self.token().span));
-        }
-        self.advance(); // Move past ->
-        let value = self.expression()?;
-        if !(key.is_literal() && value.is_literal()) {
-            return Err((
-                SyntaxError::DynamicRecordMap,
-                [key.get_range()[0], value.get_range()[1]],
-            ));
-        }
-        let span = [key.get_range()[0], value.get_range()[1]];
-        Ok(Mapping { key, value, span })
+    fn record_mapping(&self) -> NodeOrError<Mapping> {
+        self.node(SyntaxKind::Mapping, || {
+            let key = self.expression()?;
+            if !self.token().is_operator(&Operator::Returns) {
+                return Err((SyntaxError::ExpectedArrow, self.token().span));
+            }
+            self.advance(); // Move past ->
+            let value = self.expression()?;
+            if !(key.is_literal() && value.is_literal()) {
+                return Err((
+                    SyntaxError::DynamicRecordMap,
+                    key.get_range().to(value.get_range()),
+                ));
+            }
+            let span = key.get_range().to(value.get_range());
+            Ok(Mapping { key, value, span })
+        })
     }
     /// Parses a variable declaration, either const or let.
-    fn variable_declaration(&'a self, var_type: &str) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn variable_declaration(&self, var_type: &str) -> NodeOrError<Statement> {
+        let docs = self.docs();
+        let start = self.token().span.start;
         self.advance(); // Move past @let or @const.
         let name;
         let initializer;
@@ -704,7 +973,10 @@ impl<'a> Parser<'a> {
             span,
         } = &self.token()
         {
-            name = Identifier { value, span: *span };
+            name = Identifier {
+                value: value.clone(),
+                span: *span,
+            };
             self.advance(); // Move past name.
         } else {
             return Err((SyntaxError::ExpectedVariableName, self.token().span));
@@ -713,7 +985,7 @@ impl<'a> Parser<'a> {
         if self.token().is_operator(&Operator::Assign) {
             self.advance(); // Move past =
             initializer = Some(self.expression()?);
-            end = initializer.as_ref().unwrap().get_range()[1];
+            end = initializer.as_ref().unwrap().get_range().end;
         } else {
             initializer = None;
             if type_label.is_none() && initializer.is_none() {
@@ -721,7 +993,7 @@ impl<'a> Parser<'a> {
             } else if var_type == "const" && (type_label.is_none() || initializer.is_none()) {
                 return Err((SyntaxError::UninitializedConstant, name.span));
             } else {
-                end = type_label.as_ref().unwrap().get_range()[1];
+                end = type_label.as_ref().unwrap().get_range().end;
             }
         }
         if !self.token().is_semi_colon() {
@@ -737,13 +1009,14 @@ impl<'a> Parser<'a> {
                 VarKind::Const
             },
             type_label,
-            span: [start, end],
+            docs,
+            span: self.span(start, end),
         });
         Ok(exp)
     }
     /// Parses a @use import.
-    fn use_import(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn use_import(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past @use.
         let imports = self.imports()?;
         if let Token {
@@ -753,35 +1026,37 @@ impl<'a> Parser<'a> {
         {
             self.advance();
             if let Token {
-                kind:
-                    TokenKind::Literal(Literal {
-                        kind: LiteralKind::StringLiteral,
-                        value,
-                    }),
+                kind: TokenKind::Literal(Literal { kind, value, .. }),
                 span,
             } = self.token()
             {
-                let source = TextString { value, span: *span };
+                if !kind.is_string() {
+                    return Err((SyntaxError::ExpectedImportSource, self.token().span));
+                }
+                let source = TextString {
+                    value: value.clone(),
+                    span: *span,
+                };
                 self.advance();
                 if !self.token().is_semi_colon() {
                     return Err((SyntaxError::ExpectedSemiColon, self.token().span));
                 }
-                let end = self.token().span[1];
+                let end = self.token().span.end;
                 self.advance();
                 let use_stat = Statement::UseImport(UseImport {
                     imports,
                     source,
-                    span: [start, end],
+                    span: self.span(start, end),
                 });
                 Ok(use_stat)
             } else {
-                return Err((SyntaxError::ExpectedImportSource, self.token().span));
+                Err((SyntaxError::ExpectedImportSource, self.token().span))
             }
         } else {
-            return Err((SyntaxError::ExpectedFrom, self.token().span));
+            Err((SyntaxError::ExpectedFrom, self.token().span))
         }
     }
-    fn imports(&'a self) -> NodeOrError<Vec<Import<'a>>> {
+    fn imports(&self) -> NodeOrError<Vec<Import>> {
         if !self.token().is_bracket(&BracketKind::LeftCurly) {
             return Err((SyntaxError::ExpectedLCurly, self.token().span));
         }
@@ -802,192 +1077,194 @@ impl<'a> Parser<'a> {
         self.advance();
         Ok(imports)
     }
-    fn import(&'a self) -> NodeOrError<Import<'a>> {
-        let start = self.token().span[0];
-        let imported_name;
-        let local_name;
-        let collapsed_import;
-
-        let end;
-        if let Token {
-            kind: TokenKind::Identifier(TokenIdentifier { value }),
-            span,
-        } = self.token()
-        {
-            imported_name = Identifier { value, span: *span };
-            collapsed_import = false;
-        } else if let Token {
-            kind: TokenKind::Operator(Operator::Multiply),
-            span,
-        } = self.token()
-        {
-            imported_name = Identifier {
-                value: "*",
-                span: *span,
-            };
-            collapsed_import = true;
-        } else {
-            return Err((SyntaxError::ExpectedImport, self.token().span));
-        }
-        self.advance();
+    fn import(&self) -> NodeOrError<Import> {
+        self.node(SyntaxKind::Import, || {
+            let start = self.token().span.start;
+            let imported_name;
+            let local_name;
+            let collapsed_import;
 
-        if self.token().is_keyword(&Keyword::As) {
-            self.advance();
+            let end;
             if let Token {
                 kind: TokenKind::Identifier(TokenIdentifier { value }),
                 span,
             } = self.token()
             {
-                local_name = Some(Identifier { value, span: *span });
-                self.advance();
-                end = local_name.as_ref().unwrap().get_range()[1];
+                imported_name = Identifier {
+                    value: value.clone(),
+                    span: *span,
+                };
+                collapsed_import = false;
+            } else if let Token {
+                kind: TokenKind::Operator(Operator::Multiply),
+                span,
+            } = self.token()
+            {
+                imported_name = Identifier {
+                    value: "*".to_string(),
+                    span: *span,
+                };
+                collapsed_import = true;
             } else {
-                return Err((SyntaxError::ExpectedIdentifier, self.token().span));
+                return Err((SyntaxError::ExpectedImport, self.token().span));
             }
-        } else {
-            if collapsed_import {
-                return Err((SyntaxError::ExpectedAs, self.token().span));
+            self.advance();
+
+            if self.token().is_keyword(&Keyword::As) {
+                self.advance();
+                if let Token {
+                    kind: TokenKind::Identifier(TokenIdentifier { value }),
+                    span,
+                } = self.token()
+                {
+                    local_name = Some(Identifier {
+                        value: value.clone(),
+                        span: *span,
+                    });
+                    self.advance();
+                    end = local_name.as_ref().unwrap().get_range().end;
+                } else {
+                    return Err((SyntaxError::ExpectedIdentifier, self.token().span));
+                }
+            } else {
+                if collapsed_import {
+                    return Err((SyntaxError::ExpectedAs, self.token().span));
+                }
+                end = imported_name.get_range().end;
+                local_name = None;
             }
-            end = imported_name.get_range()[1];
-            local_name = None;
-        }
-        let import = Import {
-            collapsed_import,
-            imported_name,
-            local_name,
-            span: [start, end],
-        };
-        Ok(import)
+            let import = Import {
+                collapsed_import,
+                imported_name,
+                local_name,
+                span: self.span(start, end),
+            };
+            Ok(import)
+        })
     }
     /// Parses an @prepend statement.
-    fn prepend_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn prepend_statement(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past @prepend
         let source = self.expression()?;
         if !self.token().is_semi_colon() {
             Err((SyntaxError::ExpectedSemiColon, self.token().span))
         } else {
-            let end = self.token().span[1];
+            let end = self.token().span.end;
             self.advance();
             let prep_stat = Statement::PrependStatement(PrependStatement {
                 source,
-                span: [start, end],
+                span: self.span(start, end),
             });
             Ok(prep_stat)
         }
     }
     /// Parses an @tests block.
-    fn test_block(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn test_block(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past @tests.
         let body = self.block()?;
-        let end = body.get_range()[1];
+        let end = body.get_range().end;
         let test_block = Statement::TestBlock(TestBlock {
             body,
-            span: [start, end],
+            span: self.span(start, end),
         });
         Ok(test_block)
     }
     /// Parses an enum.
-    fn enum_declaration(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn enum_declaration(&self) -> NodeOrError<Statement> {
+        let docs = self.docs();
+        let start = self.token().span.start;
         self.advance();
         let (name, generic_arguments) = self.typed_identifier()?;
         if !self.token().is_bracket(&BracketKind::LeftCurly) {
             return Err((SyntaxError::ExpectedLCurly, self.token().span));
         }
         self.advance();
-        let mut variants = vec![];
-        while !(self.end() || self.token().is_bracket(&BracketKind::RightCurly)) {
-            let variant = self.variant()?;
-            variants.push(variant);
-            if self.token().is_comma() {
-                self.advance();
-            } else if !self.token().is_bracket(&BracketKind::RightCurly) {
-                return Err((SyntaxError::ExpectedCommaOrRCurly, self.token().span));
-            }
-        }
+        let variants = self.members(Self::variant);
         if self.end() {
             return Err((SyntaxError::ExpectedRCurly, self.token().span));
         }
-        let end = self.token().span[1];
+        let end = self.token().span.end;
         self.advance();
         let enum_ = Statement::Enum(Enum {
             name,
             generic_arguments,
             variants,
-            span: [start, end],
+            docs,
+            span: self.span(start, end),
         });
         Ok(enum_)
     }
-    fn variant(&'a self) -> NodeOrError<Variant<'a>> {
-        let start = self.token().span[0];
-        let name = self.get_identifer()?;
-        let end;
-        if self.token().is_bracket(&BracketKind::LeftParenthesis) {
-            self.advance();
-            let mut elements = vec![];
-            while !(self.end() || self.token().is_bracket(&BracketKind::RightParenthesis)) {
-                let type_ = self.type_name()?;
-                elements.push(type_);
-                if self.token().is_comma() {
-                    self.advance();
-                } else if !self.token().is_bracket(&BracketKind::RightParenthesis) {
+    fn variant(&self) -> NodeOrError<Variant> {
+        self.node(SyntaxKind::Variant, || {
+            let docs = self.docs();
+            let start = self.token().span.start;
+            let name = self.get_identifer()?;
+            let end;
+            if self.token().is_bracket(&BracketKind::LeftParenthesis) {
+                self.advance();
+                let mut elements = vec![];
+                while !(self.end() || self.token().is_bracket(&BracketKind::RightParenthesis)) {
+                    let type_ = self.type_name()?;
+                    elements.push(type_);
+                    if self.token().is_comma() {
+                        self.advance();
+                    } else if !self.token().is_bracket(&BracketKind::RightParenthesis) {
+                        return Err((SyntaxError::ExpectedRParen, self.token().span));
+                    }
+                }
+                if self.end() {
                     return Err((SyntaxError::ExpectedRParen, self.token().span));
                 }
+                end = self.token().span.end;
+                self.advance();
+                Ok(Variant::Tuple {
+                    name,
+                    elements,
+                    docs,
+                    span: self.span(start, end),
+                })
+            } else {
+                end = name.get_range().end;
+                Ok(Variant::Concrete {
+                    name,
+                    docs,
+                    span: self.span(start, end),
+                })
             }
-            if self.end() {
-                return Err((SyntaxError::ExpectedRParen, self.token().span));
-            }
-            end = self.token().span[1];
-            self.advance();
-            Ok(Variant::Tuple {
-                name,
-                elements,
-                span: [start, end],
-            })
-        } else {
-            end = name.get_range()[1];
-            Ok(Variant::Concrete {
-                name,
-                span: [start, end],
-            })
-        }
+        })
     }
     /// Parses an interface.
-    fn interface_declaration(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn interface_declaration(&self) -> NodeOrError<Statement> {
+        let docs = self.docs();
+        let start = self.token().span.start;
         self.advance(); // Move past @interface
         let (name, generic_arguments) = self.typed_identifier()?;
         if !self.token().is_bracket(&BracketKind::LeftCurly) {
             return Err((SyntaxError::ExpectedLCurly, self.token().span));
         }
         self.advance();
-        let mut properties = vec![];
-        while !(self.end() || self.token().is_bracket(&BracketKind::RightCurly)) {
-            let property = self.property()?;
-            properties.push(property);
-            if self.token().is_comma() {
-                self.advance();
-            } else if !self.token().is_bracket(&BracketKind::RightCurly) {
-                return Err((SyntaxError::ExpectedCommaOrRCurly, self.token().span));
-            }
-        }
+        let properties = self.members(Self::property);
         if self.end() {
             return Err((SyntaxError::ExpectedRCurly, self.token().span));
         }
-        let end = self.token().span[1];
+        let end = self.token().span.end;
         self.advance();
         let interface = Statement::Interface(Interface {
             name,
             generic_arguments,
             properties,
-            span: [start, end],
+            docs,
+            span: self.span(start, end),
         });
         Ok(interface)
     }
-    /// Parses either an attribute, an implement or method.
-    fn property(&'a self) -> NodeOrError<Property<'a>> {
+    /// Parses either an attribute, an implement or method.<br>
+    /// The node of a method or an attribute also holds the `static` or `readonly` keywords in front of its name.
+    fn property(&self) -> NodeOrError<Property> {
+        let checkpoint = self.checkpoint();
+        let docs = self.docs();
         let is_static = if self.token().is_keyword(&Keyword::Static) {
             self.advance(); // Move past static.
             true
@@ -1007,20 +1284,25 @@ impl<'a> Parser<'a> {
             kind: TokenKind::Identifier(TokenIdentifier { value }),
         } = self.token()
         {
-            name = Identifier { value, span: *span };
+            name = Identifier {
+                value: value.clone(),
+                span: *span,
+            };
             self.advance(); // Move past property name.
             if self.token().is_bracket(&BracketKind::LeftParenthesis)
                 || self.token().is_operator(&Operator::LessThan)
             {
                 // Methods cannot have the readonly tag.
                 if is_readonly {
-                    self.diagnostics
-                        .borrow_mut()
-                        .push((SyntaxError::ReadOnlyMethod, name.span));
+                    self.store_error((SyntaxError::ReadOnlyMethod, name.span));
                 }
-                self.method(name, is_static)
+                self.node_at(checkpoint, SyntaxKind::Method, || {
+                    self.method(name, is_static, docs)
+                })
             } else {
-                self.attribute(name, is_static, is_readonly)
+                self.node_at(checkpoint, SyntaxKind::Attribute, || {
+                    self.attribute(name, is_static, is_readonly, docs)
+                })
             }
         } else {
             if let Token {
@@ -1029,24 +1311,27 @@ impl<'a> Parser<'a> {
             } = self.token()
             {
                 if is_static || is_readonly {
-                    self.diagnostics
-                        .borrow_mut()
-                        .push((SyntaxError::UnexpectedKeyword, self.token().span));
+                    self.store_error((SyntaxError::UnexpectedKeyword, self.token().span));
                 }
-                self.implement()
+                self.node(SyntaxKind::Implement, || self.implement())
             } else {
                 Err((SyntaxError::ExpectedPropertyName, self.token().span))
             }
         }
     }
     /// Parses a class method.
-    fn method(&'a self, name: Identifier<'a>, is_static: bool) -> NodeOrError<Property<'a>> {
-        let start = name.get_range()[0];
+    fn method(
+        &self,
+        name: Identifier,
+        is_static: bool,
+        docs: Option<String>,
+    ) -> NodeOrError<Property> {
+        let start = name.get_range().start;
         let generic_arguments = self.maybe_generic_arguments()?;
         let parameters = self.parameters()?;
         let return_type = self.maybe_return_type()?;
         let body = self.block()?;
-        let end = body.get_range()[1];
+        let end = body.get_range().end;
         Ok(Property::Method(Method {
             name,
             generic_arguments,
@@ -1054,35 +1339,35 @@ impl<'a> Parser<'a> {
             return_type,
             is_static,
             body,
-            span: [start, end],
+            docs,
+            span: self.span(start, end),
         }))
     }
     /// Parses a class attribute.
     fn attribute(
-        &'a self,
-        key: Identifier<'a>,
+        &self,
+        key: Identifier,
         is_static: bool,
         is_readonly: bool,
-    ) -> NodeOrError<Property<'a>> {
-        let start = key.get_range()[0];
+        docs: Option<String>,
+    ) -> NodeOrError<Property> {
+        let start = key.get_range().start;
         let type_label = self.maybe_type_label()?;
         let value;
         let end;
         if self.token().is_operator(&Operator::Assign) {
             self.advance();
             value = Some(self.expression()?);
-            end = value.as_ref().unwrap().get_range()[1];
+            end = value.as_ref().unwrap().get_range().end;
         } else {
             // All static properties must be initialized.
             if is_static || is_readonly {
-                self.diagnostics
-                    .borrow_mut()
-                    .push((SyntaxError::UnknownStaticOrReadOnly, self.token().span));
+                self.store_error((SyntaxError::UnknownStaticOrReadOnly, self.token().span));
             }
             value = None;
             end = match &type_label {
-                Some(s) => s.get_range()[0],
-                None => key.get_range()[1],
+                Some(s) => s.get_range().start,
+                None => key.get_range().end,
             };
         }
         Ok(Property::Attribute(Attribute {
@@ -1091,44 +1376,49 @@ impl<'a> Parser<'a> {
             is_static,
             is_readonly,
             value,
-            span: [start, end],
+            docs,
+            span: self.span(start, end),
         }))
     }
     // Parses an implementation.
-    fn implement(&'a self) -> NodeOrError<Property<'a>> {
-        let start = self.token().span[0];
+    fn implement(&self) -> NodeOrError<Property> {
+        let start = self.token().span.start;
         self.advance(); // Move past @implement
         if let Token {
             kind: TokenKind::Identifier(TokenIdentifier { value }),
             span,
         } = self.token()
         {
-            let interface = Identifier { value, span: *span };
+            let interface = Identifier {
+                value: value.clone(),
+                span: *span,
+            };
             self.advance();
-            let end = span[1];
+            let end = span.end;
             Ok(Property::Implement(Implement {
                 interface,
-                span: [start, end],
+                span: self.span(start, end),
             }))
         } else {
             Err((SyntaxError::ExpectedInterfaceName, self.token().span))
         }
     }
     /// Parses a public statement.
-    fn public_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn public_statement(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past @public.
         let statement = self.statement()?;
-        let end = statement.get_range()[1];
+        let end = statement.get_range().end;
         let pub_stat = Statement::PublicModifier(PublicModifier {
             statement: Box::new(statement),
-            span: [start, end],
+            span: self.span(start, end),
         });
         Ok(pub_stat)
     }
     /// Parses a module.
-    fn module(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn module(&self) -> NodeOrError<Statement> {
+        let docs = self.docs();
+        let start = self.token().span.start;
         let name;
         self.advance(); // Move past @module.
         if let Token {
@@ -1136,30 +1426,34 @@ impl<'a> Parser<'a> {
             kind: TokenKind::Identifier(TokenIdentifier { value }),
         } = self.token()
         {
-            name = Identifier { value, span: *span };
+            name = Identifier {
+                value: value.clone(),
+                span: *span,
+            };
             self.advance(); // Move past module name.
         } else {
             return Err((SyntaxError::ExpectedAModuleName, self.token().span));
         }
         let body = self.block()?;
-        let end = body.get_range()[1];
+        let end = body.get_range().end;
         let module = Statement::Module(Module {
             name,
             body,
-            span: [start, end],
+            docs,
+            span: self.span(start, end),
         });
         Ok(module)
     }
-    fn unknown_injunction(&'a self) -> NodeOrError<Statement<'a>> {
+    fn unknown_injunction(&self) -> NodeOrError<Statement> {
         Err((SyntaxError::UnrecognizedInjunction, self.token().span))
     }
 }
 
 /// Control statements
-impl<'a> Parser<'a> {
+impl Parser {
     /// Parse a control statement.
-    fn control_statement(&'a self, keyword: &Keyword) -> NodeOrError<Statement<'a>> {
-        match keyword {
+    fn control_statement(&self, keyword: &Keyword) -> NodeOrError<Statement> {
+        self.node(SyntaxKind::control(keyword), || match keyword {
             Keyword::If => self.if_statememt(),
             Keyword::Println => self.print_statement(),
             Keyword::While => self.while_statement(),
@@ -1173,17 +1467,15 @@ impl<'a> Parser<'a> {
             Keyword::Else => self.illegal("else"),
             Keyword::Recover => self.illegal("recover"),
             _ => Err((SyntaxError::UnexpectedKeyword, self.token().span)),
-        }
+        })
     }
     /// Parse the condition of a while loop or an if statement.
-    fn condition(&'a self) -> NodeOrError<Expression<'a>> {
+    fn condition(&self) -> NodeOrError<Expression> {
         if !self.token().is_bracket(&BracketKind::LeftParenthesis) {
             return Err((SyntaxError::ExpectedLParen, self.token().span));
         }
         self.advance(); // Move past (
-        self.operators.borrow_mut().push(&Operator::Temp);
-        let expression = self.expression()?;
-        self.operators.borrow_mut().pop();
+        let expression = self.expression_or_error();
         if !self.token().is_bracket(&BracketKind::RightParenthesis) {
             return Err((SyntaxError::ExpectedRParen, self.token().span));
         }
@@ -1191,7 +1483,7 @@ impl<'a> Parser<'a> {
         Ok(expression)
     }
     /// Parse the consequent of a while loop, a for statement or an if statement.
-    fn consequent(&'a self) -> NodeOrError<Statement<'a>> {
+    fn consequent(&self) -> NodeOrError<Statement> {
         if self.token().is_bracket(&BracketKind::LeftCurly) {
             self.block_statement()
         } else {
@@ -1199,32 +1491,43 @@ impl<'a> Parser<'a> {
         }
     }
     /// Parses a block statement.
-    fn block_statement(&'a self) -> NodeOrError<Statement<'a>> {
+    fn block_statement(&self) -> NodeOrError<Statement> {
         Ok(Statement::BlockStatement(self.block()?))
     }
-    fn block(&'a self) -> NodeOrError<Block<'a>> {
-        let start = self.token().span[0];
-        self.advance(); // Move past {
-        let close = BracketKind::RightCurly;
-        let mut statements = vec![];
-        while !(self.end() || self.token().is_bracket(&close)) {
-            let statement = self.statement()?;
-            statements.push(statement);
-        }
-        if self.end() {
-            return Err((SyntaxError::ExpectedRCurly, self.token().span));
-        }
-        let end = self.token().span[1];
-        self.advance(); // Move past }
-        let block = Block {
-            body: statements,
-            span: [start, end],
-        };
-        Ok(block)
+    fn block(&self) -> NodeOrError<Block> {
+        self.traced("block", || {
+            self.node(SyntaxKind::Block, || {
+                if !self.token().is_bracket(&BracketKind::LeftCurly) {
+                    return Err((SyntaxError::ExpectedLCurly, self.token().span));
+                }
+                let start = self.token().span.start;
+                self.lists.borrow_mut().push(List::new(Some(self.index())));
+                self.advance(); // Move past {
+                let close = BracketKind::RightCurly;
+                let mut statements = vec![];
+                while !(self.end() || self.token().is_bracket(&close)) {
+                    let statement = self.statement_or_error();
+                    statements.push(statement);
+                }
+                let mut list = self.lists.borrow_mut().pop().unwrap();
+                list.close = self.index();
+                self.record(Event::List(list));
+                if self.end() {
+                    return Err((SyntaxError::ExpectedRCurly, self.token().span));
+                }
+                let end = self.token().span.end;
+                self.advance(); // Move past }
+                let block = Block {
+                    body: statements,
+                    span: self.span(start, end),
+                };
+                Ok(block)
+            })
+        })
     }
     /// Parse an if statement.
-    fn if_statememt(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn if_statememt(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past the if.
         let test = self.condition()?;
         let body = self.consequent()?;
@@ -1244,20 +1547,20 @@ impl<'a> Parser<'a> {
             if alternate.is_declaration() {
                 return Err((SyntaxError::IllegalDeclaration, alternate.get_range()));
             };
-            end = self.token().span[0];
+            end = alternate.get_range().end;
             if_stat = Statement::IfStatement(IfStatement {
                 test,
                 body: Box::new(body),
                 alternate: Some(Box::new(alternate)),
-                span: [start, end],
+                span: self.span(start, end),
             });
         } else {
-            end = self.token().span[0];
+            end = body.get_range().end;
             if_stat = Statement::IfStatement(IfStatement {
                 test,
                 body: Box::new(body),
                 alternate: None,
-                span: [start, end],
+                span: self.span(start, end),
             });
         }
         if self.token().is_semi_colon() {
@@ -1265,44 +1568,44 @@ impl<'a> Parser<'a> {
         }
         Ok(if_stat)
     }
-    fn while_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn while_statement(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past while.
         let test = self.condition()?;
         let body = self.consequent()?;
-        let end = body.get_range()[1];
+        let end = body.get_range().end;
         let while_stat = Statement::WhileLoop(WhileStatement {
             test,
             body: Box::new(body),
-            span: [start, end],
+            span: self.span(start, end),
         });
         Ok(while_stat)
     }
     /// Parses a print statement.
-    fn print_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn print_statement(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past print.
         let argument = self.expression()?;
         if !self.token().is_semi_colon() {
             Err((SyntaxError::ExpectedSemiColon, self.token().span))
         } else {
-            let end = self.token().span[1];
+            let end = self.token().span.end;
             self.advance(); // Move past ;
             let print_stat = Statement::PrintLnStatement(PrintLnStatement {
                 argument,
-                span: [start, end],
+                span: self.span(start, end),
             });
             Ok(print_stat)
         }
     }
     /// Parses a return statement.
-    fn return_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn return_statement(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past return.
         let argument;
         let end;
         if self.token().is_semi_colon() {
-            end = self.token().span[1];
+            end = self.token().span.end;
             self.advance();
             argument = None;
         } else {
@@ -1310,34 +1613,35 @@ impl<'a> Parser<'a> {
             if !self.token().is_semi_colon() {
                 return Err((SyntaxError::ExpectedSemiColon, self.token().span));
             } else {
-                end = self.token().span[1];
+                end = self.token().span.end;
                 self.advance(); // Move past ;
             }
         }
         let ret_stat = Statement::ReturnStatement(ReturnStatement {
             argument,
-            span: [start, end],
+            span: self.span(start, end),
         });
         Ok(ret_stat)
     }
     /// Parses a for statement.
-    fn for_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn for_statement(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past for.
         let (item, iterator) = self.for_condition()?;
         let body = self.consequent()?;
         if body.is_declaration() {
             return Err((SyntaxError::IllegalDeclaration, self.token().span));
         }
-        let end = body.get_range()[1];
+        let end = body.get_range().end;
         let for_stat = Statement::ForLoop(ForLoop {
             item,
             iterator,
-            span: [start, end],
+            body: Box::new(body),
+            span: self.span(start, end),
         });
         Ok(for_stat)
     }
-    fn for_condition(&'a self) -> NodeOrError<(Identifier<'a>, Expression<'a>)> {
+    fn for_condition(&self) -> NodeOrError<(Identifier, Expression)> {
         if !self.token().is_bracket(&BracketKind::LeftParenthesis) {
             return Err((SyntaxError::ExpectedLParen, self.token().span));
         }
@@ -1348,7 +1652,10 @@ impl<'a> Parser<'a> {
             kind: TokenKind::Identifier(TokenIdentifier { value }),
         } = self.token()
         {
-            item = Identifier { value, span: *span };
+            item = Identifier {
+                value: value.clone(),
+                span: *span,
+            };
             self.advance(); // Move past identifier name.
         } else {
             return Err((SyntaxError::ExpectedIdentifier, self.token().span));
@@ -1365,58 +1672,59 @@ impl<'a> Parser<'a> {
         Ok((item, iterator))
     }
     /// Parses a loop statement.
-    fn loop_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn loop_statement(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past loop.
-        let constraint;
-        if self.token().is_bracket(&BracketKind::LeftParenthesis) {
-            constraint = Some(self.condition()?);
+        let constraint = if self.token().is_bracket(&BracketKind::LeftParenthesis) {
+            Some(self.condition()?)
         } else {
-            constraint = None;
-        }
+            None
+        };
         let body = self.block()?;
-        let end = body.get_range()[1];
+        let end = body.get_range().end;
         if self.token().is_semi_colon() {
             self.advance();
         }
         let loop_stat = Statement::LoopStmnt(Loop {
             constraint,
             body,
-            span: [start, end],
+            span: self.span(start, end),
         });
         Ok(loop_stat)
     }
     /// Parses a break statement.
-    fn break_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn break_statement(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance();
         if !self.token().is_semi_colon() {
             return Err((SyntaxError::ExpectedSemiColon, self.token().span));
         }
-        let end = self.token().span[1];
+        let end = self.token().span.end;
         self.advance();
-        let break_stat = Statement::Break(Break { span: [start, end] });
+        let break_stat = Statement::Break(Break {
+            span: self.span(start, end),
+        });
         Ok(break_stat)
     }
     /// Parses a crash statemnet.
-    fn crash_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn crash_statement(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past crash.
         let argument = self.expression()?;
         if !self.token().is_semi_colon() {
             return Err((SyntaxError::ExpectedSemiColon, self.token().span));
         }
-        let end = self.token().span[1];
+        let end = self.token().span.end;
         self.advance();
         let crash_stat = Statement::CrashStmnt(CrashStatement {
             argument,
-            span: [start, end],
+            span: self.span(start, end),
         });
         Ok(crash_stat)
     }
     /// Parses a try block.
-    fn try_block(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn try_block(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
         self.advance(); // Move past try.
         let body = self.block()?;
         let recoverblock;
@@ -1427,10 +1735,10 @@ impl<'a> Parser<'a> {
         } = self.token()
         {
             recoverblock = Some(self.recover_block()?);
-            end = recoverblock.as_ref().unwrap().get_range()[1];
+            end = recoverblock.as_ref().unwrap().get_range().end;
         } else {
             recoverblock = None;
-            end = body.get_range()[1];
+            end = body.get_range().end;
         }
         if self.token().is_semi_colon() {
             self.advance();
@@ -1438,40 +1746,42 @@ impl<'a> Parser<'a> {
         let try_bloc = Statement::TryBlock(TryBlock {
             body,
             recover: recoverblock,
-            span: [start, end],
+            span: self.span(start, end),
         });
         Ok(try_bloc)
     }
     /// Parses a recover block.
-    fn recover_block(&'a self) -> NodeOrError<RecoverBlock<'a>> {
-        let start = self.token().span[0];
-        self.advance(); // Move past recover.
-        let params = self.parameters()?;
-        let body = self.block()?;
-        let end = body.get_range()[1];
-        let recover_block = RecoverBlock {
-            params,
-            body,
-            span: [start, end],
-        };
-        Ok(recover_block)
+    fn recover_block(&self) -> NodeOrError<RecoverBlock> {
+        self.node(SyntaxKind::RecoverBlock, || {
+            let start = self.token().span.start;
+            self.advance(); // Move past recover.
+            let params = self.parameters()?;
+            let body = self.block()?;
+            let end = body.get_range().end;
+            let recover_block = RecoverBlock {
+                params,
+                body,
+                span: self.span(start, end),
+            };
+            Ok(recover_block)
+        })
     }
     /// Parses a continue statement.
-    fn continue_statement(&'a self) -> NodeOrError<Statement<'a>> {
-        let start = self.token().span[0];
+    fn continue_statement(&self) -> NodeOrError<Statement> {
+        let start = self.token().span.start;
+        self.advance(); // Move past continue.
         if !self.token().is_semi_colon() {
             return Err((SyntaxError::ExpectedSemiColon, self.token().span));
         }
-        let end = self.token().span[1];
+        let end = self.token().span.end;
         self.advance();
         let cont_stat = Statement::Continue(Continue {
-            span: [start, end],
-            phantom: PhantomData,
+            span: self.span(start, end),
         });
         Ok(cont_stat)
     }
     /// Parses an illegal else statement.
-    fn illegal(&'a self, kind: &'a str) -> NodeOrError<Statement<'a>> {
+    fn illegal(&self, kind: &str) -> NodeOrError<Statement> {
         let message = match kind {
             "else" => SyntaxError::IllegalElse,
             "recover" => SyntaxError::IllegalRecover,
@@ -1482,61 +1792,74 @@ impl<'a> Parser<'a> {
 }
 
 /// Functions
-impl<'a> Parser<'a> {
-    fn parameters(&'a self) -> NodeOrError<Vec<Parameter<'a>>> {
-        if !self.token().is_bracket(&BracketKind::LeftParenthesis) {
-            return Err((SyntaxError::ExpectedRParen, self.token().span));
-        }
-        let mut parameters = vec![];
-        self.advance(); // Move past (
-        while !(self.end() || self.token().is_bracket(&BracketKind::RightParenthesis)) {
-            let name;
-            if let Token {
-                kind: TokenKind::Identifier(TokenIdentifier { value }),
-                span,
-            } = self.token()
-            {
-                name = Identifier { value, span: *span };
-                self.advance();
-            } else {
-                return Err((SyntaxError::ExpectedParameterName, self.token().span));
-            }
-            let label = self.maybe_type_label()?;
-            let start = name.get_range()[0];
-            let end;
-            if self.token().is_comma() {
-                end = self.token().span[1];
-                self.advance();
-            } else if !self.token().is_bracket(&BracketKind::RightParenthesis) {
+impl Parser {
+    fn parameters(&self) -> NodeOrError<Vec<Parameter>> {
+        self.node(SyntaxKind::ParameterList, || {
+            if !self.token().is_bracket(&BracketKind::LeftParenthesis) {
                 return Err((SyntaxError::ExpectedRParen, self.token().span));
-            } else {
-                end = self.token().span[0];
             }
-            let parameter = Parameter {
-                name,
-                label,
-                span: [start, end],
+            let mut parameters = vec![];
+            self.advance(); // Move past (
+            while !(self.end() || self.token().is_bracket(&BracketKind::RightParenthesis)) {
+                let parameter = self.node(SyntaxKind::Parameter, || self.parameter())?;
+                parameters.push(parameter);
+            }
+            if self.end() {
+                return Err((SyntaxError::ExpectedRParen, self.token().span));
             };
-            parameters.push(parameter);
+            self.advance();
+            Ok(parameters)
+        })
+    }
+    /// Parses a parameter, with the comma after it.
+    fn parameter(&self) -> NodeOrError<Parameter> {
+        let name;
+        if let Token {
+            kind: TokenKind::Identifier(TokenIdentifier { value }),
+            span,
+        } = self.token()
+        {
+            name = Identifier {
+                value: value.clone(),
+                span: *span,
+            };
+            self.advance();
+        } else {
+            return Err((SyntaxError::ExpectedParameterName, self.token().span));
         }
-        if self.end() {
+        let label = self.maybe_type_label()?;
+        let start = name.get_range().start;
+        let end;
+        if self.token().is_comma() {
+            end = self.token().span.end;
+            self.advance();
+        } else if !self.token().is_bracket(&BracketKind::RightParenthesis) {
             return Err((SyntaxError::ExpectedRParen, self.token().span));
+        } else {
+            end = self.token().span.start;
+        }
+        let parameter = Parameter {
+            name,
+            label,
+            span: self.span(start, end),
         };
-        self.advance();
-        Ok(parameters)
+        Ok(parameter)
     }
 }
 
 /// Types
-impl<'a> Parser<'a> {
-    fn typed_identifier(&'a self) -> NodeOrError<(Identifier, Option<Vec<GenericArgument>>)> {
+impl Parser {
+    fn typed_identifier(&self) -> NodeOrError<(Identifier, Option<Vec<GenericArgument>>)> {
         let name;
         if let Token {
             span,
             kind: TokenKind::Identifier(TokenIdentifier { value }),
         } = self.token()
         {
-            name = Identifier { value, span: *span };
+            name = Identifier {
+                value: value.clone(),
+                span: *span,
+            };
             self.advance();
         } else {
             return Err((SyntaxError::ExpectedTypeName, self.token().span));
@@ -1545,104 +1868,114 @@ impl<'a> Parser<'a> {
         Ok((name, labels))
     }
     /// Parse a generic label that may or may not exist.
-    fn maybe_generic_arguments(&'a self) -> NodeOrError<Option<Vec<GenericArgument>>> {
+    fn maybe_generic_arguments(&self) -> NodeOrError<Option<Vec<GenericArgument>>> {
         if !self.token().is_operator(&Operator::LessThan) {
             return Ok(None);
         }
         Ok(Some(self.generic_arguments()?))
     }
     /// Parse a return type that may or may not exist.
-    fn maybe_return_type(&'a self) -> NodeOrError<Option<Type<'a>>> {
+    fn maybe_return_type(&self) -> NodeOrError<Option<Type>> {
         if !self.token().is_operator(&Operator::Returns) {
             return Ok(None);
         }
         Ok(Some(self.return_type()?))
     }
     /// Parses the generic labels of a function.
-    fn generic_arguments(&'a self) -> NodeOrError<Vec<GenericArgument>> {
-        if !self.token().is_operator(&Operator::LessThan) {
-            return Err((SyntaxError::ExpectedLAngleBrac, self.token().span));
-        }
-        self.advance(); // Move past <
-        let mut labels = vec![];
-        while !(self.end() || self.token().is_operator(&Operator::GreaterThan)) {
-            let label = self.generic_argument()?;
-            labels.push(label);
-            if self.token().is_comma() {
-                self.advance();
-            } else if !self.token().is_operator(&Operator::GreaterThan) {
-                return Err((SyntaxError::ExpectedCommaOrRAngleBrac, self.token().span));
+    fn generic_arguments(&self) -> NodeOrError<Vec<GenericArgument>> {
+        self.node(SyntaxKind::GenericArgumentList, || {
+            if !self.token().is_operator(&Operator::LessThan) {
+                return Err((SyntaxError::ExpectedLAngleBrac, self.token().span));
             }
-        }
-        if self.end() {
-            return Err((SyntaxError::ExpectedRAngleBrac, self.token().span));
-        }
-        self.advance(); // Move past >
-        Ok(labels)
+            self.advance(); // Move past <
+            let mut labels = vec![];
+            while !(self.end() || self.token().is_operator(&Operator::GreaterThan)) {
+                let label = self.generic_argument()?;
+                labels.push(label);
+                if self.token().is_comma() {
+                    self.advance();
+                } else if !self.token().is_operator(&Operator::GreaterThan) {
+                    return Err((SyntaxError::ExpectedCommaOrRAngleBrac, self.token().span));
+                }
+            }
+            if self.end() {
+                return Err((SyntaxError::ExpectedRAngleBrac, self.token().span));
+            }
+            self.advance(); // Move past >
+            Ok(labels)
+        })
     }
     /// Parses a generic label.
-    fn generic_argument(&'a self) -> NodeOrError<GenericArgument> {
-        let start;
-        let name;
-        let mut implements = vec![];
-        let end;
-        if let Token {
-            span,
-            kind: TokenKind::Identifier(TokenIdentifier { value }),
-        } = self.token()
-        {
-            name = Identifier { span: *span, value };
-            start = span[0];
-            self.advance();
-            if self.token().is_keyword(&Keyword::Implements) {
-                self.advance(); // Move past implements
-                while !(self.end()
-                    || self.token().is_comma()
-                    || self.token().is_operator(&Operator::GreaterThan))
-                {
-                    let implement;
-                    if let Token {
-                        span,
-                        kind: TokenKind::Identifier(TokenIdentifier { value }),
-                    } = self.token()
-                    {
-                        implement = Identifier { value, span: *span };
-                        self.advance(); // Move past interface name.
-                    } else {
-                        return Err((SyntaxError::ExpectedInterfaceName, self.token().span));
-                    }
-                    implements.push(implement);
-                    if self.token().is_operator(&Operator::Add) {
-                        self.advance();
-                    } else if !(self.token().is_comma()
+    fn generic_argument(&self) -> NodeOrError<GenericArgument> {
+        self.node(SyntaxKind::GenericArgument, || {
+            let start;
+            let name;
+            let mut implements = vec![];
+            let end;
+            if let Token {
+                span,
+                kind: TokenKind::Identifier(TokenIdentifier { value }),
+            } = self.token()
+            {
+                name = Identifier {
+                    span: *span,
+                    value: value.clone(),
+                };
+                start = span.start;
+                self.advance();
+                if self.token().is_keyword(&Keyword::Implements) {
+                    self.advance(); // Move past implements
+                    while !(self.end()
+                        || self.token().is_comma()
                         || self.token().is_operator(&Operator::GreaterThan))
                     {
-                        return Err((SyntaxError::ExpectedRAngleBrac, self.token().span));
+                        let implement;
+                        if let Token {
+                            span,
+                            kind: TokenKind::Identifier(TokenIdentifier { value }),
+                        } = self.token()
+                        {
+                            implement = Identifier {
+                                value: value.clone(),
+                                span: *span,
+                            };
+                            self.advance(); // Move past interface name.
+                        } else {
+                            return Err((SyntaxError::ExpectedInterfaceName, self.token().span));
+                        }
+                        implements.push(implement);
+                        if self.token().is_operator(&Operator::Add) {
+                            self.advance();
+                        } else if !(self.token().is_comma()
+                            || self.token().is_operator(&Operator::GreaterThan))
+                        {
+                            return Err((SyntaxError::ExpectedRAngleBrac, self.token().span));
+                        }
                     }
+                    if self.end() {
+                        return Err((SyntaxError::ExpectedCommaOrRAngleBrac, self.token().span));
+                    }
+                    end = self.token().span.start;
+                } else {
+                    end = name.get_range().end;
                 }
-                if self.end() {
-                    return Err((SyntaxError::ExpectedCommaOrRAngleBrac, self.token().span));
-                }
-                end = self.token().span[0];
             } else {
-                end = name.get_range()[1];
+                return Err((SyntaxError::ExpectedGenericTypeParameter, self.token().span));
             }
-        } else {
-            return Err((SyntaxError::ExpectedGenericTypeParameter, self.token().span));
-        }
-        let label = GenericArgument {
-            name,
-            implements: if implements.len() > 0 {
-                Some(implements)
-            } else {
-                None
-            },
-            span: [start, end],
-        };
-        Ok(label)
+            let label = GenericArgument {
+                name,
+                implements: if !implements.is_empty() {
+                    Some(implements)
+                } else {
+                    None
+                },
+                span: self.span(start, end),
+            };
+            Ok(label)
+        })
     }
     /// Parses a return type signature.
-    fn return_type(&'a self) -> NodeOrError<Type<'a>> {
+    fn return_type(&self) -> NodeOrError<Type> {
         if !self.token().is_operator(&Operator::Returns) {
             return Err((SyntaxError::ExpectedReturnType, self.token().span));
         }
@@ -1650,7 +1983,7 @@ impl<'a> Parser<'a> {
         self.type_name()
     }
     /// Parses a type label that may or may not exist.
-    fn maybe_type_label(&'a self) -> NodeOrError<Option<Type<'a>>> {
+    fn maybe_type_label(&self) -> NodeOrError<Option<Type>> {
         if self.token().is_colon() {
             self.advance(); // Move past label :.
             Ok(Some(self.type_name()?))
@@ -1659,88 +1992,115 @@ impl<'a> Parser<'a> {
         }
     }
     /// Parses a type label.
-    fn type_name(&'a self) -> NodeOrError<Type<'a>> {
-        if self.token().is_operator(&Operator::LessThan)
-            || self.token().is_bracket(&BracketKind::LeftParenthesis)
-        {
-            self.functional_type()
-        } else {
-            self.dot_type(self.concrete_type()?)
-        }
+    fn type_name(&self) -> NodeOrError<Type> {
+        self.traced("type", || {
+            if self.token().is_operator(&Operator::LessThan)
+                || self.token().is_bracket(&BracketKind::LeftParenthesis)
+            {
+                self.functional_type()
+            } else {
+                let checkpoint = self.checkpoint();
+                let object = self.concrete_type()?;
+                self.dot_type(checkpoint, object)
+            }
+        })
     }
-    fn dot_type(&'a self, object: Type<'a>) -> NodeOrError<Type<'a>> {
+    /// Parses the properties of a type after a dot, e.g. `Map` in `collections.Map`.<br>
+    /// Each dot type holds the one before it, so its node starts at `checkpoint`, where the first type began.
+    fn dot_type(&self, checkpoint: usize, object: Type) -> NodeOrError<Type> {
         if let Token {
             kind: TokenKind::Operator(Operator::Dot),
             ..
         } = self.token()
         {
-            self.advance(); // Move past dot.
-            let property = self.concrete_type()?;
-            let type_ = Type::create_dot_type(object, property);
-            Ok(self.dot_type(type_)?)
+            let type_ = self.node_at(checkpoint, SyntaxKind::DotType, || {
+                self.advance(); // Move past dot.
+                let property = self.concrete_type()?;
+                Ok(Type::create_dot_type(object, property))
+            })?;
+            self.dot_type(checkpoint, type_)
         } else {
             Ok(object)
         }
     }
-    fn functional_type(&'a self) -> NodeOrError<Type<'a>> {
-        let start = self.token().span[0];
-        let labels;
-        if self.token().is_bracket(&BracketKind::LeftParenthesis) {
-            labels = None;
-        } else {
-            labels = Some(self.generic_arguments()?);
-        }
-        let parameters = self.parameters()?;
-        let return_type = Box::new(self.return_type()?);
-        let end = return_type.get_range()[1];
-        let func_type = Type::Function(FunctionType {
-            parameters,
-            return_type,
-            generic_arguments: labels,
-            span: [start, end],
-        });
-        Ok(func_type)
+    fn functional_type(&self) -> NodeOrError<Type> {
+        self.node(SyntaxKind::FunctionType, || {
+            let start = self.token().span.start;
+            let labels = if self.token().is_bracket(&BracketKind::LeftParenthesis) {
+                None
+            } else {
+                Some(self.generic_arguments()?)
+            };
+            let parameters = self.parameters()?;
+            let return_type = Box::new(self.return_type()?);
+            let end = return_type.get_range().end;
+            let func_type = Type::Function(FunctionType {
+                parameters,
+                return_type,
+                generic_arguments: labels,
+                span: self.span(start, end),
+            });
+            Ok(func_type)
+        })
     }
-    fn concrete_type(&'a self) -> NodeOrError<Type<'a>> {
-        let name;
-        let start;
-        if let Token {
-            span,
-            kind: TokenKind::Identifier(TokenIdentifier { value }),
-        } = self.token()
-        {
-            name = Identifier { value, span: *span };
-            start = span[0];
-            self.advance(); // Move past type name.
-        } else {
-            return Err((SyntaxError::ExpectedTypeName, self.token().span));
-        }
-        let mut arguments = vec![];
-        let end;
-        if self.token().is_operator(&Operator::LessThan) {
-            self.advance(); // Move past <
-            while !(self.end() || self.token().is_operator(&Operator::GreaterThan)) {
-                let argument = self.concrete_type()?;
-                if self.token().is_comma() {
-                    self.advance();
-                } else if !self.token().is_operator(&Operator::GreaterThan) {
-                    return Err((SyntaxError::ExpectedCommaOrRAngleBrac, self.token().span));
-                }
-                arguments.push(argument);
-            }
-            if self.end() {
-                return Err((SyntaxError::ExpectedRAngleBrac, self.token().span));
+    fn concrete_type(&self) -> NodeOrError<Type> {
+        self.node(SyntaxKind::ConcreteType, || {
+            let name;
+            let start;
+            if let Token {
+                span,
+                kind: TokenKind::Identifier(TokenIdentifier { value }),
+            } = self.token()
+            {
+                name = Identifier {
+                    value: value.clone(),
+                    span: *span,
+                };
+                start = span.start;
+                self.advance(); // Move past type name.
+            } else {
+                return Err((SyntaxError::ExpectedTypeName, self.token().span));
             }
-            end = self.token().span[1];
-            self.advance(); // Move past >
-        } else {
-            end = name.get_range()[1];
-        }
-        let conc_type = Type::Concrete(ConcreteType {
-            name,
-            arguments,
-            span: [start, end],
-        });
-        Ok(conc_type)
+            let mut arguments = vec![];
+            let end = if self.token().is_operator(&Operator::LessThan) {
+                self.advance(); // Move past <
+                while !(self.end() || self.sees_closing_angle()) {
+                    let argument = self.concrete_type()?;
+                    if self.token().is_comma() {
+                        self.advance();
+                    } else if !self.sees_closing_angle() {
+                        return Err((SyntaxError::ExpectedCommaOrRAngleBrac, self.token().span));
+                    }
+                    arguments.push(argument);
+                }
+                if self.end() {
+                    return Err((SyntaxError::ExpectedRAngleBrac, self.token().span));
+                }
+                self.close_angle()
+            } else {
+                name.get_range().end
+            };
+            let conc_type = Type::Concrete(ConcreteType {
+                name,
+                arguments,
+                span: self.span(start, end),
+            });
+            Ok(conc_type)
+        })
     }
 }
+
+/// Drops each error found at the same position as the one before it, since it follows from the recovery that the first one began, e.g. in `g(;`.
+pub(crate) fn drop_repeated_errors(errors: &mut Vec<ParserError>) {
+    errors.dedup_by_key(|(_, span)| span.start);
+}
+
+pub(crate) fn is_doc(comment: &Token) -> bool {
+    matches!(
+        &comment.kind,
+        TokenKind::Comment(Comment {
+            kind: CommentKind::Doc,
+            ..
+        })
+    )
+}
@@ -0,0 +1,585 @@
+use std::{fmt::Display, ops::Range, rc::Rc};
+
+use crate::{
+    program::{parse_with, Program},
+    scanner::Scanner,
+    typed::{AstNode, SourceFileSyntax},
+};
+use ast::{CommentKind, Injunction, Keyword, Operator, OperatorKind, Token, TokenKind};
+use utils::FileId;
+
+/// The kind of a token or node in the concrete syntax tree.<br>
+/// Nodes are named after the nodes of the program they are parsed alongside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
+    Identifier,
+    Keyword,
+    Injunction,
+    Literal,
+    Operator,
+    Punctuation,
+    Invalid,
+    Eof,
+    /// The root of the tree, which covers the whole file.
+    SourceFile,
+    /// The tokens skipped while recovering from a syntax error.
+    Error,
+    // Statements and declarations.
+    Function,
+    Class,
+    Interface,
+    Enum,
+    Record,
+    Module,
+    TypeAlias,
+    VariableDeclaration,
+    UseImport,
+    PrependStatement,
+    TestBlock,
+    PublicModifier,
+    IfStatement,
+    WhileStatement,
+    ForLoop,
+    Loop,
+    ReturnStatement,
+    Break,
+    Continue,
+    CrashStatement,
+    PrintLnStatement,
+    TryBlock,
+    Block,
+    ExpressionStatement,
+    EmptyStatement,
+    // Parts of statements and declarations.
+    RecoverBlock,
+    Import,
+    Method,
+    Attribute,
+    Implement,
+    Variant,
+    Mapping,
+    ParameterList,
+    Parameter,
+    GenericArgumentList,
+    GenericArgument,
+    // Expressions.
+    NameExpression,
+    LiteralExpression,
+    TemplateString,
+    GroupExpression,
+    UnaryExpression,
+    BinaryExpression,
+    LogicalExpression,
+    AssignmentExpression,
+    CallExpression,
+    IndexExpression,
+    DotExpression,
+    NamespaceExpression,
+    RangeExpression,
+    TernaryExpression,
+    ArrayExpression,
+    FnExpression,
+    SelfExpression,
+    // Types.
+    ConcreteType,
+    FunctionType,
+    DotType,
+}
+
+impl SyntaxKind {
+    fn of(token: &Token) -> Self {
+        match &token.kind {
+            TokenKind::Operator(_) => Self::Operator,
+            TokenKind::Punctuation(_) => Self::Punctuation,
+            TokenKind::Keyword(Keyword::Injunction(_)) => Self::Injunction,
+            TokenKind::Keyword(_) => Self::Keyword,
+            TokenKind::Literal(_) => Self::Literal,
+            TokenKind::Identifier(_) => Self::Identifier,
+            TokenKind::Invalid(_) | TokenKind::Comment(_) => Self::Invalid,
+            TokenKind::EOF => Self::Eof,
+        }
+    }
+    /// Returns the kind of the statement an injunction begins.
+    pub(crate) fn injunction(injunction: &Injunction) -> Self {
+        match injunction {
+            Injunction::Function => Self::Function,
+            Injunction::Type => Self::TypeAlias,
+            Injunction::Class => Self::Class,
+            Injunction::Record => Self::Record,
+            Injunction::Const | Injunction::Let => Self::VariableDeclaration,
+            Injunction::Use => Self::UseImport,
+            Injunction::Prepend => Self::PrependStatement,
+            Injunction::Test => Self::TestBlock,
+            Injunction::Enum => Self::Enum,
+            Injunction::Interface => Self::Interface,
+            Injunction::Module => Self::Module,
+            Injunction::Public => Self::PublicModifier,
+            Injunction::Implement | Injunction::Unknown(_) => Self::Error,
+        }
+    }
+    /// Returns the kind of the statement a keyword begins.
+    pub(crate) fn control(keyword: &Keyword) -> Self {
+        match keyword {
+            Keyword::If => Self::IfStatement,
+            Keyword::Println => Self::PrintLnStatement,
+            Keyword::While => Self::WhileStatement,
+            Keyword::Return => Self::ReturnStatement,
+            Keyword::For => Self::ForLoop,
+            Keyword::Crash => Self::CrashStatement,
+            Keyword::Loop => Self::Loop,
+            Keyword::Break => Self::Break,
+            Keyword::Try => Self::TryBlock,
+            Keyword::Continue => Self::Continue,
+            _ => Self::Error,
+        }
+    }
+    /// Returns the kind of the expression an operator makes of the operand before it.
+    pub(crate) fn operator(operator: &Operator) -> Self {
+        match operator.kind() {
+            OperatorKind::Postfix if operator == &Operator::Call => Self::CallExpression,
+            OperatorKind::Postfix => Self::IndexExpression,
+            OperatorKind::Member if operator == &Operator::Dot => Self::DotExpression,
+            OperatorKind::Member => Self::NamespaceExpression,
+            OperatorKind::Range => Self::RangeExpression,
+            OperatorKind::Logical => Self::LogicalExpression,
+            OperatorKind::Conditional => Self::TernaryExpression,
+            OperatorKind::Assignment => Self::AssignmentExpression,
+            _ => Self::BinaryExpression,
+        }
+    }
+    pub fn is_token(&self) -> bool {
+        matches!(
+            self,
+            Self::Identifier
+                | Self::Keyword
+                | Self::Injunction
+                | Self::Literal
+                | Self::Operator
+                | Self::Punctuation
+                | Self::Invalid
+                | Self::Eof
+        )
+    }
+    pub fn is_statement(&self) -> bool {
+        matches!(
+            self,
+            Self::Function
+                | Self::Class
+                | Self::Interface
+                | Self::Enum
+                | Self::Record
+                | Self::Module
+                | Self::TypeAlias
+                | Self::VariableDeclaration
+                | Self::UseImport
+                | Self::PrependStatement
+                | Self::TestBlock
+                | Self::PublicModifier
+                | Self::IfStatement
+                | Self::WhileStatement
+                | Self::ForLoop
+                | Self::Loop
+                | Self::ReturnStatement
+                | Self::Break
+                | Self::Continue
+                | Self::CrashStatement
+                | Self::PrintLnStatement
+                | Self::TryBlock
+                | Self::Block
+                | Self::ExpressionStatement
+                | Self::EmptyStatement
+        )
+    }
+    pub fn is_expression(&self) -> bool {
+        matches!(
+            self,
+            Self::NameExpression
+                | Self::LiteralExpression
+                | Self::TemplateString
+                | Self::GroupExpression
+                | Self::UnaryExpression
+                | Self::BinaryExpression
+                | Self::LogicalExpression
+                | Self::AssignmentExpression
+                | Self::CallExpression
+                | Self::IndexExpression
+                | Self::DotExpression
+                | Self::NamespaceExpression
+                | Self::RangeExpression
+                | Self::TernaryExpression
+                | Self::ArrayExpression
+                | Self::FnExpression
+                | Self::SelfExpression
+        )
+    }
+    pub fn is_type(&self) -> bool {
+        matches!(
+            self,
+            Self::ConcreteType | Self::FunctionType | Self::DotType
+        )
+    }
+}
+
+/// A step taken by the parser while building the concrete syntax tree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SyntaxEvent {
+    /// A node of the given kind starts before the next token.
+    Start(SyntaxKind),
+    /// The next token has been consumed by the innermost open node.
+    Token,
+    /// The innermost open node has finished.
+    Finish,
+}
+
+/// Text that has no meaning to the parser, but is kept so that the source can be reproduced exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trivia {
+    Whitespace(String),
+    Comment(CommentKind, String),
+}
+
+impl Trivia {
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Whitespace(text) | Self::Comment(_, text) => text,
+        }
+    }
+}
+
+/// A token as it appears in the source, with the trivia that comes before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GreenToken {
+    pub kind: SyntaxKind,
+    pub leading: Vec<Trivia>,
+    pub text: String,
+}
+
+impl GreenToken {
+    /// Returns the length of the token in bytes, including its trivia.
+    pub fn width(&self) -> usize {
+        self.leading_width() + self.text.len()
+    }
+    fn leading_width(&self) -> usize {
+        self.leading.iter().map(|trivia| trivia.text().len()).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GreenElement {
+    Node(Rc<GreenNode>),
+    Token(Rc<GreenToken>),
+}
+
+impl GreenElement {
+    pub fn width(&self) -> usize {
+        match self {
+            Self::Node(node) => node.width,
+            Self::Token(token) => token.width(),
+        }
+    }
+}
+
+/// An immutable node of the concrete syntax tree, which knows its width but not its position, so that it can be shared between trees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GreenNode {
+    pub kind: SyntaxKind,
+    pub children: Vec<GreenElement>,
+    width: usize,
+}
+
+impl GreenNode {
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        let width = children.iter().map(GreenElement::width).sum();
+        GreenNode {
+            kind,
+            children,
+            width,
+        }
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    fn write(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                GreenElement::Node(node) => node.write(text),
+                GreenElement::Token(token) => {
+                    for trivia in &token.leading {
+                        text.push_str(trivia.text());
+                    }
+                    text.push_str(&token.text);
+                }
+            }
+        }
+    }
+}
+
+struct RedData {
+    green: Rc<GreenNode>,
+    parent: Option<SyntaxNode>,
+    offset: usize,
+}
+
+/// A node of the concrete syntax tree, positioned in its file and able to reach its parent.
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<RedData>);
+
+/// A token of the concrete syntax tree, positioned in its file.
+#[derive(Clone)]
+pub struct SyntaxToken {
+    green: Rc<GreenToken>,
+    parent: SyntaxNode,
+    offset: usize,
+}
+
+#[derive(Clone)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    pub fn new_root(green: Rc<GreenNode>) -> Self {
+        SyntaxNode(Rc::new(RedData {
+            green,
+            parent: None,
+            offset: 0,
+        }))
+    }
+    pub fn green(&self) -> &Rc<GreenNode> {
+        &self.0.green
+    }
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind
+    }
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.clone()
+    }
+    /// Returns the byte range of the node, including the trivia before its first token.
+    pub fn full_range(&self) -> Range<usize> {
+        self.0.offset..self.0.offset + self.0.green.width
+    }
+    /// Returns the byte range of the node, from the start of its first token.
+    pub fn range(&self) -> Range<usize> {
+        let full = self.full_range();
+        match self.first_token() {
+            Some(token) => token.range().start..full.end,
+            None => full,
+        }
+    }
+    /// Returns the exact source text of the node, including its trivia.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.0.green.write(&mut text);
+        text
+    }
+    pub fn children(&self) -> Vec<SyntaxElement> {
+        let mut offset = self.0.offset;
+        let mut children = vec![];
+        for child in &self.0.green.children {
+            children.push(match child {
+                GreenElement::Node(node) => SyntaxElement::Node(SyntaxNode(Rc::new(RedData {
+                    green: Rc::clone(node),
+                    parent: Some(self.clone()),
+                    offset,
+                }))),
+                GreenElement::Token(token) => SyntaxElement::Token(SyntaxToken {
+                    green: Rc::clone(token),
+                    parent: self.clone(),
+                    offset,
+                }),
+            });
+            offset += child.width();
+        }
+        children
+    }
+    pub fn child_nodes(&self) -> Vec<SyntaxNode> {
+        self.children()
+            .into_iter()
+            .filter_map(|child| match child {
+                SyntaxElement::Node(node) => Some(node),
+                SyntaxElement::Token(_) => None,
+            })
+            .collect()
+    }
+    /// Returns every token in the node, in source order.
+    pub fn tokens(&self) -> Vec<SyntaxToken> {
+        let mut tokens = vec![];
+        for child in self.children() {
+            match child {
+                SyntaxElement::Node(node) => tokens.extend(node.tokens()),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+        tokens
+    }
+    pub fn first_token(&self) -> Option<SyntaxToken> {
+        self.children().into_iter().find_map(|child| match child {
+            SyntaxElement::Node(node) => node.first_token(),
+            SyntaxElement::Token(token) => Some(token),
+        })
+    }
+    /// Returns the innermost node whose tokens cover the byte at `offset`.
+    pub fn covering_node(&self, offset: usize) -> SyntaxNode {
+        for child in self.child_nodes() {
+            if child.range().contains(&offset) {
+                return child.covering_node(offset);
+            }
+        }
+        self.clone()
+    }
+    /// Returns the token whose text covers the byte at `offset`.
+    pub fn token_at(&self, offset: usize) -> Option<SyntaxToken> {
+        self.tokens()
+            .into_iter()
+            .find(|token| token.range().contains(&offset))
+    }
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind
+    }
+    pub fn text(&self) -> &str {
+        &self.green.text
+    }
+    pub fn leading(&self) -> &[Trivia] {
+        &self.green.leading
+    }
+    pub fn parent(&self) -> &SyntaxNode {
+        &self.parent
+    }
+    /// Returns the byte range of the token's text, without its trivia.
+    pub fn range(&self) -> Range<usize> {
+        let start = self.offset + self.green.leading_width();
+        start..start + self.green.text.len()
+    }
+}
+
+impl Display for SyntaxNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text())
+    }
+}
+
+/// Scans source text into a lossless stream of tokens, where every whitespace and comment is kept as trivia before the token that follows it.<br>
+/// The text after the last token is held by the end-of-file token, so joining the trivia and text of every token reproduces the source exactly.
+pub fn lossless_tokens(text: &str) -> Vec<GreenToken> {
+    let mut scanner = Scanner::new(text);
+    scanner.run();
+    lossless_tokens_from(text, &scanner.tokens, &scanner.comments)
+}
+
+/// Joins scanned tokens with the comments and whitespace between them into a lossless stream.
+fn lossless_tokens_from(text: &str, scanned: &[Token], comments: &[Token]) -> Vec<GreenToken> {
+    let mut comments = comments.iter().peekable();
+    let mut offset = 0;
+    let mut tokens = vec![];
+    for token in scanned {
+        // The end-of-file token takes the span of the token before it, but holds no text of its own.
+        let range = match token.kind {
+            TokenKind::EOF => text.len()..text.len(),
+            _ => token.span.range(),
+        };
+        let mut leading = vec![];
+        while let Some(comment) = comments.next_if(|comment| comment.span.start < range.start) {
+            let comment_range = comment.span.range();
+            push_whitespace(&mut leading, &text[offset..comment_range.start]);
+            let kind = match &comment.kind {
+                TokenKind::Comment(comment) => comment.kind.clone(),
+                _ => unreachable!(),
+            };
+            // A line comment ends at the line break, so the carriage return of a CRLF break is given back to the whitespace.
+            let end = match kind {
+                CommentKind::Line => {
+                    comment_range.start + text[comment_range.clone()].trim_end_matches('\r').len()
+                }
+                _ => comment_range.end,
+            };
+            leading.push(Trivia::Comment(
+                kind,
+                text[comment_range.start..end].to_string(),
+            ));
+            offset = end;
+        }
+        push_whitespace(&mut leading, &text[offset..range.start]);
+        tokens.push(GreenToken {
+            kind: SyntaxKind::of(token),
+            leading,
+            text: text[range.clone()].to_string(),
+        });
+        offset = range.end;
+    }
+    tokens
+}
+
+fn push_whitespace(trivia: &mut Vec<Trivia>, text: &str) {
+    if !text.is_empty() {
+        trivia.push(Trivia::Whitespace(text.to_string()));
+    }
+}
+
+/// A lossless concrete syntax tree, together with the program parsed from the same text.<br>
+/// Both come from a single scan and a single parse: as the parser consumes tokens it records where each node starts and finishes, and the tokens, with their trivia, are then arranged into those nodes.
+pub struct SyntaxTree {
+    pub root: SyntaxNode,
+    pub program: Program,
+}
+
+impl SyntaxTree {
+    /// Parses source text into a concrete syntax tree.
+    pub fn parse(text: &str) -> Self {
+        let (program, events) = parse_with(text, FileId::default(), true);
+        let tokens = lossless_tokens_from(text, &program.tokens, &program.comments);
+        let green = build(events, tokens);
+        SyntaxTree {
+            root: SyntaxNode::new_root(Rc::new(green)),
+            program,
+        }
+    }
+    /// Returns the exact text the tree was parsed from.
+    pub fn text(&self) -> String {
+        self.root.text()
+    }
+    /// Returns the typed view of the root, through which the statements of the file can be read.
+    pub fn source_file(&self) -> SourceFileSyntax {
+        SourceFileSyntax::cast(self.root.clone())
+            .expect("the root of a syntax tree is a source file")
+    }
+}
+
+/// Arranges the tokens into the nodes the parser started and finished around them.<br>
+/// A node that finished without consuming a token, because its production failed straight away, is left out. The end-of-file token belongs to the root.
+fn build(events: Vec<SyntaxEvent>, tokens: Vec<GreenToken>) -> GreenNode {
+    let mut tokens = tokens.into_iter();
+    let mut stack = vec![(SyntaxKind::SourceFile, vec![])];
+    for event in events {
+        match event {
+            SyntaxEvent::Start(kind) => stack.push((kind, vec![])),
+            SyntaxEvent::Token => {
+                let token = tokens
+                    .next()
+                    .expect("the parser consumed a token that was not scanned");
+                stack
+                    .last_mut()
+                    .unwrap()
+                    .1
+                    .push(GreenElement::Token(Rc::new(token)));
+            }
+            SyntaxEvent::Finish => {
+                let (kind, children) = stack.pop().unwrap();
+                if !children.is_empty() {
+                    let node = GreenNode::new(kind, children);
+                    stack
+                        .last_mut()
+                        .unwrap()
+                        .1
+                        .push(GreenElement::Node(Rc::new(node)));
+                }
+            }
+        }
+    }
+    let (kind, mut children) = stack.pop().unwrap();
+    children.extend(tokens.map(|token| GreenElement::Token(Rc::new(token))));
+    GreenNode::new(kind, children)
+}
@@ -0,0 +1,259 @@
+use std::ops::Range;
+
+use ast::{BracketKind, Injunction, Keyword, Punctuation, Token, TokenKind};
+use errors::{ResolverWarnings, SyntaxError};
+use utils::{SourceFile, Span, TextEdit};
+
+use crate::{program::Diagnostic, scanner::Scanner};
+
+/// A change to a source file that resolves a diagnostic, and can be applied without asking what was meant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    /// What the fix does, e.g. "Insert a semicolon".
+    pub title: String,
+    pub edits: Vec<TextEdit>,
+}
+
+impl Diagnostic {
+    /// Returns the change that resolves the problem, if there is one that is certain to be right.<br>
+    /// The fix is worked out from the tokens of the file the diagnostic was found in, so it can be suggested even when the file does not parse.
+    pub fn fix(&self, file: &SourceFile) -> Option<Fix> {
+        self.fix_in(&Tokens::new(file))
+    }
+    fn fix_in(&self, tokens: &Tokens) -> Option<Fix> {
+        let index = tokens.at(self.span().start)?;
+        match self {
+            Self::Syntax(SyntaxError::ExpectedSemiColon, _) => tokens.insert_semicolon(index),
+            Self::Syntax(SyntaxError::UninitializedUntypedVariable, _) => {
+                tokens.add_type_label(index)
+            }
+            Self::Syntax(SyntaxError::UninitializedConstant, _) => tokens.make_variable(index),
+            Self::Syntax(SyntaxError::NamedFunctionExpr, _) => tokens.unname_function(index),
+            Self::Syntax(SyntaxError::IllegalElse, _) => tokens.remove_else(index),
+            Self::ResolverWarning(ResolverWarnings::UnusedVariable(_), _) => {
+                tokens.remove_import(index)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the fixes of a list of diagnostics, leaving out the ones that change the same text as a fix before them.<br>
+/// The edits of the fixes returned can all be applied at once.
+pub fn fixes<'a>(
+    file: &SourceFile,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+) -> Vec<Fix> {
+    let tokens = Tokens::new(file);
+    let mut changed: Vec<Range<usize>> = vec![];
+    let mut fixes = vec![];
+    for fix in diagnostics
+        .into_iter()
+        .filter_map(|diagnostic| diagnostic.fix_in(&tokens))
+    {
+        // Edits that only touch are kept apart too, since the order of two insertions at the same place is ambiguous.
+        let overlaps = fix.edits.iter().any(|edit| {
+            changed
+                .iter()
+                .any(|range| edit.span.start <= range.end && range.start <= edit.span.end)
+        });
+        if !overlaps {
+            changed.extend(fix.edits.iter().map(|edit| edit.span.range()));
+            fixes.push(fix);
+        }
+    }
+    fixes
+}
+
+/// The tokens of a file.
+struct Tokens<'a> {
+    file: &'a SourceFile,
+    tokens: Vec<Token>,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(file: &'a SourceFile) -> Self {
+        let mut scanner = Scanner::in_file(&file.text, file.id);
+        scanner.run();
+        Tokens {
+            file,
+            tokens: scanner.tokens,
+        }
+    }
+    /// Returns the index of the token that starts at a byte offset.
+    fn at(&self, offset: usize) -> Option<usize> {
+        self.tokens
+            .iter()
+            .position(|token| token.span.start == offset)
+    }
+    fn edit(&self, range: Range<usize>, text: &str) -> TextEdit {
+        TextEdit::new(Span::new(self.file.id, range.start, range.end), text)
+    }
+    fn fix(title: &str, edits: Vec<TextEdit>) -> Option<Fix> {
+        Some(Fix {
+            title: title.to_string(),
+            edits,
+        })
+    }
+    /// Returns the index of the bracket that closes the one at an index.
+    fn closing(&self, open: usize) -> Option<usize> {
+        let mut depth = 0;
+        for (index, token) in self.tokens.iter().enumerate().skip(open) {
+            match &token.kind {
+                TokenKind::Punctuation(Punctuation::Bracket(bracket)) => match bracket {
+                    BracketKind::LeftCurly
+                    | BracketKind::LeftParenthesis
+                    | BracketKind::LeftSquare => depth += 1,
+                    _ => depth -= 1,
+                },
+                TokenKind::EOF => return None,
+                _ => {}
+            }
+            if depth == 0 {
+                return Some(index);
+            }
+        }
+        None
+    }
+    /// The token at `index` is where a semicolon was expected, so it goes right after the token before it.
+    fn insert_semicolon(&self, index: usize) -> Option<Fix> {
+        // The end of the file has the span of the last token, and a semicolon is far more often missing there than before the last token.
+        let index = match self.tokens.get(index + 1) {
+            Some(next) if next.is_eof() && next.span == self.tokens[index].span => index + 1,
+            _ => index,
+        };
+        let end = self.tokens[index.checked_sub(1)?].span.end;
+        Self::fix("Insert a semicolon", vec![self.edit(end..end, ";")])
+    }
+    /// Labels a variable declared without a value as `Any`, which it can be assigned anything later.
+    fn add_type_label(&self, name: usize) -> Option<Fix> {
+        let end = self.tokens[name].span.end;
+        Self::fix("Add a type label", vec![self.edit(end..end, ": Any")])
+    }
+    /// Declares a constant without a value as a variable, which can be assigned later.
+    fn make_variable(&self, name: usize) -> Option<Fix> {
+        let injunction = name.checked_sub(1)?;
+        if !self.tokens[injunction].is_keyword(&Keyword::Injunction(Injunction::Const)) {
+            return None;
+        }
+        let range = self.tokens[injunction].span.range();
+        Self::fix("Declare a variable instead", vec![self.edit(range, "@let")])
+    }
+    /// A named function expression that makes up a statement becomes a variable holding an anonymous function.
+    /// Anywhere else, the name is removed.
+    fn unname_function(&self, name: usize) -> Option<Fix> {
+        let fn_ = self.tokens[..name]
+            .iter()
+            .rposition(|token| token.is_keyword(&Keyword::Fn))?;
+        let value = &self.file.text[self.tokens[name].span.range()];
+        let remove_name = self.edit(
+            self.tokens[name - 1].span.end..self.tokens[name].span.end,
+            "",
+        );
+        let begins_statement = fn_ == 0
+            || self.tokens[fn_ - 1].is_semi_colon()
+            || self.tokens[fn_ - 1].is_bracket(&BracketKind::LeftCurly)
+            || self.tokens[fn_ - 1].is_bracket(&BracketKind::RightCurly);
+        if !begins_statement {
+            return Self::fix("Remove the name of the function", vec![remove_name]);
+        }
+        let start = self.tokens[fn_].span.start;
+        let mut edits = vec![
+            self.edit(start..start, &format!("@let {value} = ")),
+            remove_name,
+        ];
+        // The body is the first block after the parameters. Functions that return an expression are left for the next fix to end.
+        let parameters = name + 1;
+        let body = self.tokens[parameters]
+            .is_bracket(&BracketKind::LeftParenthesis)
+            .then(|| self.closing(parameters))
+            .flatten()
+            .and_then(|close| {
+                (close + 1..self.tokens.len())
+                    .take_while(|index| !self.tokens[*index].is_semi_colon())
+                    .find(|index| self.tokens[*index].is_bracket(&BracketKind::LeftCurly))
+            });
+        if let Some(close) = body.and_then(|body| self.closing(body)) {
+            if !self.tokens[close + 1].is_semi_colon() {
+                let end = self.tokens[close].span.end;
+                edits.push(self.edit(end..end, ";"));
+            }
+        }
+        Self::fix("Declare the function with @let", edits)
+    }
+    /// An `else` after a block and a semicolon was most likely meant to follow the block. Otherwise, the `else` and its block are removed.
+    fn remove_else(&self, else_: usize) -> Option<Fix> {
+        if else_ >= 2
+            && self.tokens[else_ - 1].is_semi_colon()
+            && self.tokens[else_ - 2].is_bracket(&BracketKind::RightCurly)
+        {
+            let range = self.tokens[else_ - 1].span.range();
+            return Self::fix(
+                "Remove the semicolon before else",
+                vec![self.edit(range, "")],
+            );
+        }
+        if !self.tokens[else_ + 1].is_bracket(&BracketKind::LeftCurly) {
+            // An `else if` becomes an `if`.
+            let range = self.tokens[else_].span.start..self.tokens[else_ + 1].span.start;
+            return Self::fix("Remove else", vec![self.edit(range, "")]);
+        }
+        let start = match else_ {
+            0 => 0,
+            _ => self.tokens[else_ - 1].span.end,
+        };
+        let end = self.tokens[self.closing(else_ + 1)?].span.end;
+        Self::fix("Remove the else block", vec![self.edit(start..end, "")])
+    }
+    /// Removes a name from the braces of a `@use` statement, or the whole statement if it is the only name imported.
+    fn remove_import(&self, name: usize) -> Option<Fix> {
+        // The braces of an import hold names, commas, `as` and `*`.
+        let is_imported = |token: &Token| {
+            matches!(
+                token.kind,
+                TokenKind::Identifier(_) | TokenKind::Operator(_)
+            ) || token.is_comma()
+                || token.is_keyword(&Keyword::As)
+        };
+        let open = self.tokens[..name]
+            .iter()
+            .rposition(|token| !is_imported(token))?;
+        let is_import = open >= 1
+            && self.tokens[open].is_bracket(&BracketKind::LeftCurly)
+            && self.tokens[open - 1].is_keyword(&Keyword::Injunction(Injunction::Use));
+        if !is_import {
+            return None;
+        }
+        let start = match self.tokens[name - 1].is_keyword(&Keyword::As) {
+            true => name - 2,
+            false => name,
+        };
+        let followed_by_comma = self.tokens[name + 1].is_comma();
+        let close = match followed_by_comma {
+            true => name + 2,
+            false => name + 1,
+        };
+        let only = start == open + 1 && self.tokens[close].is_bracket(&BracketKind::RightCurly);
+        let title = "Remove the unused import";
+        if only {
+            let end = (close..self.tokens.len()).find(|index| {
+                self.tokens[*index].is_semi_colon() || self.tokens[*index].is_eof()
+            })?;
+            let mut end = self.tokens[end].span.end;
+            // The line the statement was on is removed with it.
+            let rest = &self.file.text[end..];
+            let blank = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+            if rest[blank..].starts_with('\n') {
+                end += blank + 1;
+            }
+            let start = self.tokens[open - 1].span.start;
+            return Self::fix(title, vec![self.edit(start..end, "")]);
+        }
+        let range = if followed_by_comma {
+            self.tokens[start].span.start..self.tokens[name + 2].span.start
+        } else {
+            self.tokens[start - 2].span.end..self.tokens[name].span.end
+        };
+        Self::fix(title, vec![self.edit(range, "")])
+    }
+}
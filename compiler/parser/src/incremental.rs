@@ -0,0 +1,534 @@
+use std::{collections::HashSet, ops::Range};
+
+use ast::{
+    Block, Displace, Displacement, Literal, LiteralKind, Property, Statement, Token, TokenKind,
+};
+use errors::SyntaxWarning;
+use utils::{Span, TextEdit};
+
+use crate::{
+    _parser::{drop_repeated_errors, is_doc, Parser, ParserError, Provider},
+    program::{parse_text, Diagnostic, Program},
+    scanner::{line_starts, Scanner},
+};
+
+/// The number of tokens after an edit that the top-level statements are first parsed again with.
+const WINDOW: usize = 64;
+
+/// Something done by the parser while parsing a statement, which is replayed when the statement is kept after an edit.
+#[derive(Debug, Clone)]
+pub(crate) enum Event {
+    /// An error was stored.
+    Error(ParserError),
+    /// The doc comment beginning at the byte offset was attached to a declaration.
+    Documented(usize),
+    /// A block was parsed.
+    List(List),
+}
+
+/// A statement parsed at the top level of a file or in a block, with the tokens it was parsed from.
+#[derive(Debug, Clone)]
+pub(crate) struct Unit {
+    /// The index of the first token of the statement.
+    pub start: usize,
+    /// The indices of the first and the last token looked at while parsing the statement.
+    /// The first is before the statement, as the doc comments of a declaration are looked for after it.
+    pub reads: [usize; 2],
+    /// Whether the first half of a `>>` token had been consumed when the statement began.
+    pub split_angle: bool,
+    pub events: Vec<Event>,
+}
+
+/// The statements parsed at the top level of a file, or in a block.
+#[derive(Debug, Clone)]
+pub(crate) struct List {
+    /// The index of the `{` that opens the block, or `None` at the top level.
+    pub open: Option<usize>,
+    /// The index of the `}` that closes the block, or of the token the statements end at.
+    pub close: usize,
+    pub units: Vec<Unit>,
+}
+
+impl Unit {
+    pub fn new(start: usize, first_read: usize, split_angle: bool) -> Self {
+        Unit {
+            start,
+            reads: [first_read, start],
+            split_angle,
+            events: vec![],
+        }
+    }
+    /// Moves the indices of the tokens read by a statement parsed from a window of tokens, to where the window begins in the file.
+    fn offset(&mut self, by: usize) {
+        self.start += by;
+        self.reads = self.reads.map(|index| index + by);
+        for event in &mut self.events {
+            if let Event::List(list) = event {
+                list.open = list.open.map(|open| open + by);
+                list.close += by;
+                list.units.iter_mut().for_each(|unit| unit.offset(by));
+            }
+        }
+    }
+    /// Moves the indices and positions in a statement to where they are after an edit.
+    fn shift(&mut self, shift: &Shift) {
+        if self.reads[1] < shift.start {
+            return;
+        }
+        self.start = shift.index(self.start);
+        self.reads = self.reads.map(|index| shift.index(index));
+        for event in &mut self.events {
+            match event {
+                Event::Error((_, span)) => span.displace(&shift.by),
+                Event::Documented(position) => *position = shift.by.apply(*position),
+                Event::List(list) => {
+                    list.open = list.open.map(|open| shift.index(open));
+                    list.close = shift.index(list.close);
+                    list.units.iter_mut().for_each(|unit| unit.shift(shift));
+                }
+            }
+        }
+    }
+    /// Returns `true` if parsing the statement looked at any token, or the comments before any token, that an edit changed.
+    fn is_affected(&self, shift: &Shift) -> bool {
+        self.reads[0] <= shift.end && shift.start <= self.reads[1]
+    }
+}
+
+impl List {
+    pub fn new(open: Option<usize>) -> Self {
+        List {
+            open,
+            close: 0,
+            units: vec![],
+        }
+    }
+}
+
+/// How the tokens of a program moved after an edit.<br>
+/// The tokens from `start` up to `end` were replaced by the ones scanned again, and the comments between them too. The tokens from `end` on were kept, and moved by `delta` places and by the displacement `by` in the text.
+struct Shift {
+    start: usize,
+    end: usize,
+    delta: isize,
+    by: Displacement,
+}
+
+impl Shift {
+    /// Returns the index of a kept token after the edit.
+    fn index(&self, index: usize) -> usize {
+        match index >= self.end {
+            true => index.checked_add_signed(self.delta).unwrap(),
+            false => index,
+        }
+    }
+    /// Returns the index a token had before the edit, if it was kept.
+    fn old_index(&self, index: usize) -> Option<usize> {
+        let old = index.checked_add_signed(-self.delta)?;
+        (index >= self.start && old >= self.end).then_some(old)
+    }
+}
+
+impl Program {
+    /// Returns the text the program was parsed from, with its edits applied.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    /// Applies an edit to the text of the program, and brings its statements, comments and diagnostics up to date as if the new text had been parsed from scratch.<br>
+    /// Only the tokens around the edit are scanned again. The statements that looked at a changed token are parsed again, in the innermost block that holds every change if it can be, and the other statements are kept, moved to their new positions.
+    pub fn edit(&mut self, edit: &TextEdit) {
+        let range = edit.span.range();
+        let mut text = String::with_capacity(self.text.len() - range.len() + edit.text.len());
+        text.push_str(&self.text[..range.start]);
+        text.push_str(&edit.text);
+        text.push_str(&self.text[range.end..]);
+        if self.units.is_empty() {
+            *self = parse_text(&text, self.file);
+            return;
+        }
+        let shift = self.relex(&text, range, edit.text.len());
+        self.text = text;
+        let mut path = vec![];
+        let mut units = &self.units;
+        // Look for the innermost block that holds every changed token, by the statements leading to it.
+        while let Some(affected) = affected(units, &shift) {
+            let unit = &units[affected.start];
+            let block = unit
+                .events
+                .iter()
+                .enumerate()
+                .find_map(|(index, event)| match event {
+                    Event::List(list)
+                        if affected.len() == 1
+                            && list.open.is_some_and(|open| open < shift.start)
+                            && shift.end <= list.close =>
+                    {
+                        Some((index, list))
+                    }
+                    _ => None,
+                });
+            let Some((event, list)) = block else {
+                break;
+            };
+            path.push((affected.start, event));
+            units = &list.units;
+        }
+        // The top level can always be parsed again, so the loop ends there at the latest.
+        for depth in (0..=path.len()).rev() {
+            if self.reparse(&path[..depth], &shift) {
+                break;
+            }
+        }
+        self.collect_diagnostics();
+    }
+    /// Scans the tokens around an edit of `range` again, in the new text, and splices them and the comments between them into the old ones.
+    fn relex(&mut self, text: &str, range: Range<usize>, inserted: usize) -> Shift {
+        let tokens = &mut self.tokens;
+        let comments = &mut self.comments;
+        // The scanner looks past the end of a token to tell where it ends, so scanning begins two tokens before the edit,
+        // and before any interpolated string the token is in, as the scanner cannot begin inside one.
+        let touched = tokens.partition_point(|token| token.span.end < range.start);
+        let (mut restart, mut depth) = (0, 0);
+        for (index, token) in tokens[..=touched.saturating_sub(2)].iter().enumerate() {
+            if depth == 0 {
+                restart = index;
+            }
+            depth += interpolation(token);
+        }
+        let (offset, previous, comment) = match restart {
+            0 => (0, Span::new(self.file, 0, 0), 0),
+            _ => {
+                let offset = tokens[restart].span.start;
+                let comment = comments.partition_point(|comment| comment.span.start < offset);
+                let previous = match comments[..comment].last() {
+                    Some(comment) if comment.span.start > tokens[restart - 1].span.start => {
+                        comment.span
+                    }
+                    _ => tokens[restart - 1].span,
+                };
+                (offset, previous, comment)
+            }
+        };
+        // Scanning stops once it is past the edit and at a token or comment that began at the same place in the old text, outside of any interpolated string.
+        let (edit_end, new_end) = (range.end, range.start + inserted);
+        let last = tokens.len() - 1;
+        let (mut old, mut old_comment, mut depth) = (restart, comment, 0);
+        let mut scanner = Scanner::resume(text, offset, previous);
+        let resynced = scanner.run_until(|offset| {
+            let Some(offset) = (offset + edit_end).checked_sub(new_end) else {
+                return false;
+            };
+            if offset < edit_end {
+                return false;
+            }
+            while old < last && tokens[old].span.start < offset {
+                depth += interpolation(&tokens[old]);
+                old += 1;
+            }
+            while comments
+                .get(old_comment)
+                .is_some_and(|comment| comment.span.start < offset)
+            {
+                old_comment += 1;
+            }
+            let token = old < last && tokens[old].span.start == offset;
+            let comment = comments
+                .get(old_comment)
+                .is_some_and(|comment| comment.span.start == offset);
+            depth == 0 && (token || comment)
+        });
+        let (end, end_comment, by) = match resynced {
+            true => {
+                let from = match comments.get(old_comment) {
+                    Some(comment) if comment.span.start < tokens[old].span.start => {
+                        comment.span.start
+                    }
+                    _ => tokens[old].span.start,
+                };
+                let delta = new_end as isize - edit_end as isize;
+                (old, old_comment, Displacement::new(from, delta))
+            }
+            false => (
+                tokens.len(),
+                comments.len(),
+                Displacement::new(usize::MAX, 0),
+            ),
+        };
+        tokens[end..]
+            .iter_mut()
+            .for_each(|token| token.span.displace(&by));
+        comments[end_comment..]
+            .iter_mut()
+            .for_each(|comment| comment.span.displace(&by));
+        let delta = scanner.tokens.len() as isize - (end - restart) as isize;
+        tokens.splice(restart..end, std::mem::take(&mut scanner.tokens));
+        comments.splice(comment..end_comment, std::mem::take(&mut scanner.comments));
+        // The errors found in the tokens that were scanned again are replaced by the new ones.
+        let mut lexical = vec![];
+        let mut kept = vec![];
+        for (error, mut span) in std::mem::take(&mut self.lexical) {
+            if span.start < offset {
+                lexical.push((error, span));
+            } else if span.start >= by.from {
+                span.displace(&by);
+                kept.push((error, span));
+            }
+        }
+        lexical.append(&mut scanner.errors);
+        lexical.append(&mut kept);
+        self.lexical = lexical;
+        Shift {
+            start: restart,
+            end,
+            delta,
+            by,
+        }
+    }
+    /// Parses the changed statements of the block at the end of `path` again, or of the top level if `path` is empty.<br>
+    /// Parsing begins at the first statement that looked at a changed token, and goes on until it reaches the beginning of a statement after the edit that did not, which is kept, or the end of the block.
+    /// Returns `false` if the block cannot be parsed on its own, because it is not a block that can be found in the syntax tree, or because the changes end it somewhere else.
+    fn reparse(&mut self, path: &[(usize, usize)], shift: &Shift) -> bool {
+        let tokens = &self.tokens;
+        let (units, open, close) = list(&self.units, path);
+        let Some(affected) = affected(units, shift) else {
+            return false;
+        };
+        // The blocks are found in the syntax tree by where they begin, which is before the edit.
+        let starts = positions(&self.units, path, tokens);
+        if body(&mut self.statements, &starts).is_none() {
+            return false;
+        }
+        let start = units[affected.start].start;
+        let context = units[affected.start].reads[0].min(start);
+        let close = open.map(|_| shift.index(close));
+        let mut limit = match close {
+            Some(close) => close + 1,
+            None => {
+                let read = shift.index(units[affected.end - 1].reads[1].max(shift.end));
+                (read + WINDOW).min(tokens.len())
+            }
+        };
+        // Doc comments are told apart by the lines they are on.
+        let lines = line_starts(&self.text);
+        let (parser, stop, resumed) = loop {
+            let mut scanner = Scanner::new("");
+            scanner.lines = lines.clone();
+            scanner.tokens = tokens[context..limit].to_vec();
+            if limit < tokens.len() {
+                scanner.tokens.push(Token::eof(tokens[limit - 1].span));
+            }
+            // Doc comments are only looked for between the tokens in the window, or before the first token of the file.
+            let comments = &self.comments;
+            let first = match context {
+                0 => 0,
+                _ => comments
+                    .partition_point(|comment| comment.span.start < tokens[context].span.start),
+            };
+            let last = comments
+                .partition_point(|comment| comment.span.start < tokens[limit - 1].span.start);
+            scanner.comments = self.comments[first..last].to_vec();
+            let provider = Provider {
+                scanner,
+                index: start - context,
+            };
+            let parser = Parser::resume(provider, units[affected.start].split_angle);
+            let kept = &units[affected.end..];
+            let mut resumed = None;
+            let stop = context
+                + parser.parse_list(open.is_some(), |index, split_angle| {
+                    let Some(old) = shift.old_index(index + context) else {
+                        return false;
+                    };
+                    match kept.binary_search_by_key(&old, |unit| unit.start) {
+                        Ok(unit) if kept[unit].split_angle == split_angle => {
+                            resumed = Some(affected.end + unit);
+                            true
+                        }
+                        _ => false,
+                    }
+                });
+            // The statements must not have looked at the end of the window, unless it is the end of the file.
+            if limit == tokens.len() || parser.furthest() < limit - context {
+                break (parser, stop, resumed);
+            }
+            if close.is_some() {
+                return false;
+            }
+            limit = (start + 2 * (limit - start)).min(tokens.len());
+        };
+        if resumed.is_none() && close.is_some_and(|close| close != stop) {
+            return false;
+        }
+        self.reparsed = context..limit;
+        let replaced = affected.start..resumed.unwrap_or(units.len());
+        let mut statements = parser.statements.take();
+        let mut units = parser.take_units();
+        units.iter_mut().for_each(|unit| unit.offset(context));
+        // The kept statements are moved to where they are after the edit, before the new ones are put in their place.
+        body(&mut self.statements, &starts)
+            .unwrap()
+            .drain(replaced.clone());
+        list_mut(&mut self.units, path).drain(replaced.clone());
+        self.units.iter_mut().for_each(|unit| unit.shift(shift));
+        let unchanged = path.first().map_or(replaced.start, |(unit, _)| *unit);
+        for statement in &mut self.statements[unchanged..] {
+            statement.displace(&shift.by);
+        }
+        let body = body(&mut self.statements, &starts).unwrap();
+        body.splice(replaced.start..replaced.start, statements.drain(..));
+        list_mut(&mut self.units, path).splice(replaced.start..replaced.start, units);
+        true
+    }
+    /// Rebuilds the diagnostics of the program from the errors found while scanning it, and the ones recorded while parsing each statement.
+    fn collect_diagnostics(&mut self) {
+        let mut errors = vec![];
+        let mut documented = HashSet::new();
+        flatten(&self.units, &mut errors, &mut documented);
+        drop_repeated_errors(&mut errors);
+        let mut diagnostics = self
+            .lexical
+            .iter()
+            .map(|(error, span)| Diagnostic::Lexical(error.clone(), *span))
+            .collect::<Vec<_>>();
+        diagnostics.extend(
+            errors
+                .into_iter()
+                .map(|(error, span)| Diagnostic::Syntax(error, span)),
+        );
+        diagnostics.extend(
+            self.comments
+                .iter()
+                .filter(|comment| is_doc(comment) && !documented.contains(&comment.span.start))
+                .map(|comment| {
+                    Diagnostic::Warning(SyntaxWarning::OrphanedDocComment, comment.span)
+                }),
+        );
+        diagnostics.sort_by_key(|diagnostic| diagnostic.span().start);
+        self.diagnostics = diagnostics;
+    }
+}
+
+/// Returns the range of statements that looked at a changed token, if there are any.
+fn affected(units: &[Unit], shift: &Shift) -> Option<Range<usize>> {
+    let first = units.iter().position(|unit| unit.is_affected(shift))?;
+    let last = units.iter().rposition(|unit| unit.is_affected(shift))?;
+    Some(first..last + 1)
+}
+
+/// Returns the statements of the block at the end of a path, with the indices of its braces.
+fn list<'u>(units: &'u [Unit], path: &[(usize, usize)]) -> (&'u [Unit], Option<usize>, usize) {
+    let mut list = (units, None, 0);
+    for (unit, event) in path {
+        let Event::List(inner) = &list.0[*unit].events[*event] else {
+            unreachable!()
+        };
+        list = (&inner.units, inner.open, inner.close);
+    }
+    list
+}
+
+fn list_mut<'u>(mut units: &'u mut Vec<Unit>, path: &[(usize, usize)]) -> &'u mut Vec<Unit> {
+    for (unit, event) in path {
+        let Event::List(inner) = &mut units[*unit].events[*event] else {
+            unreachable!()
+        };
+        units = &mut inner.units;
+    }
+    units
+}
+
+/// Returns the index of each statement on a path, with the byte offset where the block in it that the path goes into begins.
+fn positions(units: &[Unit], path: &[(usize, usize)], tokens: &[Token]) -> Vec<(usize, usize)> {
+    let mut positions = vec![];
+    let mut units = units;
+    for (unit, event) in path {
+        let Event::List(list) = &units[*unit].events[*event] else {
+            unreachable!()
+        };
+        positions.push((*unit, tokens[list.open.unwrap()].span.start));
+        units = &list.units;
+    }
+    positions
+}
+
+/// Finds the statements of a block by the statements leading to it, and where each block on the way begins.
+fn body<'s>(
+    mut statements: &'s mut Vec<Statement>,
+    positions: &[(usize, usize)],
+) -> Option<&'s mut Vec<Statement>> {
+    for (index, start) in positions {
+        let mut found = vec![];
+        blocks(statements.get_mut(*index)?, &mut found);
+        let block = found.into_iter().find(|block| block.span.start == *start)?;
+        statements = &mut block.body;
+    }
+    Some(statements)
+}
+
+/// Collects the blocks of a statement, and of the statements nested in it without a block in between, such as the body of an if statement.
+/// The blocks of function expressions are left out.
+fn blocks<'s>(statement: &'s mut Statement, found: &mut Vec<&'s mut Block>) {
+    match statement {
+        Statement::BlockStatement(block) => found.push(block),
+        Statement::Function(function) => found.push(&mut function.body),
+        Statement::Module(module) => found.push(&mut module.body),
+        Statement::TestBlock(tests) => found.push(&mut tests.body),
+        Statement::LoopStmnt(repeat) => found.push(&mut repeat.body),
+        Statement::TryBlock(attempt) => {
+            found.push(&mut attempt.body);
+            if let Some(recover) = &mut attempt.recover {
+                found.push(&mut recover.body);
+            }
+        }
+        Statement::Class(class) => methods(&mut class.properties, found),
+        Statement::Interface(interface) => methods(&mut interface.properties, found),
+        Statement::PublicModifier(public) => blocks(&mut public.statement, found),
+        Statement::WhileLoop(repeat) => blocks(&mut repeat.body, found),
+        Statement::ForLoop(repeat) => blocks(&mut repeat.body, found),
+        Statement::IfStatement(branch) => {
+            blocks(&mut branch.body, found);
+            if let Some(alternate) = &mut branch.alternate {
+                blocks(alternate, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn methods<'s>(properties: &'s mut [Property], found: &mut Vec<&'s mut Block>) {
+    for property in properties {
+        if let Property::Method(method) = property {
+            found.push(&mut method.body);
+        }
+    }
+}
+
+/// Collects the errors recorded while parsing statements, in the order they were found, and the doc comments attached to declarations.
+fn flatten(units: &[Unit], errors: &mut Vec<ParserError>, documented: &mut HashSet<usize>) {
+    for unit in units {
+        for event in &unit.events {
+            match event {
+                Event::Error(error) => errors.push(error.clone()),
+                Event::Documented(position) => {
+                    documented.insert(*position);
+                }
+                Event::List(list) => flatten(&list.units, errors, documented),
+            }
+        }
+    }
+}
+
+/// Returns how many interpolated strings a token opens, or closes if it is negative.
+fn interpolation(token: &Token) -> isize {
+    match &token.kind {
+        TokenKind::Literal(Literal {
+            kind: LiteralKind::TemplateHead,
+            ..
+        }) => 1,
+        TokenKind::Literal(Literal {
+            kind: LiteralKind::TemplateTail,
+            ..
+        }) => -1,
+        _ => 0,
+    }
+}
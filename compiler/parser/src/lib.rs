@@ -1,8 +1,29 @@
 #![allow(dead_code)]
 
 mod _parser;
+mod cst;
+mod fix;
+mod incremental;
+mod literal;
+mod program;
 mod scanner;
 mod test;
+mod trace;
+mod typed;
 
-pub use _parser::{Parser, ParserError, Provider};
+pub use _parser::{Parser, ParserError, ParserWarning, Provider};
+pub use cst::{
+    lossless_tokens, GreenElement, GreenNode, GreenToken, SyntaxElement, SyntaxKind, SyntaxNode,
+    SyntaxToken, SyntaxTree, Trivia,
+};
+pub use fix::{fixes, Fix};
+pub use literal::{character_count, dedent, unescape};
+pub use program::{parse_file, parse_in, parse_source, Diagnostic, Program};
 pub use scanner::Scanner;
+pub use trace::{TraceEvent, TraceHook};
+pub use typed::{
+    AstNode, AttributeSyntax, BinaryExpressionSyntax, BlockSyntax, CallExpressionSyntax,
+    ClassSyntax, ExpressionStatementSyntax, ExpressionSyntax, FunctionSyntax, IfStatementSyntax,
+    LiteralExpressionSyntax, NameExpressionSyntax, ParameterSyntax, ReturnStatementSyntax,
+    SourceFileSyntax, StatementSyntax, TypeSyntax, VariableDeclarationSyntax,
+};
@@ -0,0 +1,215 @@
+use errors::LexicalError;
+use unicode_segmentation::UnicodeSegmentation;
+use utils::{FileId, Span};
+
+/// Decodes the escape sequences in the body of a string or character literal, and returns its cooked value along with any invalid escapes found.<br>
+/// An invalid escape is left out of the cooked value as a whole, from its backslash to its last character, e.g. both `"\q"` and `"\u{}"` cook to `""`.<br>
+/// `start` is the byte offset of the first character of the body in `file`. It is used to report each invalid escape with the exact span it covers in the source.
+pub fn unescape(raw: &str, file: FileId, start: usize) -> (String, Vec<(LexicalError, Span)>) {
+    let mut decoder = Decoder {
+        chars: raw.chars().collect(),
+        index: 0,
+        file,
+        offset: start,
+        cooked: String::new(),
+        errors: vec![],
+    };
+    decoder.run();
+    (decoder.cooked, decoder.errors)
+}
+
+/// Builds the cooked value of a multi-line string from its raw body, i.e. everything between its opening and closing quotes.<br>
+/// The text begins on the line after the opening quotes. If the closing quotes sit on a line of their own, that line is not part of the value,
+/// but its indentation counts towards the indentation shared by the other lines. The shared indentation and any trailing whitespace are
+/// removed from each line, and escape sequences are decoded one line at a time, so that invalid ones are reported where they appear.
+pub fn dedent(raw: &str, file: FileId, start: usize) -> (String, Vec<(LexicalError, Span)>) {
+    let mut errors = vec![];
+    // The byte offset where each line begins, counted from the start of the body.
+    let mut starts = vec![];
+    let mut lines: Vec<Vec<char>> = vec![];
+    let mut line_start = 0;
+    for line in raw.split('\n') {
+        starts.push(line_start);
+        line_start += line.len() + 1;
+        lines.push(line.strip_suffix('\r').unwrap_or(line).chars().collect());
+    }
+    let opening = lines.remove(0);
+    starts.remove(0);
+    if let Some(offset) = opening.iter().position(|char| !char.is_whitespace()) {
+        let end = opening.len() - trailing_whitespace(&opening);
+        errors.push((
+            LexicalError::MultiLineStringStart,
+            Span::new(
+                file,
+                start + byte_length(&opening[..offset]),
+                start + byte_length(&opening[..end]),
+            ),
+        ));
+    }
+    let mut indentation = usize::MAX;
+    if lines.last().is_some_and(|line| is_blank(line)) {
+        indentation = lines.pop().unwrap().len();
+    }
+    for line in lines.iter().filter(|line| !is_blank(line)) {
+        indentation = indentation.min(leading_whitespace(line));
+    }
+    let mut cooked = vec![];
+    for (index, line) in lines.iter().enumerate() {
+        if is_blank(line) {
+            cooked.push(String::new());
+            continue;
+        }
+        let body: String = line[indentation..line.len() - trailing_whitespace(line)]
+            .iter()
+            .collect();
+        let line_start = start + starts[index] + byte_length(&line[..indentation]);
+        let (value, line_errors) = unescape(&body, file, line_start);
+        cooked.push(value);
+        errors.extend(line_errors);
+    }
+    (cooked.join("\n"), errors)
+}
+
+fn byte_length(chars: &[char]) -> usize {
+    chars.iter().map(|char| char.len_utf8()).sum()
+}
+
+fn is_blank(line: &[char]) -> bool {
+    line.iter().all(|char| char.is_whitespace())
+}
+
+fn leading_whitespace(line: &[char]) -> usize {
+    line.iter().take_while(|char| char.is_whitespace()).count()
+}
+
+fn trailing_whitespace(line: &[char]) -> usize {
+    line.iter()
+        .rev()
+        .take_while(|char| char.is_whitespace())
+        .count()
+}
+
+/// Returns the number of user-perceived characters in a cooked value.<br>
+/// Combining sequences like `é` count as one character, the same way they are rendered.
+pub fn character_count(cooked: &str) -> usize {
+    cooked.graphemes(true).count()
+}
+
+/// A small scanner over the body of a literal that replaces escape sequences with the characters they represent.
+struct Decoder {
+    chars: Vec<char>,
+    index: usize,
+    file: FileId,
+    /// The byte offset of the current character in the source text.
+    offset: usize,
+    cooked: String,
+    errors: Vec<(LexicalError, Span)>,
+}
+
+impl Decoder {
+    fn current(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+    /// Moves to the next character, keeping track of its byte offset.
+    fn next(&mut self) {
+        if let Some(char) = self.current() {
+            self.offset += char.len_utf8();
+        }
+        self.index += 1;
+    }
+    fn error(&mut self, error: LexicalError, start: usize) {
+        self.errors
+            .push((error, Span::new(self.file, start, self.offset)));
+    }
+    fn run(&mut self) {
+        while let Some(char) = self.current() {
+            if char == '\\' {
+                self.escape();
+            } else {
+                self.cooked.push(char);
+                self.next();
+            }
+        }
+    }
+    /// Decodes a single escape sequence, starting at its backslash.
+    fn escape(&mut self) {
+        let start = self.offset;
+        self.next(); // Move past \
+        let decoded = match self.current() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('0') => '\0',
+            Some(char @ ('\\' | '"' | '\'' | '{' | '}')) => char,
+            Some('x') => return self.hex_escape(start),
+            Some('u') => return self.unicode_escape(start),
+            Some(char) => {
+                self.next();
+                self.error(LexicalError::UnknownEscapeSequence(char), start);
+                return;
+            }
+            None => {
+                self.error(LexicalError::UnknownEscapeSequence('\0'), start);
+                return;
+            }
+        };
+        self.next();
+        self.cooked.push(decoded);
+    }
+    /// Decodes an ASCII escape, e.g. `\x41`.
+    fn hex_escape(&mut self, start: usize) {
+        self.next(); // Move past x
+        let digits = self.hex_digits(2);
+        if digits.len() != 2 {
+            self.error(LexicalError::InvalidHexEscape, start);
+            return;
+        }
+        match u32::from_str_radix(&digits, 16) {
+            Ok(value) if value <= 0x7F => self.cooked.push(value as u8 as char),
+            _ => self.error(LexicalError::InvalidHexEscape, start),
+        }
+    }
+    /// Decodes a Unicode escape, either with exactly four digits, e.g. `\u000f`, or braced, e.g. `\u{1F600}`.
+    fn unicode_escape(&mut self, start: usize) {
+        self.next(); // Move past u
+        let digits = if self.current() == Some('{') {
+            self.next(); // Move past {
+                         // Every digit up to the closing brace belongs to the escape, so that one with too many digits is dropped whole.
+            let digits = self.hex_digits(usize::MAX);
+            let closed = self.current() == Some('}');
+            if closed {
+                self.next(); // Move past }
+            }
+            if digits.is_empty() || digits.len() > 6 || !closed {
+                self.error(LexicalError::InvalidUnicodeEscape, start);
+                return;
+            }
+            digits
+        } else {
+            let digits = self.hex_digits(4);
+            if digits.len() != 4 {
+                self.error(LexicalError::InvalidUnicodeEscape, start);
+                return;
+            }
+            digits
+        };
+        let value = u32::from_str_radix(&digits, 16).unwrap_or(u32::MAX);
+        // Surrogates and values above U+10FFFF are not scalar values, and so cannot be stored in a character.
+        match char::from_u32(value) {
+            Some(char) => self.cooked.push(char),
+            None => self.error(LexicalError::InvalidUnicodeScalar(digits), start),
+        }
+    }
+    /// Collects up to `limit` hexadecimal digits.
+    fn hex_digits(&mut self, limit: usize) -> String {
+        let mut digits = String::new();
+        while let Some(char) = self.current() {
+            if digits.len() == limit || !char.is_ascii_hexdigit() {
+                break;
+            }
+            digits.push(char);
+            self.next();
+        }
+        digits
+    }
+}
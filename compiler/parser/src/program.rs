@@ -0,0 +1,154 @@
+use std::{fmt::Display, io, ops::Range, path::Path};
+
+use crate::{
+    _parser::{drop_repeated_errors, Parser, Provider},
+    cst::SyntaxEvent,
+    incremental::Unit,
+    scanner::Scanner,
+};
+use ast::{Arena, Statement, Token};
+use errors::{LexicalError, ResolverError, ResolverWarnings, SyntaxError, SyntaxWarning};
+use utils::{FileId, SourceMap, Span};
+
+/// A problem found in a source file, while scanning it into tokens, parsing the tokens or resolving the names in it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diagnostic {
+    Lexical(LexicalError, Span),
+    Syntax(SyntaxError, Span),
+    Warning(SyntaxWarning, Span),
+    Resolver(ResolverError, Span),
+    ResolverWarning(ResolverWarnings, Span),
+}
+
+impl Diagnostic {
+    /// Returns the part of the source text the error refers to.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Lexical(_, span)
+            | Self::Syntax(_, span)
+            | Self::Warning(_, span)
+            | Self::Resolver(_, span)
+            | Self::ResolverWarning(_, span) => *span,
+        }
+    }
+    /// Returns `true` if the problem stops the program from being valid, i.e. it is not a warning.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::Warning(..) | Self::ResolverWarning(..))
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lexical(error, _) => write!(f, "{}", error),
+            Self::Syntax(error, _) => write!(f, "{}", error),
+            Self::Warning(warning, _) => write!(f, "{}", warning),
+            Self::Resolver(error, _) => write!(f, "{}", error),
+            Self::ResolverWarning(warning, _) => write!(f, "{}", warning),
+        }
+    }
+}
+
+/// A parsed source file, which owns its statements, its comments and the errors found in it.
+pub struct Program {
+    pub(crate) statements: Vec<Statement>,
+    pub comments: Vec<Token>,
+    /// The errors and warnings in the file, in the order they appear in the text.
+    pub diagnostics: Vec<Diagnostic>,
+    /// The file the program was parsed from, which the spans in it refer to.
+    pub file: FileId,
+    pub(crate) text: String,
+    /// The errors found while scanning the text.
+    pub(crate) lexical: Vec<(LexicalError, Span)>,
+    /// The top-level statements, with the tokens each was parsed from.
+    pub(crate) units: Vec<Unit>,
+    /// The tokens of the text, which are scanned again around an edit.
+    pub(crate) tokens: Vec<Token>,
+    /// The indices of the tokens that statements were last parsed from: the ones around the last edit, or all of them if the text was parsed from scratch.
+    pub(crate) reparsed: Range<usize>,
+}
+
+impl Program {
+    /// Returns the top-level statements of the program.
+    pub fn statements(&self) -> &[Statement] {
+        &self.statements
+    }
+    /// Copies the statements into an arena, which gives every node an ID.
+    pub fn to_arena(&self) -> Arena {
+        Arena::from_statements(&self.statements)
+    }
+    /// Returns `true` if the program was scanned and parsed without errors. Warnings are allowed.
+    pub fn is_valid(&self) -> bool {
+        !self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+}
+
+/// Scans and parses source text into a program.
+pub fn parse_source(text: &str) -> Program {
+    parse_text(text, FileId::default())
+}
+
+/// Scans and parses the text of a file into a program, whose spans refer to the file.
+pub(crate) fn parse_text(text: &str, file: FileId) -> Program {
+    parse_with(text, file, false).0
+}
+
+/// Scans and parses the text of a file into a program, and returns the steps the parser took to build its concrete syntax tree if `syntax` is set.
+pub(crate) fn parse_with(text: &str, file: FileId, syntax: bool) -> (Program, Vec<SyntaxEvent>) {
+    let mut scanner = Scanner::in_file(text, file);
+    scanner.run();
+    let lexical = std::mem::take(&mut scanner.errors);
+    let mut diagnostics = lexical
+        .iter()
+        .map(|(error, span)| Diagnostic::Lexical(error.clone(), *span))
+        .collect::<Vec<_>>();
+    let parser = Parser::from(Provider { scanner, index: 0 });
+    if syntax {
+        parser.build_syntax();
+    }
+    parser.parse();
+    let events = parser.take_syntax();
+    let statements = parser.statements.take();
+    let mut errors = parser.diagnostics.take();
+    drop_repeated_errors(&mut errors);
+    diagnostics.extend(
+        errors
+            .into_iter()
+            .map(|(error, span)| Diagnostic::Syntax(error, span)),
+    );
+    diagnostics.extend(
+        parser
+            .warnings
+            .take()
+            .into_iter()
+            .map(|(warning, span)| Diagnostic::Warning(warning, span)),
+    );
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span().start);
+    let units = parser.take_units();
+    let (tokens, comments) = parser.into_tokens();
+    let reparsed = 0..tokens.len();
+    let program = Program {
+        statements,
+        comments,
+        diagnostics,
+        file,
+        text: text.to_string(),
+        lexical,
+        units,
+        tokens,
+        reparsed,
+    };
+    (program, events)
+}
+
+/// Parses a file registered in a source map.
+pub fn parse_in(map: &SourceMap, file: FileId) -> Program {
+    parse_text(&map.get(file).text, file)
+}
+
+/// Reads a source file, registers it in a source map and parses it into a program.<br>
+/// The spans of the program point into the registered file, whose ID is the program's `file`.
+pub fn parse_file(map: &mut SourceMap, path: impl AsRef<Path>) -> io::Result<Program> {
+    let file = map.load(path)?;
+    Ok(parse_in(map, file))
+}
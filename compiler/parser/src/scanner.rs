@@ -1,5 +1,7 @@
-use ast::{TextSpan, Token, KEYWORDS, OPERATORS};
+use crate::literal;
+use ast::{NumericValue, Token, KEYWORDS, OPERATORS};
 use errors::LexicalError;
+use utils::{FileId, Span};
 
 /// A state machine that goes over the input text and scans it into a stream of tokens.<br>
 /// The scanner does not perform any validation on its input. It picks out recognised tokens and flag the unknown tokens as invalid.
@@ -8,19 +10,26 @@ pub struct Scanner {
     pub tokens: Vec<Token>,
     /// The result comment tokens,
     pub comments: Vec<Token>,
+    /// The byte offset where each line of the input text begins.
+    pub(crate) lines: Vec<usize>,
+    /// The file the input text is from, which the spans of the tokens refer to.
+    file: FileId,
     /// The characters in the input text.
     text: Vec<char>,
     /// The current position in the text stream while scanning.
     index: usize,
+    /// The byte offset of the current character in the input text.
+    offset: usize,
     /// Indicates that the scanning has reached the end of the character stream.
     end: bool,
     /// The current char.
     char: char,
-    /// A duo of numbers that mark the position of the scanner in the input text, by line and by column
-    pos: [u64; 2],
-    span: [[u64; 2]; 2],
-    // An array of errors encountered during parsing, such as an invalid character or an unterminated string.
-    errors: Vec<(LexicalError, TextSpan)>,
+    /// The bytes covered by the token being scanned, or the last one scanned.
+    span: Span,
+    /// An array of errors encountered during scanning, such as an invalid character, an unterminated string or a malformed escape sequence.
+    pub errors: Vec<(LexicalError, Span)>,
+    /// The number of unclosed { brackets within each embedded expression of the interpolated strings currently being scanned.
+    interpolations: Vec<usize>,
 }
 
 fn is_identifier_char(char: char) -> bool {
@@ -30,24 +39,47 @@ fn is_identifier_char(char: char) -> bool {
 impl Scanner {
     /// Creates a new scanner.
     pub fn new(input: &str) -> Self {
+        Scanner::in_file(input, FileId::default())
+    }
+    /// Creates a scanner for the text of a file, whose tokens have spans in that file.
+    pub fn in_file(input: &str, file: FileId) -> Self {
         Scanner {
             tokens: Vec::new(),
             comments: Vec::new(),
+            lines: line_starts(input),
+            file,
             text: input.chars().collect(),
             index: 0,
+            offset: 0,
             end: false,
             char: '\0',
-            pos: [1, 1],
-            span: [[0, 0], [0, 0]],
+            span: Span::new(file, 0, 0),
             errors: Vec::new(),
+            interpolations: Vec::new(),
+        }
+    }
+    /// Creates a scanner that starts at a byte offset in the input, where a token or comment begins outside of any interpolated string.<br>
+    /// `span` is the span of the token or comment before it, which the end of file token takes if nothing else is scanned.
+    pub(crate) fn resume(input: &str, offset: usize, span: Span) -> Self {
+        Scanner {
+            text: input[offset..].chars().collect(),
+            offset,
+            span,
+            ..Scanner::in_file("", span.file)
         }
     }
+    /// Returns the line of a byte offset in the input text, counted from zero.
+    pub(crate) fn line(&self, offset: usize) -> usize {
+        self.lines.partition_point(|start| *start <= offset) - 1
+    }
     // Advances to the next character in the stream.
     fn next(&mut self) {
+        if let Some(char) = self.text.get(self.index) {
+            self.offset += char.len_utf8();
+        }
         self.index += 1;
         if self.index < self.text.len() {
             self.char = self.text[self.index];
-            self.shift();
         } else {
             self.char = '\0';
             self.end = true;
@@ -59,22 +91,13 @@ impl Scanner {
             self.next();
         }
     }
-    /// Tracks the position of each character by line and column. Useful in getting the range and location of each token.
-    fn shift(&mut self) {
-        if self.char == '\n' {
-            self.pos[0] += 1;
-            self.pos[1] = 0;
-        } else {
-            self.pos[1] += 1;
-        }
-    }
-    /// Marks the current line and column as the start of a token.
+    /// Marks the current byte offset as the start of a token.
     fn mark_start(&mut self) {
-        self.span[0] = self.pos;
+        self.span.start = self.offset;
     }
-    /// Marks the current line and column as the end of a token.
+    /// Marks the current byte offset as the end of a token.
     fn mark_end(&mut self) {
-        self.span[1] = self.pos;
+        self.span.end = self.offset;
     }
     fn error(&mut self, error: LexicalError) {
         self.errors.push((error, self.span));
@@ -109,7 +132,12 @@ impl Scanner {
 impl Scanner {
     /// Entry point to the scanner.
     pub fn run(&mut self) {
-        self.char = *self.text.get(0).unwrap_or_else(|| &'\0');
+        self.run_until(|_| false);
+    }
+    /// Scans until `resync` accepts the byte offset where the next token or comment begins, and returns `true` if it did.<br>
+    /// Offsets inside the embedded expressions of an interpolated string are not offered, as the scanner could not be restarted there. If no offset is accepted, the text is scanned to the end and the end of file token is added.
+    pub(crate) fn run_until(&mut self, mut resync: impl FnMut(usize) -> bool) -> bool {
+        self.char = *self.text.first().unwrap_or(&'\0');
         if self.char != '\0' {
             loop {
                 while self.char.is_whitespace() || self.char == '\r' {
@@ -118,15 +146,22 @@ impl Scanner {
                 if self.end {
                     break;
                 }
+                if self.interpolations.is_empty() && resync(self.offset) {
+                    return true;
+                }
                 let token = self.scan_next();
                 if token.is_comment() {
-                    self.comments.push(token)
+                    self.comments.push(token);
                 } else {
                     self.tokens.push(token);
                 }
             }
         }
+        if !self.interpolations.is_empty() {
+            self.error(LexicalError::UnterminatedStringLiteral);
+        }
         self.tokens.push(Token::eof(self.span));
+        false
     }
     /// Scans for the next token in the stream.
     fn scan_next(&mut self) -> Token {
@@ -136,20 +171,29 @@ impl Scanner {
             self.block_comment()
         } else if self.sees("##") {
             self.doc_comment()
+        } else if self.sees("\"\"\"") {
+            self.multi_line_string()
         } else if self.char == '"' {
             self.string()
+        } else if let Some(fences) = self.raw_string_fences() {
+            self.raw_string(fences)
         } else if self.char == '\'' {
             self.character()
-        } else if self.char.is_digit(10) {
+        } else if self.char.is_ascii_digit() {
             self.number()
         } else if self.matches("true") || self.matches("false") {
             self.boolean()
-        } else if let Some(op) = OPERATORS.iter().find(|op| self.sees(op)) {
-            self.operator(op)
+        } else if let Some(op) = OPERATORS
+            .iter()
+            .find(|info| info.is_token() && self.sees(info.symbol))
+        {
+            self.operator(op.symbol)
         } else if self.char == ',' {
             self.comma()
         } else if self.char == '@' {
             self.injunction()
+        } else if self.char == '}' && self.interpolations.last() == Some(&0) {
+            self.template_continuation()
         } else if self.is_bracket() {
             self.bracket()
         } else if self.char == ';' {
@@ -179,8 +223,12 @@ impl Scanner {
             content.push(self.char);
             self.next();
         }
+        let unterminated = self.end;
         self.next_by(2);
         self.mark_end();
+        if unterminated {
+            self.error(LexicalError::UnterminatedBlockComment);
+        }
         Token::create_block_comment(content, self.span)
     }
     fn doc_comment(&mut self) -> Token {
@@ -197,94 +245,245 @@ impl Scanner {
     fn string(&mut self) -> Token {
         self.mark_start();
         self.next();
-        let mut value = String::new();
-        while !(self.end || self.char == '"') {
-            if self.sees("\\\\") {
-                value.push_str("\\\\");
-                self.next_by(2);
-            } else if self.sees("\\\"") {
-                value.push_str("\\\"");
-                self.next_by(2);
-            } else {
-                value.push(self.char);
-                self.next()
+        self.string_segment("string", "template head")
+    }
+    /// Scans the rest of an interpolated string after the } that closes one of its embedded expressions.
+    fn template_continuation(&mut self) -> Token {
+        self.mark_start();
+        self.interpolations.pop();
+        self.next(); // Move past }
+        self.string_segment("template tail", "template middle")
+    }
+    /// Scans string content until the closing quote, or until the { that opens an embedded expression.
+    /// The literal kind of the token depends on which of the two ends the segment.
+    fn string_segment(&mut self, closed: &str, interpolated: &str) -> Token {
+        let start = self.offset;
+        let raw = self.literal_body(&['"', '{']);
+        let value = self.unescape(&raw, start);
+        if self.char == '{' {
+            self.next(); // Move past {
+            self.mark_end();
+            self.interpolations.push(0);
+            return Token::create_decoded_literal(interpolated, raw, value, self.span);
+        }
+        let unterminated = self.end;
+        self.next(); // Move past "
+        self.mark_end();
+        if unterminated {
+            self.error(LexicalError::UnterminatedStringLiteral);
+        }
+        Token::create_decoded_literal(closed, raw, value, self.span)
+    }
+    /// Returns the number of # fences around a raw string that starts at the current character, if there is one.
+    fn raw_string_fences(&self) -> Option<usize> {
+        if self.char != 'r' {
+            return None;
+        }
+        let rest = &self.text[self.index + 1..];
+        let fences = rest.iter().take_while(|char| **char == '#').count();
+        (rest.get(fences) == Some(&'"')).then_some(fences)
+    }
+    /// Scans a raw string, e.g. `r"C:\Users"`, or `r#"He said "hi""#` with fences to allow quotes in its body.<br>
+    /// The body is kept exactly as written, so escape sequences are not decoded and braces do not open embedded expressions.
+    fn raw_string(&mut self, fences: usize) -> Token {
+        self.mark_start();
+        self.next_by(fences + 2); // Move past r, the fences and "
+        let closing = format!("\"{}", "#".repeat(fences));
+        let mut raw = String::new();
+        while !(self.end || self.sees(&closing)) {
+            raw.push(self.char);
+            self.next();
+        }
+        let unterminated = self.end;
+        self.next_by(closing.len());
+        self.mark_end();
+        if unterminated {
+            self.error(LexicalError::UnterminatedStringLiteral);
+        }
+        let value = raw.replace("\r\n", "\n");
+        Token::create_decoded_literal("raw string", raw, value, self.span)
+    }
+    /// Scans a string between triple quotes, which can span several lines.
+    /// The body is collected as written, and then stripped of its indentation and decoded by [`literal::dedent`].
+    fn multi_line_string(&mut self) -> Token {
+        self.mark_start();
+        self.next_by(3); // Move past """
+        let start = self.offset;
+        let mut raw = String::new();
+        while !(self.end || self.sees("\"\"\"")) {
+            if self.char == '\\' {
+                raw.push(self.char);
+                self.next();
+                if self.end {
+                    break;
+                }
             }
+            raw.push(self.char);
+            self.next();
         }
+        let unterminated = self.end;
+        self.next_by(3); // Move past """
         self.mark_end();
-        if self.end {
+        if unterminated {
             self.error(LexicalError::UnterminatedStringLiteral);
-        } else {
+        }
+        let (value, errors) = literal::dedent(&raw, self.file, start);
+        self.errors.extend(errors);
+        Token::create_decoded_literal("multi-line string", raw, value, self.span)
+    }
+    /// Collects the raw body of a string or character literal, up to any of the given delimiters.
+    /// Escape sequences are kept as they are, so that an escaped delimiter does not end the literal.
+    fn literal_body(&mut self, delimiters: &[char]) -> String {
+        let mut raw = String::new();
+        while !(self.end || delimiters.contains(&self.char)) {
+            if self.char == '\\' {
+                raw.push(self.char);
+                self.next();
+                if self.end {
+                    break;
+                }
+                // The braces of a code point escape, e.g. \u{1F600}, do not open an embedded expression.
+                if self.sees("u{") {
+                    while !(self.end
+                        || self.char == '}'
+                        || (self.char != '{' && delimiters.contains(&self.char)))
+                    {
+                        raw.push(self.char);
+                        self.next();
+                    }
+                    if self.char != '}' {
+                        continue;
+                    }
+                }
+            }
+            raw.push(self.char);
             self.next();
         }
-        Token::create_literal("string", value, self.span)
+        raw
+    }
+    /// Decodes the escape sequences in the body of a literal, and stores any invalid ones as errors.
+    fn unescape(&mut self, raw: &str, start: usize) -> String {
+        let (value, errors) = literal::unescape(raw, self.file, start);
+        self.errors.extend(errors);
+        value
     }
     fn boolean(&mut self) -> Token {
         self.mark_start();
-        let value;
-        if self.char == 't' {
+        let value = if self.char == 't' {
             self.next_by(4);
-            value = String::from("true");
+            String::from("true")
         } else {
             self.next_by(5);
-            value = String::from("false")
-        }
+            String::from("false")
+        };
         self.mark_end();
         Token::create_literal("boolean", value, self.span)
     }
+    /// Scans a numeric literal, and checks that it is well formed and within range.
     fn number(&mut self) -> Token {
         self.mark_start();
         let mut value = String::new();
-        if self.sees("0x") {
-            value.push_str("0x");
-            self.next_by(2);
-            while self.char.is_digit(16) {
-                value.push(self.char);
-                self.next();
-            }
+        let mut errors = vec![];
+        let reported = self.errors.len();
+        let radix = if self.sees("0x") {
+            16
         } else if self.sees("0b") {
-            value.push_str("0b");
-            self.next_by(2);
-            while self.char.is_digit(2) {
-                value.push(self.char);
-                self.next();
-            }
+            2
         } else if self.sees("0o") {
-            value.push_str("0o");
+            8
+        } else {
+            10
+        };
+        if radix != 10 {
+            let prefix: String = self.text[self.index..self.index + 2].iter().collect();
+            value.push_str(&prefix);
             self.next_by(2);
-            while self.char.is_digit(8) {
-                value.push(self.char);
-                self.next();
+            if self.digits(radix, &mut value) == 0 {
+                errors.push(LexicalError::MissingDigits(prefix));
             }
         } else {
-            while self.char.is_digit(10) {
+            self.digits(10, &mut value);
+        }
+        if self.char == '.' && !self.sees("..") && (radix == 10 || self.next_is_digit(radix)) {
+            // A name after the point is a member of the number, not a fraction.
+            let member = self
+                .text
+                .get(self.index + 1)
+                .is_some_and(|char| char.is_alphabetic() || matches!(char, '_' | '$'));
+            if !member {
                 value.push(self.char);
                 self.next();
+                if self.digits(radix, &mut value) == 0 {
+                    self.errors.push((
+                        LexicalError::MissingFraction,
+                        Span::new(self.file, self.offset - 1, self.offset),
+                    ));
+                }
+                if radix != 10 {
+                    errors.push(LexicalError::NonDecimalFloat);
+                }
             }
-            if self.char == '.' && !self.sees("..") {
+        }
+        // e is a hexadecimal digit, so hexadecimal numbers have no exponent.
+        if radix != 16 && matches!(self.char, 'e' | 'E') {
+            value.push(self.char);
+            self.next();
+            if matches!(self.char, '+' | '-') {
                 value.push(self.char);
                 self.next();
-                while self.char.is_digit(10) {
-                    value.push(self.char);
-                    self.next();
-                }
+            }
+            if self.digits(10, &mut value) == 0 {
+                errors.push(LexicalError::MissingExponent);
+            }
+            if radix != 10 {
+                errors.push(LexicalError::NonDecimalFloat);
             }
         }
-        value.push_str(self.exponent().as_str());
         self.mark_end();
+        let invalid_digits = self.errors[reported..]
+            .iter()
+            .any(|(error, _)| matches!(error, LexicalError::InvalidDigit(..)));
+        if errors.is_empty() && !invalid_digits {
+            if let Err(error) = NumericValue::parse(&value) {
+                errors.push(error);
+            }
+        }
+        for error in errors {
+            self.error(error);
+        }
         Token::create_literal("number", value, self.span)
     }
-    fn exponent(&mut self) -> String {
-        let mut exponential = String::new();
-        if self.char == 'e' {
-            loop {
-                exponential.push(self.char);
-                self.next();
-                if !self.char.is_digit(10) {
-                    break;
+    /// Collects a run of digits in the given radix, along with the `_` separators between them, and returns the number of digits.<br>
+    /// Decimal digits that are not valid in the radix are reported and collected too, so that `0b102` is one malformed number.
+    fn digits(&mut self, radix: u32, value: &mut String) -> usize {
+        let mut count = 0;
+        while self.char.is_ascii_digit() || self.char.is_digit(radix) || self.char == '_' {
+            if !self.char.is_digit(radix) && self.char != '_' {
+                self.errors.push((
+                    LexicalError::InvalidDigit(self.char, radix),
+                    Span::new(self.file, self.offset, self.offset + 1),
+                ));
+            } else if self.char == '_' {
+                let follows_digit = value.chars().last().is_some_and(|c| c.is_digit(radix));
+                if !(follows_digit && self.next_is_digit(radix)) {
+                    self.errors.push((
+                        LexicalError::MisplacedDigitSeparator,
+                        Span::new(self.file, self.offset, self.offset + 1),
+                    ));
                 }
+            } else {
+                count += 1;
             }
+            value.push(self.char);
+            self.next();
         }
-        exponential
+        count
+    }
+    /// Checks if the character after the current one is a digit in the given radix.
+    fn next_is_digit(&self, radix: u32) -> bool {
+        self.text
+            .get(self.index + 1)
+            .is_some_and(|char| char.is_digit(radix))
     }
     fn operator(&mut self, op: &str) -> Token {
         self.mark_start();
@@ -320,26 +519,32 @@ impl Scanner {
     fn character(&mut self) -> Token {
         self.mark_start();
         self.next();
-        let mut value = String::new();
-        while !(self.end || self.char == '\'') {
-            if self.sees("\\\\") {
-                value.push_str("\\\\");
-                self.next_by(2);
-            } else if self.sees("\\\'") {
-                value.push_str("\\\'");
-                self.next_by(2);
-            } else {
-                value.push(self.char);
-                self.next()
-            }
-        }
+        let start = self.offset;
+        let raw = self.literal_body(&['\'']);
+        let error_count = self.errors.len();
+        let value = self.unescape(&raw, start);
+        let unterminated = self.end;
+        self.next(); // Move past '
         self.mark_end();
-        self.next();
-        Token::create_literal("character", value, self.span)
+        if unterminated {
+            self.error(LexicalError::UnterminatedCharacterLiteral);
+        } else if self.errors.len() == error_count && literal::character_count(&value) != 1 {
+            // An escape sequence counts as the single character it represents.
+            self.error(LexicalError::InvalidCharacterCount);
+        }
+        Token::create_decoded_literal("character", raw, value, self.span)
     }
     fn bracket(&mut self) -> Token {
         self.mark_start();
         let char = self.char;
+        // Keep count of blocks opened within embedded expressions, so that their closing brackets are not mistaken for the end of the expression.
+        if let Some(depth) = self.interpolations.last_mut() {
+            match char {
+                '{' => *depth += 1,
+                '}' => *depth -= 1,
+                _ => {}
+            }
+        }
         self.next();
         self.mark_end();
         Token::create_bracket(&char, self.span)
@@ -368,3 +573,10 @@ impl Scanner {
         Token::create_unknown(value, self.span)
     }
 }
+
+/// Returns the byte offset where each line of a text begins.
+pub(crate) fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+        .collect()
+}
@@ -1,19 +1,35 @@
 #![cfg(test)]
 
-use std::{cell::RefCell, vec};
+use std::{cell::RefCell, rc::Rc, vec};
 
 use crate::{
     _parser::{Parser, Provider},
+    cst::{lossless_tokens, SyntaxKind, SyntaxNode, SyntaxTree, Trivia},
+    fix::fixes,
+    program::{parse_file, parse_in, parse_source, Diagnostic, Program},
     scanner::Scanner,
+    trace::TraceEvent,
+    typed::{
+        AstNode, BinaryExpressionSyntax, CallExpressionSyntax, ClassSyntax, FunctionSyntax,
+        NameExpressionSyntax, ReturnStatementSyntax, VariableDeclarationSyntax,
+    },
 };
 use ast::{
-    ArrayExpression, Block, BracketKind, Break, CallExpression, Comment, CommentKind, ConcreteType,
-    CrashStatement, Expression, FnExpression, Function, Identifier, IfStatement, Import,
-    Injunction, Keyword, Literal, LiteralKind, Loop, Operator, Parameter, PrependStatement,
-    PrintLnStatement, Punctuation, RecoverBlock, ReturnStatement, Statement, TestBlock, TextString,
-    Token, TokenIdentifier, TokenKind, TryBlock, Type, UseImport, VarKind, VariableDeclaration,
-    WhileStatement,
+    ArrayExpression, Attribute, Block, BracketKind, Break, CallExpression, Class, Comment,
+    CommentKind, ConcreteType, CrashStatement, Expression, ExpressionStatement, FnExpression,
+    Function, Identifier, IfStatement, Import, Injunction, Keyword, Literal, LiteralKind, Loop,
+    NodeKind, NumericValue, Operator, OperatorKind, Parameter, PrependStatement, PrintLnStatement,
+    Property, Punctuation, RecoverBlock, ReturnStatement, Statement, TemplatePart, TestBlock,
+    TextString, Token, TokenIdentifier, TokenKind, TryBlock, Type, UseImport, VarKind,
+    VariableDeclaration, WhileStatement, OPERATORS,
 };
+use errors::{LexicalError, SyntaxError, SyntaxWarning};
+use utils::{Encoding, FileId, LineColumn, SourceMap, Span};
+
+/// Returns a span of bytes in text parsed on its own.
+fn span(start: usize, end: usize) -> Span {
+    Span::new(FileId::default(), start, end)
+}
 
 #[test]
 fn it_scans_line_comment() {
@@ -26,7 +42,7 @@ fn it_scans_line_comment() {
                 kind: CommentKind::Line,
                 content: String::from(" This is a comment.")
             }),
-            span: [[1, 1], [1, 21]]
+            span: span(0, 21)
         }
     )
 }
@@ -42,9 +58,16 @@ fn it_scans_block_comment() {
                 kind: CommentKind::Block,
                 content: String::from(" This\nis\na\nblock\ncomment ")
             }),
-            span: [[1, 1], [5, 10]]
+            span: span(0, 29)
         }
-    )
+    );
+    assert!(scanner.errors.is_empty());
+    let mut scanner = Scanner::new("a; /* unclosed");
+    scanner.run();
+    assert_eq!(
+        scanner.errors,
+        vec![(LexicalError::UnterminatedBlockComment, span(3, 14))]
+    );
 }
 
 #[test]
@@ -54,7 +77,7 @@ fn it_scans_doc_comments() {
     assert_eq!(
         scanner.comments[0],
         Token {
-            span: [[1, 1], [1, 25]],
+            span: span(0, 25),
             kind: TokenKind::Comment(Comment {
                 kind: CommentKind::Doc,
                 content: String::from(" This is a doc comment.")
@@ -70,10 +93,11 @@ fn it_scans_strings() {
     assert_eq!(
         scanner.tokens[0],
         Token {
-            span: [[1, 1], [1, 19]],
+            span: span(0, 19),
             kind: TokenKind::Literal(Literal {
                 kind: LiteralKind::StringLiteral,
-                value: String::from("This is a string.")
+                value: String::from("This is a string."),
+                raw: String::from("This is a string."),
             })
         }
     )
@@ -86,12 +110,256 @@ fn it_scans_string_with_escape() {
     assert_eq!(
         scanner.tokens[0],
         Token {
-            span: [[1, 1], [1, 21]],
+            span: span(0, 21),
             kind: TokenKind::Literal(Literal {
                 kind: LiteralKind::StringLiteral,
-                value: String::from("This is a\\\" string.")
+                value: String::from("This is a\" string."),
+                raw: String::from("This is a\\\" string."),
+            })
+        }
+    )
+}
+
+#[test]
+fn it_decodes_escape_sequences() {
+    let mut scanner = Scanner::new("\"tab\\tnew\\nline \\x41\\u000f\\u{1F600} \\\\ \\{\"");
+    scanner.run();
+    assert_eq!(
+        scanner.tokens[0].kind,
+        TokenKind::Literal(Literal {
+            kind: LiteralKind::StringLiteral,
+            value: String::from("tab\tnew\nline A\u{f}\u{1F600} \\ {"),
+            raw: String::from("tab\\tnew\\nline \\x41\\u000f\\u{1F600} \\\\ \\{"),
+        })
+    );
+    assert_eq!(scanner.errors, vec![]);
+}
+
+#[test]
+fn it_reports_invalid_escapes_with_their_span() {
+    let mut scanner = Scanner::new("\"a\\qb\nc\\x8Fd\\u{D800}\\u12\"");
+    scanner.run();
+    assert_eq!(
+        scanner.errors,
+        vec![
+            (LexicalError::UnknownEscapeSequence('q'), span(2, 4)),
+            (LexicalError::InvalidHexEscape, span(7, 11)),
+            (
+                LexicalError::InvalidUnicodeScalar(String::from("D800")),
+                span(12, 20)
+            ),
+            (LexicalError::InvalidUnicodeEscape, span(20, 24)),
+        ]
+    );
+}
+
+#[test]
+fn it_drops_invalid_escapes_from_the_cooked_value() {
+    let cases = [
+        (r#""a\qb""#, "ab", LexicalError::UnknownEscapeSequence('q')),
+        (r#""a\xZb""#, "aZb", LexicalError::InvalidHexEscape),
+        (r#""a\x8Fb""#, "ab", LexicalError::InvalidHexEscape),
+        (r#""a\u12z""#, "az", LexicalError::InvalidUnicodeEscape),
+        (r#""a\u{}b""#, "ab", LexicalError::InvalidUnicodeEscape),
+        (r#""a\u{41z""#, "az", LexicalError::InvalidUnicodeEscape),
+        (
+            r#""a\u{1234567}b""#,
+            "ab",
+            LexicalError::InvalidUnicodeEscape,
+        ),
+        (
+            r#""a\u{D800}b""#,
+            "ab",
+            LexicalError::InvalidUnicodeScalar(String::from("D800")),
+        ),
+    ];
+    for (source, cooked, error) in cases {
+        let mut scanner = Scanner::new(source);
+        scanner.run();
+        match &scanner.tokens[0].kind {
+            TokenKind::Literal(literal) => assert_eq!(literal.value, cooked, "{source}"),
+            _ => unreachable!(),
+        }
+        let errors: Vec<_> = scanner.errors.into_iter().map(|(error, _)| error).collect();
+        assert_eq!(errors, vec![error], "{source}");
+    }
+}
+
+#[test]
+fn it_counts_escaped_and_combined_characters_as_one() {
+    for source in ["'\\n'", "'\\u{1F600}'", "'e\\u0301'", "'\\''"] {
+        let mut scanner = Scanner::new(source);
+        scanner.run();
+        assert_eq!(scanner.errors, vec![], "{source}");
+    }
+    let mut scanner = Scanner::new("'\\u{41'");
+    scanner.run();
+    assert_eq!(
+        scanner.errors,
+        vec![(LexicalError::InvalidUnicodeEscape, span(1, 6))]
+    );
+    let mut scanner = Scanner::new("'ab'");
+    scanner.run();
+    assert_eq!(
+        scanner.errors,
+        vec![(LexicalError::InvalidCharacterCount, span(0, 4))]
+    );
+}
+
+#[test]
+fn it_scans_raw_strings() {
+    let mut scanner = Scanner::new("r\"C:\\new{x}\" r#\"say \"hi\"\"#");
+    scanner.run();
+    assert_eq!(
+        scanner.tokens[0],
+        Token {
+            span: span(0, 12),
+            kind: TokenKind::Literal(Literal {
+                kind: LiteralKind::RawStringLiteral,
+                value: String::from("C:\\new{x}"),
+                raw: String::from("C:\\new{x}"),
             })
         }
+    );
+    assert_eq!(
+        scanner.tokens[1],
+        Token {
+            span: span(13, 26),
+            kind: TokenKind::Literal(Literal {
+                kind: LiteralKind::RawStringLiteral,
+                value: String::from("say \"hi\""),
+                raw: String::from("say \"hi\""),
+            })
+        }
+    );
+    assert_eq!(scanner.errors, vec![]);
+}
+
+#[test]
+fn it_scans_raw_strings_across_lines() {
+    let mut scanner = Scanner::new("r\"first\nsecond\" rest");
+    scanner.run();
+    assert_eq!(scanner.tokens[0].span, span(0, 15));
+    assert_eq!(
+        scanner.tokens[1],
+        Token::create_identifier(String::from("rest"), span(16, 20))
+    );
+    let mut scanner = Scanner::new("r#\"unclosed\"");
+    scanner.run();
+    assert_eq!(
+        scanner.errors,
+        vec![(LexicalError::UnterminatedStringLiteral, span(0, 12))]
+    );
+}
+
+#[test]
+fn it_strips_indentation_from_multi_line_strings() {
+    let mut scanner = Scanner::new(
+        "\"\"\"\n    SELECT *\n      FROM users  \n\n    WHERE id = \\{id}\n    \"\"\";",
+    );
+    scanner.run();
+    assert_eq!(
+        scanner.tokens[0],
+        Token {
+            span: span(0, 65),
+            kind: TokenKind::Literal(Literal {
+                kind: LiteralKind::MultiLineStringLiteral,
+                value: String::from("SELECT *\n  FROM users\n\nWHERE id = {id}"),
+                raw: String::from(
+                    "\n    SELECT *\n      FROM users  \n\n    WHERE id = \\{id}\n    "
+                ),
+            })
+        }
+    );
+    assert_eq!(scanner.tokens[1], Token::create_semi_colon(span(65, 66)));
+    assert_eq!(scanner.errors, vec![]);
+}
+
+#[test]
+fn it_keeps_the_last_line_of_a_multi_line_string_with_inline_closing_quotes() {
+    let mut scanner = Scanner::new("\"\"\"\n  one\n    two\"\"\"");
+    scanner.run();
+    assert_eq!(
+        scanner.tokens[0].kind,
+        TokenKind::Literal(Literal {
+            kind: LiteralKind::MultiLineStringLiteral,
+            value: String::from("one\n  two"),
+            raw: String::from("\n  one\n    two"),
+        })
+    );
+}
+
+#[test]
+fn it_reports_errors_in_multi_line_strings_on_their_line() {
+    let mut scanner = Scanner::new("\"\"\"text\n  a\\qb\n  \"\"\"");
+    scanner.run();
+    assert_eq!(
+        scanner.errors,
+        vec![
+            (LexicalError::MultiLineStringStart, span(3, 7)),
+            (LexicalError::UnknownEscapeSequence('q'), span(11, 13)),
+        ]
+    );
+}
+
+#[test]
+fn it_scans_interpolated_strings() {
+    let mut scanner = Scanner::new("\"Hello, {name}!\"");
+    scanner.run();
+    assert_eq!(
+        scanner.tokens,
+        vec![
+            Token {
+                span: span(0, 9),
+                kind: TokenKind::Literal(Literal {
+                    kind: LiteralKind::TemplateHead,
+                    value: String::from("Hello, "),
+                    raw: String::from("Hello, "),
+                })
+            },
+            Token {
+                span: span(9, 13),
+                kind: TokenKind::Identifier(TokenIdentifier {
+                    value: String::from("name")
+                })
+            },
+            Token {
+                span: span(13, 16),
+                kind: TokenKind::Literal(Literal {
+                    kind: LiteralKind::TemplateTail,
+                    value: String::from("!"),
+                    raw: String::from("!"),
+                })
+            },
+            Token {
+                span: span(13, 16),
+                kind: TokenKind::EOF
+            }
+        ]
+    )
+}
+
+#[test]
+fn it_scans_nested_interpolated_strings() {
+    let mut scanner = Scanner::new("\"{a} and {\"{b}\"} and \\{c}\"");
+    scanner.run();
+    let kinds: Vec<_> = scanner
+        .tokens
+        .iter()
+        .filter_map(|token| match &token.kind {
+            TokenKind::Literal(Literal { kind, value, .. }) => Some((kind.clone(), value.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (LiteralKind::TemplateHead, ""),
+            (LiteralKind::TemplateMiddle, " and "),
+            (LiteralKind::TemplateHead, ""),
+            (LiteralKind::TemplateTail, ""),
+            (LiteralKind::TemplateTail, " and {c}"),
+        ]
     )
 }
 
@@ -102,9 +370,10 @@ fn it_scans_whole_numbers() {
     assert_eq!(
         scanner.tokens[0],
         Token {
-            span: [[1, 1], [1, 2]],
+            span: span(0, 2),
             kind: TokenKind::Literal(Literal {
                 value: String::from("89"),
+                raw: String::from("89"),
                 kind: LiteralKind::NumericLiteral
             })
         }
@@ -118,10 +387,11 @@ fn it_scans_floating_numbers() {
     assert_eq!(
         scanner.tokens[0],
         Token {
-            span: [[1, 1], [1, 6]],
+            span: span(0, 6),
             kind: TokenKind::Literal(Literal {
                 kind: LiteralKind::NumericLiteral,
-                value: String::from("9.9999")
+                value: String::from("9.9999"),
+                raw: String::from("9.9999"),
             })
         }
     )
@@ -134,9 +404,10 @@ fn it_scans_hexadecimal_numbers() {
     assert_eq!(
         scanner.tokens[0],
         Token {
-            span: [[1, 1], [1, 7]],
+            span: span(0, 7),
             kind: TokenKind::Literal(Literal {
                 value: String::from("0x90384"),
+                raw: String::from("0x90384"),
                 kind: LiteralKind::NumericLiteral,
             })
         }
@@ -150,9 +421,10 @@ fn it_scans_binary_numbers() {
     assert_eq!(
         scanner.tokens[0],
         Token {
-            span: [[1, 1], [1, 7]],
+            span: span(0, 7),
             kind: TokenKind::Literal(Literal {
                 value: String::from("0b10100"),
+                raw: String::from("0b10100"),
                 kind: LiteralKind::NumericLiteral,
             })
         }
@@ -166,9 +438,10 @@ fn it_scans_octal_numbers() {
     assert_eq!(
         scanner.tokens[0],
         Token {
-            span: [[1, 1], [1, 7]],
+            span: span(0, 7),
             kind: TokenKind::Literal(Literal {
                 value: String::from("0o75266"),
+                raw: String::from("0o75266"),
                 kind: LiteralKind::NumericLiteral,
             })
         }
@@ -182,15 +455,115 @@ fn it_scans_octal_exponents() {
     assert_eq!(
         scanner.tokens[0],
         Token {
-            span: [[1, 1], [1, 9]],
+            span: span(0, 9),
             kind: TokenKind::Literal(Literal {
                 value: String::from("0o75266e2"),
+                raw: String::from("0o75266e2"),
                 kind: LiteralKind::NumericLiteral,
             })
         }
     )
 }
 
+#[test]
+fn it_scans_digit_separators_and_signed_exponents() {
+    let mut scanner = Scanner::new("1_000_000 2.5e-3 1E+2 0xFF_FF");
+    scanner.run();
+    let values: Vec<_> = scanner
+        .tokens
+        .iter()
+        .filter_map(|token| match &token.kind {
+            TokenKind::Literal(literal) => Some(literal.value.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(values, vec!["1_000_000", "2.5e-3", "1E+2", "0xFF_FF"]);
+    assert_eq!(scanner.errors, vec![]);
+}
+
+#[test]
+fn it_reports_malformed_numbers() {
+    let cases = [
+        (
+            "0x;",
+            LexicalError::MissingDigits(String::from("0x")),
+            span(0, 2),
+        ),
+        ("1e;", LexicalError::MissingExponent, span(0, 2)),
+        ("2.5e-;", LexicalError::MissingExponent, span(0, 5)),
+        ("0x1.8;", LexicalError::NonDecimalFloat, span(0, 5)),
+        ("0b1e5;", LexicalError::NonDecimalFloat, span(0, 5)),
+        ("0b102;", LexicalError::InvalidDigit('2', 2), span(4, 5)),
+        ("0o78;", LexicalError::InvalidDigit('8', 8), span(3, 4)),
+        ("19.;", LexicalError::MissingFraction, span(2, 3)),
+        ("1_;", LexicalError::MisplacedDigitSeparator, span(1, 2)),
+        ("0x_1;", LexicalError::MisplacedDigitSeparator, span(2, 3)),
+        (
+            "18446744073709551616;",
+            LexicalError::NumberOutOfRange,
+            span(0, 20),
+        ),
+        ("1e400;", LexicalError::NumberOutOfRange, span(0, 5)),
+    ];
+    for (source, error, span) in cases {
+        let mut scanner = Scanner::new(source);
+        scanner.run();
+        assert_eq!(scanner.errors, vec![(error, span)], "{source}");
+    }
+}
+
+#[test]
+fn it_keeps_malformed_numbers_in_one_token() {
+    let mut scanner = Scanner::new("0b1021; 7.;");
+    scanner.run();
+    let values: Vec<_> = scanner
+        .tokens
+        .iter()
+        .filter_map(|token| match &token.kind {
+            TokenKind::Literal(literal) => Some(literal.value.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(values, vec!["0b1021", "7."]);
+    assert_eq!(
+        scanner.errors,
+        vec![
+            (LexicalError::InvalidDigit('2', 2), span(4, 5)),
+            (LexicalError::MissingFraction, span(9, 10)),
+        ]
+    );
+}
+
+#[test]
+fn it_parses_numeric_values() {
+    let mut scanner = Scanner::new("0xFF_FF; 1_000; 2.5e-1; 18446744073709551615;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    let values: Vec<_> = parser
+        .statements
+        .take()
+        .into_iter()
+        .map(|statement| match statement {
+            Statement::ExpressionStatement(ExpressionStatement {
+                expression: Expression::NumericExpression(number),
+                ..
+            }) => number.value,
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            NumericValue::UnsignedInt(0xFFFF),
+            NumericValue::UnsignedInt(1000),
+            NumericValue::Float(0.25),
+            NumericValue::UnsignedInt(u64::MAX),
+        ]
+    );
+}
+
 #[test]
 fn it_scans_injunction() {
     let mut scanner = Scanner::new("@public");
@@ -198,7 +571,7 @@ fn it_scans_injunction() {
     assert_eq!(
         scanner.tokens[0],
         Token {
-            span: [[1, 1], [1, 7]],
+            span: span(0, 7),
             kind: TokenKind::Keyword(Keyword::Injunction(Injunction::Public))
         }
     )
@@ -211,9 +584,10 @@ fn it_scans_characters() {
     assert_eq!(
         scanner.tokens[0],
         Token {
-            span: [[1, 1], [1, 3]],
+            span: span(0, 3),
             kind: TokenKind::Literal(Literal {
                 value: String::from("h"),
+                raw: String::from("h"),
                 kind: LiteralKind::CharacterLiteral
             })
         }
@@ -228,31 +602,31 @@ fn it_scans_brackets() {
         scanner.tokens,
         vec![
             Token {
-                span: [[1, 1], [1, 2]],
+                span: span(0, 1),
                 kind: TokenKind::Punctuation(Punctuation::Bracket(BracketKind::LeftCurly))
             },
             Token {
-                span: [[1, 2], [1, 3]],
+                span: span(1, 2),
                 kind: TokenKind::Punctuation(Punctuation::Bracket(BracketKind::RightCurly))
             },
             Token {
-                span: [[1, 3], [1, 4]],
+                span: span(2, 3),
                 kind: TokenKind::Punctuation(Punctuation::Bracket(BracketKind::LeftSquare))
             },
             Token {
-                span: [[1, 4], [1, 5]],
+                span: span(3, 4),
                 kind: TokenKind::Punctuation(Punctuation::Bracket(BracketKind::RightSquare))
             },
             Token {
-                span: [[1, 5], [1, 6]],
+                span: span(4, 5),
                 kind: TokenKind::Punctuation(Punctuation::Bracket(BracketKind::LeftParenthesis))
             },
             Token {
-                span: [[1, 6], [1, 6]],
+                span: span(5, 6),
                 kind: TokenKind::Punctuation(Punctuation::Bracket(BracketKind::RightParenthesis))
             },
             Token {
-                span: [[1, 6], [1, 6]],
+                span: span(5, 6),
                 kind: TokenKind::EOF
             }
         ]
@@ -267,23 +641,23 @@ fn it_scans_identifiers_and_keywords() {
         scanner.tokens,
         vec![
             Token {
-                span: [[1, 1], [1, 5]],
+                span: span(0, 4),
                 kind: TokenKind::Identifier(TokenIdentifier {
                     value: String::from("name")
                 })
             },
             Token {
-                span: [[1, 6], [1, 8]],
+                span: span(5, 7),
                 kind: TokenKind::Keyword(Keyword::In)
             },
             Token {
-                span: [[1, 9], [1, 12]],
+                span: span(8, 12),
                 kind: TokenKind::Identifier(TokenIdentifier {
                     value: String::from("word")
                 })
             },
             Token {
-                span: [[1, 9], [1, 12]],
+                span: span(8, 12),
                 kind: TokenKind::EOF
             }
         ]
@@ -298,17 +672,17 @@ fn it_scans_operators() {
         scanner.tokens,
         vec![
             Token {
-                span: [[1, 1], [1, 5]],
+                span: span(0, 4),
                 kind: TokenKind::Identifier(TokenIdentifier {
                     value: String::from("name")
                 })
             },
             Token {
-                span: [[1, 5], [1, 6]],
+                span: span(4, 6),
                 kind: TokenKind::Operator(Operator::Increment)
             },
             Token {
-                span: [[1, 5], [1, 6]],
+                span: span(4, 6),
                 kind: TokenKind::EOF
             }
         ]
@@ -323,43 +697,45 @@ fn it_scans_operators_2() {
         scanner.tokens,
         vec![
             Token {
-                span: [[1, 1], [1, 2]],
+                span: span(0, 1),
                 kind: TokenKind::Literal(Literal {
                     kind: LiteralKind::NumericLiteral,
-                    value: String::from("2")
+                    value: String::from("2"),
+                    raw: String::from("2"),
                 })
             },
             Token {
-                span: [[1, 2], [1, 3]],
+                span: span(1, 2),
                 kind: TokenKind::Operator(Operator::Add)
             },
             Token {
-                span: [[1, 3], [1, 4]],
+                span: span(2, 3),
                 kind: TokenKind::Literal(Literal {
                     kind: LiteralKind::NumericLiteral,
-                    value: String::from("4")
+                    value: String::from("4"),
+                    raw: String::from("4"),
                 })
             },
             Token {
-                span: [[1, 4], [1, 5]],
+                span: span(3, 4),
                 kind: TokenKind::Operator(Operator::Add)
             },
             Token {
-                span: [[1, 6], [1, 12]],
+                span: span(5, 11),
                 kind: TokenKind::Identifier(TokenIdentifier {
                     value: String::from("Number")
                 })
             },
             Token {
-                span: [[1, 12], [1, 13]],
+                span: span(11, 12),
                 kind: TokenKind::Punctuation(Punctuation::Bracket(BracketKind::LeftParenthesis))
             },
             Token {
-                span: [[1, 13], [1, 13]],
+                span: span(12, 13),
                 kind: TokenKind::Punctuation(Punctuation::Bracket(BracketKind::RightParenthesis))
             },
             Token {
-                span: [[1, 13], [1, 13]],
+                span: span(12, 13),
                 kind: TokenKind::EOF
             }
         ]
@@ -374,11 +750,11 @@ fn it_scans_unknown_token() {
         scanner.tokens,
         vec![
             Token {
-                span: [[1, 1], [1, 1]],
+                span: span(0, 1),
                 kind: TokenKind::Invalid(String::from("`"))
             },
             Token {
-                span: [[1, 1], [1, 1]],
+                span: span(0, 1),
                 kind: TokenKind::EOF
             }
         ],
@@ -395,7 +771,7 @@ fn it_parses_numeric_literal() {
     let statements = parser.statements.take();
     assert_eq!(
         statements[0],
-        Statement::create_expr_stmnt(Expression::create_num_expr("2", [[1, 1], [1, 2]]))
+        Statement::create_expr_stmnt(Expression::create_num_expr("2", span(0, 1)))
     )
 }
 
@@ -409,7 +785,7 @@ fn it_parses_boolean_literal() {
     let statements = parser.statements.take();
     assert_eq!(
         statements[0],
-        Statement::create_expr_stmnt(Expression::create_bool_expr("true", [[1, 1], [1, 5]]))
+        Statement::create_expr_stmnt(Expression::create_bool_expr("true", span(0, 4)))
     )
 }
 
@@ -425,11 +801,108 @@ fn it_parses_string_literal() {
         statements[0],
         Statement::create_expr_stmnt(Expression::create_str_expr(
             "This is a string.",
-            [[1, 1], [1, 19]]
+            span(0, 19)
         ))
     )
 }
 
+#[test]
+fn it_parses_raw_and_multi_line_strings() {
+    let mut scanner = Scanner::new("r\"a\\b\";\n\"\"\"\n  c\n  \"\"\";");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    let statements = parser.statements.take();
+    assert_eq!(
+        statements,
+        vec![
+            Statement::create_expr_stmnt(Expression::create_str_expr("a\\b", span(0, 6))),
+            Statement::create_expr_stmnt(Expression::create_str_expr("c", span(8, 21))),
+        ]
+    );
+}
+
+#[test]
+fn it_parses_interpolated_string() {
+    let mut scanner = Scanner::new("\"Hello, {name}!\";");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    let statements = parser.statements.take();
+    assert_eq!(
+        statements[0],
+        Statement::create_expr_stmnt(Expression::create_template_str_expr(
+            vec![
+                TemplatePart::Text(TextString {
+                    value: "Hello, ".to_string(),
+                    span: span(1, 8)
+                }),
+                TemplatePart::Expression(Expression::create_ident_expr("name", span(9, 13))),
+                TemplatePart::Text(TextString {
+                    value: "!".to_string(),
+                    span: span(14, 15)
+                }),
+            ],
+            span(0, 16)
+        ))
+    )
+}
+
+#[test]
+fn it_parses_interpolated_string_with_nested_expressions() {
+    let mut scanner = Scanner::new("\"{a + b}{\"x{c}\"}\" + d;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    let statements = parser.statements.take();
+    assert_eq!(
+        statements[0],
+        Statement::create_expr_stmnt(Expression::create_bin_expr(
+            Expression::create_template_str_expr(
+                vec![
+                    TemplatePart::Expression(Expression::create_bin_expr(
+                        Expression::create_ident_expr("a", span(2, 3)),
+                        Operator::Add,
+                        Expression::create_ident_expr("b", span(6, 7)),
+                    )),
+                    TemplatePart::Expression(Expression::create_template_str_expr(
+                        vec![
+                            TemplatePart::Text(TextString {
+                                value: "x".to_string(),
+                                span: span(10, 11)
+                            }),
+                            TemplatePart::Expression(Expression::create_ident_expr(
+                                "c",
+                                span(12, 13)
+                            )),
+                        ],
+                        span(9, 15)
+                    )),
+                ],
+                span(0, 17)
+            ),
+            Operator::Add,
+            Expression::create_ident_expr("d", span(20, 21))
+        ))
+    )
+}
+
+#[test]
+fn it_reports_unclosed_interpolation() {
+    let mut scanner = Scanner::new("\"Hello, {name;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.diagnostics.take(),
+        vec![(SyntaxError::ExpectedRCurly, span(13, 14))]
+    )
+}
+
 #[test]
 fn it_parses_binary_expression() {
     let mut scanner = Scanner::new("2+2;");
@@ -441,9 +914,9 @@ fn it_parses_binary_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_bin_expr(
-            Expression::create_num_expr("2", [[1, 1], [1, 2]]),
-            &Operator::Add,
-            Expression::create_num_expr("2", [[1, 3], [1, 4]])
+            Expression::create_num_expr("2", span(0, 1)),
+            Operator::Add,
+            Expression::create_num_expr("2", span(2, 3))
         ))
     )
 }
@@ -460,12 +933,12 @@ fn it_parses_continuous_binary_expression() {
         statements[0],
         Statement::create_expr_stmnt(Expression::create_bin_expr(
             Expression::create_bin_expr(
-                Expression::create_num_expr("2", [[1, 1], [1, 2]]),
-                &Operator::Add,
-                Expression::create_num_expr("2", [[1, 5], [1, 6]])
+                Expression::create_num_expr("2", span(0, 1)),
+                Operator::Add,
+                Expression::create_num_expr("2", span(4, 5))
             ),
-            &Operator::Add,
-            Expression::create_num_expr("2", [[1, 9], [1, 10]])
+            Operator::Add,
+            Expression::create_num_expr("2", span(8, 9))
         ))
     )
 }
@@ -481,12 +954,12 @@ fn it_parses_binary_expression_with_multiple_operands() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_bin_expr(
-            Expression::create_num_expr("2", [[1, 1], [1, 2]]),
-            &Operator::Add,
+            Expression::create_num_expr("2", span(0, 1)),
+            Operator::Add,
             Expression::create_bin_expr(
-                Expression::create_num_expr("4", [[1, 5], [1, 6]]),
-                &Operator::Multiply,
-                Expression::create_num_expr("5", [[1, 9], [1, 10]])
+                Expression::create_num_expr("4", span(4, 5)),
+                Operator::Multiply,
+                Expression::create_num_expr("5", span(8, 9))
             ),
         ))
     )
@@ -502,10 +975,7 @@ fn it_parses_identifier() {
     let statements = parser.statements.take();
     assert_eq!(
         statements[0],
-        Statement::create_expr_stmnt(Expression::create_ident_expr(
-            "identifier_1",
-            [[1, 1], [1, 13]]
-        ))
+        Statement::create_expr_stmnt(Expression::create_ident_expr("identifier_1", span(0, 12)))
     )
 }
 
@@ -520,9 +990,9 @@ fn it_parses_call_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_call_expr(
-            Expression::create_ident_expr("doStuff", [[1, 1], [1, 8]]),
+            Expression::create_ident_expr("doStuff", span(0, 7)),
             vec![],
-            [1, 10]
+            9
         ))
     )
 }
@@ -538,12 +1008,12 @@ fn it_parses_call_expression_with_arguments() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_call_expr(
-            Expression::create_ident_expr("doStuff", [[1, 1], [1, 8]]),
+            Expression::create_ident_expr("doStuff", span(0, 7)),
             vec![
-                Expression::create_ident_expr("argument1", [[1, 9], [1, 18]]),
-                Expression::create_ident_expr("argument2", [[1, 20], [1, 29]])
+                Expression::create_ident_expr("argument1", span(8, 17)),
+                Expression::create_ident_expr("argument2", span(19, 28))
             ],
-            [1, 30]
+            29
         ))
     )
 }
@@ -559,8 +1029,8 @@ fn it_parses_dot_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_dot_expr(
-            Expression::create_ident_expr("object", [[1, 1], [1, 7]]),
-            Expression::create_ident_expr("property", [[1, 8], [1, 16]])
+            Expression::create_ident_expr("object", span(0, 6)),
+            Expression::create_ident_expr("property", span(7, 15))
         ))
     )
 }
@@ -576,8 +1046,8 @@ fn it_parses_namespace_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_namespace_expr(
-            Expression::create_ident_expr("object", [[1, 1], [1, 7]]),
-            Expression::create_ident_expr("property", [[1, 9], [1, 17]])
+            Expression::create_ident_expr("object", span(0, 6)),
+            Expression::create_ident_expr("property", span(8, 16))
         ))
     )
 }
@@ -593,9 +1063,9 @@ fn it_parses_index_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_index_expr(
-            Expression::create_ident_expr("accessor", [[1, 1], [1, 9]]),
-            Expression::create_ident_expr("property", [[1, 10], [1, 18]]),
-            [1, 19]
+            Expression::create_ident_expr("accessor", span(0, 8)),
+            Expression::create_ident_expr("property", span(9, 17)),
+            18
         ))
     )
 }
@@ -615,23 +1085,23 @@ fn it_parses_complex_expression_1() {
                 Expression::create_call_expr(
                     Expression::create_dot_expr(
                         Expression::create_call_expr(
-                            Expression::create_ident_expr("doStuff", [[1, 1], [1, 8]]),
+                            Expression::create_ident_expr("doStuff", span(0, 7)),
                             vec![Expression::create_bin_expr(
-                                Expression::create_ident_expr("arg1", [[1, 9], [1, 13]]),
-                                &Operator::Add,
-                                Expression::create_ident_expr("arg2", [[1, 16], [1, 20]])
+                                Expression::create_ident_expr("arg1", span(8, 12)),
+                                Operator::Add,
+                                Expression::create_ident_expr("arg2", span(15, 19))
                             )],
-                            [1, 21]
+                            20
                         ),
-                        Expression::create_ident_expr("with", [[1, 22], [1, 26]])
+                        Expression::create_ident_expr("with", span(21, 25))
                     ),
                     vec![],
-                    [1, 28]
+                    27
                 ),
-                Expression::create_ident_expr("anArray", [[1, 29], [1, 36]])
+                Expression::create_ident_expr("anArray", span(28, 35))
             ),
-            Expression::create_ident_expr("index", [[1, 37], [1, 42]]),
-            [1, 43]
+            Expression::create_ident_expr("index", span(36, 41)),
+            42
         ))
     )
 }
@@ -648,12 +1118,12 @@ fn it_parses_unary_expression() {
         statements[0],
         Statement::create_expr_stmnt(Expression::create_bin_expr(
             Expression::create_unary_expr(
-                [1, 1],
-                &Operator::Subtract,
-                Expression::create_num_expr("2", [[1, 2], [1, 3]])
+                0,
+                Operator::Subtract,
+                Expression::create_num_expr("2", span(1, 2))
             ),
-            &Operator::Add,
-            Expression::create_num_expr("3", [[1, 6], [1, 7]])
+            Operator::Add,
+            Expression::create_num_expr("3", span(5, 6))
         ))
     )
 }
@@ -669,12 +1139,12 @@ fn it_parses_range_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_call_expr(
-            Expression::create_ident_expr("psrandom", [[1, 1], [1, 9]]),
+            Expression::create_ident_expr("psrandom", span(0, 8)),
             vec![Expression::create_range_expr(
-                Expression::create_num_expr("2", [[1, 10], [1, 11]]),
-                Expression::create_num_expr("9", [[1, 13], [1, 14]])
+                Expression::create_num_expr("2", span(9, 10)),
+                Expression::create_num_expr("9", span(12, 13))
             )],
-            [1, 15]
+            14
         ))
     )
 }
@@ -690,9 +1160,9 @@ fn it_parses_logical_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_logical_expr(
-            Expression::create_ident_expr("is_false", [[1, 1], [1, 9]]),
-            &Operator::LogicalOr,
-            Expression::create_ident_expr("is_true", [[1, 13], [1, 20]])
+            Expression::create_ident_expr("is_false", span(0, 8)),
+            Operator::LogicalOr,
+            Expression::create_ident_expr("is_true", span(12, 19))
         ))
     )
 }
@@ -708,16 +1178,16 @@ fn it_parses_ternary_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_ternary_expr(
-            Expression::create_ident_expr("is_true", [[1, 1], [1, 8]]),
+            Expression::create_ident_expr("is_true", span(0, 7)),
             Expression::create_call_expr(
-                Expression::create_ident_expr("doStuff", [[1, 11], [1, 18]]),
+                Expression::create_ident_expr("doStuff", span(10, 17)),
                 vec![],
-                [1, 20]
+                19
             ),
             Expression::create_call_expr(
-                Expression::create_ident_expr("doOtherStuff", [[1, 23], [1, 35]]),
+                Expression::create_ident_expr("doOtherStuff", span(22, 34)),
                 vec![],
-                [1, 37]
+                36
             )
         ))
     )
@@ -737,40 +1207,37 @@ fn it_parses_nested_ternary_expression() {
         statements[0],
         Statement::create_expr_stmnt(Expression::create_ternary_expr(
             Expression::create_bin_expr(
-                Expression::create_ident_expr("age", [[1, 1], [1, 4]]),
-                &Operator::GreaterThanOrEquals,
-                Expression::create_num_expr("21", [[1, 8], [1, 10]])
+                Expression::create_ident_expr("age", span(0, 3)),
+                Operator::GreaterThanOrEquals,
+                Expression::create_num_expr("21", span(7, 9))
             ),
             Expression::create_call_expr(
-                Expression::create_ident_expr("serveDrink", [[1, 13], [1, 23]]),
+                Expression::create_ident_expr("serveDrink", span(12, 22)),
                 vec![],
-                [1, 25]
+                24
             ),
             Expression::create_ternary_expr(
                 Expression::create_bin_expr(
-                    Expression::create_ident_expr("age", [[1, 28], [1, 31]]),
-                    &Operator::GreaterThan,
-                    Expression::create_num_expr("18", [[1, 34], [1, 36]])
+                    Expression::create_ident_expr("age", span(27, 30)),
+                    Operator::GreaterThan,
+                    Expression::create_num_expr("18", span(33, 35))
                 ),
                 Expression::create_call_expr(
                     Expression::create_dot_expr(
                         Expression::create_call_expr(
-                            Expression::create_ident_expr("collectBribe", [[1, 39], [1, 51]]),
+                            Expression::create_ident_expr("collectBribe", span(38, 50)),
                             vec![],
-                            [1, 53]
+                            52
                         ),
-                        Expression::create_ident_expr("then", [[1, 54], [1, 58]])
+                        Expression::create_ident_expr("then", span(53, 57))
                     ),
-                    vec![Expression::create_ident_expr(
-                        "serveDrink",
-                        [[1, 59], [1, 69]]
-                    )],
-                    [1, 70]
+                    vec![Expression::create_ident_expr("serveDrink", span(58, 68))],
+                    69
                 ),
                 Expression::create_call_expr(
-                    Expression::create_ident_expr("reject", [[1, 73], [1, 79]]),
+                    Expression::create_ident_expr("reject", span(72, 78)),
                     vec![],
-                    [1, 81]
+                    80
                 )
             )
         ))
@@ -788,9 +1255,9 @@ fn it_parses_assignment_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_assign_expr(
-            Expression::create_ident_expr("name", [[1, 1], [1, 5]]),
-            &Operator::Assign,
-            Expression::create_str_expr("sefunmi", [[1, 8], [1, 16]])
+            Expression::create_ident_expr("name", span(0, 4)),
+            Operator::Assign,
+            Expression::create_str_expr("sefunmi", span(7, 16))
         ))
     );
 }
@@ -806,17 +1273,89 @@ fn it_parses_nested_assignment_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::create_assign_expr(
-            Expression::create_ident_expr("variable", [[1, 1], [1, 9]]),
-            &Operator::AddAssign,
+            Expression::create_ident_expr("variable", span(0, 8)),
+            Operator::AddAssign,
             Expression::create_assign_expr(
-                Expression::create_ident_expr("value", [[1, 13], [1, 18]]),
-                &Operator::Assign,
-                Expression::create_ident_expr("value2", [[1, 21], [1, 27]])
+                Expression::create_ident_expr("value", span(12, 17)),
+                Operator::Assign,
+                Expression::create_ident_expr("value2", span(20, 26))
             )
         ))
     )
 }
 
+#[test]
+fn it_parses_every_compound_assignment() {
+    for info in OPERATORS {
+        if info.kind != OperatorKind::Assignment {
+            continue;
+        }
+        let source = format!("a {} b;", info.symbol);
+        let mut scanner = Scanner::new(&source);
+        scanner.run();
+        let provider = Provider { scanner, index: 0 };
+        let parser = Parser::from(provider);
+        parser.parse();
+        let start = 3 + info.symbol.len();
+        assert_eq!(
+            parser.statements.take(),
+            vec![Statement::create_expr_stmnt(
+                Expression::create_assign_expr(
+                    Expression::create_ident_expr("a", span(0, 1)),
+                    info.operator.clone(),
+                    Expression::create_ident_expr("b", span(start, start + 1))
+                )
+            )],
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn it_parses_right_shift_and_power() {
+    let mut scanner = Scanner::new("a >> b ** c;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.statements.take()[0],
+        Statement::create_expr_stmnt(Expression::create_bin_expr(
+            Expression::create_ident_expr("a", span(0, 1)),
+            Operator::BitwiseRightShift,
+            Expression::create_bin_expr(
+                Expression::create_ident_expr("b", span(5, 6)),
+                Operator::PowerOf,
+                Expression::create_ident_expr("c", span(10, 11))
+            )
+        ))
+    )
+}
+
+#[test]
+fn it_keeps_the_operator_table_consistent() {
+    for info in OPERATORS.iter().filter(|info| info.is_token()) {
+        let operator = Operator::from(info.symbol);
+        assert_eq!(operator, info.operator);
+        if info.symbol != "^" {
+            assert_eq!(operator.to_string(), info.symbol);
+        }
+    }
+    assert_eq!(Operator::Remainder.to_string(), "%");
+    assert_eq!(Operator::from("^"), Operator::PowerOf);
+    assert_eq!(Operator::from("||="), Operator::LogicalOrAssign);
+    assert_eq!(Operator::AddAssign.compound_operator(), Some(Operator::Add));
+    assert_eq!(
+        Operator::PowerOfAssign.compound_operator(),
+        Some(Operator::PowerOf)
+    );
+    assert_eq!(
+        Operator::BitwiseRightShiftAssign.compound_operator(),
+        Some(Operator::BitwiseRightShift)
+    );
+    assert_eq!(Operator::Assign.compound_operator(), None);
+}
+
 #[test]
 fn it_parses_grouped_expression() {
     let mut scanner = Scanner::new("( 2 + 2 ) * 8;");
@@ -829,16 +1368,145 @@ fn it_parses_grouped_expression() {
         statements[0],
         Statement::create_expr_stmnt(Expression::create_bin_expr(
             Expression::create_bin_expr(
-                Expression::create_num_expr("2", [[1, 3], [1, 4]]),
-                &Operator::Add,
-                Expression::create_num_expr("2", [[1, 7], [1, 8]])
+                Expression::create_num_expr("2", span(2, 3)),
+                Operator::Add,
+                Expression::create_num_expr("2", span(6, 7))
             ),
-            &Operator::Multiply,
-            Expression::create_num_expr("8", [[1, 13], [1, 14]])
+            Operator::Multiply,
+            Expression::create_num_expr("8", span(12, 13))
         ))
     )
 }
 
+/// Creates an identifier expression starting at the given byte offset.
+fn ident(value: &str, start: usize) -> Expression {
+    Expression::create_ident_expr(value, span(start, start + value.len()))
+}
+
+#[test]
+fn it_parses_operators_by_associativity() {
+    let mut scanner = Scanner::new("a - b - c; a ** b ** c; a = b = c;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.statements.take(),
+        vec![
+            Statement::create_expr_stmnt(Expression::create_bin_expr(
+                Expression::create_bin_expr(ident("a", 0), Operator::Subtract, ident("b", 4)),
+                Operator::Subtract,
+                ident("c", 8)
+            )),
+            Statement::create_expr_stmnt(Expression::create_bin_expr(
+                ident("a", 11),
+                Operator::PowerOf,
+                Expression::create_bin_expr(ident("b", 16), Operator::PowerOf, ident("c", 21))
+            )),
+            Statement::create_expr_stmnt(Expression::create_assign_expr(
+                ident("a", 24),
+                Operator::Assign,
+                Expression::create_assign_expr(ident("b", 28), Operator::Assign, ident("c", 32))
+            )),
+        ]
+    )
+}
+
+#[test]
+fn it_parses_prefix_operators_by_precedence() {
+    let mut scanner = Scanner::new("-a * b; -a ** b; !a.b(c);");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.statements.take(),
+        vec![
+            Statement::create_expr_stmnt(Expression::create_bin_expr(
+                Expression::create_unary_expr(0, Operator::Subtract, ident("a", 1)),
+                Operator::Multiply,
+                ident("b", 5)
+            )),
+            Statement::create_expr_stmnt(Expression::create_unary_expr(
+                8,
+                Operator::Subtract,
+                Expression::create_bin_expr(ident("a", 9), Operator::PowerOf, ident("b", 14))
+            )),
+            Statement::create_expr_stmnt(Expression::create_unary_expr(
+                17,
+                Operator::LogicalNot,
+                Expression::create_call_expr(
+                    Expression::create_dot_expr(ident("a", 18), ident("b", 20)),
+                    vec![ident("c", 22)],
+                    24
+                )
+            )),
+        ]
+    )
+}
+
+#[test]
+fn it_parses_comparisons_ranges_and_nested_groups() {
+    let mut scanner = Scanner::new("a < b == c; a..b + c; ((a + b)) * c;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.statements.take(),
+        vec![
+            Statement::create_expr_stmnt(Expression::create_bin_expr(
+                Expression::create_bin_expr(ident("a", 0), Operator::LessThan, ident("b", 4)),
+                Operator::Equals,
+                ident("c", 9)
+            )),
+            Statement::create_expr_stmnt(Expression::create_range_expr(
+                ident("a", 12),
+                Expression::create_bin_expr(ident("b", 15), Operator::Add, ident("c", 19))
+            )),
+            Statement::create_expr_stmnt(Expression::create_bin_expr(
+                Expression::create_bin_expr(ident("a", 24), Operator::Add, ident("b", 28)),
+                Operator::Multiply,
+                ident("c", 34)
+            )),
+        ]
+    )
+}
+
+#[test]
+fn it_parses_ternary_inside_assignment() {
+    let mut scanner = Scanner::new("x = a ? b : c ? d : e;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.statements.take()[0],
+        Statement::create_expr_stmnt(Expression::create_assign_expr(
+            ident("x", 0),
+            Operator::Assign,
+            Expression::create_ternary_expr(
+                ident("a", 4),
+                ident("b", 8),
+                Expression::create_ternary_expr(ident("c", 12), ident("d", 16), ident("e", 20))
+            )
+        ))
+    )
+}
+
+#[test]
+fn it_reports_unclosed_group() {
+    let mut scanner = Scanner::new("(a + b;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.diagnostics.take(),
+        vec![(SyntaxError::ExpectedRParen, span(6, 7))]
+    )
+}
+
 #[test]
 fn it_parses_if_statement() {
     let mut scanner = Scanner::new(
@@ -857,24 +1525,24 @@ fn it_parses_if_statement() {
     assert_eq!(
         statements[0],
         Statement::IfStatement(IfStatement {
-            test: Expression::create_ident_expr("condition", [[1, 10], [1, 19]]),
+            test: Expression::create_ident_expr("condition", span(9, 18)),
             body: Box::new(Statement::BlockStatement(Block {
                 body: vec![Statement::create_expr_stmnt(Expression::create_call_expr(
-                    Expression::create_ident_expr("doStuff", [[2, 9], [2, 16]]),
+                    Expression::create_ident_expr("doStuff", span(31, 38)),
                     vec![],
-                    [2, 18]
+                    40
                 ))],
-                span: [[1, 21], [3, 6]]
+                span: span(20, 47)
             })),
             alternate: Some(Box::new(Statement::BlockStatement(Block {
                 body: vec![Statement::create_expr_stmnt(Expression::create_call_expr(
-                    Expression::create_ident_expr("doOtherStuff", [[4, 9], [4, 21]]),
+                    Expression::create_ident_expr("doOtherStuff", span(63, 75)),
                     vec![],
-                    [4, 23]
+                    77
                 ))],
-                span: [[3, 12], [5, 5]]
+                span: span(53, 84)
             }))),
-            span: [[1, 6], [5, 5]]
+            span: span(5, 84)
         })
     )
 }
@@ -890,20 +1558,20 @@ fn it_parses_if_statement_without_block() {
     assert_eq!(
         statements[0],
         Statement::IfStatement(IfStatement {
-            test: Expression::create_ident_expr("condition", [[1, 5], [1, 14]]),
+            test: Expression::create_ident_expr("condition", span(4, 13)),
             body: Box::new(Statement::create_expr_stmnt(Expression::create_call_expr(
-                Expression::create_ident_expr("doStuff", [[1, 16], [1, 23]]),
+                Expression::create_ident_expr("doStuff", span(15, 22)),
                 vec![],
-                [1, 25]
+                24
             ))),
             alternate: Some(Box::new(Statement::create_expr_stmnt(
                 Expression::create_call_expr(
-                    Expression::create_ident_expr("doOtherStuff", [[1, 32], [1, 44]]),
+                    Expression::create_ident_expr("doOtherStuff", span(31, 43)),
                     vec![],
-                    [1, 46]
+                    45
                 )
             ))),
-            span: [[1, 1], [1, 46]]
+            span: span(0, 45)
         })
     )
 }
@@ -919,14 +1587,14 @@ fn it_parses_if_statement_without_else() {
     assert_eq!(
         statements[0],
         Statement::IfStatement(IfStatement {
-            test: Expression::create_ident_expr("condition", [[1, 5], [1, 14]]),
+            test: Expression::create_ident_expr("condition", span(4, 13)),
             body: Box::new(Statement::create_expr_stmnt(Expression::create_call_expr(
-                Expression::create_ident_expr("doStuff", [[1, 16], [1, 23]]),
+                Expression::create_ident_expr("doStuff", span(15, 22)),
                 vec![],
-                [1, 25]
+                24
             ))),
             alternate: None,
-            span: [[1, 1], [1, 25]]
+            span: span(0, 24)
         })
     )
 }
@@ -942,8 +1610,8 @@ fn it_parses_print_statement() {
     assert_eq!(
         statements[0],
         Statement::PrintLnStatement(PrintLnStatement {
-            argument: Expression::create_str_expr("Hello, world!", [[1, 9], [1, 23]]),
-            span: [[1, 1], [1, 24]]
+            argument: Expression::create_str_expr("Hello, world!", span(8, 23)),
+            span: span(0, 24)
         })
     )
 }
@@ -959,8 +1627,8 @@ fn it_parses_prepend_statement() {
     assert_eq!(
         statements[0],
         Statement::PrependStatement(PrependStatement {
-            source: Expression::create_str_expr("./example.peb", [[1, 10], [1, 24]]),
-            span: [[1, 1], [1, 25]]
+            source: Expression::create_str_expr("./example.peb", span(9, 24)),
+            span: span(0, 25)
         })
     )
 }
@@ -978,9 +1646,9 @@ fn it_parses_test_block() {
         Statement::TestBlock(TestBlock {
             body: Block {
                 body: vec![],
-                span: [[1, 8], [1, 9]]
+                span: span(7, 9)
             },
-            span: [[1, 1], [1, 9]]
+            span: span(0, 9)
         })
     )
 }
@@ -996,13 +1664,13 @@ fn it_parses_while_statement() {
     assert_eq!(
         statements[0],
         Statement::WhileLoop(WhileStatement {
-            test: Expression::create_ident_expr("is_true", [[1, 8], [1, 15]]),
+            test: Expression::create_ident_expr("is_true", span(7, 14)),
             body: Box::new(Statement::create_expr_stmnt(Expression::create_call_expr(
-                Expression::create_ident_expr("doStuff", [[1, 17], [1, 24]]),
+                Expression::create_ident_expr("doStuff", span(16, 23)),
                 vec![],
-                [1, 26]
+                25
             ))),
-            span: [[1, 1], [1, 26]]
+            span: span(0, 25)
         })
     )
 }
@@ -1018,11 +1686,8 @@ fn it_parses_return_statement() {
     assert_eq!(
         statements[0],
         Statement::ReturnStatement(ReturnStatement {
-            argument: Some(Expression::create_str_expr(
-                "Hello, world!",
-                [[1, 8], [1, 22]]
-            )),
-            span: [[1, 1], [1, 23]]
+            argument: Some(Expression::create_str_expr("Hello, world!", span(7, 22))),
+            span: span(0, 23)
         })
     )
 }
@@ -1039,7 +1704,7 @@ fn it_parses_return_statement_without_argument() {
         statements[0],
         Statement::ReturnStatement(ReturnStatement {
             argument: None,
-            span: [[1, 1], [1, 7]]
+            span: span(0, 7)
         })
     )
 }
@@ -1055,16 +1720,16 @@ fn it_parses_loop_statement() {
     assert_eq!(
         statements[0],
         Statement::LoopStmnt(Loop {
-            constraint: Some(Expression::create_num_expr("3", [[1, 7], [1, 8]])),
+            constraint: Some(Expression::create_num_expr("3", span(6, 7))),
             body: Block {
                 body: vec![Statement::create_expr_stmnt(Expression::create_call_expr(
-                    Expression::create_ident_expr("doStuff", [[1, 12], [1, 19]]),
+                    Expression::create_ident_expr("doStuff", span(11, 18)),
                     vec![],
-                    [1, 21]
+                    20
                 ))],
-                span: [[1, 10], [1, 23]]
+                span: span(9, 23)
             },
-            span: [[1, 1], [1, 23]]
+            span: span(0, 23)
         })
     )
 }
@@ -1077,12 +1742,7 @@ fn it_parses_break_statement() {
     let parser = Parser::from(provider);
     parser.parse();
     let statements = parser.statements.take();
-    assert_eq!(
-        statements[0],
-        Statement::Break(Break {
-            span: [[1, 1], [1, 6]],
-        })
-    )
+    assert_eq!(statements[0], Statement::Break(Break { span: span(0, 6) }))
 }
 
 #[test]
@@ -1097,14 +1757,14 @@ fn it_parses_crash_statement() {
         statements[0],
         Statement::CrashStmnt(CrashStatement {
             argument: Expression::create_call_expr(
-                Expression::create_ident_expr("Error", [[1, 7], [1, 12]]),
+                Expression::create_ident_expr("Error", span(6, 11)),
                 vec![Expression::create_str_expr(
                     "This is an error.",
-                    [[1, 13], [1, 31]]
+                    span(12, 31)
                 )],
-                [1, 33]
+                32
             ),
-            span: [[1, 1], [1, 33]]
+            span: span(0, 33)
         })
     )
 }
@@ -1122,24 +1782,24 @@ fn it_parses_try_recover_block() {
         Statement::TryBlock(TryBlock {
             body: Block {
                 body: vec![],
-                span: [[1, 5], [1, 7]]
+                span: span(4, 6)
             },
             recover: Some(RecoverBlock {
                 params: vec![Parameter {
                     label: None,
                     name: Identifier {
-                        value: "e",
-                        span: [[1, 17], [1, 18]]
+                        value: "e".to_string(),
+                        span: span(16, 17)
                     },
-                    span: [[1, 17], [1, 18]]
+                    span: span(16, 17)
                 }],
-                span: [[1, 8], [1, 21]],
+                span: span(7, 21),
                 body: Block {
                     body: vec![],
-                    span: [[1, 20], [1, 21]]
+                    span: span(19, 21)
                 }
             }),
-            span: [[1, 1], [1, 21]],
+            span: span(0, 21),
         })
     )
 }
@@ -1159,42 +1819,42 @@ fn it_parses_use_import() {
                 Import {
                     collapsed_import: false,
                     imported_name: Identifier {
-                        value: "querySelector",
-                        span: [[1, 7], [1, 20]]
+                        value: "querySelector".to_string(),
+                        span: span(6, 19)
                     },
                     local_name: None,
-                    span: [[1, 7], [1, 20]]
+                    span: span(6, 19)
                 },
                 Import {
                     collapsed_import: false,
                     imported_name: Identifier {
-                        value: "createElement",
-                        span: [[1, 22], [1, 35]]
+                        value: "createElement".to_string(),
+                        span: span(21, 34)
                     },
                     local_name: Some(Identifier {
-                        value: "createDocumentElement",
-                        span: [[1, 39], [1, 60]]
+                        value: "createDocumentElement".to_string(),
+                        span: span(38, 59)
                     }),
-                    span: [[1, 22], [1, 60]]
+                    span: span(21, 59)
                 },
                 Import {
                     collapsed_import: true,
                     imported_name: Identifier {
-                        value: "*",
-                        span: [[1, 62], [1, 63]]
+                        value: "*".to_string(),
+                        span: span(61, 62)
                     },
                     local_name: Some(Identifier {
-                        value: "document",
-                        span: [[1, 67], [1, 75]]
+                        value: "document".to_string(),
+                        span: span(66, 74)
                     }),
-                    span: [[1, 62], [1, 75]]
+                    span: span(61, 74)
                 }
             ],
             source: TextString {
-                value: "pile:document",
-                span: [[1, 82], [1, 96]]
+                value: "pile:document".to_string(),
+                span: span(81, 96)
             },
-            span: [[1, 1], [1, 97]]
+            span: span(0, 97)
         })
     )
 }
@@ -1216,63 +1876,64 @@ fn it_parses_plain_function() {
         statements[0],
         Statement::Function(Function {
             name: Identifier {
-                value: "add",
-                span: [[2, 15], [2, 18]]
+                value: "add".to_string(),
+                span: span(16, 19)
             },
             generic_arguments: None,
             parameters: vec![
                 Parameter {
                     name: Identifier {
-                        value: "x",
-                        span: [[2, 20], [2, 21]]
+                        value: "x".to_string(),
+                        span: span(21, 22)
                     },
                     label: Some(Type::Concrete(ConcreteType {
                         name: Identifier {
-                            value: "Number",
-                            span: [[2, 23], [2, 29]]
+                            value: "Number".to_string(),
+                            span: span(24, 30)
                         },
                         arguments: vec![],
 
-                        span: [[2, 23], [2, 29]]
+                        span: span(24, 30)
                     })),
-                    span: [[2, 20], [2, 30]]
+                    span: span(21, 31)
                 },
                 Parameter {
                     name: Identifier {
-                        value: "y",
-                        span: [[2, 31], [2, 32]]
+                        value: "y".to_string(),
+                        span: span(32, 33)
                     },
                     label: Some(Type::Concrete(ConcreteType {
                         name: Identifier {
-                            value: "Number",
-                            span: [[2, 34], [2, 40]]
+                            value: "Number".to_string(),
+                            span: span(35, 41)
                         },
                         arguments: vec![],
-                        span: [[2, 34], [2, 40]]
+                        span: span(35, 41)
                     })),
-                    span: [[2, 31], [2, 40]]
+                    span: span(32, 41)
                 }
             ],
             return_type: Some(Type::Concrete(ConcreteType {
                 name: Identifier {
-                    value: "Number",
-                    span: [[2, 45], [2, 51]]
+                    value: "Number".to_string(),
+                    span: span(46, 52)
                 },
                 arguments: vec![],
-                span: [[2, 45], [2, 51]]
+                span: span(46, 52)
             })),
             body: Block {
                 body: vec![Statement::ReturnStatement(ReturnStatement {
                     argument: Some(Expression::create_bin_expr(
-                        Expression::create_ident_expr("x", [[3, 16], [3, 17]]),
-                        &Operator::Add,
-                        Expression::create_ident_expr("y", [[3, 20], [3, 21]]),
+                        Expression::create_ident_expr("x", span(70, 71)),
+                        Operator::Add,
+                        Expression::create_ident_expr("y", span(74, 75)),
                     )),
-                    span: [[3, 9], [4, 0]]
+                    span: span(63, 76)
                 })],
-                span: [[2, 52], [4, 5]]
+                span: span(53, 82)
             },
-            span: [[2, 5], [4, 5]]
+            docs: None,
+            span: span(6, 82)
         })
     )
 }
@@ -1288,27 +1949,27 @@ fn it_parses_functional_expression() {
     assert_eq!(
         statements[0],
         Statement::create_expr_stmnt(Expression::CallExpression(CallExpression {
-            callee: Box::new(Expression::create_ident_expr("map", [[1, 1], [1, 4]])),
+            callee: Box::new(Expression::create_ident_expr("map", span(0, 3))),
             arguments: vec![Expression::FnExpression(FnExpression {
                 labels: None,
                 parameters: vec![Parameter {
                     name: Identifier {
-                        value: "item",
-                        span: [[1, 9], [1, 13]]
+                        value: "item".to_string(),
+                        span: span(8, 12)
                     },
                     label: None,
-                    span: [[1, 9], [1, 13]]
+                    span: span(8, 12)
                 }],
                 return_type: None,
                 body: None,
                 implicit_return: Some(Box::new(Expression::create_bin_expr(
-                    Expression::create_ident_expr("item", [[1, 15], [1, 19]]),
-                    &Operator::Multiply,
-                    Expression::create_num_expr("2", [[1, 22], [1, 23]])
+                    Expression::create_ident_expr("item", span(14, 18)),
+                    Operator::Multiply,
+                    Expression::create_num_expr("2", span(21, 22))
                 ),)),
-                span: [[1, 5], [1, 23]]
+                span: span(4, 22)
             })],
-            span: [[1, 1], [1, 25]]
+            span: span(0, 24)
         }))
     )
 }
@@ -1325,23 +1986,24 @@ fn it_parses_let_statement() {
         statements[0],
         Statement::VariableDeclaration(VariableDeclaration {
             name: Identifier {
-                value: "name",
-                span: [[1, 6], [1, 10]]
+                value: "name".to_string(),
+                span: span(5, 9)
             },
             kind: VarKind::Let,
             initializer: Some(Expression::StringExpression(TextString {
-                value: "Akomolafe",
-                span: [[1, 21], [1, 31]]
+                value: "Akomolafe".to_string(),
+                span: span(20, 31)
             })),
             type_label: Some(Type::Concrete(ConcreteType {
                 name: Identifier {
-                    value: "String",
-                    span: [[1, 12], [1, 18]]
+                    value: "String".to_string(),
+                    span: span(11, 17)
                 },
                 arguments: vec![],
-                span: [[1, 12], [1, 18]]
+                span: span(11, 17)
             })),
-            span: [[1, 1], [1, 31]]
+            docs: None,
+            span: span(0, 31)
         })
     )
 }
@@ -1359,40 +2021,41 @@ fn it_parses_const_statement() {
         statements[0],
         Statement::VariableDeclaration(VariableDeclaration {
             name: Identifier {
-                value: "NAMES",
-                span: [[1, 8], [1, 13]]
+                value: "NAMES".to_string(),
+                span: span(7, 12)
             },
             kind: VarKind::Const,
             initializer: Some(Expression::ArrayExpression(ArrayExpression {
                 elements: vec![
                     Expression::StringExpression(TextString {
-                        value: "Akomolafe",
-                        span: [[1, 36], [1, 46]]
+                        value: "Akomolafe".to_string(),
+                        span: span(35, 46)
                     }),
                     Expression::StringExpression(TextString {
-                        value: "Sefunmi",
-                        span: [[1, 49], [1, 57]]
+                        value: "Sefunmi".to_string(),
+                        span: span(48, 57)
                     })
                 ],
-                span: [[1, 35], [1, 59]]
+                span: span(34, 58)
             })),
             type_label: Some(Type::Concrete(ConcreteType {
                 name: Identifier {
-                    value: "ArrayList",
-                    span: [[1, 15], [1, 24]]
+                    value: "ArrayList".to_string(),
+                    span: span(14, 23)
                 },
                 arguments: vec![Type::Concrete(ConcreteType {
                     name: Identifier {
-                        value: "String",
-                        span: [[1, 25], [1, 31]]
+                        value: "String".to_string(),
+                        span: span(24, 30)
                     },
                     arguments: vec![],
-                    span: [[1, 25], [1, 31]]
+                    span: span(24, 30)
                 }),],
-                span: [[1, 15], [1, 32]]
+                span: span(14, 31)
             })),
 
-            span: [[1, 1], [1, 59]]
+            docs: None,
+            span: span(0, 58)
         })
     )
 }
@@ -1445,6 +2108,28 @@ fn it_parses_type_alias() {
     assert_eq!(parser.diagnostics, RefCell::new(vec![]))
 }
 
+#[test]
+fn it_parses_nested_type_arguments() {
+    let mut scanner = Scanner::new("@type Grid = Array<Array<Number>>;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(parser.diagnostics, RefCell::new(vec![]));
+    let statements = parser.statements.take();
+    let Statement::TypeAlias(alias) = &statements[0] else {
+        unreachable!()
+    };
+    let Type::Concrete(outer) = &alias.value else {
+        unreachable!()
+    };
+    assert_eq!(outer.span, span(13, 33));
+    let Type::Concrete(inner) = &outer.arguments[0] else {
+        unreachable!()
+    };
+    assert_eq!(inner.span, span(19, 32));
+}
+
 #[test]
 fn it_parses_interface() {
     let mut scanner = Scanner::new(
@@ -1533,3 +2218,638 @@ fn it_parses_enum() {
     parser.parse();
     assert_eq!(parser.diagnostics, RefCell::new(vec![]))
 }
+
+#[test]
+fn it_recovers_from_errors_inside_function_bodies() {
+    let mut scanner = Scanner::new(
+        "@function main() {\n    @let a = ;\n    b +;\n    c();\n}\n@function other() {}",
+    );
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.diagnostics.take(),
+        vec![
+            (SyntaxError::ExpectedExpression, span(32, 33)),
+            (SyntaxError::ExpectedExpression, span(41, 42))
+        ]
+    );
+    let statements = parser.statements.take();
+    assert_eq!(statements.len(), 2);
+    let Statement::Function(Function { body, .. }) = &statements[0] else {
+        panic!("expected a function, found {:?}", statements[0]);
+    };
+    assert_eq!(body.body[0], Statement::Error(span(23, 33)));
+    assert_eq!(body.body[1], Statement::Error(span(38, 42)));
+    assert!(matches!(body.body[2], Statement::ExpressionStatement(_)));
+}
+
+#[test]
+fn it_recovers_from_errors_inside_class_bodies() {
+    let mut scanner = Scanner::new(
+        "@class A {\n    port: = 1,\n    name: String\n    age: Number,\n    id: Number\n}",
+    );
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.diagnostics.take(),
+        vec![
+            (SyntaxError::ExpectedTypeName, span(21, 22)),
+            (SyntaxError::ExpectedCommaOrRCurly, span(47, 50))
+        ]
+    );
+    let statements = parser.statements.take();
+    let Statement::Class(Class { properties, .. }) = &statements[0] else {
+        panic!("expected a class, found {:?}", statements[0]);
+    };
+    let names = properties
+        .iter()
+        .map(|property| match property {
+            Property::Attribute(Attribute { key, .. }) => key.value.as_str(),
+            property => panic!("expected an attribute, found {:?}", property),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["name", "id"]);
+}
+
+#[test]
+fn it_recovers_from_errors_in_arguments_and_conditions() {
+    let mut scanner = Scanner::new("f(1, +, 3);\nif (x +) {\n    y;\n}");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.diagnostics.take(),
+        vec![
+            (SyntaxError::ExpectedExpression, span(6, 7)),
+            (SyntaxError::ExpectedExpression, span(19, 20))
+        ]
+    );
+    let statements = parser.statements.take();
+    let Statement::ExpressionStatement(ExpressionStatement {
+        expression: Expression::CallExpression(CallExpression { arguments, .. }),
+        ..
+    }) = &statements[0]
+    else {
+        panic!("expected a call, found {:?}", statements[0]);
+    };
+    assert_eq!(arguments.len(), 3);
+    assert_eq!(arguments[1], Expression::Error(span(5, 6)));
+    let Statement::IfStatement(IfStatement { test, body, .. }) = &statements[1] else {
+        panic!("expected an if statement, found {:?}", statements[1]);
+    };
+    assert_eq!(test, &Expression::Error(span(16, 19)));
+    assert!(
+        matches!(body.as_ref(), Statement::BlockStatement(Block { body, .. }) if body.len() == 1)
+    );
+}
+
+#[test]
+fn it_reports_one_error_where_recovery_begins() {
+    let program = parse_source("g(;\nh(1 2);");
+    assert_eq!(
+        program.diagnostics,
+        vec![
+            Diagnostic::Syntax(SyntaxError::ExpectedExpression, span(2, 3)),
+            Diagnostic::Syntax(SyntaxError::ExpectedFunctionArgument, span(8, 9)),
+        ]
+    );
+}
+
+#[test]
+fn it_skips_tokens_that_cannot_begin_a_statement() {
+    let mut scanner = Scanner::new(", else } a;\nb c\n@let d = 1;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    parser.parse();
+    assert_eq!(
+        parser.diagnostics.take(),
+        vec![
+            (SyntaxError::ExpectedExpression, span(0, 1)),
+            (SyntaxError::ExpectedExpression, span(7, 8)),
+            (SyntaxError::ExpectedSemiColon, span(14, 15))
+        ]
+    );
+    let statements = parser.statements.take();
+    assert_eq!(statements.len(), 5);
+    assert!(matches!(statements[2], Statement::ExpressionStatement(_)));
+    assert_eq!(statements[3], Statement::Error(span(12, 15)));
+    assert!(matches!(statements[4], Statement::VariableDeclaration(_)));
+}
+
+#[test]
+fn it_reports_productions_and_tokens_to_the_trace_hook() {
+    let events = Rc::new(RefCell::new(vec![]));
+    let mut scanner = Scanner::new("a;");
+    scanner.run();
+    let provider = Provider { scanner, index: 0 };
+    let parser = Parser::from(provider);
+    let recorder = Rc::clone(&events);
+    parser.trace(move |event| {
+        recorder.borrow_mut().push(match event {
+            TraceEvent::Enter(production) => format!("enter {production}"),
+            TraceEvent::Exit(production) => format!("exit {production}"),
+            TraceEvent::Consume(token) => format!("consume {:?}", token.span.range()),
+        })
+    });
+    parser.parse();
+    assert_eq!(
+        events.take(),
+        vec![
+            "enter statement",
+            "enter expression",
+            "enter operand",
+            "consume 0..1",
+            "exit operand",
+            "exit expression",
+            "consume 1..2",
+            "exit statement"
+        ]
+    );
+}
+
+#[test]
+fn it_parses_source_into_an_owned_program() {
+    let program = parse_source("// The answer.\n@let answer = 42;\n@let x = ;\n@let c = 'ab';");
+    assert_eq!(program.statements().len(), 3);
+    assert!(matches!(
+        program.statements()[0],
+        Statement::VariableDeclaration(_)
+    ));
+    assert_eq!(program.statements()[1], Statement::Error(span(33, 43)));
+    assert_eq!(program.comments.len(), 1);
+    assert_eq!(
+        program.diagnostics,
+        vec![
+            Diagnostic::Syntax(SyntaxError::ExpectedExpression, span(42, 43)),
+            Diagnostic::Lexical(LexicalError::InvalidCharacterCount, span(53, 57))
+        ]
+    );
+    assert!(!program.is_valid());
+}
+
+#[test]
+fn it_keeps_statements_after_the_source_is_dropped() {
+    let program = {
+        let source = String::from("@let name = \"Pebble\";");
+        parse_source(&source)
+    };
+    let Statement::VariableDeclaration(VariableDeclaration {
+        name, initializer, ..
+    }) = &program.statements()[0]
+    else {
+        panic!("expected a variable, found {:?}", program.statements()[0]);
+    };
+    assert_eq!(name.value, "name");
+    assert!(
+        matches!(initializer, Some(Expression::StringExpression(TextString { value, .. })) if *value == "Pebble")
+    );
+    assert!(program.is_valid());
+}
+
+#[test]
+fn it_lowers_statements_into_an_arena() {
+    let arena = parse_source("@let total = count + count;\nprintln \"done\";").to_arena();
+    assert_eq!(arena.roots().len(), 2);
+    let NodeKind::VariableDeclaration {
+        name,
+        initializer: Some(initializer),
+        ..
+    } = arena.kind(arena.roots()[0])
+    else {
+        panic!(
+            "expected a variable, found {:?}",
+            arena.kind(arena.roots()[0])
+        );
+    };
+    assert_eq!(arena.name(*name), Some("total"));
+    assert_eq!(arena.span(*name), span(5, 10));
+    let NodeKind::Binary {
+        operator,
+        left,
+        right,
+    } = arena.kind(*initializer)
+    else {
+        panic!(
+            "expected a binary expression, found {:?}",
+            arena.kind(*initializer)
+        );
+    };
+    assert_eq!(operator, &Operator::Add);
+    // Both operands name the same symbol, but remain separate nodes.
+    assert_ne!(left, right);
+    assert_eq!(arena.kind(*left), arena.kind(*right));
+    assert_eq!(arena.parent(*left), Some(*initializer));
+    assert_eq!(arena.parent(*initializer), Some(arena.roots()[0]));
+    assert_eq!(arena.parent(arena.roots()[0]), None);
+    assert_eq!(arena.children(*initializer), vec![*left, *right]);
+    assert_eq!(arena.interner.len(), 3);
+    assert!(arena.interner.get("done").is_some());
+}
+
+#[test]
+fn it_locates_diagnostics_in_registered_files() {
+    let mut map = SourceMap::new();
+    map.add("first.peb", "@let a = 1;");
+    let file = map.add("second.peb", "@let é = 1 2;");
+    let program = parse_in(&map, file);
+    assert_eq!(program.file, file);
+    let span = program.diagnostics[0].span();
+    assert_eq!(span, Span::new(file, 12, 13));
+    assert_eq!(map.slice(span), "2");
+    assert_eq!(
+        map.start(span, Encoding::Utf16),
+        LineColumn {
+            line: 0,
+            column: 11
+        }
+    );
+}
+
+#[test]
+fn it_registers_parsed_files_in_the_source_map() {
+    let path = std::env::temp_dir().join(format!("pebble-parser-{}.peb", std::process::id()));
+    std::fs::write(&path, "@let a = 1 2;").unwrap();
+    let mut map = SourceMap::new();
+    map.add("first.peb", "@let b = 1;");
+    let program = parse_file(&mut map, &path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_ne!(program.file, FileId::default());
+    assert_eq!(map.get(program.file).name, path.to_string_lossy());
+    assert_eq!(map.slice(program.diagnostics[0].span()), "2");
+}
+
+#[test]
+fn it_keeps_trivia_in_the_lossless_token_stream() {
+    let text = "@let a = 1; // one\r\n\t/* é */ b;\n";
+    let tokens = lossless_tokens(text);
+    let joined = tokens
+        .iter()
+        .flat_map(|token| {
+            token
+                .leading
+                .iter()
+                .map(Trivia::text)
+                .chain(std::iter::once(token.text.as_str()))
+        })
+        .collect::<String>();
+    assert_eq!(joined, text);
+    let b = tokens.iter().find(|token| token.text == "b").unwrap();
+    assert_eq!(
+        b.leading,
+        vec![
+            Trivia::Whitespace(String::from(" ")),
+            Trivia::Comment(CommentKind::Line, String::from("// one")),
+            Trivia::Whitespace(String::from("\r\n\t")),
+            Trivia::Comment(CommentKind::Block, String::from("/* é */")),
+            Trivia::Whitespace(String::from(" ")),
+        ]
+    );
+    let eof = tokens.last().unwrap();
+    assert_eq!(eof.kind, SyntaxKind::Eof);
+    assert_eq!(eof.leading, vec![Trivia::Whitespace(String::from("\n"))]);
+}
+
+#[test]
+fn it_round_trips_source_through_the_syntax_tree() {
+    let text = "// Adds numbers.\n@function add (x: Number, y: Number) -> Number {\n  return x + y; /* sum */\n}\n\n@let é = add(1, 2) ;\n";
+    let tree = SyntaxTree::parse(text);
+    assert_eq!(tree.text(), text);
+    assert_eq!(tree.root.kind(), SyntaxKind::SourceFile);
+    assert_eq!(tree.root.full_range(), 0..text.len());
+    assert!(tree.program.is_valid());
+
+    let statements = tree.source_file().statements();
+    let function = FunctionSyntax::cast(statements[0].syntax().clone()).unwrap();
+    assert_eq!(function.syntax().kind(), SyntaxKind::Function);
+    assert_eq!(
+        function.syntax().range().start,
+        text.find("@function").unwrap()
+    );
+    assert!(function
+        .syntax()
+        .text()
+        .starts_with("// Adds numbers.\n@function"));
+    assert!(function.syntax().text().ends_with('}'));
+    assert_eq!(function.name().unwrap().text(), "add");
+    let parameters = function.parameters();
+    assert_eq!(parameters.len(), 2);
+    assert_eq!(parameters[1].name().unwrap().text(), "y");
+    assert_eq!(
+        parameters[1].type_label().unwrap().syntax().kind(),
+        SyntaxKind::ConcreteType
+    );
+    assert_eq!(
+        function.return_type().unwrap().syntax().text().trim(),
+        "Number"
+    );
+
+    let body = function.body().unwrap().statements();
+    let value = ReturnStatementSyntax::cast(body[0].syntax().clone())
+        .and_then(|statement| statement.argument())
+        .unwrap();
+    let sum = BinaryExpressionSyntax::cast(value.syntax().clone()).unwrap();
+    assert_eq!(sum.operator().unwrap().text(), "+");
+    let y = NameExpressionSyntax::cast(sum.right().unwrap().syntax().clone()).unwrap();
+    assert_eq!(y.name().unwrap().text(), "y");
+    let x = tree.root.covering_node(text.find("x + y").unwrap());
+    assert_eq!(x.kind(), SyntaxKind::NameExpression);
+    assert_eq!(x.parent().unwrap().kind(), SyntaxKind::BinaryExpression);
+
+    let variable = VariableDeclarationSyntax::cast(statements[1].syntax().clone()).unwrap();
+    assert_eq!(variable.name().unwrap().text(), "é");
+    let call =
+        CallExpressionSyntax::cast(variable.initializer().unwrap().syntax().clone()).unwrap();
+    assert_eq!(call.callee().unwrap().syntax().text(), " add");
+    assert_eq!(call.arguments().len(), 2);
+
+    let token = tree.root.token_at(text.find("}\n").unwrap()).unwrap();
+    assert_eq!(token.text(), "}");
+    assert_eq!(token.parent().kind(), SyntaxKind::Block);
+    assert!(matches!(
+        token.leading()[1],
+        Trivia::Comment(CommentKind::Block, _)
+    ));
+    // Text with errors is kept too, with the tokens skipped while recovering in error nodes.
+    let broken = "@function f() {\n  @let = ;\n  g(1,, \"é\");\n";
+    let tree = SyntaxTree::parse(broken);
+    assert_eq!(tree.text(), broken);
+    assert!(!tree.program.is_valid());
+    let error = tree.root.covering_node(broken.find("= ;").unwrap());
+    assert_eq!(error.kind(), SyntaxKind::Error);
+    assert_eq!(error.text(), " = ;");
+}
+
+#[test]
+fn it_nests_syntax_nodes_by_precedence() {
+    let text = "a = b + c * d.e(f)[0];\n@class A {\n  static readonly size: Number = 1,\n  get() -> core.Number {}\n}";
+    let tree = SyntaxTree::parse(text);
+    assert_eq!(tree.text(), text);
+    let kinds = |node: &SyntaxNode| {
+        node.child_nodes()
+            .iter()
+            .map(SyntaxNode::kind)
+            .collect::<Vec<_>>()
+    };
+    let statements = tree.root.child_nodes();
+    let assignment = &statements[0].child_nodes()[0];
+    assert_eq!(assignment.kind(), SyntaxKind::AssignmentExpression);
+    let sum = &assignment.child_nodes()[1];
+    assert_eq!(sum.kind(), SyntaxKind::BinaryExpression);
+    let product = &sum.child_nodes()[1];
+    assert_eq!(product.text(), " c * d.e(f)[0]");
+    assert_eq!(
+        kinds(product),
+        vec![SyntaxKind::NameExpression, SyntaxKind::IndexExpression]
+    );
+    let index = &product.child_nodes()[1];
+    assert_eq!(
+        kinds(index),
+        vec![SyntaxKind::CallExpression, SyntaxKind::LiteralExpression]
+    );
+    assert_eq!(
+        kinds(&index.child_nodes()[0]),
+        vec![SyntaxKind::DotExpression, SyntaxKind::NameExpression]
+    );
+
+    let class = ClassSyntax::cast(statements[1].clone()).unwrap();
+    let attribute = &class.attributes()[0];
+    assert_eq!(
+        attribute.syntax().text(),
+        "\n  static readonly size: Number = 1"
+    );
+    assert_eq!(attribute.name().unwrap().text(), "size");
+    let method = &class.methods()[0];
+    assert_eq!(method.name().unwrap().text(), "get");
+    assert_eq!(
+        kinds(method.return_type().unwrap().syntax()),
+        vec![SyntaxKind::ConcreteType, SyntaxKind::ConcreteType]
+    );
+    assert_eq!(
+        method.return_type().unwrap().syntax().kind(),
+        SyntaxKind::DotType
+    );
+}
+
+#[test]
+fn it_attaches_doc_comments_to_declarations() {
+    let program = parse_source(
+        "## Reads a file.\n## Returns its contents.\n@public @function readFile(p: Path) -> String {\n    crash \"Not implemented\";\n}\n## A person.\n@class Person {\n    ## Their name.\n    name: String,\n    ## Greets them.\n    greet() {}\n}\n@enum Direction {\n    ## Upwards.\n    Up,\n    Down\n}\n",
+    );
+    assert!(program.is_valid());
+    let statements = program.statements();
+    assert_eq!(
+        statements[0].docs(),
+        Some("Reads a file.\nReturns its contents.")
+    );
+    let Statement::Class(Class {
+        properties, docs, ..
+    }) = &statements[1]
+    else {
+        panic!("expected a class, found {:?}", statements[1]);
+    };
+    assert_eq!(docs.as_deref(), Some("A person."));
+    assert_eq!(properties[0].docs(), Some("Their name."));
+    assert_eq!(properties[1].docs(), Some("Greets them."));
+    let arena = program.to_arena();
+    let variants = arena
+        .iter()
+        .filter(|(_, node)| matches!(node.kind, NodeKind::Variant { .. }))
+        .map(|(id, _)| arena.docs(id))
+        .collect::<Vec<_>>();
+    assert_eq!(variants, vec![Some("Upwards."), None]);
+    // The docs of a public function belong to the function, not to the modifier.
+    let NodeKind::Public { statement } = arena.kind(arena.roots()[0]) else {
+        panic!("expected a public modifier");
+    };
+    assert_eq!(arena.docs(arena.roots()[0]), None);
+    assert_eq!(
+        arena.docs(*statement),
+        Some("Reads a file.\nReturns its contents.")
+    );
+}
+
+#[test]
+fn it_warns_about_orphaned_doc_comments() {
+    let text = "## Not attached, because of the blank line.\n\n@let a = 1;\n## Not attached to an expression.\na = 2;\n## Attached.\n@let b = 1;\n## At the end.";
+    let program = parse_source(text);
+    assert!(program.is_valid());
+    assert_eq!(program.statements()[2].docs(), Some("Attached."));
+    assert_eq!(program.statements()[0].docs(), None);
+    let warnings = program
+        .diagnostics
+        .iter()
+        .map(|diagnostic| match diagnostic {
+            Diagnostic::Warning(warning, span) => {
+                (warning, text[..span.start].matches('\n').count() + 1)
+            }
+            _ => panic!("expected a warning, found {:?}", diagnostic),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            (&SyntaxWarning::OrphanedDocComment, 1),
+            (&SyntaxWarning::OrphanedDocComment, 4),
+            (&SyntaxWarning::OrphanedDocComment, 8),
+        ]
+    );
+}
+
+/// Applies the fixes of the problems in a source text, and returns the fixed text with the titles of the fixes.
+fn fixed(text: &str) -> (String, Vec<String>) {
+    let mut map = SourceMap::new();
+    let file = map.add("main.peb", text);
+    let program = parse_in(&map, file);
+    let fixes = fixes(map.get(file), &program.diagnostics);
+    let edits = fixes
+        .iter()
+        .flat_map(|fix| fix.edits.clone())
+        .collect::<Vec<_>>();
+    let titles = fixes.into_iter().map(|fix| fix.title).collect();
+    (map.get(file).apply(&edits), titles)
+}
+
+#[test]
+fn it_fixes_missing_semicolons_and_uninitialized_variables() {
+    let (text, titles) = fixed("@let a = 1\n@let b;\n@const c: Number;\nprintln a");
+    assert_eq!(
+        text,
+        "@let a = 1;\n@let b: Any;\n@let c: Number;\nprintln a;"
+    );
+    assert_eq!(
+        titles,
+        vec![
+            "Insert a semicolon",
+            "Add a type label",
+            "Declare a variable instead",
+            "Insert a semicolon"
+        ]
+    );
+    assert!(parse_source(&text).is_valid());
+}
+
+#[test]
+fn it_fixes_named_function_expressions_and_illegal_elses() {
+    let (text, _) = fixed("fn square(x) {\n    return x * x;\n}\n@let f = fn named() {};");
+    assert_eq!(
+        text,
+        "@let square = fn(x) {\n    return x * x;\n};\n@let f = fn() {};"
+    );
+    assert!(parse_source(&text).is_valid());
+    let (text, titles) = fixed("if (a) {\n};\nelse {\n}\nprintln a;\nelse {\n    println b;\n}");
+    assert_eq!(text, "if (a) {\n}\nelse {\n}\nprintln a;");
+    assert_eq!(
+        titles,
+        vec!["Remove the semicolon before else", "Remove the else block"]
+    );
+}
+
+/// Parses the text of an edited program from scratch, and checks that the program matches it.
+fn assert_reparsed(program: &Program) {
+    let text = program.text();
+    let parsed = parse_source(text);
+    assert_eq!(program.statements(), parsed.statements(), "{text:?}");
+    assert_eq!(program.comments, parsed.comments, "{text:?}");
+    assert_eq!(program.diagnostics, parsed.diagnostics, "{text:?}");
+    let mut scanner = Scanner::new(text);
+    scanner.run();
+    assert_eq!(program.tokens, scanner.tokens, "{text:?}");
+}
+
+#[test]
+fn it_reparses_only_the_statements_an_edit_touches() {
+    let functions = (0..50)
+        .map(|index| format!("@function f{index}() {{\n    @let a = {index};\n    g(a);\n}}\n"))
+        .collect::<String>();
+    let text = format!("{functions}## Documented.\n@function last() {{\n    h();\n}}\n");
+    let mut program = parse_source(&text);
+    let file = SourceMap::new().add("main.pb", text.clone());
+    let offset = text.find("g(a)").unwrap();
+    let edit = |start: usize, end: usize, text: &str| {
+        utils::TextEdit::new(Span::new(file, start, end), text)
+    };
+    program.edit(&edit(offset, offset + 1, "first"));
+    assert_reparsed(&program);
+    // Only the block of the first function is parsed again.
+    assert!(program.reparsed.len() < 20);
+    let offset = program.text().find("g(a)").unwrap();
+    program.edit(&edit(offset, offset, "@let b = \"{a}\";\n    "));
+    program.edit(&edit(0, 0, "/* Moves everything. */\n"));
+    assert_reparsed(&program);
+    // A doc comment that loses its declaration is reported as orphaned.
+    let offset = program.text().find("@function last").unwrap();
+    program.edit(&edit(offset, offset + "@function last() {".len(), "{"));
+    assert_reparsed(&program);
+    assert!(program
+        .diagnostics
+        .iter()
+        .any(|diagnostic| matches!(diagnostic, Diagnostic::Warning(..))));
+}
+
+#[test]
+fn it_matches_a_full_parse_after_random_edits() {
+    let source = "## Reads a file.\n@public @function read(path: Path) -> Array<Array<String>> {\n    @let lines = [];\n    for (line in path.lines()) {\n        if (line.isEmpty()) { continue; } else { lines.push(\"{line} and {count}\"); }\n    }\n    return lines;\n}\n@class Person {\n    ## Their name.\n    name: String,\n    greet() {\n        println 'c';\n    }\n}\n// A comment.\n@enum Direction {\n    Up,\n    Down(Number)\n}\n@tests {\n    @function itWorks() {\n        try {\n            crash r#\"raw\"#;\n        } recover (error) {\n            loop { break; }\n        }\n    }\n}\n";
+    let snippets = [
+        "{",
+        "}",
+        "(",
+        ")",
+        ";",
+        ",",
+        "\"",
+        "'",
+        "\n",
+        " ",
+        "x",
+        "é",
+        ">>",
+        "1.5",
+        "/*",
+        "*/",
+        "## Docs.\n",
+        "// Note\n",
+        "@public ",
+        "@let a = 1;",
+        "@function f() {}",
+        "\"a {b} c\"",
+        "\"{",
+        "if (a) { b; } else { c; }",
+        "Array<Array<T>>",
+        "r#\"",
+        "\"\"\"\n",
+        "@module m { x; }",
+        "fn() { y; }",
+    ];
+    // A xorshift generator, so that the edits are the same on every run.
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut random = |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % bound as u64) as usize
+    };
+    let file = SourceMap::new().add("main.pb", source);
+    for _ in 0..20 {
+        let mut program = parse_source(source);
+        for _ in 0..60 {
+            let text = program.text();
+            let boundary = |offset: usize| (0..=offset).rev().find(|at| text.is_char_boundary(*at));
+            let start = boundary(random(text.len() + 1)).unwrap();
+            let end = boundary((start + random(12)).min(text.len()))
+                .unwrap()
+                .max(start);
+            let inserted = match random(3) {
+                0 => "",
+                _ => snippets[random(snippets.len())],
+            };
+            program.edit(&utils::TextEdit::new(Span::new(file, start, end), inserted));
+            assert_reparsed(&program);
+        }
+    }
+}
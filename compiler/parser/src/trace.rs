@@ -0,0 +1,16 @@
+use ast::Token;
+
+/// A step taken by the parser, reported to the hook set with `Parser::trace`.
+#[derive(Debug, PartialEq)]
+pub enum TraceEvent<'t> {
+    /// The parser has started a production, e.g. `"statement"` or `"expression"`.
+    Enter(&'static str),
+    /// The parser has finished a production, whether or not it succeeded.
+    Exit(&'static str),
+    /// The parser has moved past a token.
+    Consume(&'t Token),
+}
+
+/// A function that receives every step taken by the parser, for debugging.<br>
+/// The hook owns whatever it records into, e.g. an `Rc<RefCell<Vec<String>>>`, since it is kept by the parser and cannot borrow from the caller.
+pub type TraceHook = Box<dyn FnMut(TraceEvent<'_>)>;
@@ -0,0 +1,214 @@
+use crate::cst::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+
+/// A typed view over a node of the concrete syntax tree.<br>
+/// A view holds nothing but the node, and reads each part of it from the tokens and nodes under it when asked, so a part missing from malformed text is `None`.
+pub trait AstNode: Sized {
+    /// Returns the view of `node`, if the node is of a kind the view is for.
+    fn cast(node: SyntaxNode) -> Option<Self>;
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+/// Declares views over the nodes whose kinds pass a test.
+macro_rules! views {
+    ($($(#[$doc:meta])* $view:ident: |$kind:ident| $test:expr;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone)]
+            pub struct $view(SyntaxNode);
+
+            impl AstNode for $view {
+                fn cast(node: SyntaxNode) -> Option<Self> {
+                    let $kind = node.kind();
+                    ($test).then(|| Self(node))
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.0
+                }
+            }
+        )*
+    };
+}
+
+views! {
+    /// The root of the tree.
+    SourceFileSyntax: |kind| kind == SyntaxKind::SourceFile;
+    /// Any statement or declaration.
+    StatementSyntax: |kind| kind.is_statement();
+    /// Any expression.
+    ExpressionSyntax: |kind| kind.is_expression();
+    /// Any type.
+    TypeSyntax: |kind| kind.is_type();
+    /// A function declaration, or a method of a class or an interface.
+    FunctionSyntax: |kind| matches!(kind, SyntaxKind::Function | SyntaxKind::Method);
+    ParameterSyntax: |kind| kind == SyntaxKind::Parameter;
+    BlockSyntax: |kind| kind == SyntaxKind::Block;
+    VariableDeclarationSyntax: |kind| kind == SyntaxKind::VariableDeclaration;
+    /// A class or an interface.
+    ClassSyntax: |kind| matches!(kind, SyntaxKind::Class | SyntaxKind::Interface);
+    AttributeSyntax: |kind| kind == SyntaxKind::Attribute;
+    IfStatementSyntax: |kind| kind == SyntaxKind::IfStatement;
+    ReturnStatementSyntax: |kind| kind == SyntaxKind::ReturnStatement;
+    ExpressionStatementSyntax: |kind| kind == SyntaxKind::ExpressionStatement;
+    /// An expression with an operator between two operands, including logical operators and assignments.
+    BinaryExpressionSyntax: |kind| matches!(
+        kind,
+        SyntaxKind::BinaryExpression
+            | SyntaxKind::LogicalExpression
+            | SyntaxKind::AssignmentExpression
+    );
+    CallExpressionSyntax: |kind| kind == SyntaxKind::CallExpression;
+    NameExpressionSyntax: |kind| kind == SyntaxKind::NameExpression;
+    LiteralExpressionSyntax: |kind| kind == SyntaxKind::LiteralExpression;
+}
+
+/// Returns the first child of `node` that the view is for.
+fn child<N: AstNode>(node: &SyntaxNode) -> Option<N> {
+    node.child_nodes().into_iter().find_map(N::cast)
+}
+
+/// Returns every child of `node` that the view is for, in source order.
+fn children<N: AstNode>(node: &SyntaxNode) -> Vec<N> {
+    node.child_nodes().into_iter().filter_map(N::cast).collect()
+}
+
+/// Returns the first token of the given kind directly under `node`.
+fn token(node: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxToken> {
+    node.children().into_iter().find_map(|child| match child {
+        SyntaxElement::Token(token) if token.kind() == kind => Some(token),
+        _ => None,
+    })
+}
+
+impl SourceFileSyntax {
+    pub fn statements(&self) -> Vec<StatementSyntax> {
+        children(&self.0)
+    }
+}
+
+impl FunctionSyntax {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Identifier)
+    }
+    pub fn parameters(&self) -> Vec<ParameterSyntax> {
+        self.0
+            .child_nodes()
+            .into_iter()
+            .find(|node| node.kind() == SyntaxKind::ParameterList)
+            .map_or_else(Vec::new, |list| children(&list))
+    }
+    pub fn return_type(&self) -> Option<TypeSyntax> {
+        child(&self.0)
+    }
+    pub fn body(&self) -> Option<BlockSyntax> {
+        child(&self.0)
+    }
+}
+
+impl ParameterSyntax {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Identifier)
+    }
+    pub fn type_label(&self) -> Option<TypeSyntax> {
+        child(&self.0)
+    }
+}
+
+impl BlockSyntax {
+    pub fn statements(&self) -> Vec<StatementSyntax> {
+        children(&self.0)
+    }
+}
+
+impl VariableDeclarationSyntax {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Identifier)
+    }
+    pub fn type_label(&self) -> Option<TypeSyntax> {
+        child(&self.0)
+    }
+    pub fn initializer(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+}
+
+impl ClassSyntax {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Identifier)
+    }
+    pub fn methods(&self) -> Vec<FunctionSyntax> {
+        children(&self.0)
+    }
+    pub fn attributes(&self) -> Vec<AttributeSyntax> {
+        children(&self.0)
+    }
+}
+
+impl AttributeSyntax {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Identifier)
+    }
+    pub fn type_label(&self) -> Option<TypeSyntax> {
+        child(&self.0)
+    }
+    pub fn value(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+}
+
+impl IfStatementSyntax {
+    pub fn condition(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+    pub fn body(&self) -> Option<StatementSyntax> {
+        child(&self.0)
+    }
+    /// Returns the statement after `else`.
+    pub fn alternate(&self) -> Option<StatementSyntax> {
+        children(&self.0).into_iter().nth(1)
+    }
+}
+
+impl ReturnStatementSyntax {
+    pub fn argument(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+}
+
+impl ExpressionStatementSyntax {
+    pub fn expression(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+}
+
+impl BinaryExpressionSyntax {
+    pub fn left(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+    pub fn operator(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Operator)
+    }
+    pub fn right(&self) -> Option<ExpressionSyntax> {
+        children(&self.0).into_iter().nth(1)
+    }
+}
+
+impl CallExpressionSyntax {
+    pub fn callee(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+    pub fn arguments(&self) -> Vec<ExpressionSyntax> {
+        children(&self.0).into_iter().skip(1).collect()
+    }
+}
+
+impl NameExpressionSyntax {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Identifier)
+    }
+}
+
+impl LiteralExpressionSyntax {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Literal)
+    }
+}
@@ -0,0 +1,39 @@
+use database::Database;
+
+use crate::{fmt::source_paths, report, Args};
+
+/// Reports the problems in .peb files, including the imports of names that the other files do not export.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    if let Some((name, _)) = args.options.first() {
+        return Err(format!("Unknown option --{name}."));
+    }
+    let paths = source_paths(args.positional)?;
+
+    let mut database = Database::new();
+    let mut files = vec![];
+    for path in &paths {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("Could not read {}: {error}", path.display()))?;
+        files.push(database.set_source(&path.display().to_string(), text));
+    }
+    let (mut errors, mut warnings) = (0, 0);
+    for file in files {
+        for diagnostic in database.check(file).iter() {
+            report::print(database.sources(), file, diagnostic);
+            if diagnostic.is_error() {
+                errors += 1;
+            } else {
+                warnings += 1;
+            }
+        }
+    }
+
+    if errors > 0 {
+        Err(format!(
+            "{errors} error(s) and {warnings} warning(s) found."
+        ))
+    } else {
+        Ok(())
+    }
+}
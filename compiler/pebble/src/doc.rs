@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use docs::{Format, Pile};
+
+use crate::{report, Args};
+
+/// Generates the documentation of a pile.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    let root = PathBuf::from(args.positional.first().copied().unwrap_or("."));
+    let mut out = root.join("docs");
+    let mut formats = vec![Format::Html, Format::Markdown];
+    for (name, value) in args.options {
+        match name {
+            "out" => out = PathBuf::from(value),
+            "format" => {
+                formats = match value {
+                    "html" => vec![Format::Html],
+                    "markdown" | "md" => vec![Format::Markdown],
+                    "both" => vec![Format::Html, Format::Markdown],
+                    _ => return Err(format!("Unknown format '{value}'.")),
+                }
+            }
+            _ => return Err(format!("Unknown option --{name}.")),
+        }
+    }
+    let pile = Pile::load(&root)
+        .map_err(|error| format!("Could not read the pile at {}: {error}", root.display()))?;
+    for (file, diagnostic) in &pile.diagnostics {
+        report::print(&pile.sources, *file, diagnostic);
+    }
+    let written = docs::write(&pile, &out, &formats)
+        .map_err(|error| format!("Could not write to {}: {error}", out.display()))?;
+    for path in written {
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
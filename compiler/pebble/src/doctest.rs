@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use docs::{extract, resolve_names, Check, Pile};
+
+use crate::{report, Args};
+
+/// Parses every ```pebble example in the doc comments of a pile, and resolves the names in it.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    if let Some((name, _)) = args.options.first() {
+        return Err(format!("Unknown option --{name}."));
+    }
+    let root = PathBuf::from(args.positional.first().copied().unwrap_or("."));
+    let pile = Pile::load(&root)
+        .map_err(|error| format!("Could not read the pile at {}: {error}", root.display()))?;
+    let tests = pile.sources.files().flat_map(extract).collect::<Vec<_>>();
+    println!("running {} doc tests", tests.len());
+    let checks: [Check; 1] = [Box::new(resolve_names)];
+    let (mut passed, mut ignored, mut failed) = (0, 0, vec![]);
+    for test in &tests {
+        let name = test.name(&pile.sources);
+        if test.ignore {
+            println!("test {name} ... ignored");
+            ignored += 1;
+            continue;
+        }
+        // There is no type checker or executor yet, so examples are only parsed and resolved.
+        let failures = test.run(&checks);
+        if failures.is_empty() {
+            println!("test {name} ... ok");
+            passed += 1;
+        } else {
+            println!("test {name} ... FAILED");
+            failed.push(failures);
+        }
+    }
+    for failure in failed.iter().flatten() {
+        report::print_at(&pile.sources, failure.span, &failure.diagnostic);
+    }
+    let result = if failed.is_empty() { "ok" } else { "FAILED" };
+    println!(
+        "\ntest result: {result}. {passed} passed; {} failed; {ignored} ignored",
+        failed.len()
+    );
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} doc tests failed.", failed.len()))
+    }
+}
@@ -0,0 +1,74 @@
+use database::Database;
+use parser::fixes;
+use utils::Encoding;
+
+use crate::{fmt::source_paths, Args};
+
+/// Fixing a problem can uncover one that the parser did not reach, so files are fixed again until nothing is left to fix, at most this many times.
+const PASSES: usize = 8;
+
+/// Applies the fixes of the problems found in .peb files, or with `--check`, lists the files that have problems that can be fixed.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["check"])?;
+    let check = args.flags.contains(&"check");
+    if let Some((name, _)) = args.options.first() {
+        return Err(format!("Unknown option --{name}."));
+    }
+    let paths = source_paths(args.positional)?;
+
+    let mut database = Database::new();
+    let mut fixable = 0;
+    for path in &paths {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("Could not read {}: {error}", path.display()))?;
+        let name = path.display().to_string();
+        let file = database.set_source(&name, text);
+        let mut applied = 0;
+        for _ in 0..PASSES {
+            let fixes = fixes(database.sources().get(file), database.check(file).iter());
+            if fixes.is_empty() {
+                break;
+            }
+            if check {
+                applied += fixes.len();
+                break;
+            }
+            for fix in &fixes {
+                let edit = &fix.edits[0];
+                let start = database.sources().start(edit.span, Encoding::Chars);
+                println!(
+                    "{}:{}:{}: {}",
+                    path.display(),
+                    start.line + 1,
+                    start.column + 1,
+                    fix.title
+                );
+            }
+            applied += fixes.len();
+            let edits = fixes
+                .into_iter()
+                .flat_map(|fix| fix.edits)
+                .collect::<Vec<_>>();
+            let fixed = database.sources().get(file).apply(&edits);
+            database.set_source(&name, fixed);
+        }
+        if applied == 0 {
+            continue;
+        }
+        fixable += 1;
+        if check {
+            println!("{}", path.display());
+        } else {
+            std::fs::write(path, &database.sources().get(file).text)
+                .map_err(|error| format!("Could not write {}: {error}", path.display()))?;
+        }
+    }
+
+    if check && fixable > 0 {
+        Err(format!(
+            "{fixable} file(s) have problems that can be fixed."
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use std::path::{Path, PathBuf};
+
+use formatter::{format, FormatError, Options};
+use utils::SourceMap;
+
+use crate::{report, Args};
+
+/// Formats .peb files in place, or with `--check`, lists the ones that are not formatted.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["check"])?;
+    let check = args.flags.contains(&"check");
+    let mut options = Options::default();
+    for (name, value) in &args.options {
+        match *name {
+            "width" => {
+                options.width = value
+                    .parse()
+                    .map_err(|_| format!("Expected a number of columns, found '{value}'."))?;
+            }
+            _ => return Err(format!("Unknown option --{name}.")),
+        }
+    }
+    let paths = source_paths(args.positional)?;
+
+    let mut sources = SourceMap::new();
+    let (mut unformatted, mut failed) = (0, 0);
+    for path in &paths {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("Could not read {}: {error}", path.display()))?;
+        let formatted = match format(&text, &options) {
+            Ok(formatted) => formatted,
+            Err(error) => {
+                if let FormatError::Invalid(diagnostics) = &error {
+                    let file = sources.add(path.display().to_string(), text.as_str());
+                    let errors = diagnostics
+                        .iter()
+                        .filter(|diagnostic| diagnostic.is_error());
+                    for diagnostic in errors {
+                        report::print(&sources, file, diagnostic);
+                    }
+                }
+                eprintln!("{}: {error}", path.display());
+                failed += 1;
+                continue;
+            }
+        };
+        if formatted == text {
+            continue;
+        }
+        unformatted += 1;
+        if check {
+            println!("{}", path.display());
+        } else {
+            std::fs::write(path, formatted)
+                .map_err(|error| format!("Could not write {}: {error}", path.display()))?;
+        }
+    }
+
+    if failed > 0 {
+        Err(format!("{failed} file(s) could not be formatted."))
+    } else if check && unformatted > 0 {
+        Err(format!("{unformatted} file(s) are not formatted."))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the .peb files named, and the ones in the folders named, in order. No names stand for the current folder.
+pub fn source_paths(roots: Vec<&str>) -> Result<Vec<PathBuf>, String> {
+    let roots = match roots.is_empty() {
+        true => vec!["."],
+        false => roots,
+    };
+    let mut paths = vec![];
+    for root in roots {
+        let root = Path::new(root);
+        if root.is_dir() {
+            source_files(root, &mut paths)
+                .map_err(|error| format!("Could not read {}: {error}", root.display()))?;
+        } else {
+            paths.push(root.to_path_buf());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn source_files(folder: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            source_files(&path, paths)?;
+        } else if path.extension().is_some_and(|extension| extension == "peb") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
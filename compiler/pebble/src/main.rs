@@ -0,0 +1,76 @@
+mod check;
+mod doc;
+mod doctest;
+mod fix;
+mod fmt;
+mod report;
+
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: pebble <command> [options]
+
+Commands:
+    check [paths...]  Reports the problems in .peb files, and the .peb files in folders. Defaults to the current folder.
+    doc [pile]        Generates documentation for the public items of a pile.
+                      --out <folder>       Where to write the documentation. Defaults to <pile>/docs.
+                      --format <format>    html, markdown or both. Defaults to both.
+    doctest [pile]    Checks that the ```pebble examples in the doc comments of a pile parse.
+    fix [paths...]    Applies the fixes suggested for problems in .peb files, such as missing semicolons and unused imports.
+                      --check              Lists the files that have problems that can be fixed, without changing them.
+    fmt [paths...]    Formats .peb files, and the .peb files in folders. Defaults to the current folder.
+                      --check              Lists the files that are not formatted, without changing them.
+                      --width <columns>    The width lines should fit in. Defaults to 100.
+    help              Shows this message.";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let result = match args.first().map(String::as_str) {
+        Some("check") => check::run(&args[1..]),
+        Some("doc") => doc::run(&args[1..]),
+        Some("doctest") => doctest::run(&args[1..]),
+        Some("fix") => fix::run(&args[1..]),
+        Some("fmt") => fmt::run(&args[1..]),
+        Some("help" | "--help" | "-h") | None => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Some(command) => Err(format!("Unknown command '{command}'.\n\n{USAGE}")),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The arguments of a command, split into positional arguments, `--name value` options and `--name` flags.
+pub struct Args<'a> {
+    pub positional: Vec<&'a str>,
+    pub options: Vec<(&'a str, &'a str)>,
+    pub flags: Vec<&'a str>,
+}
+
+impl<'a> Args<'a> {
+    /// Splits the arguments of a command. Options named in `flags` take no value.
+    pub fn parse(args: &'a [String], flags: &[&str]) -> Result<Self, String> {
+        let mut parsed = Args {
+            positional: vec![],
+            options: vec![],
+            flags: vec![],
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if flags.contains(&name) => parsed.flags.push(name),
+                Some(name) => match args.next() {
+                    Some(value) => parsed.options.push((name, value.as_str())),
+                    None => return Err(format!("Expected a value after --{name}.")),
+                },
+                None => parsed.positional.push(arg.as_str()),
+            }
+        }
+        Ok(parsed)
+    }
+}
@@ -0,0 +1,28 @@
+use parser::Diagnostic;
+use utils::{Encoding, FileId, SourceMap, Span};
+
+/// Prints a diagnostic found in a file with the line and column it refers to.<br>
+/// The file is given, as text parsed on its own is not registered in `sources` under the same ID.
+pub fn print(sources: &SourceMap, file: FileId, diagnostic: &Diagnostic) {
+    let span = Span {
+        file,
+        ..diagnostic.span()
+    };
+    print_at(sources, span, diagnostic);
+}
+
+/// Prints a diagnostic that has already been located in a file, e.g. one found in an example in a doc comment.
+pub fn print_at(sources: &SourceMap, span: Span, diagnostic: &Diagnostic) {
+    let start = sources.start(span, Encoding::Chars);
+    let severity = if diagnostic.is_error() {
+        "error"
+    } else {
+        "warning"
+    };
+    eprintln!(
+        "{}:{}:{}: {severity}: {diagnostic}",
+        sources.get(span.file).name,
+        start.line + 1,
+        start.column + 1
+    );
+}
@@ -0,0 +1,327 @@
+use std::collections::HashSet;
+
+use parser::Diagnostic;
+use utils::Span;
+
+/// Identifies a binding in a [`Resolution`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BindingId(pub(crate) u32);
+
+impl BindingId {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Identifies a scope in a [`Resolution`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScopeId(pub(crate) u32);
+
+impl ScopeId {
+    /// The scope of the builtins and the top-level declarations of a file.
+    pub const ROOT: ScopeId = ScopeId(0);
+
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// The kinds of things a name can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BindingKind {
+    Variable,
+    Constant,
+    Parameter,
+    Function,
+    Class,
+    Interface,
+    Enum,
+    Variant,
+    Record,
+    TypeAlias,
+    Module,
+    Method,
+    Attribute,
+    TypeParameter,
+    /// A name brought in from another file with `@use`.
+    Import,
+    /// A name that is available everywhere without being declared, e.g. `String` or `core`.
+    Builtin,
+}
+
+impl BindingKind {
+    /// Returns `true` if the name can be written as a type label.
+    pub fn is_type(&self) -> bool {
+        matches!(
+            self,
+            Self::Class
+                | Self::Interface
+                | Self::Enum
+                | Self::TypeAlias
+                | Self::TypeParameter
+                | Self::Import
+                | Self::Builtin
+        )
+    }
+    /// Returns `true` if the name can be used as a value in an expression.
+    pub fn is_value(&self) -> bool {
+        !matches!(
+            self,
+            Self::Interface | Self::TypeAlias | Self::TypeParameter
+        )
+    }
+    /// Returns `true` if the binding can be called with arguments.
+    pub fn is_callable(&self) -> bool {
+        matches!(self, Self::Function | Self::Method | Self::Class)
+    }
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Variable => "variable",
+            Self::Constant => "constant",
+            Self::Parameter => "parameter",
+            Self::Function => "function",
+            Self::Class => "class",
+            Self::Interface => "interface",
+            Self::Enum => "enum",
+            Self::Variant => "variant",
+            Self::Record => "record",
+            Self::TypeAlias => "type",
+            Self::Module => "module",
+            Self::Method => "method",
+            Self::Attribute => "attribute",
+            Self::TypeParameter => "type parameter",
+            Self::Import => "import",
+            Self::Builtin => "builtin",
+        }
+    }
+}
+
+/// A name declared in a file, along with what is known about it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub name: String,
+    pub kind: BindingKind,
+    /// The name as it is written in the declaration.
+    pub span: Span,
+    /// The whole declaration, including its body.
+    pub declaration: Span,
+    pub docs: Option<String>,
+    /// The type of a value, or the return type of a function or method, written as Pebble source, e.g. `Array<String>`.<br>
+    /// It is the type label when there is one, and the inferred type when there is not.
+    pub type_: Option<String>,
+    /// The declaration the binding is a member or parameter of, such as the class of a method or the function of a parameter.
+    pub parent: Option<BindingId>,
+    pub scope: ScopeId,
+    /// The byte offset from which the name can be used, which is the start of its scope for declarations that are hoisted, and the end of the declaration for variables.
+    pub visible_from: usize,
+    pub is_public: bool,
+    pub is_static: bool,
+    pub is_readonly: bool,
+}
+
+impl Binding {
+    /// Returns `true` if the binding is not declared in the file, i.e. it is a builtin.
+    pub fn is_builtin(&self) -> bool {
+        self.kind == BindingKind::Builtin
+    }
+}
+
+/// A use of a declared name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reference {
+    pub span: Span,
+    pub binding: BindingId,
+}
+
+/// A region of a file in which names can be declared, such as a block or the parameters of a function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    pub span: Span,
+    pub parent: Option<ScopeId>,
+    pub bindings: Vec<BindingId>,
+}
+
+/// A name brought in with `@use`, which refers to an export of another file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedName {
+    /// The binding the import declares in the file.
+    pub binding: BindingId,
+    /// The path of the file imported from, as it is written.
+    pub source: String,
+    /// The name of the export in the file imported from.
+    pub imported_name: String,
+    pub imported_span: Span,
+    /// The name given to the export with `as`, if it is renamed in the file.
+    pub local_span: Option<Span>,
+}
+
+/// A use of a member of a declaration imported from another file, e.g. `area` in `shape.area()` where the class of `shape` is imported.<br>
+/// The member is only known once the files are linked in a workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignMember {
+    /// The import the member is reached through.
+    pub owner: BindingId,
+    pub name: String,
+    pub span: Span,
+}
+
+/// The names declared in a file and the uses of each one.
+#[derive(Debug, Default, PartialEq)]
+pub struct Resolution {
+    pub bindings: Vec<Binding>,
+    /// The uses of names in the file, in the order they appear.
+    pub references: Vec<Reference>,
+    /// The names imported from other files, in the order they appear.
+    pub imports: Vec<ImportedName>,
+    /// The uses of members of imported declarations, in the order they appear.
+    pub foreign_members: Vec<ForeignMember>,
+    /// The interfaces each class implements, as pairs of the class and the interface, which can be an import.
+    pub implements: Vec<(BindingId, BindingId)>,
+    /// The scopes of the file. The first holds the builtins and the top-level declarations.
+    pub scopes: Vec<Scope>,
+    /// The names that could not be resolved, and the problems found while resolving them.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Resolution {
+    pub fn binding(&self, id: BindingId) -> &Binding {
+        &self.bindings[id.index()]
+    }
+    /// Returns every binding with its ID.
+    pub fn iter(&self) -> impl Iterator<Item = (BindingId, &Binding)> {
+        (0..).map(BindingId).zip(&self.bindings)
+    }
+    /// Returns the spans where a binding is used, not counting its declaration.
+    pub fn references_to(&self, id: BindingId) -> impl Iterator<Item = Span> + '_ {
+        self.references
+            .iter()
+            .filter(move |reference| reference.binding == id)
+            .map(|reference| reference.span)
+    }
+    /// Returns the members of a declaration, such as the methods and attributes of a class, or the parameters of a function.
+    pub fn members_of(&self, id: BindingId) -> impl Iterator<Item = (BindingId, &Binding)> {
+        self.iter()
+            .filter(move |(_, binding)| binding.parent == Some(id))
+    }
+    /// Returns the member of a declaration with the given name.
+    pub fn member(&self, id: BindingId, name: &str) -> Option<BindingId> {
+        self.members_of(id)
+            .find(|(_, binding)| binding.name == name && binding.kind != BindingKind::Parameter)
+            .map(|(id, _)| id)
+    }
+    /// Returns a line of Pebble source that declares a binding, e.g. `@function add(a: Number, b: Number) -> Number`.
+    pub fn signature(&self, id: BindingId) -> String {
+        let binding = self.binding(id);
+        let name = &binding.name;
+        let typed = match &binding.type_ {
+            Some(type_) => format!("{name}: {type_}"),
+            None => name.clone(),
+        };
+        let modifiers = match (binding.is_static, binding.is_readonly) {
+            (true, true) => "static readonly ",
+            (true, false) => "static ",
+            (false, true) => "readonly ",
+            (false, false) => "",
+        };
+        match binding.kind {
+            BindingKind::Variable => format!("@let {typed}"),
+            BindingKind::Constant => format!("@const {typed}"),
+            BindingKind::Parameter | BindingKind::Attribute => format!("{modifiers}{typed}"),
+            BindingKind::Function => format!("@function {name}{}", self.callable(id)),
+            BindingKind::Method => format!("{modifiers}{name}{}", self.callable(id)),
+            BindingKind::Class
+            | BindingKind::Interface
+            | BindingKind::Enum
+            | BindingKind::Record
+            | BindingKind::Module => {
+                format!("@{} {name}{}", binding.kind.label(), self.generics(id))
+            }
+            BindingKind::TypeAlias => match &binding.type_ {
+                Some(type_) => format!("@type {name}{} = {type_}", self.generics(id)),
+                None => format!("@type {name}{}", self.generics(id)),
+            },
+            BindingKind::Variant => match binding.parent {
+                Some(parent) => format!("{}::{name}", self.binding(parent).name),
+                None => name.clone(),
+            },
+            BindingKind::Import => format!("@use {{ {name} }}"),
+            BindingKind::TypeParameter | BindingKind::Builtin => name.clone(),
+        }
+    }
+    /// Returns the generic arguments, parameters and return type of a function or method, e.g. `<T>(a: T) -> Nil`.
+    pub fn callable(&self, id: BindingId) -> String {
+        let parameters = self
+            .members_of(id)
+            .filter(|(_, binding)| binding.kind == BindingKind::Parameter)
+            .map(|(_, parameter)| match &parameter.type_ {
+                Some(type_) => format!("{}: {type_}", parameter.name),
+                None => parameter.name.clone(),
+            })
+            .collect::<Vec<_>>();
+        let mut callable = format!("{}({})", self.generics(id), parameters.join(", "));
+        if let Some(return_type) = &self.binding(id).type_ {
+            callable.push_str(" -> ");
+            callable.push_str(return_type);
+        }
+        callable
+    }
+    fn generics(&self, id: BindingId) -> String {
+        let generics = self
+            .members_of(id)
+            .filter(|(_, binding)| binding.kind == BindingKind::TypeParameter)
+            .map(|(_, binding)| binding.name.as_str())
+            .collect::<Vec<_>>();
+        if generics.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", generics.join(", "))
+        }
+    }
+    /// Returns the binding whose name, or a use of whose name, is at a byte offset.
+    pub fn binding_at(&self, offset: usize) -> Option<BindingId> {
+        let reference = self
+            .references
+            .iter()
+            .find(|reference| contains(reference.span, offset))
+            .map(|reference| reference.binding);
+        reference.or_else(|| {
+            self.iter()
+                .find(|(_, binding)| !binding.is_builtin() && contains(binding.span, offset))
+                .map(|(id, _)| id)
+        })
+    }
+    /// Returns the innermost scope that covers a byte offset.
+    pub fn scope_at(&self, offset: usize) -> ScopeId {
+        let mut scope = ScopeId::ROOT;
+        // Scopes are stored as they are entered, so each scope comes after the scopes around it.
+        for (index, candidate) in self.scopes.iter().enumerate().skip(1) {
+            if contains(candidate.span, offset) {
+                scope = ScopeId(index as u32);
+            }
+        }
+        scope
+    }
+    /// Returns the bindings that can be used by name at a byte offset, innermost first.<br>
+    /// A binding shadowed by one with the same name in an inner scope is left out.
+    pub fn visible_at(&self, offset: usize) -> Vec<BindingId> {
+        let mut names = HashSet::new();
+        let mut visible = vec![];
+        let mut scope = Some(self.scope_at(offset));
+        while let Some(id) = scope {
+            let current = &self.scopes[id.index()];
+            for binding in &current.bindings {
+                let declared = self.binding(*binding);
+                if declared.visible_from <= offset && names.insert(declared.name.as_str()) {
+                    visible.push(*binding);
+                }
+            }
+            scope = current.parent;
+        }
+        visible
+    }
+}
+
+/// Returns `true` if a byte offset is within a span, or at its end.
+pub fn contains(span: Span, offset: usize) -> bool {
+    span.contains(offset)
+}
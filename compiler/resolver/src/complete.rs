@@ -0,0 +1,669 @@
+use std::collections::HashMap;
+
+use ast::{
+    BracketKind, Injunction, Keyword, LiteralKind, Operator, Punctuation, Token, TokenKind,
+    INJUNCTIONS,
+};
+use parser::Scanner;
+use utils::Span;
+
+use crate::{head_of, BindingId, BindingKind, Resolution, ScopeId};
+
+/// A suggestion for the word at the cursor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    /// The declaration of a binding, e.g. `@function add(a: Number, b: Number) -> Number`.
+    pub detail: Option<String>,
+    pub docs: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Keyword,
+    Injunction,
+    Binding(BindingKind),
+}
+
+/// The suggestions for a position, and the word they replace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completions {
+    /// The part of the word at the cursor that has already been typed, which is empty if the cursor does not follow a word.
+    pub span: Span,
+    pub items: Vec<Completion>,
+}
+
+/// The statements that can start a block nested in a declaration, such as the body of a function.
+const NESTED_INJUNCTIONS: [&str; 8] = [
+    "let",
+    "const",
+    "function",
+    "class",
+    "enum",
+    "type",
+    "interface",
+    "record",
+];
+
+/// The kinds of block the cursor can be in, found by matching the braces before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Construct {
+    Function,
+    Class,
+    Enum,
+    Module,
+    Loop,
+    Block,
+}
+
+/// Suggests what can be written at a byte offset in a file, e.g. the names in scope, the members of a class after `.`, or the exports of another file in `@use { }`.<br>
+/// The suggestions are worked out from the tokens before the cursor and the names resolved in the file, so the file does not have to be valid.
+/// `exports` resolves the file a `@use` statement imports from, given its path as written.
+pub fn complete(
+    source: &str,
+    resolution: &Resolution,
+    offset: usize,
+    exports: &dyn Fn(&str) -> Option<Resolution>,
+) -> Completions {
+    let mut scanner = Scanner::new(source);
+    scanner.run();
+    let empty = Completions {
+        span: Span::new(Default::default(), offset, offset),
+        items: vec![],
+    };
+    // Nothing is suggested inside comments and strings.
+    if in_text(&scanner, offset) {
+        return empty;
+    }
+    let count = count_before(&scanner, offset);
+    let mut before = scanner.tokens[..count].iter().collect::<Vec<_>>();
+    let mut span = empty.span;
+    // A word that ends at the cursor is the one being completed, so the suggestions depend on what comes before it.
+    if let Some(last) = before.last() {
+        let is_word = matches!(last.kind, TokenKind::Identifier(_) | TokenKind::Keyword(_))
+            || matches!(&last.kind, TokenKind::Literal(literal) if literal.kind == LiteralKind::BooleanLiteral);
+        if is_word && last.span.end == offset {
+            span.start = last.span.start;
+            before.pop();
+        }
+    }
+    let completer = Completer {
+        resolution,
+        offset,
+        names: names(resolution),
+        constructs: constructs(&before),
+        tokens: before,
+    };
+    let typing_injunction = matches!(
+        scanner.tokens.get(count.wrapping_sub(1)),
+        Some(Token { kind: TokenKind::Keyword(Keyword::Injunction(_)), .. }) if span.start != offset
+    );
+    let items = if typing_injunction {
+        completer.injunctions()
+    } else if let Some(items) = completer.imports(&scanner.tokens[count..], exports) {
+        items
+    } else {
+        completer.suggestions()
+    };
+    Completions { span, items }
+}
+
+/// The signature of the function a call is made to, and the argument being written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureHelp {
+    /// The name of the function with its generic arguments, parameters and return type, e.g. `add(a: Number, b: Number) -> Number`.
+    pub label: String,
+    /// The parameters as they are written in the label.
+    pub parameters: Vec<String>,
+    /// The index of the argument the position is in.
+    pub active_parameter: usize,
+    pub docs: Option<String>,
+}
+
+/// Finds the call whose arguments are being written at a byte offset, and describes the function, method or constructor it calls.<br>
+/// Like completions, it is worked out from the tokens before the position, so the call does not have to be closed.
+pub fn signature_help(
+    source: &str,
+    resolution: &Resolution,
+    offset: usize,
+) -> Option<SignatureHelp> {
+    let mut scanner = Scanner::new(source);
+    scanner.run();
+    if in_text(&scanner, offset) {
+        return None;
+    }
+    let tokens = scanner.tokens[..count_before(&scanner, offset)]
+        .iter()
+        .collect::<Vec<_>>();
+    // The call is the innermost `(` left open before the position, and the argument is the number of commas after it.
+    let mut depth = 0;
+    let mut commas = 0;
+    let mut open = None;
+    for (index, token) in tokens.iter().enumerate().rev() {
+        match &token.kind {
+            TokenKind::Punctuation(Punctuation::Bracket(bracket)) => match bracket {
+                BracketKind::RightParenthesis
+                | BracketKind::RightSquare
+                | BracketKind::RightCurly => depth += 1,
+                BracketKind::LeftParenthesis if depth == 0 => {
+                    open = Some(index);
+                    break;
+                }
+                _ if depth == 0 => return None,
+                _ => depth -= 1,
+            },
+            TokenKind::Punctuation(Punctuation::SemiColon) if depth == 0 => return None,
+            TokenKind::Punctuation(Punctuation::Comma) if depth == 0 => commas += 1,
+            _ => {}
+        }
+    }
+    let callee = open?.checked_sub(1)?;
+    // The parameters of a declaration are not the arguments of a call.
+    let declares = callee
+        .checked_sub(1)
+        .map(|index| &tokens[index].kind)
+        .is_some_and(|kind| {
+            matches!(
+                kind,
+                TokenKind::Keyword(Keyword::Fn | Keyword::Injunction(Injunction::Function))
+            )
+        });
+    let completer = Completer {
+        resolution,
+        offset,
+        names: names(resolution),
+        constructs: constructs(&tokens),
+        tokens,
+    };
+    let in_class = completer
+        .constructs
+        .last()
+        .is_some_and(|(construct, _)| *construct == Construct::Class);
+    if declares || in_class {
+        return None;
+    }
+    let mut id = completer.named(callee)?;
+    let binding = resolution.binding(id);
+    if binding.span == completer.tokens[callee].span {
+        return None;
+    }
+    let name = binding.name.clone();
+    let docs = binding.docs.clone();
+    match binding.kind {
+        BindingKind::Function | BindingKind::Method => {}
+        // Classes are constructed with the parameters of their constructor, which has the name of the class.
+        BindingKind::Class => match resolution.member(id, &name) {
+            Some(constructor) => id = constructor,
+            None => {
+                return Some(SignatureHelp {
+                    label: format!("{name}()"),
+                    parameters: vec![],
+                    active_parameter: commas,
+                    docs,
+                })
+            }
+        },
+        _ => return None,
+    }
+    let parameters = resolution
+        .members_of(id)
+        .filter(|(_, binding)| binding.kind == BindingKind::Parameter)
+        .map(|(_, parameter)| match &parameter.type_ {
+            Some(type_) => format!("{}: {type_}", parameter.name),
+            None => parameter.name.clone(),
+        })
+        .collect();
+    Some(SignatureHelp {
+        label: format!("{name}{}", resolution.callable(id)),
+        parameters,
+        active_parameter: commas,
+        docs: resolution.binding(id).docs.clone().or(docs),
+    })
+}
+
+/// Returns `true` if an offset is inside a comment or a string.
+fn in_text(scanner: &Scanner, offset: usize) -> bool {
+    scanner
+        .tokens
+        .iter()
+        .chain(&scanner.comments)
+        .any(|token| is_text(token) && token.span.start < offset && offset < token.span.end)
+}
+
+/// Returns the number of tokens that end before an offset.
+fn count_before(scanner: &Scanner, offset: usize) -> usize {
+    scanner
+        .tokens
+        .iter()
+        .take_while(|token| token.span.end <= offset && !token.is_eof())
+        .count()
+}
+
+fn is_text(token: &Token) -> bool {
+    match &token.kind {
+        TokenKind::Comment(_) => true,
+        TokenKind::Literal(literal) => !matches!(
+            literal.kind,
+            LiteralKind::NumericLiteral | LiteralKind::BooleanLiteral
+        ),
+        _ => false,
+    }
+}
+
+/// Maps the span of every name the resolver has seen to its binding.
+pub(crate) fn names(resolution: &Resolution) -> HashMap<Span, BindingId> {
+    let mut names = HashMap::new();
+    for reference in &resolution.references {
+        names.insert(reference.span, reference.binding);
+    }
+    for (id, binding) in resolution.iter() {
+        if !binding.is_builtin() {
+            names.insert(binding.span, id);
+        }
+    }
+    names
+}
+
+/// Returns the blocks that are open before the cursor, outermost first, with the index of the `{` of each.
+fn constructs(tokens: &[&Token]) -> Vec<(Construct, usize)> {
+    let mut open: Vec<(Construct, usize)> = vec![];
+    for (index, token) in tokens.iter().enumerate() {
+        if token.is_bracket(&BracketKind::LeftCurly) {
+            let outer = open.last().map(|(construct, _)| *construct);
+            open.push((construct(tokens, index, outer), index));
+        } else if token.is_bracket(&BracketKind::RightCurly) {
+            open.pop();
+        }
+    }
+    open
+}
+
+/// Works out what kind of block a `{` opens from the tokens that begin its statement, e.g. `@function` or `while`.
+fn construct(tokens: &[&Token], brace: usize, outer: Option<Construct>) -> Construct {
+    let mut index = brace;
+    // Commas between angle brackets separate generic arguments, e.g. `@function map<K, V>() {`.
+    let mut angles = 0;
+    while index > 0 {
+        let token = tokens[index - 1];
+        if token.is_operator(&Operator::GreaterThan) {
+            angles += 1;
+        } else if token.is_operator(&Operator::LessThan) && angles > 0 {
+            angles -= 1;
+        }
+        if token.is_bracket(&BracketKind::RightParenthesis) {
+            match matching(tokens, index - 1) {
+                Some(open) => index = open,
+                None => break,
+            }
+        } else if token.is_semi_colon()
+            || token.is_bracket(&BracketKind::LeftCurly)
+            || token.is_bracket(&BracketKind::RightCurly)
+            || (token.kind == TokenKind::Punctuation(Punctuation::Comma) && angles == 0)
+        {
+            break;
+        } else {
+            index -= 1;
+        }
+    }
+    let header = tokens[index..brace]
+        .iter()
+        .skip_while(|token| {
+            matches!(
+                token.kind,
+                TokenKind::Keyword(
+                    Keyword::Injunction(Injunction::Public) | Keyword::Static | Keyword::ReadOnly
+                )
+            )
+        })
+        .collect::<Vec<_>>();
+    match header.first().map(|token| &token.kind) {
+        Some(TokenKind::Keyword(keyword)) => match keyword {
+            Keyword::Injunction(Injunction::Function) | Keyword::Fn => Construct::Function,
+            Keyword::Injunction(Injunction::Class | Injunction::Interface) => Construct::Class,
+            Keyword::Injunction(Injunction::Enum) => Construct::Enum,
+            Keyword::Injunction(Injunction::Module) => Construct::Module,
+            Keyword::For | Keyword::While | Keyword::Loop => Construct::Loop,
+            _ => Construct::Block,
+        },
+        // A method or a constructor is a name followed by its parameters.
+        Some(TokenKind::Identifier(_))
+            if outer == Some(Construct::Class)
+                && header
+                    .get(1)
+                    .is_some_and(|token| token.is_bracket(&BracketKind::LeftParenthesis)) =>
+        {
+            Construct::Function
+        }
+        _ => Construct::Block,
+    }
+}
+
+/// Returns the index of the `(` that the `)` at `close` closes.
+fn matching(tokens: &[&Token], close: usize) -> Option<usize> {
+    let mut depth = 0;
+    for index in (0..=close).rev() {
+        if tokens[index].is_bracket(&BracketKind::RightParenthesis) {
+            depth += 1;
+        } else if tokens[index].is_bracket(&BracketKind::LeftParenthesis) {
+            depth -= 1;
+            if depth == 0 {
+                return Some(index);
+            }
+        }
+    }
+    None
+}
+
+/// What the expression before a `.` or `::` refers to, whose members can follow it.
+#[derive(Debug, Clone, Copy)]
+enum Receiver {
+    /// A value whose type is the binding, e.g. a variable holding an instance of a class.
+    Instance(BindingId),
+    /// The binding itself, e.g. a class, a module or an enum.
+    Static(BindingId),
+}
+
+struct Completer<'a> {
+    resolution: &'a Resolution,
+    /// The byte offset of the cursor.
+    offset: usize,
+    names: HashMap<Span, BindingId>,
+    constructs: Vec<(Construct, usize)>,
+    /// The tokens before the cursor, without the word being completed.
+    tokens: Vec<&'a Token>,
+}
+
+impl<'a> Completer<'a> {
+    fn suggestions(&self) -> Vec<Completion> {
+        let last = self.tokens.last().map(|token| &token.kind);
+        let innermost = self.constructs.last().map(|(construct, _)| *construct);
+        match last {
+            Some(TokenKind::Operator(Operator::Dot | Operator::Namespace)) => {
+                match self.receiver(self.tokens.len() - 1) {
+                    Some(receiver) => self.members(receiver),
+                    None => vec![],
+                }
+            }
+            Some(TokenKind::Operator(Operator::Colon | Operator::Returns))
+            | Some(TokenKind::Keyword(
+                Keyword::Implements | Keyword::Injunction(Injunction::Implement),
+            )) => self.bindings(|kind| kind.is_type()),
+            Some(TokenKind::Keyword(Keyword::Injunction(Injunction::Public))) => self
+                .injunctions()
+                .into_iter()
+                .filter(|completion| NESTED_INJUNCTIONS.contains(&&completion.label[1..]))
+                .chain([self.injunction("module")])
+                .collect(),
+            _ if innermost == Some(Construct::Enum) => vec![],
+            Some(TokenKind::Punctuation(
+                Punctuation::Comma | Punctuation::Bracket(BracketKind::LeftCurly),
+            )) if innermost == Some(Construct::Class) => {
+                let mut items = keywords(&["static", "readonly"]);
+                items.push(self.injunction("implement"));
+                items
+            }
+            None
+            | Some(TokenKind::Punctuation(
+                Punctuation::SemiColon
+                | Punctuation::Bracket(BracketKind::LeftCurly | BracketKind::RightCurly),
+            )) => self.statements(),
+            _ => {
+                let mut items = self.bindings(|kind| kind.is_value());
+                items.extend(keywords(&["true", "false", "fn"]));
+                if self.inside(Construct::Class) {
+                    items.extend(keywords(&["self"]));
+                }
+                items
+            }
+        }
+    }
+    /// Suggests what can begin a statement: declarations, the keywords valid in the enclosing blocks, and the names in scope.
+    fn statements(&self) -> Vec<Completion> {
+        let nested = self
+            .constructs
+            .iter()
+            .any(|(construct, _)| *construct != Construct::Module);
+        let mut items = self.bindings(|kind| kind.is_value());
+        if nested {
+            items.extend(NESTED_INJUNCTIONS.iter().map(|name| self.injunction(name)));
+        } else {
+            items.extend(self.injunctions());
+        }
+        items.extend(keywords(&[
+            "if", "for", "while", "loop", "println", "crash", "try",
+        ]));
+        if self.inside(Construct::Function) {
+            items.extend(keywords(&["return"]));
+        }
+        // A loop in a function that encloses the cursor does not let it break out of the function.
+        let in_loop = self
+            .constructs
+            .iter()
+            .rev()
+            .take_while(|(construct, _)| *construct != Construct::Function)
+            .any(|(construct, _)| *construct == Construct::Loop);
+        if in_loop {
+            items.extend(keywords(&["break", "continue"]));
+        }
+        if self
+            .tokens
+            .last()
+            .is_some_and(|token| token.is_bracket(&BracketKind::RightCurly))
+        {
+            items.extend(keywords(&["else", "recover"]));
+        }
+        items
+    }
+    fn inside(&self, construct: Construct) -> bool {
+        self.constructs.iter().any(|(open, _)| *open == construct)
+    }
+    fn injunctions(&self) -> Vec<Completion> {
+        INJUNCTIONS
+            .iter()
+            .map(|name| self.injunction(name))
+            .collect()
+    }
+    fn injunction(&self, name: &str) -> Completion {
+        Completion {
+            label: format!("@{name}"),
+            kind: CompletionKind::Injunction,
+            detail: None,
+            docs: None,
+        }
+    }
+    /// Suggests the names that can be used at the cursor, innermost first.
+    fn bindings(&self, filter: impl Fn(BindingKind) -> bool) -> Vec<Completion> {
+        self.resolution
+            .visible_at(self.offset)
+            .into_iter()
+            .filter(|id| filter(self.resolution.binding(*id).kind))
+            .map(|id| self.completion(self.resolution, id))
+            .collect()
+    }
+    fn completion(&self, resolution: &Resolution, id: BindingId) -> Completion {
+        let binding = resolution.binding(id);
+        Completion {
+            label: binding.name.clone(),
+            kind: CompletionKind::Binding(binding.kind),
+            detail: (!binding.is_builtin()).then(|| resolution.signature(id)),
+            docs: binding.docs.clone(),
+        }
+    }
+    /// Suggests the members that can follow a receiver.
+    fn members(&self, receiver: Receiver) -> Vec<Completion> {
+        let (id, instance) = match receiver {
+            Receiver::Instance(id) => (id, true),
+            Receiver::Static(id) => (id, false),
+        };
+        let owner = self.resolution.binding(id);
+        let inside_owner = crate::contains(owner.declaration, self.offset);
+        self.resolution
+            .members_of(id)
+            .filter(|(_, member)| match member.kind {
+                BindingKind::Parameter | BindingKind::TypeParameter => false,
+                // Constructors are called through the name of the class.
+                BindingKind::Method if member.name == owner.name => false,
+                _ if owner.kind == BindingKind::Module => member.is_public || inside_owner,
+                BindingKind::Variant => !instance,
+                BindingKind::Method | BindingKind::Attribute => member.is_static != instance,
+                _ => true,
+            })
+            .map(|(member, _)| self.completion(self.resolution, member))
+            .collect()
+    }
+    /// Works out what the expression ending just before the token at `end` refers to.
+    fn receiver(&self, end: usize) -> Option<Receiver> {
+        let index = end.checked_sub(1)?;
+        let token = self.tokens[index];
+        match &token.kind {
+            TokenKind::Keyword(Keyword::Self_) => {
+                let (_, brace) = self
+                    .constructs
+                    .iter()
+                    .rev()
+                    .find(|(construct, _)| *construct == Construct::Class)?;
+                // The name of the class is the last name before its `{`, e.g. `@class Point<T> {`.
+                let class = self.tokens[..*brace].iter().rposition(|token| {
+                    token.is_keyword(&Keyword::Injunction(Injunction::Class))
+                        || token.is_keyword(&Keyword::Injunction(Injunction::Interface))
+                })?;
+                let id = self.named(class + 1)?;
+                Some(Receiver::Instance(id))
+            }
+            TokenKind::Identifier(_) => {
+                let id = self.named(index)?;
+                let binding = self.resolution.binding(id);
+                match binding.kind {
+                    BindingKind::Class
+                    | BindingKind::Interface
+                    | BindingKind::Enum
+                    | BindingKind::Module => Some(Receiver::Static(id)),
+                    _ => Some(Receiver::Instance(
+                        self.type_named(binding.type_.as_deref()?)?,
+                    )),
+                }
+            }
+            TokenKind::Punctuation(Punctuation::Bracket(BracketKind::RightParenthesis)) => {
+                let open = matching(&self.tokens, index)?;
+                let callee = self.named(open.checked_sub(1)?)?;
+                let binding = self.resolution.binding(callee);
+                match binding.kind {
+                    BindingKind::Class => Some(Receiver::Instance(callee)),
+                    BindingKind::Function | BindingKind::Method => Some(Receiver::Instance(
+                        self.type_named(binding.type_.as_deref()?)?,
+                    )),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+    /// Finds the binding of the name at `index`, which is a member of the receiver before it if it follows a `.` or `::`.
+    fn named(&self, index: usize) -> Option<BindingId> {
+        let token = self.tokens.get(index)?;
+        let TokenKind::Identifier(name) = &token.kind else {
+            return None;
+        };
+        if let Some(id) = self.names.get(&token.span) {
+            return Some(*id);
+        }
+        let follows_member = index > 0
+            && matches!(
+                self.tokens[index - 1].kind,
+                TokenKind::Operator(Operator::Dot | Operator::Namespace)
+            );
+        if follows_member {
+            let (Receiver::Instance(owner) | Receiver::Static(owner)) = self.receiver(index - 1)?;
+            return self.resolution.member(owner, &name.value);
+        }
+        self.resolution
+            .visible_at(self.offset)
+            .into_iter()
+            .find(|id| self.resolution.binding(*id).name == name.value)
+    }
+    /// Finds the declaration of a type from how it is written, e.g. `Point` for `Point<Number>`.
+    fn type_named(&self, type_: &str) -> Option<BindingId> {
+        let name = head_of(type_);
+        let is_type = |id: &BindingId| {
+            let binding = self.resolution.binding(*id);
+            binding.name == name && binding.kind.is_type()
+        };
+        let visible = self
+            .resolution
+            .visible_at(self.offset)
+            .into_iter()
+            .find(is_type);
+        visible.or_else(|| self.resolution.iter().map(|(id, _)| id).find(is_type))
+    }
+    /// Suggests the exports of a file when the cursor is in the braces of `@use { } from "..."`.<br>
+    /// Returns `None` if the cursor is not in an import.
+    fn imports(
+        &self,
+        after: &[Token],
+        exports: &dyn Fn(&str) -> Option<Resolution>,
+    ) -> Option<Vec<Completion>> {
+        let mut listed = vec![];
+        let mut index = self.tokens.len();
+        loop {
+            let token = self.tokens.get(index.checked_sub(1)?)?;
+            match &token.kind {
+                TokenKind::Identifier(name) => listed.push(name.value.clone()),
+                TokenKind::Punctuation(Punctuation::Comma) | TokenKind::Keyword(Keyword::As) => {}
+                TokenKind::Punctuation(Punctuation::Bracket(BracketKind::LeftCurly)) => break,
+                _ => return None,
+            }
+            index -= 1;
+        }
+        let injunction = self.tokens.get(index.checked_sub(2)?)?;
+        if !injunction.is_keyword(&Keyword::Injunction(Injunction::Use)) {
+            return None;
+        }
+        let mut after = after
+            .iter()
+            .skip_while(|token| !token.is_bracket(&BracketKind::RightCurly));
+        for token in after.by_ref() {
+            if let TokenKind::Identifier(name) = &token.kind {
+                listed.push(name.value.clone());
+            }
+            if token.is_bracket(&BracketKind::RightCurly) {
+                break;
+            }
+        }
+        let (Some(from), Some(path)) = (after.next(), after.next()) else {
+            return Some(vec![]);
+        };
+        let TokenKind::Literal(path) = &path.kind else {
+            return Some(vec![]);
+        };
+        if !from.is_keyword(&Keyword::From) || !path.kind.is_string() {
+            return Some(vec![]);
+        }
+        let Some(imported) = exports(&path.value) else {
+            return Some(vec![]);
+        };
+        let items = imported
+            .iter()
+            .filter(|(_, binding)| {
+                binding.scope == ScopeId::ROOT
+                    && binding.parent.is_none()
+                    && binding.is_public
+                    && !listed.contains(&binding.name)
+            })
+            .map(|(id, _)| self.completion(&imported, id))
+            .collect();
+        Some(items)
+    }
+}
+
+fn keywords(names: &[&str]) -> Vec<Completion> {
+    names
+        .iter()
+        .map(|name| Completion {
+            label: name.to_string(),
+            kind: CompletionKind::Keyword,
+            detail: None,
+            docs: None,
+        })
+        .collect()
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use ast::{CommentKind, Keyword, LiteralKind, Token, TokenKind};
+use parser::Scanner;
+use utils::Span;
+
+use crate::{BindingId, BindingKind, Resolution, BUILTIN_TYPES};
+
+/// What a token is, as far as an editor coloring it is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SemanticTokenKind {
+    Keyword,
+    /// A word that starts a declaration or a directive, e.g. `@function` or `@use`.
+    Injunction,
+    Comment,
+    String,
+    Number,
+    Operator,
+    Variable,
+    Parameter,
+    Function,
+    Method,
+    Property,
+    Class,
+    Enum,
+    EnumMember,
+    Interface,
+    Struct,
+    Type,
+    TypeParameter,
+    Namespace,
+}
+
+impl SemanticTokenKind {
+    /// Every kind, in the order of their indices in a legend.
+    pub const ALL: [SemanticTokenKind; 19] = [
+        Self::Keyword,
+        Self::Injunction,
+        Self::Comment,
+        Self::String,
+        Self::Number,
+        Self::Operator,
+        Self::Variable,
+        Self::Parameter,
+        Self::Function,
+        Self::Method,
+        Self::Property,
+        Self::Class,
+        Self::Enum,
+        Self::EnumMember,
+        Self::Interface,
+        Self::Struct,
+        Self::Type,
+        Self::TypeParameter,
+        Self::Namespace,
+    ];
+
+    /// Returns the name of the kind in the Language Server Protocol, which defines every kind except `injunction`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Keyword => "keyword",
+            Self::Injunction => "injunction",
+            Self::Comment => "comment",
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Operator => "operator",
+            Self::Variable => "variable",
+            Self::Parameter => "parameter",
+            Self::Function => "function",
+            Self::Method => "method",
+            Self::Property => "property",
+            Self::Class => "class",
+            Self::Enum => "enum",
+            Self::EnumMember => "enumMember",
+            Self::Interface => "interface",
+            Self::Struct => "struct",
+            Self::Type => "type",
+            Self::TypeParameter => "typeParameter",
+            Self::Namespace => "namespace",
+        }
+    }
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|kind| kind == self).unwrap()
+    }
+}
+
+/// Something more that is known about a token, on top of its kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SemanticModifier {
+    /// The token is the name in a declaration, rather than a use of it.
+    Declaration,
+    /// The token is a constant, or an attribute that cannot be changed after construction.
+    Readonly,
+    Static,
+    /// The token is a doc comment.
+    Documentation,
+    /// The token is a builtin, such as `String` or `core`.
+    DefaultLibrary,
+}
+
+impl SemanticModifier {
+    /// Every modifier, in the order of their bits in a legend.
+    pub const ALL: [SemanticModifier; 5] = [
+        Self::Declaration,
+        Self::Readonly,
+        Self::Static,
+        Self::Documentation,
+        Self::DefaultLibrary,
+    ];
+
+    /// Returns the name of the modifier in the Language Server Protocol.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Declaration => "declaration",
+            Self::Readonly => "readonly",
+            Self::Static => "static",
+            Self::Documentation => "documentation",
+            Self::DefaultLibrary => "defaultLibrary",
+        }
+    }
+    /// Returns the bit of the modifier in a set of modifiers encoded as a number.
+    pub fn bit(&self) -> u32 {
+        1 << Self::ALL
+            .iter()
+            .position(|modifier| modifier == self)
+            .unwrap()
+    }
+}
+
+/// A classified piece of a source file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticToken {
+    pub span: Span,
+    pub kind: SemanticTokenKind,
+    pub modifiers: Vec<SemanticModifier>,
+}
+
+/// Classifies the tokens of a file, using the names resolved in it to tell what each identifier refers to.<br>
+/// The tokens are returned in the order they appear. Punctuation and names that could not be resolved are left out.
+pub fn semantic_tokens(source: &str, resolution: &Resolution) -> Vec<SemanticToken> {
+    let mut scanner = Scanner::new(source);
+    scanner.run();
+    let mut names = HashMap::new();
+    for reference in &resolution.references {
+        names.insert(reference.span, (reference.binding, false));
+    }
+    for (id, binding) in resolution.iter() {
+        if !binding.is_builtin() {
+            names.insert(binding.span, (id, true));
+        }
+    }
+    let mut tokens = scanner
+        .tokens
+        .iter()
+        .chain(&scanner.comments)
+        .filter_map(|token| classify(token, &names, resolution))
+        .collect::<Vec<_>>();
+    tokens.sort_by_key(|token| token.span.start);
+    tokens
+}
+
+fn classify(
+    token: &Token,
+    names: &HashMap<Span, (BindingId, bool)>,
+    resolution: &Resolution,
+) -> Option<SemanticToken> {
+    let mut modifiers = vec![];
+    let kind = match &token.kind {
+        TokenKind::Keyword(Keyword::Injunction(_)) => SemanticTokenKind::Injunction,
+        TokenKind::Keyword(_) => SemanticTokenKind::Keyword,
+        TokenKind::Operator(_) => SemanticTokenKind::Operator,
+        TokenKind::Comment(comment) => {
+            if comment.kind == CommentKind::Doc {
+                modifiers.push(SemanticModifier::Documentation);
+            }
+            SemanticTokenKind::Comment
+        }
+        TokenKind::Literal(literal) => match literal.kind {
+            LiteralKind::NumericLiteral => SemanticTokenKind::Number,
+            LiteralKind::BooleanLiteral => SemanticTokenKind::Keyword,
+            _ => SemanticTokenKind::String,
+        },
+        TokenKind::Identifier(_) => {
+            let (id, is_declaration) = *names.get(&token.span)?;
+            let binding = resolution.binding(id);
+            if is_declaration {
+                modifiers.push(SemanticModifier::Declaration);
+            }
+            if binding.is_readonly || binding.kind == BindingKind::Constant {
+                modifiers.push(SemanticModifier::Readonly);
+            }
+            if binding.is_static {
+                modifiers.push(SemanticModifier::Static);
+            }
+            match binding.kind {
+                BindingKind::Variable | BindingKind::Constant | BindingKind::Import => {
+                    SemanticTokenKind::Variable
+                }
+                BindingKind::Parameter => SemanticTokenKind::Parameter,
+                BindingKind::Function => SemanticTokenKind::Function,
+                BindingKind::Method => SemanticTokenKind::Method,
+                BindingKind::Attribute => SemanticTokenKind::Property,
+                BindingKind::Class => SemanticTokenKind::Class,
+                BindingKind::Enum => SemanticTokenKind::Enum,
+                BindingKind::Variant => SemanticTokenKind::EnumMember,
+                BindingKind::Interface => SemanticTokenKind::Interface,
+                BindingKind::Record => SemanticTokenKind::Struct,
+                BindingKind::TypeAlias => SemanticTokenKind::Type,
+                BindingKind::TypeParameter => SemanticTokenKind::TypeParameter,
+                BindingKind::Module => SemanticTokenKind::Namespace,
+                BindingKind::Builtin => {
+                    modifiers.push(SemanticModifier::DefaultLibrary);
+                    if BUILTIN_TYPES.contains(&binding.name.as_str()) {
+                        SemanticTokenKind::Class
+                    } else {
+                        SemanticTokenKind::Namespace
+                    }
+                }
+            }
+        }
+        TokenKind::Punctuation(_) | TokenKind::Invalid(_) | TokenKind::EOF => return None,
+    };
+    Some(SemanticToken {
+        span: token.span,
+        kind,
+        modifiers,
+    })
+}
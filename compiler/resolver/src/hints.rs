@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use ast::{Arena, NodeId, NodeKind, Statement};
+use utils::Span;
+
+use crate::{complete::names, BindingId, BindingKind, Resolution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlayHintKind {
+    /// The type of a name declared without a type label, or the return type of a function declared without one.
+    Type,
+    /// The name of the parameter an argument is passed to.
+    Parameter,
+}
+
+/// A label shown in the text of a file without being written in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlayHint {
+    pub position: usize,
+    /// The label as it would be written, e.g. `: Number`, `-> Nil` or `sides:`.
+    pub label: String,
+    pub kind: InlayHintKind,
+    /// The label is followed by a space, to keep it apart from the text after it.
+    pub padding: bool,
+}
+
+/// Works out the labels that show what is left unwritten in a file: the types of names declared without type labels, the names of the parameters arguments are passed to, and the `-> Nil` of functions declared without a return type.<br>
+/// The hints are returned in the order they appear. Types that cannot be inferred are left out.
+pub fn inlay_hints(statements: &[Statement], resolution: &Resolution) -> Vec<InlayHint> {
+    let arena = Arena::from_statements(statements);
+    let hinter = Hinter {
+        arena: &arena,
+        resolution,
+        names: names(resolution),
+    };
+    let mut hints = vec![];
+    for (id, node) in arena.iter() {
+        match &node.kind {
+            NodeKind::VariableDeclaration {
+                name,
+                type_label: None,
+                ..
+            }
+            | NodeKind::Parameter { name, label: None }
+            | NodeKind::Attribute {
+                key: name,
+                type_label: None,
+                ..
+            }
+            | NodeKind::ForLoop { item: name, .. } => hints.extend(hinter.type_of(*name)),
+            NodeKind::Call { callee, arguments } => {
+                hints.extend(hinter.parameter_names(*callee, arguments))
+            }
+            NodeKind::Function {
+                name,
+                return_type: None,
+                body,
+                ..
+            }
+            | NodeKind::Method {
+                name,
+                return_type: None,
+                body,
+                ..
+            } if !hinter.is_constructor(*name) => hints.push(hinter.returns_nil(*body)),
+            // A function assigned to a variable with a function type returns what the type says.
+            NodeKind::FnExpression {
+                return_type: None,
+                body: Some(body),
+                ..
+            } if !hinter.is_typed_by_variable(id) => hints.push(hinter.returns_nil(*body)),
+            _ => {}
+        }
+    }
+    hints.sort_by_key(|hint| hint.position);
+    hints
+}
+
+struct Hinter<'a> {
+    arena: &'a Arena,
+    resolution: &'a Resolution,
+    names: HashMap<Span, BindingId>,
+}
+
+impl Hinter<'_> {
+    fn binding(&self, node: NodeId) -> Option<BindingId> {
+        self.names.get(&self.arena.span(node)).copied()
+    }
+    /// Shows the type of the name declared at a node after it, if it is known.
+    fn type_of(&self, name: NodeId) -> Option<InlayHint> {
+        let binding = self.resolution.binding(self.binding(name)?);
+        Some(InlayHint {
+            position: self.arena.span(name).end,
+            label: format!(": {}", binding.type_.as_ref()?),
+            kind: InlayHintKind::Type,
+            padding: false,
+        })
+    }
+    /// Shows the names of the parameters before the arguments of a call to a function, a method or the constructor of a class.<br>
+    /// Arguments that are names spelled like their parameters already say what they are, so they have no hint.
+    fn parameter_names(&self, callee: NodeId, arguments: &[NodeId]) -> Vec<InlayHint> {
+        let callee = match self.arena.kind(callee) {
+            NodeKind::Dot { property, .. } | NodeKind::Namespace { property, .. } => *property,
+            _ => callee,
+        };
+        let Some(mut id) = self.binding(callee) else {
+            return vec![];
+        };
+        let binding = self.resolution.binding(id);
+        if binding.kind == BindingKind::Class {
+            match self.resolution.member(id, &binding.name) {
+                Some(constructor) => id = constructor,
+                None => return vec![],
+            }
+        }
+        if !self.resolution.binding(id).kind.is_callable() {
+            return vec![];
+        }
+        let parameters = self
+            .resolution
+            .members_of(id)
+            .filter(|(_, binding)| binding.kind == BindingKind::Parameter);
+        parameters
+            .zip(arguments)
+            .filter(|((_, parameter), argument)| {
+                self.arena.name(**argument) != Some(parameter.name.as_str())
+            })
+            .map(|((_, parameter), argument)| InlayHint {
+                position: self.arena.span(*argument).start,
+                label: format!("{}:", parameter.name),
+                kind: InlayHintKind::Parameter,
+                padding: true,
+            })
+            .collect()
+    }
+    fn is_constructor(&self, name: NodeId) -> bool {
+        let Some(id) = self.binding(name) else {
+            return false;
+        };
+        let binding = self.resolution.binding(id);
+        binding.kind == BindingKind::Method
+            && binding
+                .parent
+                .is_some_and(|parent| self.resolution.binding(parent).name == binding.name)
+    }
+    fn is_typed_by_variable(&self, function: NodeId) -> bool {
+        let parent = self
+            .arena
+            .parent(function)
+            .map(|parent| self.arena.kind(parent));
+        matches!(
+            parent,
+            Some(NodeKind::VariableDeclaration {
+                type_label: Some(_),
+                ..
+            })
+        )
+    }
+    /// Shows the return type of a function declared without one before its body.
+    fn returns_nil(&self, body: NodeId) -> InlayHint {
+        InlayHint {
+            position: self.arena.span(body).start,
+            label: "-> Nil".to_string(),
+            kind: InlayHintKind::Type,
+            padding: true,
+        }
+    }
+}
@@ -1 +1,22 @@
-pub struct Resolver {}
+mod binding;
+mod complete;
+mod highlight;
+mod hints;
+mod outline;
+mod resolve;
+mod test;
+mod types;
+mod workspace;
+
+pub use binding::*;
+pub use complete::{
+    complete, signature_help, Completion, CompletionKind, Completions, SignatureHelp,
+};
+pub use highlight::{semantic_tokens, SemanticModifier, SemanticToken, SemanticTokenKind};
+pub use hints::{inlay_hints, InlayHint, InlayHintKind};
+pub use outline::{folding_ranges, outline, FoldingKind, FoldingRange, OutlineItem, OutlineKind};
+pub use resolve::{resolve, Resolver, BUILTIN_TYPES, BUILTIN_VALUES};
+pub use types::{generic_list, head_of, parameter_list, type_name};
+pub use workspace::{
+    fuzzy_score, is_identifier, relative_to, Occurrence, RenameError, Symbol, Workspace,
+};
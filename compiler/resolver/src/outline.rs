@@ -0,0 +1,341 @@
+use ast::{
+    CommentKind, Enum, Injunction, Keyword, Location, Method, Property, Statement, Token,
+    TokenKind, Variant,
+};
+use parser::Scanner;
+use utils::{Encoding, SourceMap, Span};
+
+use crate::{generic_list, parameter_list, type_name};
+
+/// What a declaration in an outline is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineKind {
+    Variable,
+    Constant,
+    Function,
+    Class,
+    Interface,
+    Constructor,
+    Method,
+    Attribute,
+    Enum,
+    Variant,
+    Record,
+    TypeAlias,
+    Module,
+    /// A `@tests` block, with the functions declared in it.
+    Tests,
+}
+
+/// A declaration in a file, with the declarations nested in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineItem {
+    pub name: String,
+    pub kind: OutlineKind,
+    /// What the declaration takes or holds, e.g. `(a: Number) -> Number` for a function, or the type label of a variable.
+    pub detail: Option<String>,
+    pub is_public: bool,
+    /// The whole declaration, including its body.
+    pub span: Span,
+    /// The name in the declaration, or `@tests` for a test block.
+    pub name_span: Span,
+    pub children: Vec<OutlineItem>,
+}
+
+/// Lists the declarations at the top of a file, each with the ones nested in it: the declarations in modules and test blocks, the members of classes and interfaces, and the variants of enums.<br>
+/// The names declared in the bodies of functions are left out, as they are not reachable from outside.
+pub fn outline(statements: &[Statement]) -> Vec<OutlineItem> {
+    statements.iter().filter_map(item).collect()
+}
+
+fn item(statement: &Statement) -> Option<OutlineItem> {
+    let item = |name: &str, kind, name_span, detail, children| OutlineItem {
+        name: name.to_string(),
+        kind,
+        detail,
+        is_public: false,
+        span: statement.get_range(),
+        name_span,
+        children,
+    };
+    let item = match statement {
+        Statement::PublicModifier(public) => {
+            let mut item = self::item(&public.statement)?;
+            item.is_public = true;
+            item.span = public.span;
+            item
+        }
+        Statement::VariableDeclaration(variable) => item(
+            &variable.name.value,
+            match variable.kind.is_const() {
+                true => OutlineKind::Constant,
+                false => OutlineKind::Variable,
+            },
+            variable.name.span,
+            variable.type_label.as_ref().map(type_name),
+            vec![],
+        ),
+        Statement::Function(function) => item(
+            &function.name.value,
+            OutlineKind::Function,
+            function.name.span,
+            Some(callable(
+                &function.generic_arguments,
+                &function.parameters,
+                &function.return_type,
+            )),
+            vec![],
+        ),
+        Statement::Class(class) => item(
+            &class.name.value,
+            OutlineKind::Class,
+            class.name.span,
+            generics(&class.generic_arguments),
+            members(&class.name.value, &class.properties),
+        ),
+        Statement::Interface(interface) => item(
+            &interface.name.value,
+            OutlineKind::Interface,
+            interface.name.span,
+            generics(&interface.generic_arguments),
+            members(&interface.name.value, &interface.properties),
+        ),
+        Statement::Enum(Enum {
+            name,
+            generic_arguments,
+            variants,
+            ..
+        }) => item(
+            &name.value,
+            OutlineKind::Enum,
+            name.span,
+            generics(generic_arguments),
+            variants.iter().map(variant).collect(),
+        ),
+        Statement::Record(record) => item(
+            &record.name.value,
+            OutlineKind::Record,
+            record.name.span,
+            None,
+            vec![],
+        ),
+        Statement::TypeAlias(alias) => item(
+            &alias.name.value,
+            OutlineKind::TypeAlias,
+            alias.name.span,
+            Some(type_name(&alias.value)),
+            vec![],
+        ),
+        Statement::Module(module) => item(
+            &module.name.value,
+            OutlineKind::Module,
+            module.name.span,
+            None,
+            outline(&module.body.body),
+        ),
+        Statement::TestBlock(tests) => {
+            let span = Span {
+                end: tests.span.start + "@tests".len(),
+                ..tests.span
+            };
+            item(
+                "@tests",
+                OutlineKind::Tests,
+                span,
+                None,
+                outline(&tests.body.body),
+            )
+        }
+        _ => return None,
+    };
+    Some(item)
+}
+
+fn members(owner: &str, properties: &[Property]) -> Vec<OutlineItem> {
+    properties
+        .iter()
+        .filter_map(|property| match property {
+            Property::Method(method) => Some(self::method(owner, method)),
+            Property::Attribute(attribute) => Some(OutlineItem {
+                name: attribute.key.value.to_string(),
+                kind: OutlineKind::Attribute,
+                detail: attribute.type_label.as_ref().map(type_name),
+                is_public: false,
+                span: attribute.span,
+                name_span: attribute.key.span,
+                children: vec![],
+            }),
+            Property::Implement(_) => None,
+        })
+        .collect()
+}
+
+fn method(owner: &str, method: &Method) -> OutlineItem {
+    // A constructor is a method with the name of its class.
+    let kind = match method.name.value == owner {
+        true => OutlineKind::Constructor,
+        false => OutlineKind::Method,
+    };
+    OutlineItem {
+        name: method.name.value.to_string(),
+        kind,
+        detail: Some(callable(
+            &method.generic_arguments,
+            &method.parameters,
+            &method.return_type,
+        )),
+        is_public: false,
+        span: method.span,
+        name_span: method.name.span,
+        children: vec![],
+    }
+}
+
+fn variant(variant: &Variant) -> OutlineItem {
+    let (name, detail) = match variant {
+        Variant::Tuple { name, elements, .. } => {
+            let elements = elements.iter().map(type_name).collect::<Vec<_>>();
+            (name, Some(format!("({})", elements.join(", "))))
+        }
+        Variant::Concrete { name, .. } => (name, None),
+    };
+    OutlineItem {
+        name: name.value.to_string(),
+        kind: OutlineKind::Variant,
+        detail,
+        is_public: false,
+        span: variant.get_range(),
+        name_span: name.span,
+        children: vec![],
+    }
+}
+
+fn callable(
+    generic_arguments: &Option<Vec<ast::GenericArgument>>,
+    parameters: &[ast::Parameter],
+    return_type: &Option<ast::Type>,
+) -> String {
+    let mut callable = format!(
+        "{}({})",
+        generic_list(generic_arguments),
+        parameter_list(parameters)
+    );
+    if let Some(return_type) = return_type {
+        callable.push_str(" -> ");
+        callable.push_str(&type_name(return_type));
+    }
+    callable
+}
+
+fn generics(generic_arguments: &Option<Vec<ast::GenericArgument>>) -> Option<String> {
+    Some(generic_list(generic_arguments)).filter(|generics| !generics.is_empty())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldingKind {
+    /// The inside of a pair of braces, such as the body of a function or a class.
+    Block,
+    /// A run of doc comments, or a block comment over several lines.
+    Comment,
+    /// A run of `@use` statements.
+    Imports,
+}
+
+/// A run of lines that an editor can collapse, from `start` to `end`, counting from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoldingRange {
+    pub start: u64,
+    pub end: u64,
+    pub kind: FoldingKind,
+}
+
+/// Finds the parts of a file that can be folded: the insides of braces over several lines, runs of doc comments and runs of `@use` statements.<br>
+/// The ranges are worked out from the tokens, so they can be found in files that do not parse. The line a block is closed on is left out of it, so that the closing brace stays visible.
+pub fn folding_ranges(source: &str) -> Vec<FoldingRange> {
+    let mut scanner = Scanner::new(source);
+    scanner.run();
+    let mut sources = SourceMap::new();
+    let file = sources.add("", source);
+    let line = |offset| sources.get(file).line_column(offset, Encoding::Utf8).line as u64 + 1;
+    let tokens = &scanner.tokens;
+    let mut ranges = vec![];
+    let mut open = vec![];
+    for (index, token) in tokens.iter().enumerate() {
+        if token.is_bracket(&ast::BracketKind::LeftCurly) {
+            open.push(index);
+        } else if token.is_bracket(&ast::BracketKind::RightCurly) {
+            let Some(start) = open.pop() else {
+                continue;
+            };
+            // The braces of a `@use` statement are folded with the statement.
+            let in_import = start > 0 && is_use(&tokens[start - 1]);
+            let (start, end) = (line(tokens[start].span.start), line(token.span.start));
+            if !in_import && end > start + 1 {
+                ranges.push(FoldingRange {
+                    start,
+                    end: end - 1,
+                    kind: FoldingKind::Block,
+                });
+            }
+        }
+    }
+    let mut index = 0;
+    while index < tokens.len() {
+        if !is_use(&tokens[index]) {
+            index += 1;
+            continue;
+        }
+        let start = line(tokens[index].span.start);
+        let mut end = start;
+        // A run goes on for as long as the statement after each `@use` statement is another one.
+        while index < tokens.len() && is_use(&tokens[index]) {
+            let Some(semicolon) = tokens[index..].iter().position(Token::is_semi_colon) else {
+                index = tokens.len();
+                break;
+            };
+            index += semicolon;
+            end = line(tokens[index].span.start);
+            index += 1;
+        }
+        if end > start {
+            ranges.push(FoldingRange {
+                start,
+                end,
+                kind: FoldingKind::Imports,
+            });
+        }
+    }
+    let mut comments = scanner.comments.iter().peekable();
+    while let Some(comment) = comments.next() {
+        let TokenKind::Comment(kind) = &comment.kind else {
+            continue;
+        };
+        let start = line(comment.span.start);
+        // Line and doc comments end with the line they are on.
+        let mut end = match kind.kind {
+            CommentKind::Block => line(comment.span.end),
+            _ => start,
+        };
+        if kind.kind == CommentKind::Doc {
+            while let Some(next) = comments.next_if(|next| {
+                matches!(&next.kind, TokenKind::Comment(next) if next.kind == CommentKind::Doc)
+                    && line(next.span.start) == end + 1
+            }) {
+                end = line(next.span.start);
+            }
+        }
+        if end > start {
+            ranges.push(FoldingRange {
+                start,
+                end,
+                kind: FoldingKind::Comment,
+            });
+        }
+    }
+    ranges.sort_by_key(|range| (range.start, range.end));
+    ranges
+}
+
+fn is_use(token: &Token) -> bool {
+    token.is_keyword(&Keyword::Injunction(Injunction::Use))
+}
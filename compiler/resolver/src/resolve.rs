@@ -0,0 +1,944 @@
+use ast::{
+    Attribute, Block, Class, ConcreteType, DotType, Enum, Expression, FnExpression, ForLoop,
+    Function, FunctionType, GenericArgument, Identifier, IfStatement, Interface, Location, Loop,
+    Method, Module, Operator, Parameter, Property, Record, RecoverBlock, Statement, TemplatePart,
+    TryBlock, Type, TypeAlias, UseImport, VarKind, VariableDeclaration, Variant, WhileStatement,
+};
+use errors::{ResolverError, ResolverWarnings};
+use parser::Diagnostic;
+use utils::Stage;
+
+use crate::{
+    types::{generic_list, head_of, parameter_list, type_name},
+    Binding, BindingId, BindingKind, ForeignMember, ImportedName, Reference, Resolution, Scope,
+    ScopeId,
+};
+use utils::Span;
+
+/// The types that can be named anywhere without being declared or imported.
+pub const BUILTIN_TYPES: [&str; 10] = [
+    "Number",
+    "String",
+    "Boolean",
+    "Character",
+    "Nil",
+    "Array",
+    "Result",
+    "UnsignedInt",
+    "Float",
+    "Any",
+];
+
+/// The values that can be named anywhere without being declared or imported.
+pub const BUILTIN_VALUES: [&str; 1] = ["core"];
+
+/// Resolves every name in a file to the declaration it refers to.<br>
+/// Functions, types, modules and imports can be used anywhere in the block they are declared in, while variables can only be used after their declaration.
+/// Statements and expressions that could not be parsed are skipped, so a file with syntax errors is resolved as far as it can be.
+pub fn resolve(statements: &[Statement]) -> Resolution {
+    let mut resolver = Resolver::new();
+    resolver.statements(statements);
+    resolver.finish()
+}
+
+/// Walks a syntax tree, declaring names in scopes as it meets them and looking up every name that is used.
+pub struct Resolver {
+    resolution: Resolution,
+    /// The names visible at the point being resolved, with a field for each scope.
+    stage: Stage<String, BindingId>,
+    scope: ScopeId,
+    /// The declaration that names declared now are members of, such as the module whose body is being resolved.
+    container: Option<BindingId>,
+    /// The classes and interfaces whose bodies are being resolved, innermost last. `self` and `Self` refer to the last one.
+    classes: Vec<BindingId>,
+    /// The variable whose initializer is being resolved.
+    initializing: Option<String>,
+    /// The names used after `::` that the enum or module before them had no member for when they were met.<br>
+    /// They are looked up again once the whole file is resolved, as the body of a module can come after the names in it are used.
+    unknown_members: Vec<(BindingId, String, Span)>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        let mut resolver = Resolver {
+            resolution: Resolution::default(),
+            stage: Stage::forgetful(),
+            scope: ScopeId::ROOT,
+            container: None,
+            classes: vec![],
+            initializing: None,
+            unknown_members: vec![],
+        };
+        resolver.resolution.scopes.push(Scope {
+            // The root scope covers the whole file, whatever its length.
+            span: Span {
+                end: usize::MAX,
+                ..Span::default()
+            },
+            parent: None,
+            bindings: vec![],
+        });
+        for name in BUILTIN_TYPES.into_iter().chain(BUILTIN_VALUES) {
+            let identifier = Identifier {
+                value: name.to_string(),
+                span: Span::default(),
+            };
+            resolver.declare(&identifier, BindingKind::Builtin, Span::default(), true);
+        }
+        resolver
+    }
+    /// Returns the resolved file, with an error for every member of an enum or module that is not declared,
+    /// and a warning for every local name that is never used.
+    pub fn finish(mut self) -> Resolution {
+        for (owner, name, span) in std::mem::take(&mut self.unknown_members) {
+            match self.resolution.member(owner, &name) {
+                Some(member) => self.refer(span, member),
+                None => {
+                    let owner = self.resolution.binding(owner).name.clone();
+                    self.error(ResolverError::UndefinedMember(owner, name), span);
+                }
+            }
+        }
+        let unused = self
+            .resolution
+            .iter()
+            .filter(|(id, binding)| {
+                matches!(
+                    binding.kind,
+                    BindingKind::Variable | BindingKind::Constant | BindingKind::Import
+                ) && !binding.is_public
+                    && !binding.name.starts_with('_')
+                    && self.resolution.references_to(*id).next().is_none()
+            })
+            .map(|(_, binding)| {
+                Diagnostic::ResolverWarning(
+                    ResolverWarnings::UnusedVariable(binding.name.clone()),
+                    binding.span,
+                )
+            })
+            .collect::<Vec<_>>();
+        self.resolution.diagnostics.extend(unused);
+        self.resolution
+            .diagnostics
+            .sort_by_key(|diagnostic| diagnostic.span().start);
+        self.resolution
+    }
+    fn enter(&mut self, span: Span) {
+        let id = ScopeId(self.resolution.scopes.len() as u32);
+        self.resolution.scopes.push(Scope {
+            span,
+            parent: Some(self.scope),
+            bindings: vec![],
+        });
+        self.stage.enter();
+        self.scope = id;
+    }
+    fn exit(&mut self) {
+        self.stage.exit();
+        self.scope = self.resolution.scopes[self.scope.index()]
+            .parent
+            .unwrap_or(ScopeId::ROOT);
+    }
+    /// Declares a name in the current scope, as a member of the current container.
+    fn declare(
+        &mut self,
+        name: &Identifier,
+        kind: BindingKind,
+        declaration: Span,
+        hoisted: bool,
+    ) -> BindingId {
+        let id = self.member(name, kind, declaration, self.container);
+        let binding = &mut self.resolution.bindings[id.index()];
+        if hoisted {
+            binding.visible_from = self.resolution.scopes[self.scope.index()].span.start;
+        }
+        self.resolution.scopes[self.scope.index()].bindings.push(id);
+        self.stage.set(name.value.to_string(), id);
+        id
+    }
+    /// Records a name that is not visible on its own, but only through its parent, e.g. a method of a class.
+    fn member(
+        &mut self,
+        name: &Identifier,
+        kind: BindingKind,
+        declaration: Span,
+        parent: Option<BindingId>,
+    ) -> BindingId {
+        let id = BindingId(self.resolution.bindings.len() as u32);
+        self.resolution.bindings.push(Binding {
+            name: name.value.to_string(),
+            kind,
+            span: name.span,
+            declaration,
+            docs: None,
+            type_: None,
+            parent,
+            scope: self.scope,
+            visible_from: declaration.end,
+            is_public: false,
+            is_static: false,
+            is_readonly: false,
+        });
+        id
+    }
+    fn binding_mut(&mut self, id: BindingId) -> &mut Binding {
+        &mut self.resolution.bindings[id.index()]
+    }
+    fn refer(&mut self, span: Span, binding: BindingId) {
+        self.resolution.references.push(Reference { span, binding });
+    }
+    fn error(&mut self, error: ResolverError, span: Span) {
+        self.resolution
+            .diagnostics
+            .push(Diagnostic::Resolver(error, span));
+    }
+    fn lookup(&self, name: &str) -> Option<BindingId> {
+        self.stage.lookup(name.to_string()).copied()
+    }
+
+    fn statements(&mut self, statements: &[Statement]) {
+        let hoisted = statements
+            .iter()
+            .map(|statement| self.hoist(statement))
+            .collect::<Vec<_>>();
+        for (statement, id) in statements.iter().zip(hoisted) {
+            self.statement(statement, id);
+        }
+    }
+    fn block(&mut self, block: &Block) {
+        self.enter(block.span);
+        self.statements(&block.body);
+        self.exit();
+    }
+    /// Declares the names of a statement that can be used before it, i.e. everything but variables.
+    fn hoist(&mut self, statement: &Statement) -> Option<BindingId> {
+        let (statement, is_public) = match statement {
+            Statement::PublicModifier(public) => (public.statement.as_ref(), true),
+            statement => (statement, false),
+        };
+        let (name, kind) = match statement {
+            Statement::Function(Function { name, .. }) => (name, BindingKind::Function),
+            Statement::Class(Class { name, .. }) => (name, BindingKind::Class),
+            Statement::Interface(Interface { name, .. }) => (name, BindingKind::Interface),
+            Statement::Enum(Enum { name, .. }) => (name, BindingKind::Enum),
+            Statement::Record(Record { name, .. }) => (name, BindingKind::Record),
+            Statement::TypeAlias(TypeAlias { name, .. }) => (name, BindingKind::TypeAlias),
+            Statement::Module(Module { name, .. }) => (name, BindingKind::Module),
+            Statement::UseImport(use_import) => {
+                self.imports(use_import);
+                return None;
+            }
+            _ => return None,
+        };
+        let id = self.declare(name, kind, statement.get_range(), true);
+        let binding = self.binding_mut(id);
+        binding.docs = statement.docs().map(str::to_string);
+        binding.is_public = is_public;
+        Some(id)
+    }
+    fn imports(&mut self, use_import: &UseImport) {
+        for import in &use_import.imports {
+            // A collapsed import without a name spreads the exports of the file, which cannot be known from this file alone.
+            let name = match &import.local_name {
+                Some(local_name) => local_name,
+                None if import.collapsed_import => continue,
+                None => &import.imported_name,
+            };
+            let binding = self.declare(name, BindingKind::Import, import.span, true);
+            self.resolution.imports.push(ImportedName {
+                binding,
+                source: use_import.source.value.to_string(),
+                imported_name: import.imported_name.value.to_string(),
+                imported_span: import.imported_name.span,
+                local_span: import.local_name.as_ref().map(|local_name| local_name.span),
+            });
+        }
+    }
+    fn statement(&mut self, statement: &Statement, hoisted: Option<BindingId>) {
+        match statement {
+            Statement::PublicModifier(public) => match public.statement.as_ref() {
+                Statement::VariableDeclaration(variable) => {
+                    let id = self.variable(variable);
+                    self.binding_mut(id).is_public = true;
+                }
+                statement => self.statement(statement, hoisted),
+            },
+            Statement::VariableDeclaration(variable) => {
+                self.variable(variable);
+            }
+            Statement::Function(function) => {
+                let id = self.declared(statement, hoisted);
+                self.function(function, id);
+            }
+            Statement::Class(Class {
+                generic_arguments,
+                properties,
+                span,
+                ..
+            })
+            | Statement::Interface(Interface {
+                generic_arguments,
+                properties,
+                span,
+                ..
+            }) => {
+                let id = self.declared(statement, hoisted);
+                self.class(id, generic_arguments, properties, *span);
+            }
+            Statement::Enum(enum_) => {
+                let id = self.declared(statement, hoisted);
+                self.enum_(enum_, id);
+            }
+            Statement::Record(Record { mappings, .. }) => {
+                self.declared(statement, hoisted);
+                for mapping in mappings {
+                    self.expression(&mapping.key);
+                    self.expression(&mapping.value);
+                }
+            }
+            Statement::TypeAlias(TypeAlias {
+                generic_arguments,
+                value,
+                span,
+                ..
+            }) => {
+                let id = self.declared(statement, hoisted);
+                self.enter(*span);
+                self.generic_arguments(generic_arguments, Some(id));
+                self.type_(value);
+                self.exit();
+                self.binding_mut(id).type_ = Some(type_name(value));
+            }
+            Statement::Module(Module { body, .. }) => {
+                let id = self.declared(statement, hoisted);
+                let outer = self.container.replace(id);
+                self.block(body);
+                self.container = outer;
+            }
+            Statement::TestBlock(test) => self.block(&test.body),
+            Statement::BlockStatement(block) => self.block(block),
+            Statement::IfStatement(IfStatement {
+                test,
+                body,
+                alternate,
+                ..
+            }) => {
+                self.expression(test);
+                self.statement(body, None);
+                if let Some(alternate) = alternate {
+                    self.statement(alternate, None);
+                }
+            }
+            Statement::WhileLoop(WhileStatement { test, body, .. }) => {
+                self.expression(test);
+                self.statement(body, None);
+            }
+            Statement::LoopStmnt(Loop {
+                constraint, body, ..
+            }) => {
+                if let Some(constraint) = constraint {
+                    self.expression(constraint);
+                }
+                self.block(body);
+            }
+            Statement::ForLoop(ForLoop {
+                item,
+                iterator,
+                body,
+                span,
+            }) => {
+                self.expression(iterator);
+                self.enter(*span);
+                let id = self.declare(item, BindingKind::Variable, item.span, false);
+                let element = self
+                    .infer(iterator)
+                    .and_then(|iterator| element_of(&iterator));
+                self.binding_mut(id).type_ = element;
+                self.statement(body, None);
+                self.exit();
+            }
+            Statement::TryBlock(TryBlock { body, recover, .. }) => {
+                self.block(body);
+                if let Some(RecoverBlock { params, span, body }) = recover {
+                    self.enter(*span);
+                    self.parameters(params, None);
+                    self.block(body);
+                    self.exit();
+                }
+            }
+            Statement::PrintLnStatement(print) => self.expression(&print.argument),
+            Statement::CrashStmnt(crash) => self.expression(&crash.argument),
+            Statement::ExpressionStatement(statement) => self.expression(&statement.expression),
+            Statement::ReturnStatement(statement) => {
+                if let Some(argument) = &statement.argument {
+                    self.expression(argument);
+                }
+            }
+            // Imports are declared when they are hoisted.
+            Statement::UseImport(_)
+            | Statement::PrependStatement(_)
+            | Statement::Break(_)
+            | Statement::Continue(_)
+            | Statement::EmptyStatement(_)
+            | Statement::Error(_) => {}
+        }
+    }
+    /// Returns the binding hoisted for a declaration, declaring it now if it was not, e.g. because it is the body of an if statement.
+    fn declared(&mut self, statement: &Statement, hoisted: Option<BindingId>) -> BindingId {
+        match hoisted {
+            Some(id) => id,
+            None => self
+                .hoist(statement)
+                .expect("declarations are always hoisted"),
+        }
+    }
+    fn variable(&mut self, variable: &VariableDeclaration) -> BindingId {
+        let VariableDeclaration {
+            name,
+            kind,
+            initializer,
+            type_label,
+            docs,
+            span,
+        } = variable;
+        if let Some(label) = type_label {
+            self.type_(label);
+        }
+        let mut type_ = type_label.as_ref().map(type_name);
+        if let Some(initializer) = initializer {
+            self.initializing = Some(name.value.to_string());
+            self.expression(initializer);
+            self.initializing = None;
+            type_ = type_.or_else(|| self.infer(initializer));
+            // The parameters of a function assigned to a variable with a function type take their types from it.
+            if let (Some(Type::Function(label)), Expression::FnExpression(function)) =
+                (type_label, initializer)
+            {
+                self.type_parameters(&function.parameters, &label.parameters);
+            }
+        }
+        let kind = match kind {
+            VarKind::Let => BindingKind::Variable,
+            VarKind::Const => BindingKind::Constant,
+        };
+        let id = self.declare(name, kind, *span, false);
+        let binding = self.binding_mut(id);
+        binding.docs = docs.clone();
+        binding.type_ = type_;
+        id
+    }
+    /// Gives each parameter without a type label the type of the parameter in the same place in a function type.
+    fn type_parameters(&mut self, parameters: &[Parameter], labels: &[Parameter]) {
+        for (parameter, label) in parameters.iter().zip(labels) {
+            let (None, Some(label)) = (&parameter.label, &label.label) else {
+                continue;
+            };
+            let declared = self.resolution.iter().find(|(_, binding)| {
+                binding.kind == BindingKind::Parameter && binding.span == parameter.name.span
+            });
+            if let Some((id, _)) = declared {
+                self.binding_mut(id).type_ = Some(type_name(label));
+            }
+        }
+    }
+    fn function(&mut self, function: &Function, id: BindingId) {
+        let Function {
+            generic_arguments,
+            parameters,
+            return_type,
+            body,
+            span,
+            ..
+        } = function;
+        self.callable(
+            Some(id),
+            generic_arguments,
+            parameters,
+            return_type,
+            *span,
+            |resolver| resolver.block(body),
+        );
+        self.binding_mut(id).type_ = return_type.as_ref().map(type_name);
+    }
+    /// Resolves the generic arguments, parameters, return type and body of a function, method or functional expression in a scope of their own.
+    fn callable(
+        &mut self,
+        id: Option<BindingId>,
+        generic_arguments: &Option<Vec<GenericArgument>>,
+        parameters: &[Parameter],
+        return_type: &Option<Type>,
+        span: Span,
+        body: impl FnOnce(&mut Self),
+    ) {
+        self.enter(span);
+        let outer = self.container.take();
+        self.generic_arguments(generic_arguments, id);
+        self.parameters(parameters, id);
+        if let Some(return_type) = return_type {
+            self.type_(return_type);
+        }
+        body(self);
+        self.container = outer;
+        self.exit();
+    }
+    fn generic_arguments(
+        &mut self,
+        arguments: &Option<Vec<GenericArgument>>,
+        parent: Option<BindingId>,
+    ) {
+        for argument in arguments.iter().flatten() {
+            for interface in argument.implements.iter().flatten() {
+                self.type_reference(interface);
+            }
+            let id = self.declare(
+                &argument.name,
+                BindingKind::TypeParameter,
+                argument.span,
+                true,
+            );
+            self.binding_mut(id).parent = parent;
+        }
+    }
+    fn parameters(&mut self, parameters: &[Parameter], parent: Option<BindingId>) {
+        for parameter in parameters {
+            if let Some(label) = &parameter.label {
+                self.type_(label);
+            }
+            let id = self.declare(
+                &parameter.name,
+                BindingKind::Parameter,
+                parameter.span,
+                false,
+            );
+            let binding = self.binding_mut(id);
+            binding.parent = parent;
+            binding.type_ = parameter.label.as_ref().map(type_name);
+        }
+    }
+    fn class(
+        &mut self,
+        id: BindingId,
+        generic_arguments: &Option<Vec<GenericArgument>>,
+        properties: &[Property],
+        span: Span,
+    ) {
+        self.enter(span);
+        self.classes.push(id);
+        self.generic_arguments(generic_arguments, Some(id));
+        // Members are declared before any body is resolved, so that methods can use each other through `self`.
+        let members = properties
+            .iter()
+            .map(|property| match property {
+                Property::Method(Method {
+                    name,
+                    is_static,
+                    return_type,
+                    docs,
+                    span,
+                    ..
+                }) => {
+                    let member = self.member(name, BindingKind::Method, *span, Some(id));
+                    let binding = self.binding_mut(member);
+                    binding.is_static = *is_static;
+                    binding.docs = docs.clone();
+                    binding.type_ = return_type.as_ref().map(type_name);
+                    Some(member)
+                }
+                Property::Attribute(Attribute {
+                    key,
+                    type_label,
+                    is_static,
+                    is_readonly,
+                    docs,
+                    span,
+                    ..
+                }) => {
+                    let member = self.member(key, BindingKind::Attribute, *span, Some(id));
+                    let binding = self.binding_mut(member);
+                    binding.is_static = *is_static;
+                    binding.is_readonly = *is_readonly;
+                    binding.docs = docs.clone();
+                    binding.type_ = type_label.as_ref().map(type_name);
+                    Some(member)
+                }
+                Property::Implement(implement) => {
+                    self.type_reference(&implement.interface);
+                    if let Some(interface) = self.lookup(&implement.interface.value) {
+                        self.resolution.implements.push((id, interface));
+                    }
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        for (property, member) in properties.iter().zip(members) {
+            match property {
+                Property::Method(Method {
+                    generic_arguments,
+                    parameters,
+                    return_type,
+                    body,
+                    span,
+                    ..
+                }) => self.callable(
+                    member,
+                    generic_arguments,
+                    parameters,
+                    return_type,
+                    *span,
+                    |resolver| resolver.block(body),
+                ),
+                Property::Attribute(Attribute {
+                    type_label, value, ..
+                }) => {
+                    if let Some(label) = type_label {
+                        self.type_(label);
+                    }
+                    if let (Some(value), Some(member)) = (value, member) {
+                        self.expression(value);
+                        if type_label.is_none() {
+                            self.binding_mut(member).type_ = self.infer(value);
+                        }
+                    }
+                }
+                Property::Implement(_) => {}
+            }
+        }
+        self.classes.pop();
+        self.exit();
+    }
+    fn enum_(&mut self, enum_: &Enum, id: BindingId) {
+        self.enter(enum_.span);
+        self.generic_arguments(&enum_.generic_arguments, Some(id));
+        for variant in &enum_.variants {
+            let (name, span) = match variant {
+                Variant::Tuple { name, span, .. } | Variant::Concrete { name, span, .. } => {
+                    (name, *span)
+                }
+            };
+            let member = self.member(name, BindingKind::Variant, span, Some(id));
+            let binding = self.binding_mut(member);
+            binding.docs = variant.docs().map(str::to_string);
+            binding.type_ = Some(enum_.name.value.to_string());
+            if let Variant::Tuple { elements, .. } = variant {
+                elements.iter().for_each(|element| self.type_(element));
+            }
+        }
+        self.exit();
+    }
+
+    fn type_(&mut self, type_: &Type) {
+        match type_ {
+            Type::Concrete(ConcreteType {
+                name, arguments, ..
+            }) => {
+                self.type_reference(name);
+                arguments.iter().for_each(|argument| self.type_(argument));
+            }
+            Type::Function(FunctionType {
+                parameters,
+                return_type,
+                generic_arguments,
+                span,
+            }) => {
+                self.enter(*span);
+                self.generic_arguments(generic_arguments, None);
+                for label in parameters
+                    .iter()
+                    .filter_map(|parameter| parameter.label.as_ref())
+                {
+                    self.type_(label);
+                }
+                self.type_(return_type);
+                self.exit();
+            }
+            // Only the first level is looked up, as the rest are members of other files or modules.
+            Type::Dot(DotType { levels, .. }) => match levels.first() {
+                Some(Type::Concrete(ConcreteType { name, .. })) => self.value(name),
+                Some(level) => self.type_(level),
+                None => {}
+            },
+        }
+    }
+    fn type_reference(&mut self, name: &Identifier) {
+        if name.value == "Self" {
+            if let Some(class) = self.classes.last() {
+                self.refer(name.span, *class);
+                return;
+            }
+        }
+        match self.lookup(&name.value) {
+            Some(id) => {
+                if !self.resolution.binding(id).kind.is_type() {
+                    let error = ResolverError::UsingVariableAsType(name.value.to_string());
+                    self.error(error, name.span);
+                }
+                self.refer(name.span, id);
+            }
+            None => self.error(
+                ResolverError::UndefinedType(name.value.to_string()),
+                name.span,
+            ),
+        }
+    }
+    fn value(&mut self, name: &Identifier) {
+        match self.lookup(&name.value) {
+            Some(id) => {
+                if !self.resolution.binding(id).kind.is_value() {
+                    let error = ResolverError::UsingTypeAsVariable(name.value.to_string());
+                    self.error(error, name.span);
+                }
+                self.refer(name.span, id);
+            }
+            None if self.initializing.as_deref() == Some(&name.value) => self.error(
+                ResolverError::UseBeforeAssign(name.value.to_string()),
+                name.span,
+            ),
+            None => self.error(
+                ResolverError::UndefinedVariable(name.value.to_string()),
+                name.span,
+            ),
+        }
+    }
+    fn expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::IdentifierExpression(identifier) => self.value(identifier),
+            Expression::TemplateString(template) => {
+                for part in &template.parts {
+                    if let TemplatePart::Expression(expression) = part {
+                        self.expression(expression);
+                    }
+                }
+            }
+            Expression::BinaryExpression(ast::BinaryExpression { operands, .. })
+            | Expression::LogicalExpression(ast::LogicalExpression { operands, .. })
+            | Expression::AssignmentExpression(ast::AssignmentExpression { operands, .. })
+            | Expression::ArrayExpression(ast::ArrayExpression {
+                elements: operands, ..
+            })
+            | Expression::IndexExpression(ast::IndexExpression {
+                accessor_and_property: operands,
+                ..
+            })
+            | Expression::RangeExpression(ast::RangeExpression {
+                boundaries: operands,
+                ..
+            }) => operands.iter().for_each(|operand| self.expression(operand)),
+            Expression::UnaryExpression(unary) => self.expression(&unary.operand),
+            Expression::CallExpression(call) => {
+                self.expression(&call.callee);
+                call.arguments
+                    .iter()
+                    .for_each(|argument| self.expression(argument));
+            }
+            Expression::DotExpression(ast::DotExpression {
+                object, property, ..
+            })
+            | Expression::NamespaceExpression(ast::NamespaceExpression {
+                object, property, ..
+            }) => {
+                self.expression(object);
+                if let (Expression::IdentifierExpression(property), Some(owner)) =
+                    (property.as_ref(), self.owner_of(object))
+                {
+                    match self.resolution.member(owner, &property.value) {
+                        Some(member) => self.refer(property.span, member),
+                        // Members of classes can come from the interfaces they implement, so only enums and modules are known to be missing one.
+                        None if matches!(expression, Expression::NamespaceExpression(_))
+                            && matches!(
+                                self.resolution.binding(owner).kind,
+                                BindingKind::Enum | BindingKind::Module
+                            ) =>
+                        {
+                            self.unknown_members.push((
+                                owner,
+                                property.value.to_string(),
+                                property.span,
+                            ));
+                        }
+                        // The members of an import are declared in another file.
+                        None if self.resolution.binding(owner).kind == BindingKind::Import => {
+                            self.resolution.foreign_members.push(ForeignMember {
+                                owner,
+                                name: property.value.to_string(),
+                                span: property.span,
+                            });
+                        }
+                        None => {}
+                    }
+                }
+            }
+            Expression::TernaryExpression(ternary) => {
+                self.expression(&ternary.test);
+                self.expression(&ternary.consequent);
+                self.expression(&ternary.alternate);
+            }
+            Expression::FnExpression(FnExpression {
+                labels,
+                parameters,
+                return_type,
+                body,
+                implicit_return,
+                span,
+            }) => self.callable(None, labels, parameters, return_type, *span, |resolver| {
+                if let Some(body) = body {
+                    resolver.block(body);
+                }
+                if let Some(implicit_return) = implicit_return {
+                    resolver.expression(implicit_return);
+                }
+            }),
+            Expression::StringExpression(_)
+            | Expression::NumericExpression(_)
+            | Expression::BooleanExpression(_)
+            | Expression::CharacterExpression(_)
+            | Expression::SelfExpression(_)
+            | Expression::Error(_) => {}
+        }
+    }
+    /// Returns the binding an expression names, if it is a name or a member of a known declaration.
+    fn binding_of(&self, expression: &Expression) -> Option<BindingId> {
+        match expression {
+            Expression::IdentifierExpression(identifier) => self.lookup(&identifier.value),
+            Expression::DotExpression(ast::DotExpression {
+                object, property, ..
+            })
+            | Expression::NamespaceExpression(ast::NamespaceExpression {
+                object, property, ..
+            }) => match property.as_ref() {
+                Expression::IdentifierExpression(property) => self
+                    .owner_of(object)
+                    .and_then(|owner| self.resolution.member(owner, &property.value)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+    /// Returns the declaration whose members an expression has, e.g. the class of an instance, or a module itself.<br>
+    /// The declaration can be an import, whose members are those of the export it names.
+    fn owner_of(&self, expression: &Expression) -> Option<BindingId> {
+        if let Expression::SelfExpression(_) = expression {
+            return self.classes.last().copied();
+        }
+        if let Some(id) = self.binding_of(expression) {
+            let binding = self.resolution.binding(id);
+            if matches!(
+                binding.kind,
+                BindingKind::Module
+                    | BindingKind::Class
+                    | BindingKind::Interface
+                    | BindingKind::Enum
+                    | BindingKind::Import
+            ) {
+                return Some(id);
+            }
+        }
+        let type_ = self.infer(expression)?;
+        let owner = self.lookup(head_of(&type_))?;
+        let kind = self.resolution.binding(owner).kind;
+        matches!(
+            kind,
+            BindingKind::Class | BindingKind::Interface | BindingKind::Enum | BindingKind::Import
+        )
+        .then_some(owner)
+    }
+    /// Works out the type of an expression from the literals, declarations and type labels it is built from.<br>
+    /// Returns `None` for expressions whose type cannot be known without a type checker.
+    fn infer(&self, expression: &Expression) -> Option<String> {
+        match expression {
+            Expression::NumericExpression(_) => Some("Number".to_string()),
+            Expression::StringExpression(_) | Expression::TemplateString(_) => {
+                Some("String".to_string())
+            }
+            Expression::BooleanExpression(_) => Some("Boolean".to_string()),
+            Expression::CharacterExpression(_) => Some("Character".to_string()),
+            Expression::SelfExpression(_) => {
+                let class = self.classes.last()?;
+                Some(self.resolution.binding(*class).name.clone())
+            }
+            Expression::ArrayExpression(array) => {
+                let element = self.infer(array.elements.first()?)?;
+                Some(format!("Array<{element}>"))
+            }
+            Expression::IdentifierExpression(_)
+            | Expression::DotExpression(_)
+            | Expression::NamespaceExpression(_) => {
+                let binding = self.resolution.binding(self.binding_of(expression)?);
+                match binding.kind {
+                    BindingKind::Variable
+                    | BindingKind::Constant
+                    | BindingKind::Parameter
+                    | BindingKind::Attribute
+                    | BindingKind::Variant => binding.type_.clone(),
+                    _ => None,
+                }
+            }
+            Expression::CallExpression(call) => {
+                let binding = self.resolution.binding(self.binding_of(&call.callee)?);
+                match binding.kind {
+                    BindingKind::Class => Some(binding.name.clone()),
+                    BindingKind::Function | BindingKind::Method => {
+                        Some(binding.type_.clone().unwrap_or_else(|| "Nil".to_string()))
+                    }
+                    BindingKind::Variant => binding.type_.clone(),
+                    _ => None,
+                }
+            }
+            Expression::BinaryExpression(binary) => {
+                let left = self.infer(&binary.operands[0]);
+                let right = self.infer(&binary.operands[1]);
+                match binary.operator {
+                    Operator::Equals
+                    | Operator::NotEquals
+                    | Operator::GreaterThan
+                    | Operator::LessThan
+                    | Operator::GreaterThanOrEquals
+                    | Operator::LessThanOrEquals => Some("Boolean".to_string()),
+                    Operator::Add if left.as_deref() == Some("String") => left,
+                    _ if left.is_some() && left == right => left,
+                    _ => None,
+                }
+            }
+            Expression::LogicalExpression(_) => Some("Boolean".to_string()),
+            Expression::UnaryExpression(unary) => match unary.operator {
+                Operator::LogicalNot => Some("Boolean".to_string()),
+                _ => self.infer(&unary.operand),
+            },
+            Expression::IndexExpression(index) => {
+                element_of(&self.infer(&index.accessor_and_property[0])?)
+            }
+            Expression::TernaryExpression(ternary) => self.infer(&ternary.consequent),
+            Expression::AssignmentExpression(assignment) => self.infer(&assignment.operands[1]),
+            Expression::FnExpression(FnExpression {
+                labels,
+                parameters,
+                return_type,
+                implicit_return,
+                ..
+            }) => {
+                let returned = match (return_type, implicit_return) {
+                    (Some(return_type), _) => type_name(return_type),
+                    (None, Some(implicit_return)) => self.infer(implicit_return)?,
+                    (None, None) => "Nil".to_string(),
+                };
+                Some(format!(
+                    "{}({}) -> {returned}",
+                    generic_list(labels),
+                    parameter_list(parameters)
+                ))
+            }
+            Expression::RangeExpression(_) | Expression::Error(_) => None,
+        }
+    }
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the type of the elements of an array type, e.g. `String` for `Array<String>`.
+fn element_of(type_: &str) -> Option<String> {
+    let element = type_.strip_prefix("Array<")?.strip_suffix('>')?;
+    Some(element.to_string())
+}
@@ -0,0 +1,873 @@
+#![cfg(test)]
+
+use ast::INJUNCTIONS;
+use errors::{ResolverError, ResolverWarnings};
+use parser::{fixes, parse_source, Diagnostic};
+use utils::{FileId, SourceMap, Span};
+
+use crate::{
+    complete, folding_ranges, fuzzy_score, inlay_hints, outline, resolve, semantic_tokens,
+    signature_help, BindingKind, CompletionKind, Completions, FoldingKind, InlayHintKind,
+    OutlineItem, RenameError, Resolution, SemanticTokenKind, Workspace,
+};
+
+fn resolved(source: &str) -> Resolution {
+    let program = parse_source(source);
+    resolve(program.statements())
+}
+
+/// Returns the one-based line and column of a byte offset in `text`, as an editor shows them.
+fn line_column(text: &str, offset: usize) -> [usize; 2] {
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap().chars().count() + 1;
+    [line, column]
+}
+
+/// Returns the byte offset of a one-based line and column in `text`.
+fn offset(text: &str, line: usize, column: usize) -> usize {
+    let start = text
+        .split_inclusive('\n')
+        .take(line - 1)
+        .map(str::len)
+        .sum::<usize>();
+    start
+        + text[start..]
+            .chars()
+            .take(column - 1)
+            .map(char::len_utf8)
+            .sum::<usize>()
+}
+
+/// Returns the name and line of the binding every reference in the file refers to, in order.<br>
+/// Builtins are not declared in the file, so they are given line 0.
+fn references<'r>(source: &str, resolution: &'r Resolution) -> Vec<(&'r str, usize)> {
+    resolution
+        .references
+        .iter()
+        .map(|reference| {
+            let binding = resolution.binding(reference.binding);
+            let line = match binding.span.is_empty() {
+                true => 0,
+                false => line_column(source, binding.span.start)[0],
+            };
+            (binding.name.as_str(), line)
+        })
+        .collect()
+}
+
+#[test]
+fn it_resolves_names_to_their_declarations() {
+    let source = "@let name = \"Pebble\";
+@function greet(greeting: String) -> String {
+    @let name = greeting;
+    return name;
+}
+println greet(name);";
+    let resolution = resolved(source);
+    assert!(resolution.diagnostics.is_empty());
+    assert_eq!(
+        references(source, &resolution),
+        vec![
+            ("String", 0),
+            ("String", 0),
+            ("greeting", 2),
+            ("name", 3),
+            ("greet", 2),
+            ("name", 1),
+        ]
+    );
+}
+
+#[test]
+fn it_hoists_declarations_but_not_variables() {
+    let source = "println later();
+println x;
+@let x = 1;
+@function later() -> Number {
+    return x;
+}";
+    let resolution = resolved(source);
+    assert_eq!(
+        resolution.diagnostics,
+        vec![Diagnostic::Resolver(
+            ResolverError::UndefinedVariable("x".to_string()),
+            Span::new(FileId::default(), 25, 26)
+        )]
+    );
+    assert_eq!(references(source, &resolution)[0], ("later", 4));
+}
+
+#[test]
+fn it_reports_misused_names() {
+    let resolution = resolved(
+        "@interface Shape {}
+@let count = 0;
+@let area: count = Shape;
+@let total = total + 1;
+@let unused = 2;
+@let _ignored = 3;
+println area + missing;
+@function draw(shape: Circle) {}",
+    );
+    let diagnostics = resolution
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        diagnostics,
+        vec![
+            ResolverError::UsingVariableAsType("count".to_string()).to_string(),
+            ResolverError::UsingTypeAsVariable("Shape".to_string()).to_string(),
+            ResolverWarnings::UnusedVariable("total".to_string()).to_string(),
+            ResolverError::UseBeforeAssign("total".to_string()).to_string(),
+            ResolverWarnings::UnusedVariable("unused".to_string()).to_string(),
+            ResolverError::UndefinedVariable("missing".to_string()).to_string(),
+            ResolverError::UndefinedType("Circle".to_string()).to_string(),
+        ]
+    );
+}
+
+#[test]
+fn it_resolves_members_of_classes_modules_and_enums() {
+    let source = "@class Point {
+    x: Number,
+    ## Moves the point.
+    move(by: Number) -> Point {
+        self.x = self.x + by;
+        return self;
+    }
+}
+@enum Direction { Up, Down }
+@module geometry {
+    @public @function origin() -> Point {}
+}
+@let point = geometry.origin();
+point.move(2).x;
+@let up = Direction::Up;
+println up;";
+    let resolution = resolved(source);
+    assert!(resolution.diagnostics.is_empty());
+    let members = references(source, &resolution)
+        .into_iter()
+        .filter(|(name, _)| ["x", "move", "origin", "Up"].contains(name))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        members,
+        vec![
+            ("x", 2),
+            ("x", 2),
+            ("origin", 11),
+            ("move", 4),
+            ("x", 2),
+            ("Up", 9),
+        ]
+    );
+    let point = resolution
+        .iter()
+        .find(|(_, binding)| binding.name == "point")
+        .unwrap()
+        .1;
+    assert_eq!(point.type_.as_deref(), Some("Point"));
+    let up = resolution
+        .iter()
+        .find(|(_, binding)| binding.name == "up")
+        .unwrap()
+        .1;
+    assert_eq!(up.type_.as_deref(), Some("Direction"));
+}
+
+#[test]
+fn it_reports_unknown_members_of_enums_and_modules() {
+    let source = "@function first() -> Color {
+    return shapes::circle() + shapes::square();
+}
+@enum Color { Red, Green }
+@module shapes {
+    @public @function square() {}
+}
+@let d = Color::Blue;
+@let e = Color::Red;
+println d + e + shapes::square() + shapes::circle;";
+    let resolution = resolved(source);
+    let diagnostics = resolution
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.to_string(),
+                line_column(source, diagnostic.span().start),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        diagnostics,
+        vec![
+            (
+                ResolverError::UndefinedMember("shapes".to_string(), "circle".to_string())
+                    .to_string(),
+                [2, 20]
+            ),
+            (
+                ResolverError::UndefinedMember("Color".to_string(), "Blue".to_string()).to_string(),
+                [8, 17]
+            ),
+            (
+                ResolverError::UndefinedMember("shapes".to_string(), "circle".to_string())
+                    .to_string(),
+                [10, 44]
+            ),
+        ]
+    );
+    // Members used before the module that declares them are still found.
+    let squares = references(source, &resolution)
+        .into_iter()
+        .filter(|reference| *reference == ("square", 6))
+        .count();
+    assert_eq!(squares, 2);
+}
+
+#[test]
+fn it_describes_bindings() {
+    let resolution = resolved(
+        "@function map<T>(items: Array<T>, transform: (item: T) -> T) -> Array<T> {}
+@let names = [\"a\", \"b\"];
+@class Box { static readonly size: Number }",
+    );
+    let signatures = resolution
+        .iter()
+        .filter(|(_, binding)| {
+            matches!(
+                binding.kind,
+                BindingKind::Function | BindingKind::Variable | BindingKind::Attribute
+            )
+        })
+        .map(|(id, _)| resolution.signature(id))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        signatures,
+        vec![
+            "@function map<T>(items: Array<T>, transform: (item: T) -> T) -> Array<T>",
+            "@let names: Array<String>",
+            "static readonly size: Number",
+        ]
+    );
+}
+
+#[test]
+fn it_finds_visible_bindings_at_a_position() {
+    let source = "@let outer = 1;
+@function f(parameter: Number) {
+    @let inner = 2;
+
+    @let later = 3;
+}";
+    let resolution = resolved(source);
+    let visible = resolution
+        .visible_at(offset(source, 4, 5))
+        .into_iter()
+        .map(|id| resolution.binding(id).name.as_str())
+        .filter(|name| !name.chars().next().unwrap().is_uppercase() && *name != "core")
+        .collect::<Vec<_>>();
+    assert_eq!(visible, vec!["inner", "parameter", "f", "outer"]);
+    let binding = resolution.binding_at(offset(source, 3, 11)).unwrap();
+    assert_eq!(resolution.binding(binding).name, "inner");
+}
+
+#[test]
+fn it_resolves_files_with_syntax_errors() {
+    let source = "@let value = 1;
+@function broken() {
+    @let = 2;
+    println value;
+}
+println value + ;";
+    let program = parse_source(source);
+    assert!(!program.is_valid());
+    let resolution = resolve(program.statements());
+    assert!(references(source, &resolution).contains(&("value", 1)));
+}
+
+#[test]
+fn it_classifies_tokens() {
+    let source = "## A point.
+@class Point {
+    static readonly origin: Number,
+    x: Number,
+}
+@const limit = 10;
+@function scale<T>(point: Point, by: T) -> String {
+    return point.x * limit; // Scales.
+}";
+    let resolution = resolved(source);
+    let tokens = semantic_tokens(source, &resolution)
+        .into_iter()
+        .filter(|token| token.kind != SemanticTokenKind::Operator)
+        .map(|token| {
+            let modifiers = token
+                .modifiers
+                .iter()
+                .map(|modifier| format!(" {}", modifier.name()))
+                .collect::<String>();
+            let [line, column] = line_column(source, token.span.start);
+            format!("{line}:{column} {}{modifiers}", token.kind.name())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tokens,
+        vec![
+            "1:1 comment documentation",
+            "2:1 injunction",
+            "2:8 class declaration",
+            "3:5 keyword",
+            "3:12 keyword",
+            "3:21 property declaration readonly static",
+            "3:29 class defaultLibrary",
+            "4:5 property declaration",
+            "4:8 class defaultLibrary",
+            "6:1 injunction",
+            "6:8 variable declaration readonly",
+            "6:16 number",
+            "7:1 injunction",
+            "7:11 function declaration",
+            "7:17 typeParameter declaration",
+            "7:20 parameter declaration",
+            "7:27 class",
+            "7:34 parameter declaration",
+            "7:38 typeParameter",
+            "7:44 class defaultLibrary",
+            "8:5 keyword",
+            "8:12 parameter",
+            "8:18 property",
+            "8:22 variable readonly",
+            "8:29 comment",
+        ]
+    );
+}
+
+/// Completes the source at the `|` in it, resolving imports from `./shapes.peb` to `shapes`.
+fn completions(source: &str, shapes: &str) -> Completions {
+    let offset = source.find('|').unwrap();
+    let source = source.replacen('|', "", 1);
+    let resolution = resolved(&source);
+    let exports = |path: &str| (path == "./shapes.peb").then(|| resolved(shapes));
+    complete(&source, &resolution, offset, &exports)
+}
+
+fn labels(source: &str) -> Vec<String> {
+    completions(source, "")
+        .items
+        .into_iter()
+        .map(|completion| completion.label)
+        .collect()
+}
+
+#[test]
+fn it_completes_names_and_keywords_in_scope() {
+    let labels = labels(
+        "@let outer = 1;
+@function f(parameter: Number) {
+    for (item in [1, 2]) {
+        @let inner = item;
+        |
+    }
+    @let later = 2;
+}",
+    );
+    let position = |label: &str| labels.iter().position(|item| item == label);
+    for label in [
+        "inner",
+        "item",
+        "parameter",
+        "f",
+        "outer",
+        "@let",
+        "return",
+        "break",
+    ] {
+        assert!(position(label).is_some(), "{label} is missing");
+    }
+    assert!(position("inner") < position("outer"));
+    for label in ["later", "@use", "@public", "else"] {
+        assert!(position(label).is_none(), "{label} is suggested");
+    }
+}
+
+#[test]
+fn it_completes_members_in_incomplete_code() {
+    let class = "@class Point {
+    x: Number,
+    static origin: Number,
+    Point(x: Number) {
+        self.|
+    },
+    move(by: Number) -> Point {
+        return self;
+    }
+}
+@enum Direction { Up, Down }
+@module geometry {
+    @public @function area() -> Number {}
+    @function helper() {}
+}
+@let point = Point(1);
+";
+    assert_eq!(labels(class).join(" "), "x move");
+    let class = class.replace("self.|", "self.x = x;");
+    let complete_after = |code: &str| labels(&format!("{class}{code}")).join(" ");
+    assert_eq!(complete_after("point.move(2).|"), "x move");
+    assert_eq!(complete_after("point.m|"), "x move");
+    assert_eq!(complete_after("Point.|"), "origin");
+    assert_eq!(complete_after("Direction::|"), "Up Down");
+    assert_eq!(complete_after("geometry.|"), "area");
+    assert_eq!(complete_after("println missing.|"), "");
+}
+
+#[test]
+fn it_completes_injunctions_and_imports() {
+    let typed = completions("@let a = 1;\n@fu|", "");
+    assert_eq!(typed.span, Span::new(FileId::default(), 12, 15));
+    assert!(typed
+        .items
+        .iter()
+        .all(|item| item.kind == CompletionKind::Injunction));
+    assert_eq!(typed.items.len(), INJUNCTIONS.len());
+    let shapes = "## A shape with corners.
+@public @class Square {}
+@public @function area() -> Number {}
+@function hidden() {}
+@public @let unit = 1;";
+    let imports = completions("@use { Square, | } from \"./shapes.peb\";", shapes);
+    let items = imports
+        .items
+        .iter()
+        .map(|item| (item.label.as_str(), item.detail.as_deref().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        items,
+        vec![
+            ("area", "@function area() -> Number"),
+            ("unit", "@let unit: Number")
+        ]
+    );
+    assert!(labels("@let text = \"hel|lo\"; // a comment").is_empty());
+    assert!(labels("// a comm|ent").is_empty());
+}
+
+fn workspace() -> (Workspace, FileId, FileId) {
+    let mut sources = SourceMap::new();
+    let shapes = sources.add(
+        "pile/src/shapes.peb",
+        "@public @interface Drawable {}
+@public @class Shape {
+    @implement Drawable,
+    sides: Number,
+}
+@public @function make() -> Shape {
+    @let shape: Shape = Shape(3);
+    return shape;
+}",
+    );
+    let main = sources.add(
+        "pile/src/main.peb",
+        "@use { Shape, Drawable as Paintable, make } from \"./shapes.peb\";
+@let first: Shape = make();
+@function draw(item: Paintable) -> Shape {}",
+    );
+    (Workspace::new(sources), shapes, main)
+}
+
+#[test]
+fn it_renames_symbols_across_files() {
+    let (workspace, shapes, main) = workspace();
+    let at = |file, line, column| offset(&workspace.sources.get(file).text, line, column);
+    let edits = workspace.rename(main, at(main, 2, 14), "Polygon").unwrap();
+    assert_eq!(edits.len(), 7);
+    assert_eq!(
+        workspace.sources.get(shapes).apply(&edits),
+        "@public @interface Drawable {}
+@public @class Polygon {
+    @implement Drawable,
+    sides: Number,
+}
+@public @function make() -> Polygon {
+    @let shape: Polygon = Polygon(3);
+    return shape;
+}"
+    );
+    assert_eq!(
+        workspace.sources.get(main).apply(&edits),
+        "@use { Polygon, Drawable as Paintable, make } from \"./shapes.peb\";
+@let first: Polygon = make();
+@function draw(item: Paintable) -> Polygon {}"
+    );
+    // Renaming an interface keeps the name it is imported as, and renaming that name leaves the interface alone.
+    let edits = workspace
+        .rename(shapes, at(shapes, 3, 16), "Visible")
+        .unwrap();
+    assert!(workspace
+        .sources
+        .get(main)
+        .apply(&edits)
+        .starts_with("@use { Shape, Visible as Paintable,"));
+    assert!(workspace
+        .sources
+        .get(shapes)
+        .apply(&edits)
+        .contains("@implement Visible,"));
+    let edits = workspace.rename(main, at(main, 3, 24), "Canvas").unwrap();
+    assert_eq!(edits.len(), 2);
+    assert!(edits.iter().all(|edit| edit.span.file == main));
+}
+
+#[test]
+fn it_rejects_renames_that_collide() {
+    let (workspace, shapes, main) = workspace();
+    let at = |file, line, column| offset(&workspace.sources.get(file).text, line, column);
+    assert_eq!(
+        workspace.rename(shapes, at(shapes, 6, 19), "first"),
+        Err(RenameError::Collision {
+            name: "first".to_string(),
+            file: main,
+            span: Span::new(main, 70, 75)
+        })
+    );
+    assert!(matches!(
+        workspace.rename(shapes, at(shapes, 2, 16), "Number"),
+        Err(RenameError::Collision { .. })
+    ));
+    assert_eq!(
+        workspace.rename(shapes, at(shapes, 8, 12), "if"),
+        Err(RenameError::InvalidName("if".to_string()))
+    );
+    assert_eq!(
+        workspace.rename(shapes, at(shapes, 4, 13), "String"),
+        Err(RenameError::Builtin("Number".to_string()))
+    );
+    let references = workspace
+        .occurrences(workspace.symbol_at(main, at(main, 1, 9)).unwrap())
+        .into_iter()
+        .map(|occurrence| {
+            let text = &workspace.sources.get(occurrence.file).text;
+            let [line, _] = line_column(text, occurrence.span.start);
+            (occurrence.file == shapes, line)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        references,
+        vec![
+            (true, 2),
+            (true, 6),
+            (true, 7),
+            (true, 7),
+            (false, 1),
+            (false, 2),
+            (false, 3)
+        ]
+    );
+}
+
+fn measured() -> (Workspace, FileId, FileId) {
+    let mut sources = SourceMap::new();
+    let shapes = sources.add(
+        "pile/src/shapes.peb",
+        "@public @interface Measured {
+    area() -> Number {}
+}
+@public @class Square {
+    @implement Measured,
+    side: Number,
+    Square(side: Number) {
+        self.side = side;
+    },
+    area() -> Number {
+        return self.side * self.side;
+    }
+}",
+    );
+    let main = sources.add(
+        "pile/src/main.peb",
+        "@use { Square, Measured } from \"./shapes.peb\";
+@let square: Square = Square(2);
+@let size = square.area();
+@function measure(shape: Measured) -> Number {
+    return shape.area();
+}",
+    );
+    (Workspace::new(sources), shapes, main)
+}
+
+#[test]
+fn it_renames_classes_with_their_constructors() {
+    let (workspace, shapes, main) = measured();
+    let at = |file, line, column| offset(&workspace.sources.get(file).text, line, column);
+    let edits = workspace.rename(main, at(main, 2, 14), "Tile").unwrap();
+    let renamed = workspace.sources.get(shapes).apply(&edits);
+    assert!(renamed.contains("@public @class Tile {"));
+    assert!(renamed.contains("    Tile(side: Number) {"));
+    assert_eq!(
+        workspace
+            .sources
+            .get(main)
+            .apply(&edits)
+            .lines()
+            .take(2)
+            .collect::<Vec<_>>(),
+        vec![
+            "@use { Tile, Measured } from \"./shapes.peb\";",
+            "@let square: Tile = Tile(2);"
+        ]
+    );
+    // Renaming the constructor renames the class.
+    let edits_from_constructor = workspace.rename(shapes, at(shapes, 7, 5), "Tile").unwrap();
+    assert_eq!(edits_from_constructor, edits);
+}
+
+#[test]
+fn it_renames_methods_through_implementations_and_other_files() {
+    let (workspace, shapes, main) = measured();
+    let at = |file, line, column| offset(&workspace.sources.get(file).text, line, column);
+    let edits = workspace.rename(main, at(main, 3, 20), "surface").unwrap();
+    assert_eq!(edits.len(), 4);
+    let renamed = workspace.sources.get(shapes).apply(&edits);
+    assert!(renamed.contains("    surface() -> Number {}"));
+    assert!(renamed.contains("    surface() -> Number {\n"));
+    let renamed = workspace.sources.get(main).apply(&edits);
+    assert!(renamed.contains("@let size = square.surface();"));
+    assert!(renamed.contains("return shape.surface();"));
+    // The same edits are made from the method in the interface.
+    assert_eq!(
+        workspace
+            .rename(shapes, at(shapes, 2, 5), "surface")
+            .unwrap(),
+        edits
+    );
+}
+
+#[test]
+fn it_rejects_renames_onto_the_name_of_a_class() {
+    let (workspace, shapes, _) = measured();
+    let at = |line, column| offset(&workspace.sources.get(shapes).text, line, column);
+    let constructor = Span::new(shapes, at(7, 5), at(7, 11));
+    assert_eq!(
+        workspace.rename(shapes, at(10, 5), "Square"),
+        Err(RenameError::Collision {
+            name: "Square".to_string(),
+            file: shapes,
+            span: constructor
+        })
+    );
+    assert!(matches!(
+        workspace.rename(shapes, at(4, 16), "area"),
+        Err(RenameError::Collision { .. })
+    ));
+    assert!(matches!(
+        workspace.rename(shapes, at(2, 5), "side"),
+        Err(RenameError::Collision { .. })
+    ));
+    // A member named after a class without a constructor would become one.
+    let (workspace, shapes, _) = self::workspace();
+    let at = |line, column| offset(&workspace.sources.get(shapes).text, line, column);
+    assert_eq!(
+        workspace.rename(shapes, at(4, 5), "Shape"),
+        Err(RenameError::Collision {
+            name: "Shape".to_string(),
+            file: shapes,
+            span: Span::new(shapes, at(2, 16), at(2, 21))
+        })
+    );
+}
+
+#[test]
+fn it_removes_unused_imports() {
+    let mut map = SourceMap::new();
+    let source = "@use { A, B as C, D } from \"./x.peb\";\n@use { E as F } from \"./y.peb\";\n@let unused = C;\n";
+    let file = map.add("main.peb", source);
+    let resolution = resolved(source);
+    let fixes = fixes(map.get(file), &resolution.diagnostics);
+    // An unused variable cannot be removed the same way, since its value may have effects.
+    assert_eq!(fixes.len(), 3);
+    let edits = fixes
+        .into_iter()
+        .flat_map(|fix| fix.edits)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        map.get(file).apply(&edits),
+        "@use { B as C } from \"./x.peb\";\n@let unused = C;\n"
+    );
+}
+
+#[test]
+fn it_hints_inferred_types_parameter_names_and_nil_returns() {
+    let source = "@class Shape {
+    Shape(sides: Number) {},
+    describe(prefix) {
+        println prefix;
+    }
+}
+@function area(width: Number, height: Number) -> Number {
+    return width * height;
+}
+@let width = 2;
+@let total = area(width, 3);
+@let shape = Shape(4);
+@let double: (n: Number) -> Number = fn(n) { return n * 2; };
+for (item in [1, 2]) {}";
+    let program = parse_source(source);
+    let resolution = resolve(program.statements());
+    let hints = inlay_hints(program.statements(), &resolution)
+        .into_iter()
+        .map(|hint| {
+            let kind = match hint.kind {
+                InlayHintKind::Type => "type",
+                InlayHintKind::Parameter => "parameter",
+            };
+            (hint.position, hint.label, kind)
+        })
+        .collect::<Vec<_>>();
+    let hint =
+        |line, column, label: &str, kind| (offset(source, line, column), label.to_string(), kind);
+    assert_eq!(
+        hints,
+        vec![
+            hint(3, 22, "-> Nil", "type"),
+            hint(10, 11, ": Number", "type"),
+            hint(11, 11, ": Number", "type"),
+            hint(11, 26, "height:", "parameter"),
+            hint(12, 11, ": Shape", "type"),
+            hint(12, 20, "sides:", "parameter"),
+            hint(13, 42, ": Number", "type"),
+            hint(14, 10, ": Number", "type"),
+        ]
+    );
+}
+
+#[test]
+fn it_helps_with_the_signature_of_the_call_being_written() {
+    let source = "## Works out the area of a rectangle.
+@function area(width: Number, height: Number) -> Number {
+    return width * height;
+}
+@class Shape {
+    Shape(sides: Number) {}
+}
+println area(Shape(2), ";
+    let resolution = resolved(source);
+    let help = signature_help(source, &resolution, offset(source, 8, 24)).unwrap();
+    assert_eq!(help.label, "area(width: Number, height: Number) -> Number");
+    assert_eq!(help.parameters, vec!["width: Number", "height: Number"]);
+    assert_eq!(help.active_parameter, 1);
+    assert_eq!(
+        help.docs.as_deref(),
+        Some("Works out the area of a rectangle.")
+    );
+    let help = signature_help(source, &resolution, offset(source, 8, 20)).unwrap();
+    assert_eq!(help.label, "Shape(sides: Number)");
+    assert_eq!(help.active_parameter, 0);
+    // The parameters of a declaration are not arguments.
+    assert_eq!(
+        signature_help(source, &resolution, offset(source, 2, 16)),
+        None
+    );
+}
+
+#[test]
+fn it_outlines_declarations_and_finds_folding_ranges() {
+    let source = "@use { a } from \"./a.peb\";
+@use { b } from \"./b.peb\";
+## A shape with sides.
+## It can be drawn.
+@public @class Shape<T> {
+    sides: Number,
+    Shape(sides: Number) {},
+    area() -> Number {
+        @let inner = 1;
+        return inner;
+    }
+}
+@enum Direction { Up, Down(Number) }
+@module geometry {
+    @const ORIGIN: Number = 0;
+}
+@tests {
+    @function it_works() {}
+}";
+    let program = parse_source(source);
+    let describe = |items: &[OutlineItem]| {
+        items
+            .iter()
+            .map(|item| format!("{:?} {}", item.kind, item.name))
+            .collect::<Vec<_>>()
+    };
+    let outline = outline(program.statements());
+    assert_eq!(
+        describe(&outline),
+        vec![
+            "Class Shape",
+            "Enum Direction",
+            "Module geometry",
+            "Tests @tests"
+        ]
+    );
+    assert!(outline[0].is_public);
+    assert_eq!(outline[0].detail.as_deref(), Some("<T>"));
+    assert_eq!(line_column(source, outline[0].name_span.start), [5, 16]);
+    assert_eq!(
+        describe(&outline[0].children),
+        vec!["Attribute sides", "Constructor Shape", "Method area"]
+    );
+    assert_eq!(
+        outline[0].children[2].detail.as_deref(),
+        Some("() -> Number")
+    );
+    assert_eq!(
+        describe(&outline[1].children),
+        vec!["Variant Up", "Variant Down"]
+    );
+    assert_eq!(outline[1].children[1].detail.as_deref(), Some("(Number)"));
+    assert_eq!(describe(&outline[2].children), vec!["Constant ORIGIN"]);
+    assert_eq!(describe(&outline[3].children), vec!["Function it_works"]);
+
+    let ranges = folding_ranges(source)
+        .into_iter()
+        .map(|range| (range.start, range.end, range.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ranges,
+        vec![
+            (1, 2, FoldingKind::Imports),
+            (3, 4, FoldingKind::Comment),
+            (5, 11, FoldingKind::Block),
+            (8, 10, FoldingKind::Block),
+            (14, 15, FoldingKind::Block),
+            (17, 18, FoldingKind::Block),
+        ]
+    );
+}
+
+#[test]
+fn it_searches_symbols_across_a_workspace() {
+    let (workspace, shapes, main) = workspace();
+    let found = |query| {
+        workspace
+            .search(query)
+            .into_iter()
+            .map(|symbol| (symbol.file, workspace.binding(symbol).name.clone()))
+            .collect::<Vec<_>>()
+    };
+    // Locals, parameters and imports are not searched.
+    assert_eq!(
+        found("s"),
+        vec![
+            (shapes, "Shape".to_string()),
+            (shapes, "sides".to_string()),
+            (main, "first".to_string()),
+        ]
+    );
+    assert_eq!(found("drwbl"), vec![(shapes, "Drawable".to_string())]);
+    assert_eq!(found("mk")[0].1, "make");
+    assert!(found("xyz").is_empty());
+    assert!(fuzzy_score("sh", "Shape") > fuzzy_score("sh", "push"));
+    assert!(fuzzy_score("dw", "drawWindow") > fuzzy_score("dw", "drawnow"));
+}
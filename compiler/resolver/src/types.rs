@@ -0,0 +1,71 @@
+use ast::{ConcreteType, DotType, FunctionType, GenericArgument, Parameter, Type};
+
+/// Writes a type label back out as Pebble source, e.g. `Array<String>` or `(a: Number) -> Nil`.
+pub fn type_name(type_: &Type) -> String {
+    match type_ {
+        Type::Concrete(ConcreteType {
+            name, arguments, ..
+        }) => {
+            if arguments.is_empty() {
+                name.value.to_string()
+            } else {
+                let arguments = arguments.iter().map(type_name).collect::<Vec<_>>();
+                format!("{}<{}>", name.value, arguments.join(", "))
+            }
+        }
+        Type::Function(FunctionType {
+            parameters,
+            return_type,
+            generic_arguments,
+            ..
+        }) => format!(
+            "{}({}) -> {}",
+            generic_list(generic_arguments),
+            parameter_list(parameters),
+            type_name(return_type)
+        ),
+        Type::Dot(DotType { levels, .. }) => {
+            levels.iter().map(type_name).collect::<Vec<_>>().join(".")
+        }
+    }
+}
+
+/// Writes generic arguments as they are declared, e.g. `<T implements Display>`, or nothing if there are none.
+pub fn generic_list(arguments: &Option<Vec<GenericArgument>>) -> String {
+    let Some(arguments) = arguments else {
+        return String::new();
+    };
+    let arguments = arguments
+        .iter()
+        .map(|argument| match &argument.implements {
+            Some(interfaces) if !interfaces.is_empty() => {
+                let interfaces = interfaces.iter().map(|interface| interface.value.as_str());
+                format!(
+                    "{} implements {}",
+                    argument.name.value,
+                    interfaces.collect::<Vec<_>>().join(" + ")
+                )
+            }
+            _ => argument.name.value.to_string(),
+        })
+        .collect::<Vec<_>>();
+    format!("<{}>", arguments.join(", "))
+}
+
+/// Writes parameters as they are declared, e.g. `a: Number, b`.
+pub fn parameter_list(parameters: &[Parameter]) -> String {
+    parameters
+        .iter()
+        .map(|parameter| match &parameter.label {
+            Some(label) => format!("{}: {}", parameter.name.value, type_name(label)),
+            None => parameter.name.value.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the name of the declaration a type refers to, e.g. `Array` for `Array<String>`.
+pub fn head_of(type_: &str) -> &str {
+    let end = type_.find(['<', '.', '(']).unwrap_or(type_.len());
+    &type_[..end]
+}
@@ -0,0 +1,554 @@
+use std::{collections::HashMap, fmt::Display, rc::Rc};
+
+use ast::KEYWORDS;
+use parser::parse_in;
+use utils::{FileId, SourceMap, Span, TextEdit};
+
+use crate::{
+    contains, resolve, Binding, BindingId, BindingKind, ImportedName, Resolution, ScopeId,
+};
+
+/// A binding in one of the files of a workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol {
+    pub file: FileId,
+    pub binding: BindingId,
+}
+
+/// A place where a symbol is written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Occurrence {
+    pub file: FileId,
+    pub span: Span,
+    pub is_declaration: bool,
+}
+
+/// The reasons a symbol cannot be renamed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameError {
+    /// There is no name at the position.
+    NoSymbol,
+    /// Builtins are not declared in the workspace.
+    Builtin(String),
+    /// The new name is not an identifier, or is a keyword.
+    InvalidName(String),
+    /// Another binding with the new name is declared where the symbol is, or is used where the symbol is visible.
+    Collision {
+        name: String,
+        file: FileId,
+        span: Span,
+    },
+}
+
+impl Display for RenameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSymbol => write!(f, "There is no name to rename here."),
+            Self::Builtin(name) => write!(f, "'{name}' is built in, so it cannot be renamed."),
+            Self::InvalidName(name) => write!(f, "'{name}' is not a valid name."),
+            Self::Collision { name, .. } => {
+                write!(
+                    f,
+                    "The name '{name}' is already used where the renamed name is visible."
+                )
+            }
+        }
+    }
+}
+
+/// The files of a pile resolved together, with each import linked to the export it names.
+pub struct Workspace {
+    pub sources: SourceMap,
+    resolutions: HashMap<FileId, Rc<Resolution>>,
+    /// The export each import refers to, by the binding the import declares.
+    links: HashMap<Symbol, Symbol>,
+}
+
+impl Workspace {
+    /// Parses and resolves every file in a source map. The names of the files are their paths, which the paths in `@use` statements are relative to.
+    pub fn new(sources: SourceMap) -> Self {
+        let mut resolutions = HashMap::new();
+        for file in sources.files() {
+            let program = parse_in(&sources, file.id);
+            resolutions.insert(file.id, Rc::new(resolve(program.statements())));
+        }
+        Self::from_resolutions(sources, resolutions)
+    }
+    /// Links the files in a source map that have already been resolved, e.g. by a query database. There must be a resolution for every file.
+    pub fn from_resolutions(
+        sources: SourceMap,
+        resolutions: HashMap<FileId, Rc<Resolution>>,
+    ) -> Self {
+        let mut links = HashMap::new();
+        for file in sources.files() {
+            for import in &resolutions[&file.id].imports {
+                let Some(target) = sources.find(&relative_to(&file.name, &import.source)) else {
+                    continue;
+                };
+                let export = resolutions[&target.id].iter().find(|(_, binding)| {
+                    binding.parent.is_none()
+                        && binding.is_public
+                        && binding.name == import.imported_name
+                });
+                if let Some((binding, _)) = export {
+                    let import = Symbol {
+                        file: file.id,
+                        binding: import.binding,
+                    };
+                    let export = Symbol {
+                        file: target.id,
+                        binding,
+                    };
+                    links.insert(import, export);
+                }
+            }
+        }
+        Workspace {
+            sources,
+            resolutions,
+            links,
+        }
+    }
+    pub fn resolution(&self, file: FileId) -> &Resolution {
+        &self.resolutions[&file]
+    }
+    pub fn binding(&self, symbol: Symbol) -> &Binding {
+        self.resolution(symbol.file).binding(symbol.binding)
+    }
+    /// Returns the export an import refers to, if the file it imports from is in the workspace.
+    pub fn export_of(&self, import: Symbol) -> Option<Symbol> {
+        self.links.get(&import).copied()
+    }
+    /// Finds the symbol named at a byte offset. A name imported without `as` is the export it refers to.
+    pub fn symbol_at(&self, file: FileId, offset: usize) -> Option<Symbol> {
+        let resolution = self.resolution(file);
+        // The name of the export in an import that renames it refers to the export, not to the new name.
+        let renamed = resolution
+            .imports
+            .iter()
+            .find(|import| import.local_span.is_some() && contains(import.imported_span, offset));
+        if let Some(import) = renamed {
+            return self.export_of(Symbol {
+                file,
+                binding: import.binding,
+            });
+        }
+        match resolution.binding_at(offset) {
+            Some(binding) => Some(self.canonical(Symbol { file, binding })),
+            None => self.foreign_member_at(file, offset),
+        }
+    }
+    /// Finds the member named at a byte offset, where it is reached through an import of the declaration it belongs to.
+    fn foreign_member_at(&self, file: FileId, offset: usize) -> Option<Symbol> {
+        let used = self
+            .resolution(file)
+            .foreign_members
+            .iter()
+            .find(|used| contains(used.span, offset))?;
+        let owner = self.export_of(Symbol {
+            file,
+            binding: used.owner,
+        })?;
+        let member = self
+            .resolution(owner.file)
+            .member(owner.binding, &used.name)?;
+        Some(Symbol {
+            file: owner.file,
+            binding: member,
+        })
+    }
+    /// Follows an import that keeps the name of its export to the export.
+    fn canonical(&self, symbol: Symbol) -> Symbol {
+        match self.import(symbol) {
+            Some(import) if import.local_span.is_none() => self.export_of(symbol).unwrap_or(symbol),
+            _ => symbol,
+        }
+    }
+    /// Follows any import, whether or not it renames its export, to the export.
+    fn declaration(&self, symbol: Symbol) -> Symbol {
+        match self.import(symbol) {
+            Some(_) => self.export_of(symbol).unwrap_or(symbol),
+            None => symbol,
+        }
+    }
+    fn import(&self, symbol: Symbol) -> Option<&ImportedName> {
+        self.resolution(symbol.file)
+            .imports
+            .iter()
+            .find(|import| import.binding == symbol.binding)
+    }
+    /// Returns the imports of a symbol in other files.
+    fn imports_of(&self, symbol: Symbol) -> impl Iterator<Item = (Symbol, &ImportedName)> {
+        self.sources.files().flat_map(move |file| {
+            self.resolution(file.id)
+                .imports
+                .iter()
+                .map(move |import| {
+                    let binding = Symbol {
+                        file: file.id,
+                        binding: import.binding,
+                    };
+                    (binding, import)
+                })
+                .filter(move |(binding, _)| self.export_of(*binding) == Some(symbol))
+        })
+    }
+    /// Returns the symbols whose uses are written with the name of a symbol: the symbol itself, and the imports of it that keep its name.
+    fn namesakes(&self, symbol: Symbol) -> Vec<Symbol> {
+        let imports = self
+            .imports_of(symbol)
+            .filter(|(_, import)| import.local_span.is_none())
+            .map(|(import, _)| import);
+        std::iter::once(symbol).chain(imports).collect()
+    }
+    /// Returns every place a symbol is written in the workspace, ordered by file and position.<br>
+    /// These are its declaration, its uses, the names in the imports of it, and the uses of the imports that keep its name.
+    pub fn occurrences(&self, symbol: Symbol) -> Vec<Occurrence> {
+        let symbol = self.canonical(symbol);
+        let mut occurrences = vec![];
+        if !self.binding(symbol).is_builtin() {
+            occurrences.push(Occurrence {
+                file: symbol.file,
+                span: self.binding(symbol).span,
+                is_declaration: true,
+            });
+        }
+        for (import, imported) in self.imports_of(symbol) {
+            occurrences.push(Occurrence {
+                file: import.file,
+                span: imported.imported_span,
+                is_declaration: false,
+            });
+        }
+        for namesake in self.namesakes(symbol) {
+            let uses = self
+                .resolution(namesake.file)
+                .references_to(namesake.binding);
+            occurrences.extend(uses.map(|span| Occurrence {
+                file: namesake.file,
+                span,
+                is_declaration: false,
+            }));
+        }
+        occurrences.extend(self.foreign_uses(symbol));
+        occurrences.sort_by_key(|occurrence| (occurrence.file, occurrence.span.start));
+        occurrences
+    }
+    /// Returns the uses of a member in other files, which reach it through the imports of the declaration it belongs to.
+    fn foreign_uses(&self, member: Symbol) -> Vec<Occurrence> {
+        let binding = self.binding(member);
+        let Some(parent) = binding.parent else {
+            return vec![];
+        };
+        let owner = Symbol {
+            file: member.file,
+            binding: parent,
+        };
+        self.imports_of(owner)
+            .flat_map(|(import, _)| {
+                self.resolution(import.file)
+                    .foreign_members
+                    .iter()
+                    .filter(move |used| used.owner == import.binding && used.name == binding.name)
+                    .map(move |used| Occurrence {
+                        file: import.file,
+                        span: used.span,
+                        is_declaration: false,
+                    })
+            })
+            .collect()
+    }
+    /// Returns the class a method is the constructor of, i.e. the class whose name the method has.
+    fn constructed_class(&self, symbol: Symbol) -> Option<Symbol> {
+        let binding = self.binding(symbol);
+        let class = Symbol {
+            file: symbol.file,
+            binding: binding.parent?,
+        };
+        let is_constructor = binding.kind == BindingKind::Method
+            && self.binding(class).kind == BindingKind::Class
+            && self.binding(class).name == binding.name;
+        is_constructor.then_some(class)
+    }
+    /// Returns the classes and interfaces linked to a class or interface through `@implement`, directly or through others, starting with itself.<br>
+    /// Imports are followed to the declarations they name, so the links can cross files.
+    fn implementation_family(&self, owner: Symbol) -> Vec<Symbol> {
+        let mut family = vec![owner];
+        let mut index = 0;
+        while let Some(current) = family.get(index).copied() {
+            index += 1;
+            for file in self.sources.files() {
+                for (class, interface) in &self.resolution(file.id).implements {
+                    let class = Symbol {
+                        file: file.id,
+                        binding: *class,
+                    };
+                    let interface = self.declaration(Symbol {
+                        file: file.id,
+                        binding: *interface,
+                    });
+                    let linked = if class == current {
+                        interface
+                    } else if interface == current {
+                        class
+                    } else {
+                        continue;
+                    };
+                    if !family.contains(&linked) {
+                        family.push(linked);
+                    }
+                }
+            }
+        }
+        family
+    }
+    /// Returns the symbols that must be renamed along with a symbol, starting with itself.<br>
+    /// A class is renamed with its constructor, and a method or attribute with the ones of the same name in the interfaces and classes it is implemented through.
+    fn renamed_together(&self, symbol: Symbol) -> Vec<Symbol> {
+        let binding = self.binding(symbol);
+        let resolution = self.resolution(symbol.file);
+        if binding.kind == BindingKind::Class {
+            let constructor = resolution
+                .member(symbol.binding, &binding.name)
+                .map(|binding| Symbol {
+                    file: symbol.file,
+                    binding,
+                });
+            return std::iter::once(symbol).chain(constructor).collect();
+        }
+        let parent = match (binding.kind, binding.parent) {
+            (BindingKind::Method | BindingKind::Attribute, Some(parent)) => parent,
+            _ => return vec![symbol],
+        };
+        let owner = Symbol {
+            file: symbol.file,
+            binding: parent,
+        };
+        self.implementation_family(owner)
+            .into_iter()
+            .filter_map(|owner| {
+                let member = self
+                    .resolution(owner.file)
+                    .member(owner.binding, &binding.name)?;
+                Some(Symbol {
+                    file: owner.file,
+                    binding: member,
+                })
+            })
+            .collect()
+    }
+    /// Renames the symbol named at a byte offset everywhere it is written, and returns the edits that do it.<br>
+    /// A class is renamed together with its constructor, and a member together with the members it implements or is implemented by, in every file.
+    pub fn rename(
+        &self,
+        file: FileId,
+        offset: usize,
+        name: &str,
+    ) -> Result<Vec<TextEdit>, RenameError> {
+        let symbol = self.symbol_at(file, offset).ok_or(RenameError::NoSymbol)?;
+        let symbol = self.constructed_class(symbol).unwrap_or(symbol);
+        let binding = self.binding(symbol);
+        if binding.is_builtin() {
+            return Err(RenameError::Builtin(binding.name.clone()));
+        }
+        if !is_identifier(name) {
+            return Err(RenameError::InvalidName(name.to_string()));
+        }
+        if binding.name == name {
+            return Ok(vec![]);
+        }
+        let renamed = self.renamed_together(symbol);
+        for symbol in &renamed {
+            for namesake in self.namesakes(*symbol) {
+                self.check_collisions(namesake, name)?;
+            }
+        }
+        let mut edits = renamed
+            .iter()
+            .flat_map(|symbol| self.occurrences(*symbol))
+            .filter_map(|occurrence| {
+                // Every occurrence begins with the old name, which is all that is replaced.
+                let start = occurrence.span.start;
+                let span = Span::new(occurrence.file, start, start + binding.name.len());
+                let written = self.sources.get(occurrence.file).text.get(span.range())?;
+                (written == binding.name).then(|| TextEdit::new(span, name))
+            })
+            .collect::<Vec<_>>();
+        edits.sort_by_key(|edit| (edit.span.file, edit.span.start));
+        edits.dedup();
+        Ok(edits)
+    }
+    /// Finds the declarations in the workspace whose names fuzzily match a query, best matches first.<br>
+    /// The declarations searched are the ones at the top of each file and the members of classes, interfaces, enums and modules. An empty query matches all of them.
+    pub fn search(&self, query: &str) -> Vec<Symbol> {
+        let mut matches = vec![];
+        for file in self.sources.files() {
+            let resolution = self.resolution(file.id);
+            for (binding, declared) in resolution.iter() {
+                let searched = match declared.kind {
+                    BindingKind::Parameter
+                    | BindingKind::TypeParameter
+                    | BindingKind::Import
+                    | BindingKind::Builtin => false,
+                    _ => declared.scope == ScopeId::ROOT || declared.parent.is_some(),
+                };
+                if !searched {
+                    continue;
+                }
+                if let Some(score) = fuzzy_score(query, &declared.name) {
+                    let symbol = Symbol {
+                        file: file.id,
+                        binding,
+                    };
+                    matches.push((score, symbol));
+                }
+            }
+        }
+        matches.sort_by(|(a, first), (b, second)| {
+            let (first, second) = (self.binding(*first), self.binding(*second));
+            b.cmp(a)
+                .then(first.name.len().cmp(&second.name.len()))
+                .then(first.name.cmp(&second.name))
+        });
+        matches.into_iter().map(|(_, symbol)| symbol).collect()
+    }
+    /// Checks that giving a symbol a new name would not make it, or another binding, refer to something else.
+    fn check_collisions(&self, symbol: Symbol, name: &str) -> Result<(), RenameError> {
+        let resolution = self.resolution(symbol.file);
+        let binding = self.binding(symbol);
+        let collision = |span| RenameError::Collision {
+            name: name.to_string(),
+            file: symbol.file,
+            span,
+        };
+        // A member with the name of its class would become the constructor of the class.
+        if binding.kind == BindingKind::Class {
+            if let Some(member) = resolution.member(symbol.binding, name) {
+                return Err(collision(resolution.binding(member).span));
+            }
+        }
+        // Members are only reached through their parent, so they can only collide with each other, or with the name of a class.
+        if let Some(parent) = binding.parent {
+            if let Some(sibling) = resolution.member(parent, name) {
+                return Err(collision(resolution.binding(sibling).span));
+            }
+            let owner = resolution.binding(parent);
+            if owner.kind == BindingKind::Class && owner.name == name {
+                return Err(collision(owner.span));
+            }
+            let is_member = matches!(
+                binding.kind,
+                BindingKind::Method | BindingKind::Attribute | BindingKind::Variant
+            ) || resolution.binding(parent).kind == BindingKind::Module;
+            if is_member {
+                return Ok(());
+            }
+        }
+        let scope = &resolution.scopes[binding.scope.index()];
+        if let Some(other) = scope
+            .bindings
+            .iter()
+            .find(|other| resolution.binding(**other).name == name)
+        {
+            return Err(collision(resolution.binding(*other).span));
+        }
+        // A use of the symbol where another binding with the name is visible would refer to that binding.
+        for span in resolution.references_to(symbol.binding) {
+            let shadowed = resolution
+                .visible_at(span.start)
+                .into_iter()
+                .any(|other| resolution.binding(other).name == name);
+            if shadowed {
+                return Err(collision(span));
+            }
+        }
+        // A use of another binding with the name where the symbol is visible would refer to the symbol.
+        for reference in &resolution.references {
+            let other = resolution.binding(reference.binding);
+            let captured = other.name == name
+                && binding.visible_from <= reference.span.start
+                && self.encloses(resolution, binding.scope, reference.span.start);
+            if captured {
+                return Err(collision(reference.span));
+            }
+        }
+        Ok(())
+    }
+    /// Returns `true` if a byte offset is in a scope or in a scope nested in it.
+    fn encloses(&self, resolution: &Resolution, scope: crate::ScopeId, offset: usize) -> bool {
+        let mut current = Some(resolution.scope_at(offset));
+        while let Some(id) = current {
+            if id == scope {
+                return true;
+            }
+            current = resolution.scopes[id.index()].parent;
+        }
+        false
+    }
+}
+
+/// Returns `true` if a name can be declared, i.e. it is made of letters, digits, `_` and `$`, does not start with a digit, and is not a keyword.
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    let valid = |char: char| char == '_' || char == '$' || char.is_alphanumeric();
+    !first.is_numeric()
+        && valid(first)
+        && chars.all(valid)
+        && !KEYWORDS.contains(&name)
+        && !["true", "false"].contains(&name)
+}
+
+/// Scores how well a query matches a name, or returns `None` if the characters of the query do not all appear in the name in order.<br>
+/// Case is ignored. Characters matched at the start of the name, at the start of a word in it, or right after the character matched before them score higher, and the best way of matching the query is scored.
+pub fn fuzzy_score(query: &str, name: &str) -> Option<u32> {
+    let name = name.chars().collect::<Vec<_>>();
+    let bonus = |index: usize| match index {
+        0 => 8,
+        _ if matches!(name[index - 1], '_' | '$')
+            || (name[index - 1].is_lowercase() && name[index].is_uppercase()) =>
+        {
+            4
+        }
+        _ => 0,
+    };
+    // The best score of the characters of the query matched so far, by one past the index the last of them was matched at, so that `0` stands for none matched.
+    let mut best: Vec<Option<u32>> = vec![None; name.len() + 1];
+    best[0] = Some(0);
+    for wanted in query.chars().filter(|char| !char.is_whitespace()) {
+        let mut next = vec![None; name.len() + 1];
+        for index in 0..name.len() {
+            if !name[index].to_lowercase().eq(wanted.to_lowercase()) {
+                continue;
+            }
+            next[index + 1] = (0..=index)
+                .filter_map(|after| {
+                    let contiguous = after > 0 && after == index;
+                    Some(best[after]? + if contiguous { 3 } else { 0 })
+                })
+                .max()
+                .map(|score| score + 1 + bonus(index));
+        }
+        best = next;
+    }
+    best.into_iter().flatten().max()
+}
+
+/// Works out the name of the file a path in a `@use` statement refers to, from the name of the file the statement is in.<br>
+/// Both are split at `/`, so names can be file paths or URIs.
+pub fn relative_to(name: &str, path: &str) -> String {
+    let mut parts = name.split('/').collect::<Vec<_>>();
+    parts.pop();
+    for part in path.split('/') {
+        match part {
+            "." | "" => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
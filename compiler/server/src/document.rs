@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use database::Database;
+use lsp_types::{Position, Range, TextDocumentContentChangeEvent, Url};
+use parser::{Diagnostic, Program};
+use resolver::{Resolution, Workspace};
+use utils::{Encoding, FileId, LineColumn, SourceFile, Span, TextEdit};
+
+/// An open file, along with its syntax tree, the names resolved in it and the problems found in it.
+pub struct Document {
+    pub file: FileId,
+    pub version: i32,
+    pub program: Rc<Program>,
+    pub resolution: Rc<Resolution>,
+    pub diagnostics: Rc<Vec<Diagnostic>>,
+}
+
+/// The files the client has opened, which are analyzed again when they change.<br>
+/// The files are kept in a query database by their URIs, along with the files read from the disk for the workspace, so that a file is only analyzed again when it or something it depends on has changed.
+#[derive(Default)]
+pub struct Documents {
+    pub database: Database,
+    /// The file and the version of each open document.
+    documents: HashMap<Url, (FileId, i32)>,
+}
+
+impl Documents {
+    /// Stores the text of a file, replacing its previous text if it is already open.
+    pub fn open(&mut self, uri: Url, version: i32, text: String) {
+        let file = self.database.set_source(uri.as_str(), text);
+        self.documents.insert(uri, (file, version));
+    }
+    /// Applies the changes the client made to an open file, in order, and returns `false` if the file is not open.<br>
+    /// A change with a range replaces that part of the text, and only the statements around it are parsed again. A change without one replaces the whole text.
+    pub fn change(
+        &mut self,
+        uri: &Url,
+        version: i32,
+        changes: Vec<TextDocumentContentChangeEvent>,
+    ) -> bool {
+        let Some((file, current)) = self.documents.get_mut(uri) else {
+            return false;
+        };
+        for change in changes {
+            let Some(range) = change.range else {
+                self.database.set_source(uri.as_str(), change.text);
+                continue;
+            };
+            let source = self.database.sources().get(*file);
+            let start = to_offset(source, range.start);
+            let span = Span::new(*file, start, to_offset(source, range.end));
+            self.database.edit(&TextEdit::new(span, change.text));
+        }
+        *current = version;
+        true
+    }
+    pub fn uris(&self) -> impl Iterator<Item = &Url> {
+        self.documents.keys()
+    }
+    pub fn close(&mut self, uri: &Url) {
+        self.documents.remove(uri);
+    }
+    /// Returns an open file, analyzing it if it has changed since it was last asked for.
+    pub fn get(&self, uri: &Url) -> Option<(Document, &SourceFile)> {
+        let (file, version) = *self.documents.get(uri)?;
+        let document = Document {
+            file,
+            version,
+            program: self.database.parse(file),
+            resolution: self.database.resolve(file),
+            diagnostics: self.database.check(file),
+        };
+        Some((document, self.database.sources().get(file)))
+    }
+    /// Resolves the open files together with the Pebble files under `root`, so that names can be followed from one file to another.<br>
+    /// The files are named by their URIs, and a file that is open is read from the client rather than from the disk.
+    pub fn workspace(&mut self, root: Option<&Path>) -> Workspace {
+        let mut open = self.documents.iter().collect::<Vec<_>>();
+        open.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+        let mut files = open
+            .into_iter()
+            .map(|(_, (file, _))| *file)
+            .collect::<Vec<_>>();
+        let mut paths = vec![];
+        if let Some(root) = root {
+            source_files(root, &mut paths);
+        }
+        paths.sort();
+        for path in paths {
+            let Ok(uri) = Url::from_file_path(&path) else {
+                continue;
+            };
+            if self.documents.contains_key(&uri) {
+                continue;
+            }
+            if let Ok(text) = fs::read_to_string(&path) {
+                files.push(self.database.set_source(uri.as_str(), text));
+            }
+        }
+        self.database.workspace(&files)
+    }
+}
+
+/// Collects the `.peb` files in a folder and the folders in it, leaving out hidden folders and build output.
+fn source_files(folder: &Path, paths: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if path.is_dir() {
+            if !name.starts_with('.') && name != "target" && name != "node_modules" {
+                source_files(&path, paths);
+            }
+        } else if path.extension().is_some_and(|extension| extension == "peb") {
+            paths.push(path);
+        }
+    }
+}
+
+/// Converts a client position, whose column counts UTF-16 code units from zero, into a byte offset in a file.
+pub fn to_offset(file: &SourceFile, position: Position) -> usize {
+    let line_column = LineColumn {
+        line: position.line as usize,
+        column: position.character as usize,
+    };
+    file.offset(line_column, Encoding::Utf16)
+        .unwrap_or(file.text.len())
+}
+
+/// Converts a byte offset in a file into a position the client understands.
+pub fn to_position(file: &SourceFile, offset: usize) -> Position {
+    let LineColumn { line, column } = file.line_column(offset, Encoding::Utf16);
+    Position::new(line as u32, column as u32)
+}
+
+/// Converts a byte span into a range the client understands.
+pub fn to_range(file: &SourceFile, span: Span) -> Range {
+    Range::new(to_position(file, span.start), to_position(file, span.end))
+}
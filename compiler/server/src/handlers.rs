@@ -0,0 +1,562 @@
+use std::collections::HashMap;
+
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CompletionItem, CompletionItemKind,
+    CompletionTextEdit, DiagnosticSeverity, DocumentSymbol, Documentation, FoldingRange,
+    FoldingRangeKind, Hover, HoverContents, InlayHint, InlayHintKind, InlayHintLabel, Location,
+    MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, Position, Range,
+    SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensEdit,
+    SemanticTokensLegend, SignatureHelp, SignatureInformation, SymbolInformation, SymbolKind,
+    TextEdit, Url, WorkspaceEdit,
+};
+use parser::{parse_source, Diagnostic};
+use resolver::{
+    complete, outline, resolve, BindingId, BindingKind, CompletionKind, FoldingKind, OutlineItem,
+    OutlineKind, RenameError, Resolution, SemanticModifier, SemanticTokenKind, Workspace,
+    BUILTIN_TYPES,
+};
+use utils::{Encoding, LineColumn, SourceFile};
+
+use crate::document::{to_offset, to_position, to_range, Document, Documents};
+
+/// Collects the problems found while parsing and resolving a file.
+pub fn diagnostics(document: &Document, file: &SourceFile) -> Vec<lsp_types::Diagnostic> {
+    problems(document)
+        .map(|diagnostic| to_diagnostic(file, diagnostic))
+        .collect()
+}
+
+fn problems(document: &Document) -> impl Iterator<Item = &Diagnostic> {
+    document.diagnostics.iter()
+}
+
+fn to_diagnostic(file: &SourceFile, diagnostic: &Diagnostic) -> lsp_types::Diagnostic {
+    lsp_types::Diagnostic {
+        range: to_range(file, diagnostic.span()),
+        severity: Some(if diagnostic.is_error() {
+            DiagnosticSeverity::ERROR
+        } else {
+            DiagnosticSeverity::WARNING
+        }),
+        source: Some("pebble".to_string()),
+        message: diagnostic.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Offers the fixes of the problems in a range of a file as quick fixes, and every fix in the file as a single action when there is more than one.
+pub fn code_actions(
+    document: &Document,
+    file: &SourceFile,
+    uri: &Url,
+    range: Range,
+) -> Vec<CodeActionOrCommand> {
+    let action = |title: String, kind, edits: Vec<utils::TextEdit>, diagnostics| {
+        let edits = edits
+            .into_iter()
+            .map(|edit| TextEdit::new(to_range(file, edit.span), edit.text))
+            .collect();
+        CodeActionOrCommand::CodeAction(CodeAction {
+            title,
+            kind: Some(kind),
+            diagnostics,
+            edit: Some(WorkspaceEdit::new(HashMap::from([(uri.clone(), edits)]))),
+            is_preferred: Some(true),
+            ..Default::default()
+        })
+    };
+    let overlaps = |other: Range| other.start <= range.end && range.start <= other.end;
+    let mut actions = vec![];
+    for diagnostic in problems(document) {
+        let Some(fix) = diagnostic.fix(file) else {
+            continue;
+        };
+        // A missing semicolon is reported at the token after it, so fixes are also offered where they change the text.
+        let problem = to_diagnostic(file, diagnostic);
+        let is_in_range = overlaps(problem.range)
+            || fix
+                .edits
+                .iter()
+                .any(|edit| overlaps(to_range(file, edit.span)));
+        if is_in_range {
+            actions.push(action(
+                fix.title,
+                CodeActionKind::QUICKFIX,
+                fix.edits,
+                Some(vec![problem]),
+            ));
+        }
+    }
+    let mut problems = problems(document).collect::<Vec<_>>();
+    problems.sort_by_key(|diagnostic| diagnostic.span().start);
+    let fixes = parser::fixes(file, problems);
+    if fixes.len() > 1 {
+        let edits = fixes.into_iter().flat_map(|fix| fix.edits).collect();
+        actions.push(action(
+            "Fix every problem that can be fixed".to_string(),
+            CodeActionKind::SOURCE_FIX_ALL,
+            edits,
+            None,
+        ));
+    }
+    actions
+}
+
+/// Describes the name at a position with its declaration and its doc comments.
+pub fn hover(document: &Document, file: &SourceFile, position: Position) -> Option<Hover> {
+    let resolution = &document.resolution;
+    let offset = to_offset(file, position);
+    let id = resolution.binding_at(offset)?;
+    let binding = resolution.binding(id);
+    let mut value = format!("```pebble\n{}\n```", resolution.signature(id));
+    if let Some(docs) = &binding.docs {
+        value.push_str("\n\n---\n\n");
+        value.push_str(docs);
+    }
+    let range = resolution
+        .references
+        .iter()
+        .map(|reference| reference.span)
+        .chain(std::iter::once(binding.span))
+        .find(|span| resolver::contains(*span, offset))
+        .map(|span| to_range(file, span));
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        }),
+        range,
+    })
+}
+
+/// Finds the declaration of the name at a position. Builtins have no declaration to go to.
+pub fn definition(
+    document: &Document,
+    file: &SourceFile,
+    uri: &Url,
+    position: Position,
+) -> Option<Location> {
+    let resolution = &document.resolution;
+    let id = resolution.binding_at(to_offset(file, position))?;
+    let binding = resolution.binding(id);
+    if binding.is_builtin() {
+        return None;
+    }
+    Some(Location::new(uri.clone(), to_range(file, binding.span)))
+}
+
+/// Finds every use of the name at a position in the workspace, and its declaration if `include_declaration` is set.
+pub fn references(
+    workspace: &Workspace,
+    uri: &Url,
+    position: Position,
+    include_declaration: bool,
+) -> Vec<Location> {
+    let Some(file) = workspace.sources.find(uri.as_str()) else {
+        return vec![];
+    };
+    let Some(symbol) = workspace.symbol_at(file.id, to_offset(file, position)) else {
+        return vec![];
+    };
+    workspace
+        .occurrences(symbol)
+        .into_iter()
+        .filter(|occurrence| include_declaration || !occurrence.is_declaration)
+        .filter_map(|occurrence| {
+            let file = workspace.sources.get(occurrence.file);
+            let uri = Url::parse(&file.name).ok()?;
+            Some(Location::new(uri, to_range(file, occurrence.span)))
+        })
+        .collect()
+}
+
+/// Finds the name at a position if it can be renamed, and returns where it is written.
+pub fn prepare_rename(workspace: &Workspace, uri: &Url, position: Position) -> Option<Range> {
+    let file = workspace.sources.find(uri.as_str())?;
+    let offset = to_offset(file, position);
+    let symbol = workspace.symbol_at(file.id, offset)?;
+    if workspace.binding(symbol).is_builtin() {
+        return None;
+    }
+    let occurrence = workspace
+        .occurrences(symbol)
+        .into_iter()
+        .find(|occurrence| {
+            occurrence.file == file.id && resolver::contains(occurrence.span, offset)
+        })?;
+    Some(to_range(file, occurrence.span))
+}
+
+/// Renames the name at a position in every file of the workspace it is written in.
+pub fn rename(
+    workspace: &Workspace,
+    uri: &Url,
+    position: Position,
+    name: &str,
+) -> Result<WorkspaceEdit, RenameError> {
+    let file = workspace
+        .sources
+        .find(uri.as_str())
+        .ok_or(RenameError::NoSymbol)?;
+    let edits = workspace.rename(file.id, to_offset(file, position), name)?;
+    let mut changes = HashMap::<Url, Vec<TextEdit>>::new();
+    for edit in edits {
+        let file = workspace.sources.get(edit.span.file);
+        let Ok(uri) = Url::parse(&file.name) else {
+            continue;
+        };
+        let range = to_range(file, edit.span);
+        changes
+            .entry(uri)
+            .or_default()
+            .push(TextEdit::new(range, edit.text));
+    }
+    Ok(WorkspaceEdit::new(changes))
+}
+
+/// Suggests what can be written at a position, in the order the completion engine ranks them.
+pub fn completion(documents: &Documents, uri: &Url, position: Position) -> Vec<CompletionItem> {
+    let Some((document, file)) = documents.get(uri) else {
+        return vec![];
+    };
+    let exports = |path: &str| imported(documents, uri, path);
+    let completions = complete(
+        &file.text,
+        &document.resolution,
+        to_offset(file, position),
+        &exports,
+    );
+    let range = to_range(file, completions.span);
+    completions
+        .items
+        .into_iter()
+        .enumerate()
+        .map(|(index, completion)| {
+            let kind = match completion.kind {
+                CompletionKind::Keyword | CompletionKind::Injunction => CompletionItemKind::KEYWORD,
+                CompletionKind::Binding(kind) => match kind {
+                    BindingKind::Variable | BindingKind::Parameter => CompletionItemKind::VARIABLE,
+                    BindingKind::Constant => CompletionItemKind::CONSTANT,
+                    BindingKind::Function => CompletionItemKind::FUNCTION,
+                    BindingKind::Class | BindingKind::Record => CompletionItemKind::CLASS,
+                    BindingKind::Interface => CompletionItemKind::INTERFACE,
+                    BindingKind::Enum => CompletionItemKind::ENUM,
+                    BindingKind::Variant => CompletionItemKind::ENUM_MEMBER,
+                    BindingKind::TypeAlias | BindingKind::TypeParameter => {
+                        CompletionItemKind::TYPE_PARAMETER
+                    }
+                    BindingKind::Module => CompletionItemKind::MODULE,
+                    BindingKind::Method => CompletionItemKind::METHOD,
+                    BindingKind::Attribute => CompletionItemKind::PROPERTY,
+                    BindingKind::Import => CompletionItemKind::REFERENCE,
+                    BindingKind::Builtin if BUILTIN_TYPES.contains(&completion.label.as_str()) => {
+                        CompletionItemKind::CLASS
+                    }
+                    BindingKind::Builtin => CompletionItemKind::MODULE,
+                },
+            };
+            let documentation = completion.docs.map(|docs| {
+                Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: docs,
+                })
+            });
+            CompletionItem {
+                // Injunctions start with `@`, which editors do not count as part of a word, so the typed word is replaced explicitly.
+                text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(
+                    range,
+                    completion.label.clone(),
+                ))),
+                label: completion.label,
+                kind: Some(kind),
+                detail: completion.detail,
+                documentation,
+                sort_text: Some(format!("{index:04}")),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Describes the function called where arguments are being written.
+pub fn signature_help(
+    document: &Document,
+    file: &SourceFile,
+    position: Position,
+) -> Option<SignatureHelp> {
+    let help =
+        resolver::signature_help(&file.text, &document.resolution, to_offset(file, position))?;
+    let parameters = help
+        .parameters
+        .into_iter()
+        .map(|parameter| ParameterInformation {
+            label: ParameterLabel::Simple(parameter),
+            documentation: None,
+        })
+        .collect();
+    let documentation = help.docs.map(|docs| {
+        Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: docs,
+        })
+    });
+    let active_parameter = Some(help.active_parameter as u32);
+    Some(SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label: help.label,
+            documentation,
+            parameters: Some(parameters),
+            active_parameter,
+        }],
+        active_signature: Some(0),
+        active_parameter,
+    })
+}
+
+/// Shows the types and parameter names left unwritten in a range of a file. A type hint can be double-clicked to write it out.
+pub fn inlay_hints(document: &Document, file: &SourceFile, range: Range) -> Vec<InlayHint> {
+    resolver::inlay_hints(document.program.statements(), &document.resolution)
+        .into_iter()
+        .filter_map(|hint| {
+            let position = to_position(file, hint.position);
+            if position < range.start || range.end < position {
+                return None;
+            }
+            let (kind, text_edits) = match hint.kind {
+                resolver::InlayHintKind::Type => (
+                    InlayHintKind::TYPE,
+                    Some(vec![TextEdit::new(
+                        Range::new(position, position),
+                        match hint.padding {
+                            true => format!("{} ", hint.label),
+                            false => hint.label.clone(),
+                        },
+                    )]),
+                ),
+                resolver::InlayHintKind::Parameter => (InlayHintKind::PARAMETER, None),
+            };
+            Some(InlayHint {
+                position,
+                label: InlayHintLabel::String(hint.label),
+                kind: Some(kind),
+                text_edits,
+                tooltip: None,
+                padding_left: None,
+                padding_right: Some(hint.padding),
+                data: None,
+            })
+        })
+        .collect()
+}
+
+/// Resolves the file that a `@use` statement in the file at `uri` imports from, preferring its open version to the one on disk.
+fn imported(documents: &Documents, uri: &Url, path: &str) -> Option<Resolution> {
+    let imported = uri.join(path).ok()?;
+    let text = match documents.get(&imported) {
+        Some((_, file)) => file.text.clone(),
+        None => std::fs::read_to_string(imported.to_file_path().ok()?).ok()?,
+    };
+    let program = parse_source(&text);
+    Some(resolve(program.statements()))
+}
+
+/// Lists the declarations at the top of a file, each with the declarations nested in it, such as the methods of a class.
+pub fn document_symbols(document: &Document, file: &SourceFile) -> Vec<DocumentSymbol> {
+    outline(document.program.statements())
+        .into_iter()
+        .map(|item| document_symbol(file, item))
+        .collect()
+}
+
+fn document_symbol(file: &SourceFile, item: OutlineItem) -> DocumentSymbol {
+    let kind = match item.kind {
+        OutlineKind::Variable => SymbolKind::VARIABLE,
+        OutlineKind::Constant => SymbolKind::CONSTANT,
+        OutlineKind::Function => SymbolKind::FUNCTION,
+        OutlineKind::Class => SymbolKind::CLASS,
+        OutlineKind::Interface => SymbolKind::INTERFACE,
+        OutlineKind::Constructor => SymbolKind::CONSTRUCTOR,
+        OutlineKind::Method => SymbolKind::METHOD,
+        OutlineKind::Attribute => SymbolKind::PROPERTY,
+        OutlineKind::Enum => SymbolKind::ENUM,
+        OutlineKind::Variant => SymbolKind::ENUM_MEMBER,
+        OutlineKind::Record => SymbolKind::STRUCT,
+        OutlineKind::TypeAlias => SymbolKind::TYPE_PARAMETER,
+        OutlineKind::Module => SymbolKind::MODULE,
+        OutlineKind::Tests => SymbolKind::NAMESPACE,
+    };
+    let children = item
+        .children
+        .into_iter()
+        .map(|child| document_symbol(file, child))
+        .collect::<Vec<_>>();
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name: item.name,
+        detail: item.detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: to_range(file, item.span),
+        selection_range: to_range(file, item.name_span),
+        children: (!children.is_empty()).then_some(children),
+    }
+}
+
+/// Finds the parts of a file that can be folded. Lines are counted from 0 in LSP, and from 1 in Pebble.
+pub fn folding_ranges(file: &SourceFile) -> Vec<FoldingRange> {
+    resolver::folding_ranges(&file.text)
+        .into_iter()
+        .map(|range| FoldingRange {
+            start_line: range.start as u32 - 1,
+            start_character: None,
+            end_line: range.end as u32 - 1,
+            end_character: None,
+            kind: Some(match range.kind {
+                FoldingKind::Block => FoldingRangeKind::Region,
+                FoldingKind::Comment => FoldingRangeKind::Comment,
+                FoldingKind::Imports => FoldingRangeKind::Imports,
+            }),
+            collapsed_text: None,
+        })
+        .collect()
+}
+
+/// Searches the declarations of every file in the workspace, and names the declaration each is a member of.
+pub fn workspace_symbols(workspace: &Workspace, query: &str) -> Vec<SymbolInformation> {
+    workspace
+        .search(query)
+        .into_iter()
+        .filter_map(|symbol| {
+            let file = workspace.sources.get(symbol.file);
+            let resolution = workspace.resolution(symbol.file);
+            let binding = resolution.binding(symbol.binding);
+            let uri = Url::parse(&file.name).ok()?;
+            #[allow(deprecated)]
+            Some(SymbolInformation {
+                name: binding.name.clone(),
+                kind: symbol_kind(resolution, symbol.binding)?,
+                tags: None,
+                deprecated: None,
+                location: Location::new(uri, to_range(file, binding.declaration)),
+                container_name: binding
+                    .parent
+                    .map(|parent| resolution.binding(parent).name.clone()),
+            })
+        })
+        .collect()
+}
+
+fn symbol_kind(resolution: &Resolution, id: BindingId) -> Option<SymbolKind> {
+    let binding = resolution.binding(id);
+    let constructor = binding.kind == BindingKind::Method
+        && binding
+            .parent
+            .is_some_and(|parent| resolution.binding(parent).name == binding.name);
+    let kind = match binding.kind {
+        BindingKind::Variable => SymbolKind::VARIABLE,
+        BindingKind::Constant => SymbolKind::CONSTANT,
+        BindingKind::Function => SymbolKind::FUNCTION,
+        BindingKind::Class => SymbolKind::CLASS,
+        BindingKind::Interface => SymbolKind::INTERFACE,
+        BindingKind::Enum => SymbolKind::ENUM,
+        BindingKind::Variant => SymbolKind::ENUM_MEMBER,
+        BindingKind::Record => SymbolKind::STRUCT,
+        BindingKind::TypeAlias => SymbolKind::TYPE_PARAMETER,
+        BindingKind::Module => SymbolKind::MODULE,
+        BindingKind::Method if constructor => SymbolKind::CONSTRUCTOR,
+        BindingKind::Method => SymbolKind::METHOD,
+        BindingKind::Attribute => SymbolKind::PROPERTY,
+        BindingKind::Parameter
+        | BindingKind::TypeParameter
+        | BindingKind::Import
+        | BindingKind::Builtin => return None,
+    };
+    Some(kind)
+}
+
+/// Names the token kinds and modifiers, whose indices and bits are what the encoded tokens refer to.
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: SemanticTokenKind::ALL
+            .iter()
+            .map(|kind| SemanticTokenType::new(kind.name()))
+            .collect(),
+        token_modifiers: SemanticModifier::ALL
+            .iter()
+            .map(|modifier| SemanticTokenModifier::new(modifier.name()))
+            .collect(),
+    }
+}
+
+/// Classifies the tokens of a file and encodes each one relative to the token before it, as the protocol requires.<br>
+/// A token that spans several lines, such as a block comment, is split into one token per line.
+pub fn semantic_tokens(document: &Document, file: &SourceFile) -> Vec<SemanticToken> {
+    let mut encoded = vec![];
+    let mut previous = LineColumn::default();
+    for token in resolver::semantic_tokens(&file.text, &document.resolution) {
+        let span = token.span;
+        let token_type = token.kind.index() as u32;
+        let token_modifiers_bitset = token
+            .modifiers
+            .iter()
+            .fold(0, |bits, modifier| bits | modifier.bit());
+        let start = file.line_column(span.start, Encoding::Utf16);
+        let end = file.line_column(span.end, Encoding::Utf16);
+        for line in start.line..=end.line {
+            let from = if line == start.line { start.column } else { 0 };
+            let to = if line == end.line {
+                end.column
+            } else {
+                let range = file.line_range(line).unwrap_or_default();
+                file.line_column(range.end, Encoding::Utf16).column
+            };
+            if to <= from {
+                continue;
+            }
+            let delta_start = if line == previous.line {
+                from - previous.column
+            } else {
+                from
+            };
+            encoded.push(SemanticToken {
+                delta_line: (line - previous.line) as u32,
+                delta_start: delta_start as u32,
+                length: (to - from) as u32,
+                token_type,
+                token_modifiers_bitset,
+            });
+            previous = LineColumn { line, column: from };
+        }
+    }
+    encoded
+}
+
+/// Describes how to turn the tokens sent before into the current ones, as a single edit replacing the tokens that differ.
+pub fn semantic_token_edits(
+    previous: &[SemanticToken],
+    current: &[SemanticToken],
+) -> Vec<SemanticTokensEdit> {
+    let prefix = previous
+        .iter()
+        .zip(current)
+        .take_while(|(previous, current)| previous == current)
+        .count();
+    let suffix = previous[prefix..]
+        .iter()
+        .rev()
+        .zip(current[prefix..].iter().rev())
+        .take_while(|(previous, current)| previous == current)
+        .count();
+    let deleted = previous.len() - prefix - suffix;
+    let inserted = &current[prefix..current.len() - suffix];
+    if deleted == 0 && inserted.is_empty() {
+        return vec![];
+    }
+    // Edits count the integers of the encoded array, of which every token takes five.
+    vec![SemanticTokensEdit {
+        start: prefix as u32 * 5,
+        delete_count: deleted as u32 * 5,
+        data: Some(inserted.to_vec()),
+    }]
+}
@@ -0,0 +1,9 @@
+mod document;
+mod handlers;
+mod server;
+mod test;
+mod transport;
+
+pub use document::{to_offset, to_position, to_range, Document, Documents};
+pub use server::{serve, Server};
+pub use transport::{read_message, write_message, ParseError};
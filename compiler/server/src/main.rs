@@ -0,0 +1,6 @@
+use std::io;
+
+/// Runs the language server over the standard input and output.
+fn main() -> io::Result<()> {
+    server::serve(io::stdin().lock(), io::stdout().lock())
+}
@@ -0,0 +1,411 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    rc::Rc,
+};
+
+use lsp_types::{
+    CodeActionParams, CodeActionProviderCapability, CompletionOptions, CompletionParams,
+    CompletionResponse, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DocumentSymbolParams, DocumentSymbolResponse, FoldingRangeParams,
+    FoldingRangeProviderCapability, GotoDefinitionParams, HoverParams, HoverProviderCapability,
+    InitializeParams, InitializeResult, InlayHintParams, OneOf, PrepareRenameResponse,
+    PublishDiagnosticsParams, ReferenceParams, RenameOptions, RenameParams, SemanticTokens,
+    SemanticTokensDelta, SemanticTokensDeltaParams, SemanticTokensFullDeltaResult,
+    SemanticTokensFullOptions, SemanticTokensOptions, SemanticTokensParams, ServerCapabilities,
+    ServerInfo, SignatureHelpOptions, SignatureHelpParams, TextDocumentPositionParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url, WorkspaceSymbolParams,
+    WorkspaceSymbolResponse,
+};
+use parser::Diagnostic;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::{
+    document::Documents,
+    handlers,
+    transport::{read_message, write_message, ParseError},
+};
+
+/// The error codes defined by JSON-RPC and the Language Server Protocol.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_REQUEST: i64 = -32600;
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+const REQUEST_FAILED: i64 = -32803;
+
+type RequestResult = Result<Value, (i64, String)>;
+
+/// Answers a client's requests about the Pebble files it has open, and tells it about the problems in them.
+pub struct Server<W: Write> {
+    output: W,
+    documents: Documents,
+    /// The folder the client opened, whose Pebble files are searched for the uses of names.
+    root: Option<PathBuf>,
+    /// The semantic tokens last sent for each file, which the client can ask for changes against.
+    tokens: HashMap<Url, SemanticTokens>,
+    /// The problems last published for each open file, so that files other than the one that changed are only published again when theirs change.
+    published: HashMap<Url, Rc<Vec<Diagnostic>>>,
+    /// The number of semantic token results sent, which identifies the next one.
+    results: u64,
+    initialized: bool,
+    shutting_down: bool,
+}
+
+/// Serves a client over a pair of streams, e.g. the standard input and output, until it sends `exit` or closes its end.
+pub fn serve(mut input: impl BufRead, output: impl Write) -> io::Result<()> {
+    Server::new(output).run(&mut input)
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(output: W) -> Self {
+        Server {
+            output,
+            documents: Documents::default(),
+            root: None,
+            tokens: HashMap::new(),
+            published: HashMap::new(),
+            results: 0,
+            initialized: false,
+            shutting_down: false,
+        }
+    }
+    pub fn run(&mut self, input: &mut impl BufRead) -> io::Result<()> {
+        while let Some(message) = read_message(input)? {
+            let message = match message {
+                Ok(message) => message,
+                Err(ParseError(error)) => {
+                    // The request could not be read, so neither could its ID.
+                    let response = json!({
+                        "jsonrpc": "2.0",
+                        "id": Value::Null,
+                        "error": { "code": PARSE_ERROR, "message": error },
+                    });
+                    write_message(&mut self.output, &response)?;
+                    continue;
+                }
+            };
+            if message["method"] == "exit" {
+                break;
+            }
+            self.handle(message)?;
+        }
+        Ok(())
+    }
+    /// Responds to a request, or acts on a notification, which has no `id`.
+    fn handle(&mut self, message: Value) -> io::Result<()> {
+        let method = message["method"].as_str().unwrap_or_default().to_string();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let Some(id) = message.get("id").cloned() else {
+            return self.notification(&method, params);
+        };
+        let response = match self.request(&method, params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        };
+        write_message(&mut self.output, &response)
+    }
+    fn request(&mut self, method: &str, params: Value) -> RequestResult {
+        if method == "initialize" {
+            let params: InitializeParams = parse(params)?;
+            #[allow(deprecated)]
+            let root = match params.workspace_folders.as_deref() {
+                Some([folder, ..]) => Some(folder.uri.clone()),
+                _ => params.root_uri,
+            };
+            self.root = root.and_then(|root| root.to_file_path().ok());
+            self.initialized = true;
+            return to_value(InitializeResult {
+                capabilities: capabilities(),
+                server_info: Some(ServerInfo {
+                    name: "pebble-server".to_string(),
+                    version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                }),
+            });
+        }
+        if !self.initialized {
+            return Err((
+                SERVER_NOT_INITIALIZED,
+                "The server is not initialized.".into(),
+            ));
+        }
+        if self.shutting_down {
+            return Err((INVALID_REQUEST, "The server is shutting down.".into()));
+        }
+        match method {
+            "shutdown" => {
+                self.shutting_down = true;
+                Ok(Value::Null)
+            }
+            "textDocument/hover" => {
+                let params: HoverParams = parse(params)?;
+                let position = params.text_document_position_params;
+                let Some((document, file)) = self.documents.get(&position.text_document.uri) else {
+                    return Ok(Value::Null);
+                };
+                to_value(handlers::hover(&document, file, position.position))
+            }
+            "textDocument/definition" => {
+                let params: GotoDefinitionParams = parse(params)?;
+                let position = params.text_document_position_params;
+                let uri = &position.text_document.uri;
+                let Some((document, file)) = self.documents.get(uri) else {
+                    return Ok(Value::Null);
+                };
+                to_value(handlers::definition(
+                    &document,
+                    file,
+                    uri,
+                    position.position,
+                ))
+            }
+            "textDocument/references" => {
+                let params: ReferenceParams = parse(params)?;
+                let position = params.text_document_position;
+                let workspace = self.documents.workspace(self.root.as_deref());
+                let locations = handlers::references(
+                    &workspace,
+                    &position.text_document.uri,
+                    position.position,
+                    params.context.include_declaration,
+                );
+                to_value(locations)
+            }
+            "textDocument/prepareRename" => {
+                let params: TextDocumentPositionParams = parse(params)?;
+                let workspace = self.documents.workspace(self.root.as_deref());
+                let range = handlers::prepare_rename(
+                    &workspace,
+                    &params.text_document.uri,
+                    params.position,
+                );
+                to_value(range.map(PrepareRenameResponse::Range))
+            }
+            "textDocument/rename" => {
+                let params: RenameParams = parse(params)?;
+                let position = params.text_document_position;
+                let workspace = self.documents.workspace(self.root.as_deref());
+                let edit = handlers::rename(
+                    &workspace,
+                    &position.text_document.uri,
+                    position.position,
+                    &params.new_name,
+                )
+                .map_err(|error| (REQUEST_FAILED, error.to_string()))?;
+                to_value(edit)
+            }
+            "textDocument/completion" => {
+                let params: CompletionParams = parse(params)?;
+                let position = params.text_document_position;
+                let items = handlers::completion(
+                    &self.documents,
+                    &position.text_document.uri,
+                    position.position,
+                );
+                to_value(CompletionResponse::Array(items))
+            }
+            "textDocument/signatureHelp" => {
+                let params: SignatureHelpParams = parse(params)?;
+                let position = params.text_document_position_params;
+                let Some((document, file)) = self.documents.get(&position.text_document.uri) else {
+                    return Ok(Value::Null);
+                };
+                to_value(handlers::signature_help(&document, file, position.position))
+            }
+            "textDocument/inlayHint" => {
+                let params: InlayHintParams = parse(params)?;
+                let Some((document, file)) = self.documents.get(&params.text_document.uri) else {
+                    return Ok(Value::Null);
+                };
+                to_value(handlers::inlay_hints(&document, file, params.range))
+            }
+            "textDocument/codeAction" => {
+                let params: CodeActionParams = parse(params)?;
+                let uri = &params.text_document.uri;
+                let Some((document, file)) = self.documents.get(uri) else {
+                    return Ok(Value::Null);
+                };
+                to_value(handlers::code_actions(&document, file, uri, params.range))
+            }
+            "textDocument/documentSymbol" => {
+                let params: DocumentSymbolParams = parse(params)?;
+                let Some((document, file)) = self.documents.get(&params.text_document.uri) else {
+                    return Ok(Value::Null);
+                };
+                let symbols = handlers::document_symbols(&document, file);
+                to_value(DocumentSymbolResponse::Nested(symbols))
+            }
+            "textDocument/foldingRange" => {
+                let params: FoldingRangeParams = parse(params)?;
+                let Some((_, file)) = self.documents.get(&params.text_document.uri) else {
+                    return Ok(Value::Null);
+                };
+                to_value(handlers::folding_ranges(file))
+            }
+            "workspace/symbol" => {
+                let params: WorkspaceSymbolParams = parse(params)?;
+                let workspace = self.documents.workspace(self.root.as_deref());
+                let symbols = handlers::workspace_symbols(&workspace, &params.query);
+                to_value(WorkspaceSymbolResponse::Flat(symbols))
+            }
+            "textDocument/semanticTokens/full" => {
+                let params: SemanticTokensParams = parse(params)?;
+                to_value(self.semantic_tokens(params.text_document.uri))
+            }
+            "textDocument/semanticTokens/full/delta" => {
+                let params: SemanticTokensDeltaParams = parse(params)?;
+                let uri = params.text_document.uri;
+                let previous = self.tokens.remove(&uri).filter(|previous| {
+                    previous.result_id.as_ref() == Some(&params.previous_result_id)
+                });
+                let Some(current) = self.semantic_tokens(uri) else {
+                    return Ok(Value::Null);
+                };
+                // The client is sent everything again if it asks for changes to tokens that have been replaced since.
+                let Some(previous) = previous else {
+                    return to_value(SemanticTokensFullDeltaResult::Tokens(current));
+                };
+                to_value(SemanticTokensFullDeltaResult::TokensDelta(
+                    SemanticTokensDelta {
+                        result_id: current.result_id,
+                        edits: handlers::semantic_token_edits(&previous.data, &current.data),
+                    },
+                ))
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method {method}."))),
+        }
+    }
+    /// Classifies the tokens of an open file, and remembers them so that the next request can be answered with the changes.
+    fn semantic_tokens(&mut self, uri: Url) -> Option<SemanticTokens> {
+        let (document, file) = self.documents.get(&uri)?;
+        self.results += 1;
+        let tokens = SemanticTokens {
+            result_id: Some(self.results.to_string()),
+            data: handlers::semantic_tokens(&document, file),
+        };
+        self.tokens.insert(uri, tokens.clone());
+        Some(tokens)
+    }
+    fn notification(&mut self, method: &str, params: Value) -> io::Result<()> {
+        // Notifications cannot be answered, so ones that cannot be read are dropped.
+        match method {
+            "textDocument/didOpen" => {
+                if let Ok(params) = parse::<DidOpenTextDocumentParams>(params) {
+                    let document = params.text_document;
+                    self.documents
+                        .open(document.uri.clone(), document.version, document.text);
+                    return self.publish_changes(&document.uri);
+                }
+            }
+            "textDocument/didChange" => {
+                if let Ok(params) = parse::<DidChangeTextDocumentParams>(params) {
+                    let document = params.text_document;
+                    let changes = params.content_changes;
+                    if self
+                        .documents
+                        .change(&document.uri, document.version, changes)
+                    {
+                        return self.publish_changes(&document.uri);
+                    }
+                }
+            }
+            "textDocument/didClose" => {
+                if let Ok(params) = parse::<DidCloseTextDocumentParams>(params) {
+                    let uri = params.text_document.uri;
+                    self.documents.close(&uri);
+                    self.tokens.remove(&uri);
+                    self.published.remove(&uri);
+                    let params = PublishDiagnosticsParams::new(uri, vec![], None);
+                    return self.notify("textDocument/publishDiagnostics", params);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    /// Publishes the problems in a file that changed, and in the other open files whose problems changed with it, such as the ones that import from it.
+    fn publish_changes(&mut self, uri: &Url) -> io::Result<()> {
+        self.publish(uri)?;
+        let mut others = self
+            .documents
+            .uris()
+            .filter(|other| *other != uri)
+            .cloned()
+            .collect::<Vec<_>>();
+        others.sort();
+        for other in others {
+            let Some((document, _)) = self.documents.get(&other) else {
+                continue;
+            };
+            if self.published.get(&other) != Some(&document.diagnostics) {
+                self.publish(&other)?;
+            }
+        }
+        Ok(())
+    }
+    fn publish(&mut self, uri: &Url) -> io::Result<()> {
+        let Some((document, file)) = self.documents.get(uri) else {
+            return Ok(());
+        };
+        let diagnostics = handlers::diagnostics(&document, file);
+        let params =
+            PublishDiagnosticsParams::new(uri.clone(), diagnostics, Some(document.version));
+        self.published.insert(uri.clone(), document.diagnostics);
+        self.notify("textDocument/publishDiagnostics", params)
+    }
+    fn notify(&mut self, method: &str, params: impl serde::Serialize) -> io::Result<()> {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        write_message(&mut self.output, &message)
+    }
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+            TextDocumentSyncKind::INCREMENTAL,
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: Default::default(),
+        })),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        workspace_symbol_provider: Some(OneOf::Left(true)),
+        folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+        signature_help_provider: Some(SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".into(), ",".into()]),
+            ..Default::default()
+        }),
+        inlay_hint_provider: Some(OneOf::Left(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![".".into(), ":".into(), "@".into()]),
+            ..Default::default()
+        }),
+        semantic_tokens_provider: Some(
+            SemanticTokensOptions {
+                legend: handlers::legend(),
+                full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                ..Default::default()
+            }
+            .into(),
+        ),
+        ..Default::default()
+    }
+}
+
+fn parse<T: DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|error| (INVALID_PARAMS, error.to_string()))
+}
+
+fn to_value(result: impl serde::Serialize) -> RequestResult {
+    Ok(serde_json::to_value(result).expect("responses are always valid JSON"))
+}
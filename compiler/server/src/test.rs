@@ -0,0 +1,604 @@
+#![cfg(test)]
+
+use std::io::Cursor;
+
+use lsp_types::Url;
+use serde_json::{json, Value};
+
+use crate::{read_message, serve, write_message};
+
+const URI: &str = "file:///project/main.peb";
+
+const SOURCE: &str = "## The shape everything is drawn with.
+@class Shape {
+    sides: Number,
+    Shape(sides: Number) {
+        self.sides = sides;
+    },
+    area() -> Number {
+        return self.sides;
+    }
+}
+@let shape = Shape(3);
+println shape.area();
+println missing;";
+
+/// Plays the part of a client, sending every message in turn, and returns everything the server sent back.
+fn exchange(messages: Vec<Value>) -> Vec<Value> {
+    let mut input = vec![];
+    for message in &messages {
+        write_message(&mut input, message).unwrap();
+    }
+    let mut output = vec![];
+    serve(Cursor::new(input), &mut output).unwrap();
+    let mut output = Cursor::new(output);
+    let mut responses = vec![];
+    while let Some(response) = read_message(&mut output).unwrap() {
+        responses.push(response.unwrap());
+    }
+    responses
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+fn initialize() -> Vec<Value> {
+    vec![
+        request(0, "initialize", json!({ "capabilities": {} })),
+        notification("initialized", json!({})),
+        notification(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": URI, "languageId": "pebble", "version": 1, "text": SOURCE }
+            }),
+        ),
+    ]
+}
+
+fn at(line: u32, character: u32) -> Value {
+    json!({
+        "textDocument": { "uri": URI },
+        "position": { "line": line, "character": character },
+    })
+}
+
+/// Sends a request about the opened file and returns its result.
+fn ask(method: &str, params: Value) -> Value {
+    let mut messages = initialize();
+    messages.push(request(1, method, params));
+    let responses = exchange(messages);
+    let response = responses
+        .iter()
+        .find(|response| response["id"] == 1)
+        .expect("every request is answered");
+    response["result"].clone()
+}
+
+#[test]
+fn it_initializes_and_shuts_down() {
+    let responses = exchange(vec![
+        request(0, "initialize", json!({ "capabilities": {} })),
+        request(1, "textDocument/unknown", json!({})),
+        request(2, "shutdown", Value::Null),
+        request(3, "textDocument/hover", at(0, 0)),
+        notification("exit", Value::Null),
+        request(4, "shutdown", Value::Null),
+    ]);
+    assert_eq!(responses.len(), 4);
+    let capabilities = &responses[0]["result"]["capabilities"];
+    assert_eq!(capabilities["textDocumentSync"], 2);
+    assert_eq!(capabilities["hoverProvider"], true);
+    assert_eq!(capabilities["definitionProvider"], true);
+    assert_eq!(
+        responses[0]["result"]["serverInfo"]["name"],
+        "pebble-server"
+    );
+    assert_eq!(responses[1]["error"]["code"], -32601);
+    assert_eq!(responses[2]["result"], Value::Null);
+    assert_eq!(responses[3]["error"]["code"], -32600);
+}
+
+#[test]
+fn it_answers_malformed_messages_and_keeps_reading() {
+    let mut input = b"Content-Length: 5\r\n\r\n{bad}Content-Type: text\r\n\r\n".to_vec();
+    write_message(
+        &mut input,
+        &request(0, "initialize", json!({ "capabilities": {} })),
+    )
+    .unwrap();
+    let mut output = vec![];
+    serve(Cursor::new(input), &mut output).unwrap();
+    let mut output = Cursor::new(output);
+    let mut responses = vec![];
+    while let Some(response) = read_message(&mut output).unwrap() {
+        responses.push(response.unwrap());
+    }
+    assert_eq!(responses.len(), 3);
+    for response in &responses[..2] {
+        assert_eq!(response["id"], Value::Null);
+        assert_eq!(response["error"]["code"], -32700);
+    }
+    assert_eq!(responses[2]["id"], 0);
+    assert!(responses[2]["result"]["capabilities"].is_object());
+}
+
+#[test]
+fn it_reports_malformed_messages_without_failing() {
+    let mut input = Cursor::new(b"Content-Length: 2\r\n\r\n{]".to_vec());
+    assert!(matches!(read_message(&mut input), Ok(Some(Err(_)))));
+    assert!(matches!(read_message(&mut input), Ok(None)));
+    let mut input = Cursor::new(b"Content-Length: 9\r\n\r\n{}".to_vec());
+    assert!(read_message(&mut input).is_err());
+}
+
+#[test]
+fn it_publishes_diagnostics_when_a_file_changes() {
+    let mut messages = initialize();
+    messages.push(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "@let = 1;" }],
+        }),
+    ));
+    messages.push(notification(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": URI } }),
+    ));
+    let published = exchange(messages)
+        .into_iter()
+        .filter(|message| message["method"] == "textDocument/publishDiagnostics")
+        .map(|message| message["params"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(published.len(), 3);
+    let opened = published[0]["diagnostics"].as_array().unwrap();
+    assert_eq!(opened.len(), 1);
+    assert_eq!(opened[0]["message"], "Cannot find a value named 'missing'.");
+    assert_eq!(
+        opened[0]["range"],
+        json!({ "start": { "line": 12, "character": 8 }, "end": { "line": 12, "character": 15 } })
+    );
+    assert_eq!(published[1]["version"], 2);
+    assert!(!published[1]["diagnostics"].as_array().unwrap().is_empty());
+    assert_eq!(published[2]["diagnostics"], json!([]));
+}
+
+#[test]
+fn it_applies_changes_to_part_of_a_file() {
+    let mut messages = initialize();
+    let range = |line, start, end| json!({ "start": { "line": line, "character": start }, "end": { "line": line, "character": end } });
+    messages.push(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [
+                { "range": range(12, 8, 15), "text": "shap" },
+                { "range": range(12, 12, 12), "text": "e.sides" },
+            ],
+        }),
+    ));
+    messages.push(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 3 },
+            "contentChanges": [{ "range": range(7, 15, 19), "text": "other" }],
+        }),
+    ));
+    let published = exchange(messages)
+        .into_iter()
+        .filter(|message| message["method"] == "textDocument/publishDiagnostics")
+        .map(|message| message["params"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(published.len(), 3);
+    assert_eq!(published[1]["version"], 2);
+    assert_eq!(published[1]["diagnostics"], json!([]));
+    let changed = published[2]["diagnostics"].as_array().unwrap();
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0]["message"], "Cannot find a value named 'other'.");
+    assert_eq!(changed[0]["range"], range(7, 15, 20));
+}
+
+#[test]
+fn it_publishes_the_files_that_import_from_a_file_that_changed() {
+    let shapes = "file:///project/lib/shapes.peb";
+    let mut messages = initialize();
+    messages.push(notification(
+        "textDocument/didOpen",
+        json!({
+            "textDocument": {
+                "uri": shapes,
+                "languageId": "pebble",
+                "version": 1,
+                "text": "@public @class Square {}\n@function hidden() {}",
+            }
+        }),
+    ));
+    messages.push(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "@use { Square, hidden } from \"./lib/shapes.peb\";\nprintln Square;\nprintln hidden;" }],
+        }),
+    ));
+    messages.push(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": shapes, "version": 2 },
+            "contentChanges": [{
+                "range": { "start": { "line": 1, "character": 0 }, "end": { "line": 1, "character": 0 } },
+                "text": "@public ",
+            }],
+        }),
+    ));
+    let published = exchange(messages)
+        .into_iter()
+        .filter(|message| message["method"] == "textDocument/publishDiagnostics")
+        .map(|message| message["params"].clone())
+        .collect::<Vec<_>>();
+    let uris = published
+        .iter()
+        .map(|params| params["uri"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(uris, vec![URI, shapes, URI, shapes, URI]);
+    let imported = published[2]["diagnostics"].as_array().unwrap();
+    assert_eq!(imported.len(), 1);
+    assert_eq!(
+        imported[0]["message"],
+        "'hidden' is not public, so it cannot be imported."
+    );
+    assert_eq!(published[4]["version"], 2);
+    assert_eq!(published[4]["diagnostics"], json!([]));
+}
+
+#[test]
+fn it_shows_declarations_and_docs_on_hover() {
+    let hover = ask("textDocument/hover", at(10, 15));
+    let value = hover["contents"]["value"].as_str().unwrap();
+    assert_eq!(
+        value,
+        "```pebble\n@class Shape\n```\n\n---\n\nThe shape everything is drawn with."
+    );
+    assert_eq!(
+        hover["range"]["start"],
+        json!({ "line": 10, "character": 13 })
+    );
+    assert_eq!(ask("textDocument/hover", at(12, 0)), Value::Null);
+}
+
+#[test]
+fn it_finds_definitions_and_references() {
+    let definition = ask("textDocument/definition", at(11, 15));
+    assert_eq!(definition["uri"], URI);
+    assert_eq!(
+        definition["range"],
+        json!({ "start": { "line": 6, "character": 4 }, "end": { "line": 6, "character": 8 } })
+    );
+    let mut params = at(2, 5);
+    params["context"] = json!({ "includeDeclaration": true });
+    let references = ask("textDocument/references", params);
+    let lines = references
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|location| location["range"]["start"]["line"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(lines, vec![2, 4, 7]);
+}
+
+#[test]
+fn it_lists_document_symbols() {
+    let symbols = ask(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let describe = |symbol: &Value| {
+        format!(
+            "{} {}",
+            symbol["name"].as_str().unwrap(),
+            symbol["kind"].as_u64().unwrap()
+        )
+    };
+    let symbols = symbols.as_array().unwrap();
+    assert_eq!(
+        symbols.iter().map(describe).collect::<Vec<_>>(),
+        vec!["Shape 5", "shape 13"]
+    );
+    let members = symbols[0]["children"].as_array().unwrap();
+    assert_eq!(
+        members.iter().map(describe).collect::<Vec<_>>(),
+        vec!["sides 7", "Shape 9", "area 6"]
+    );
+}
+
+#[test]
+fn it_sends_semantic_tokens_and_their_changes() {
+    let mut messages = initialize();
+    messages.push(request(
+        1,
+        "textDocument/semanticTokens/full",
+        json!({ "textDocument": { "uri": URI } }),
+    ));
+    messages.push(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": SOURCE.replace("println missing;", "println shape;") }],
+        }),
+    ));
+    messages.push(request(
+        2,
+        "textDocument/semanticTokens/full/delta",
+        delta_of("1"),
+    ));
+    messages.push(request(
+        3,
+        "textDocument/semanticTokens/full/delta",
+        delta_of("1"),
+    ));
+    let responses = exchange(messages)
+        .into_iter()
+        .filter(|message| message.get("id").is_some())
+        .collect::<Vec<_>>();
+    let full = &responses[1]["result"];
+    assert_eq!(full["resultId"], "1");
+    let data = full["data"].as_array().unwrap();
+    // The doc comment comes first, then `@class` on the next line and the name of the class after it.
+    assert_eq!(data[..15], [0, 0, 38, 2, 8, 1, 0, 6, 1, 0, 0, 7, 5, 11, 1]);
+    // `missing` could not be resolved, so it had no token, while `shape` is a variable after the last token.
+    let delta = &responses[2]["result"];
+    assert_eq!(delta["resultId"], "2");
+    assert_eq!(
+        delta["edits"],
+        json!([{ "start": data.len(), "deleteCount": 0, "data": [0, 8, 5, 6, 0] }])
+    );
+    // Tokens that have been replaced since are sent again in full.
+    assert_eq!(responses[3]["result"]["resultId"], "3");
+    assert!(responses[3]["result"]["data"].is_array());
+}
+
+#[test]
+fn it_offers_fixes_as_code_actions() {
+    let mut messages = initialize();
+    messages.push(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "@let a = 1\nprintln a" }],
+        }),
+    ));
+    let range =
+        json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 10 } });
+    messages.push(request(
+        1,
+        "textDocument/codeAction",
+        json!({ "textDocument": { "uri": URI }, "range": range, "context": { "diagnostics": [] } }),
+    ));
+    let responses = exchange(messages);
+    let actions = &responses
+        .iter()
+        .find(|response| response["id"] == 1)
+        .unwrap()["result"];
+    let actions = actions.as_array().unwrap();
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[0]["title"], "Insert a semicolon");
+    assert_eq!(actions[0]["kind"], "quickfix");
+    assert_eq!(
+        actions[0]["diagnostics"][0]["message"],
+        "Expected a semicolon."
+    );
+    assert_eq!(
+        actions[0]["edit"]["changes"][URI],
+        json!([{
+            "range": { "start": { "line": 0, "character": 10 }, "end": { "line": 0, "character": 10 } },
+            "newText": ";",
+        }])
+    );
+    // Both semicolons are inserted by the action that fixes everything.
+    assert_eq!(actions[1]["kind"], "source.fixAll");
+    assert_eq!(
+        actions[1]["edit"]["changes"][URI].as_array().unwrap().len(),
+        2
+    );
+}
+
+#[test]
+fn it_shows_inlay_hints_and_signature_help() {
+    let range =
+        json!({ "start": { "line": 10, "character": 0 }, "end": { "line": 11, "character": 0 } });
+    let hints = ask(
+        "textDocument/inlayHint",
+        json!({ "textDocument": { "uri": URI }, "range": range }),
+    );
+    assert_eq!(
+        hints,
+        json!([
+            {
+                "position": { "line": 10, "character": 10 },
+                "label": ": Shape",
+                "kind": 1,
+                "textEdits": [{
+                    "range": { "start": { "line": 10, "character": 10 }, "end": { "line": 10, "character": 10 } },
+                    "newText": ": Shape",
+                }],
+                "paddingRight": false,
+            },
+            {
+                "position": { "line": 10, "character": 19 },
+                "label": "sides:",
+                "kind": 2,
+                "paddingRight": true,
+            },
+        ])
+    );
+    let help = ask("textDocument/signatureHelp", at(10, 19));
+    assert_eq!(help["signatures"][0]["label"], "Shape(sides: Number)");
+    assert_eq!(
+        help["signatures"][0]["parameters"],
+        json!([{ "label": "sides: Number" }])
+    );
+    assert_eq!(help["activeParameter"], 0);
+}
+
+#[test]
+fn it_folds_blocks_and_searches_workspace_symbols() {
+    let ranges = ask(
+        "textDocument/foldingRange",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let lines = ranges
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|range| {
+            (
+                range["startLine"].as_u64().unwrap(),
+                range["endLine"].as_u64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(lines, vec![(1, 8), (3, 4), (6, 7)]);
+    assert_eq!(ranges[0]["kind"], "region");
+    let symbols = ask("workspace/symbol", json!({ "query": "ara" }));
+    assert_eq!(
+        symbols,
+        json!([{
+            "name": "area",
+            "kind": 6,
+            "location": {
+                "uri": URI,
+                "range": { "start": { "line": 6, "character": 4 }, "end": { "line": 8, "character": 5 } },
+            },
+            "containerName": "Shape",
+        }])
+    );
+}
+
+fn delta_of(previous: &str) -> Value {
+    json!({ "textDocument": { "uri": URI }, "previousResultId": previous })
+}
+
+#[test]
+fn it_completes_members_and_imports_from_open_files() {
+    let members = ask("textDocument/completion", at(11, 14));
+    let labels = |items: &Value| {
+        items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(labels(&members), vec!["sides", "area"]);
+    assert_eq!(members[1]["detail"], "area() -> Number");
+    assert_eq!(
+        members[1]["textEdit"]["range"],
+        json!({ "start": { "line": 11, "character": 14 }, "end": { "line": 11, "character": 14 } })
+    );
+    let mut messages = initialize();
+    messages.push(notification(
+        "textDocument/didOpen",
+        json!({
+            "textDocument": {
+                "uri": "file:///project/lib/shapes.peb",
+                "languageId": "pebble",
+                "version": 1,
+                "text": "@public @class Square {}\n@function hidden() {}",
+            }
+        }),
+    ));
+    messages.push(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "@use {  } from \"./lib/shapes.peb\";" }],
+        }),
+    ));
+    messages.push(request(1, "textDocument/completion", at(0, 7)));
+    let responses = exchange(messages);
+    let imports = &responses
+        .iter()
+        .find(|response| response["id"] == 1)
+        .unwrap()["result"];
+    assert_eq!(labels(imports), vec!["Square"]);
+    assert_eq!(imports[0]["kind"], 7);
+}
+
+#[test]
+fn it_renames_names_used_in_files_that_are_not_open() {
+    let root = std::env::temp_dir().join(format!("pebble-server-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    let shapes = root.join("src/shapes.peb");
+    std::fs::write(
+        &shapes,
+        "@public @class Shape {}\n@let unused: Shape = Shape();",
+    )
+    .unwrap();
+    let root_uri = Url::from_directory_path(&root).unwrap();
+    let main_uri = root_uri.join("src/main.peb").unwrap();
+    let shapes_uri = Url::from_file_path(&shapes).unwrap();
+    let position = |line: u32, character: u32| {
+        json!({
+            "textDocument": { "uri": main_uri },
+            "position": { "line": line, "character": character },
+        })
+    };
+    let mut rename = position(1, 13);
+    rename["newName"] = json!("Polygon");
+    let mut collision = position(1, 13);
+    collision["newName"] = json!("shape");
+    let responses = exchange(vec![
+        request(
+            0,
+            "initialize",
+            json!({ "capabilities": {}, "rootUri": root_uri }),
+        ),
+        notification(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": main_uri,
+                    "languageId": "pebble",
+                    "version": 1,
+                    "text": "@use { Shape } from \"./shapes.peb\";\n@let shape: Shape = Shape();",
+                }
+            }),
+        ),
+        request(1, "textDocument/prepareRename", position(1, 0)),
+        request(2, "textDocument/prepareRename", position(1, 13)),
+        request(3, "textDocument/rename", rename),
+        request(4, "textDocument/rename", collision),
+    ]);
+    std::fs::remove_dir_all(&root).unwrap();
+    let result = |id: u64| {
+        responses
+            .iter()
+            .find(|response| response["id"] == id)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(result(1)["result"], Value::Null);
+    assert_eq!(
+        result(2)["result"],
+        json!({ "start": { "line": 1, "character": 12 }, "end": { "line": 1, "character": 17 } })
+    );
+    let changes = &result(3)["result"]["changes"];
+    let lines = |uri: &Url| {
+        changes[uri.as_str()]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edit| {
+                assert_eq!(edit["newText"], "Polygon");
+                edit["range"]["start"]["line"].as_u64().unwrap()
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(lines(&main_uri), vec![0, 1, 1]);
+    assert_eq!(lines(&shapes_uri), vec![0, 1, 1]);
+    assert_eq!(result(4)["error"]["code"], -32803);
+}
@@ -0,0 +1,45 @@
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+/// A message whose header or body could not be read, which the client is told about before the next message is read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError(pub String);
+
+/// Reads the next message sent by the client, which is a JSON body after a `Content-Length` header.<br>
+/// Returns `None` when the client has closed its end of the stream. A malformed message is a `ParseError`, and only a failure of the stream itself is an `Err`.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Result<Value, ParseError>>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let Some(length) = length else {
+        return Ok(Some(Err(ParseError(
+            "A message has no valid Content-Length header.".to_string(),
+        ))));
+    };
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(
+        serde_json::from_slice(&body).map_err(|error| ParseError(error.to_string())),
+    ))
+}
+
+/// Sends a message to the client.
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
@@ -1,5 +1,7 @@
+mod source_map;
 mod stack;
 mod stage;
 
+pub use source_map::{Encoding, FileId, LineColumn, SourceFile, SourceMap, Span, TextEdit};
 pub use stack::Stack;
 pub use stage::Stage;
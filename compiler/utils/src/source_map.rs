@@ -0,0 +1,300 @@
+use std::{io, ops::Range, path::Path};
+
+/// Identifies a file registered in a [`SourceMap`].<br>
+/// Text parsed without a source map is given the default ID, which is that of the first file registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct FileId(u32);
+
+/// A range of bytes in a source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub file: FileId,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(file: FileId, start: usize, end: usize) -> Self {
+        Span { file, start, end }
+    }
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+    /// Returns `true` if the span covers the byte at `offset`, or ends exactly at it.
+    pub fn contains(&self, offset: usize) -> bool {
+        self.start <= offset && offset <= self.end
+    }
+    /// Returns the span from the start of this span to the end of `other`.
+    pub fn to(&self, other: Span) -> Span {
+        Span::new(self.file, self.start, other.end)
+    }
+}
+
+/// A replacement of the text a span covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(span: Span, text: impl Into<String>) -> Self {
+        TextEdit {
+            span,
+            text: text.into(),
+        }
+    }
+}
+
+/// The unit that columns are counted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Columns count bytes.
+    Utf8,
+    /// Columns count UTF-16 code units, as the Language Server Protocol expects.
+    Utf16,
+    /// Columns count characters.
+    Chars,
+}
+
+/// A zero-based line and column in a source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The text of a source file, with the offsets of its lines.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub id: FileId,
+    pub name: String,
+    pub text: String,
+    /// The byte offset where each line begins.
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    fn new(id: FileId, name: String, text: String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        SourceFile {
+            id,
+            name,
+            text,
+            line_starts,
+        }
+    }
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+    /// Returns the byte range of a line, without its line break.
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line)?;
+        let end = match self.line_starts.get(line + 1) {
+            Some(next) => next - 1,
+            None => self.text.len(),
+        };
+        Some(start..end)
+    }
+    /// Returns the line and column of a byte offset, with the column counted in `encoding`.
+    /// Offsets past the end of the text are placed at the end.
+    pub fn line_column(&self, offset: usize, encoding: Encoding) -> LineColumn {
+        let offset = self.floor_char_boundary(offset);
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let before = &self.text[self.line_starts[line]..offset];
+        LineColumn {
+            line,
+            column: measure(before, encoding),
+        }
+    }
+    /// Returns the byte offset of a line and column, with the column counted in `encoding`.
+    /// Returns `None` if the line does not exist. A column past the end of its line is placed at the end.
+    pub fn offset(&self, position: LineColumn, encoding: Encoding) -> Option<usize> {
+        let range = self.line_range(position.line)?;
+        let line = &self.text[range.clone()];
+        let mut counted = 0;
+        for (index, char) in line.char_indices() {
+            if counted >= position.column {
+                return Some(range.start + index);
+            }
+            counted += measure_char(char, encoding);
+        }
+        Some(range.end)
+    }
+    /// Returns the text a span covers.
+    pub fn slice(&self, span: Span) -> &str {
+        &self.text[span.range()]
+    }
+    /// Returns the text of the file with the edits made to it applied. Edits made to other files are ignored.<br>
+    /// The edits must not overlap, but can be in any order.
+    pub fn apply(&self, edits: &[TextEdit]) -> String {
+        let mut edits = edits
+            .iter()
+            .filter(|edit| edit.span.file == self.id)
+            .collect::<Vec<_>>();
+        edits.sort_by_key(|edit| edit.span.start);
+        let mut text = String::with_capacity(self.text.len());
+        let mut copied = 0;
+        for edit in edits {
+            text.push_str(&self.text[copied..edit.span.start]);
+            text.push_str(&edit.text);
+            copied = edit.span.end;
+        }
+        text.push_str(&self.text[copied..]);
+        text
+    }
+    fn floor_char_boundary(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+}
+
+fn measure_char(char: char, encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Utf8 => char.len_utf8(),
+        Encoding::Utf16 => char.len_utf16(),
+        Encoding::Chars => 1,
+    }
+}
+
+fn measure(text: &str, encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Utf8 => text.len(),
+        Encoding::Utf16 => text.encode_utf16().count(),
+        Encoding::Chars => text.chars().count(),
+    }
+}
+
+/// A database of the source files in a compilation, which gives each file an ID so that spans in different files can be told apart.
+#[derive(Debug, Default, Clone)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Registers a file with its text, and returns its ID.
+    pub fn add(&mut self, name: impl Into<String>, text: impl Into<String>) -> FileId {
+        let id = FileId(self.files.len() as u32);
+        self.files
+            .push(SourceFile::new(id, name.into(), text.into()));
+        id
+    }
+    /// Reads a file from disk and registers it. A file that is already registered is not read again.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> io::Result<FileId> {
+        let name = path.as_ref().to_string_lossy().to_string();
+        if let Some(file) = self.find(&name) {
+            return Ok(file.id);
+        }
+        let text = std::fs::read_to_string(path)?;
+        Ok(self.add(name, text))
+    }
+    /// Replaces the text of a registered file, keeping its ID.
+    pub fn update(&mut self, id: FileId, text: impl Into<String>) {
+        let file = &mut self.files[id.0 as usize];
+        *file = SourceFile::new(id, std::mem::take(&mut file.name), text.into());
+    }
+    pub fn get(&self, id: FileId) -> &SourceFile {
+        &self.files[id.0 as usize]
+    }
+    /// Returns the registered file with the given name.
+    pub fn find(&self, name: &str) -> Option<&SourceFile> {
+        self.files.iter().find(|file| file.name == name)
+    }
+    pub fn files(&self) -> impl Iterator<Item = &SourceFile> {
+        self.files.iter()
+    }
+    /// Returns the line and column where a span begins.
+    pub fn start(&self, span: Span, encoding: Encoding) -> LineColumn {
+        self.get(span.file).line_column(span.start, encoding)
+    }
+    /// Returns the line and column where a span ends.
+    pub fn end(&self, span: Span, encoding: Encoding) -> LineColumn {
+        self.get(span.file).line_column(span.end, encoding)
+    }
+    /// Returns the text a span covers.
+    pub fn slice(&self, span: Span) -> &str {
+        self.get(span.file).slice(span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_offsets_in_every_encoding() {
+        let mut map = SourceMap::new();
+        let file = map.add("main.peb", "@let a = 1;\n@let é😀b = 2;");
+        let source = map.get(file);
+        let offset = source.text.find('b').unwrap();
+        assert_eq!(
+            source.line_column(offset, Encoding::Utf8),
+            LineColumn {
+                line: 1,
+                column: 11
+            }
+        );
+        assert_eq!(
+            source.line_column(offset, Encoding::Utf16),
+            LineColumn { line: 1, column: 8 }
+        );
+        assert_eq!(
+            source.line_column(offset, Encoding::Chars),
+            LineColumn { line: 1, column: 7 }
+        );
+        for encoding in [Encoding::Utf8, Encoding::Utf16, Encoding::Chars] {
+            let position = source.line_column(offset, encoding);
+            assert_eq!(source.offset(position, encoding), Some(offset));
+        }
+    }
+
+    #[test]
+    fn it_slices_spans_in_their_own_file() {
+        let mut map = SourceMap::new();
+        map.add("first.peb", "");
+        let file = map.add("second.peb", "a;\nlet é = b;");
+        let name = Span::new(file, 7, 9);
+        assert_eq!(map.slice(name), "é");
+        assert_eq!(map.slice(Span::new(file, 0, 1).to(name)), "a;\nlet é");
+        assert_eq!(
+            map.start(name, Encoding::Utf16),
+            LineColumn { line: 1, column: 4 }
+        );
+    }
+
+    #[test]
+    fn it_keeps_file_ids_when_updating() {
+        let mut map = SourceMap::new();
+        let file = map.add("main.peb", "a");
+        map.update(file, "a\nb");
+        assert_eq!(map.get(file).line_count(), 2);
+        assert_eq!(map.find("main.peb").map(|file| file.id), Some(file));
+    }
+
+    #[test]
+    fn it_applies_edits_in_any_order() {
+        let mut map = SourceMap::new();
+        let file = map.add("main.peb", "@let a = b;");
+        let other = map.add("other.peb", "");
+        let edits = [
+            TextEdit::new(Span::new(file, 9, 10), "value"),
+            TextEdit::new(Span::new(other, 0, 0), "ignored"),
+            TextEdit::new(Span::new(file, 5, 6), "name"),
+        ];
+        assert_eq!(map.get(file).apply(&edits), "@let name = value;");
+    }
+}
@@ -6,6 +6,12 @@ pub struct Stack<T> {
     pub is_empty: bool,
 }
 
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Stack<T> {
     /// Create a new stack.
     pub fn new() -> Self {
@@ -19,7 +25,7 @@ impl<T> Stack<T> {
     pub fn push(&mut self, data: T) {
         self.arr.push(data);
         self.size += 1;
-        self.is_empty = self.is_empty && false;
+        self.is_empty = false;
     }
     /// Removes a piece of data from the stack and returns it.
     pub fn pop(&mut self) -> Option<T> {
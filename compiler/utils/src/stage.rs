@@ -76,7 +76,7 @@ where
                         return Some(s);
                     }
                 }
-                return None;
+                None
             }
         }
     }
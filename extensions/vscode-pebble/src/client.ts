@@ -1,4 +1,3 @@
-import * as path from "path";
 import { workspace, ExtensionContext } from "vscode";
 import {
    LanguageClient,
@@ -12,35 +11,25 @@ let client: LanguageClient;
 
 export const pebbleLanguageClient = {
    activate(context: ExtensionContext) {
-      // The server is implemented in node
-      let serverModule = context.asAbsolutePath(path.join("dist", "server.js"));
-      // The debug options for the server
-      // --inspect=6009: runs the server in Node's Inspector mode so VS Code can attach to the server for debugging
-      let debugOptions = { execArgv: ["--nolazy", "--inspect=6009"] };
-
-      // If the extension is launched in debug mode then the debug server options are used
-      // Otherwise the run options are used
-      let serverOptions: ServerOptions = {
-         run: {
-            command: "server.exe",
-            transport: TransportKind.ipc,
-            module: serverModule,
-         },
+      // The server is the `pebble-server` binary built from compiler/server, found on the PATH unless configured otherwise.
+      const command = workspace
+         .getConfiguration("pebble")
+         .get<string>("serverPath", "pebble-server");
+      const executable: Executable = {
+         command,
+         transport: TransportKind.stdio,
+      };
+      const serverOptions: ServerOptions = {
+         run: executable,
          debug: {
-            module: serverModule,
-            transport: TransportKind.ipc,
-            options: debugOptions,
+            ...executable,
+            options: { env: { ...process.env, RUST_BACKTRACE: "1" } },
          },
       };
 
       // Options to control the language client
-      let clientOptions: LanguageClientOptions = {
-         // Register the server for plain text documents
+      const clientOptions: LanguageClientOptions = {
          documentSelector: [{ scheme: "file", language: "peb" }],
-         synchronize: {
-            // Notify the server about file changes to '.clientrc files contained in the workspace
-            fileEvents: workspace.createFileSystemWatcher("**/.clientrc"),
-         },
       };
 
       // Create the language client and start the client.
@@ -1,11 +0,0 @@
-import * as vscode from "vscode";
-
-const tokenTypes = [
-   "class",
-   "function",
-   "enum",
-   "interface",
-   "variable",
-   "constant",
-];
-export default function registerTokenProvider() {}